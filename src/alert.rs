@@ -0,0 +1,187 @@
+use crate::sentry::{parse_stats_period, Issue};
+use anyhow::{Context, Result};
+use std::collections::{HashMap, VecDeque};
+
+/// Issue counter an alert rule watches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlertMetric {
+    /// Events of the issue
+    Count,
+    /// Users affected by the issue
+    Users,
+}
+
+impl std::fmt::Display for AlertMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertMetric::Count => write!(f, "count"),
+            AlertMetric::Users => write!(f, "users"),
+        }
+    }
+}
+
+/// Threshold on how much an issue counter grows within a window, e.g. `count>100 in 10m`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub threshold: u64,
+    /// Window length in seconds
+    pub window: u64,
+}
+
+impl AlertRule {
+    pub fn parse(rule: &str) -> Result<Self> {
+        let (condition, window) = rule
+            .split_once(" in ")
+            .with_context(|| format!("Invalid alert '{}'. Use e.g. 'count>100 in 10m'", rule))?;
+        let (metric, threshold) = condition
+            .split_once('>')
+            .with_context(|| format!("Invalid alert condition '{}'", condition.trim()))?;
+        let metric = match metric.trim() {
+            "count" | "events" => AlertMetric::Count,
+            "users" => AlertMetric::Users,
+            other => anyhow::bail!("Unknown alert metric '{}'. Use count or users", other),
+        };
+        let threshold = threshold
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid alert threshold '{}'", threshold.trim()))?;
+        Ok(Self {
+            metric,
+            threshold,
+            window: parse_stats_period(window.trim())?,
+        })
+    }
+}
+
+impl std::fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let window = match self.window {
+            w if w % 3600 == 0 => format!("{}h", w / 3600),
+            w if w % 60 == 0 => format!("{}m", w / 60),
+            w => format!("{}s", w),
+        };
+        write!(f, "{}>{} in {}", self.metric, self.threshold, window)
+    }
+}
+
+/// Samples of issue counters over time, to tell how much they grew within a window.
+#[derive(Debug, Default)]
+pub struct RateTracker {
+    samples: HashMap<String, VecDeque<(u64, u64, u64)>>,
+    /// Longest window any rule looks at; older samples are dropped
+    retention: u64,
+}
+
+impl RateTracker {
+    pub fn new(rules: &[AlertRule]) -> Self {
+        Self {
+            samples: HashMap::new(),
+            retention: rules.iter().map(|rule| rule.window).max().unwrap_or(0),
+        }
+    }
+
+    /// Records the counters of an issue at `now` (seconds on any monotonic clock).
+    pub fn record(&mut self, issue: &Issue, now: u64) {
+        let samples = self.samples.entry(issue.id.clone()).or_default();
+        samples.push_back((now, issue.count as u64, issue.user_count as u64));
+        // Keep one sample at or before the oldest window start as the baseline
+        while samples.len() > 1 && samples[1].0 + self.retention <= now {
+            samples.pop_front();
+        }
+    }
+
+    /// Growth of a counter since the start of the window ending at the latest sample.
+    pub fn increase(&self, issue_id: &str, metric: AlertMetric, window: u64) -> u64 {
+        let Some(samples) = self.samples.get(issue_id) else {
+            return 0;
+        };
+        let Some(&latest) = samples.back() else {
+            return 0;
+        };
+        let baseline = samples
+            .iter()
+            .rev()
+            .find(|sample| sample.0 + window <= latest.0)
+            .or(samples.front())
+            .copied()
+            .unwrap_or(latest);
+        let value = |sample: (u64, u64, u64)| match metric {
+            AlertMetric::Count => sample.1,
+            AlertMetric::Users => sample.2,
+        };
+        value(latest).saturating_sub(value(baseline))
+    }
+
+    /// First rule the issue currently breaks, if any. Issues seen for the first time
+    /// have no baseline yet and never alert.
+    pub fn breached<'a>(&self, issue_id: &str, rules: &'a [AlertRule]) -> Option<&'a AlertRule> {
+        rules
+            .iter()
+            .find(|rule| self.increase(issue_id, rule.metric, rule.window) > rule.threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: &str, count: u32, user_count: u32) -> Issue {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": "Test Issue",
+            "status": "unresolved",
+            "level": "error",
+            "culprit": "test.js:42",
+            "lastSeen": "2024-01-01T00:00:00Z",
+            "count": count,
+            "userCount": user_count
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_alert_rule() {
+        assert_eq!(
+            AlertRule::parse("count>100 in 10m").unwrap(),
+            AlertRule {
+                metric: AlertMetric::Count,
+                threshold: 100,
+                window: 600,
+            }
+        );
+        assert_eq!(
+            AlertRule::parse("users > 5 in 1h").unwrap().metric,
+            AlertMetric::Users
+        );
+        assert!(AlertRule::parse("count>100").is_err());
+        assert!(AlertRule::parse("latency>100 in 10m").is_err());
+        assert!(AlertRule::parse("count>many in 10m").is_err());
+    }
+
+    #[test]
+    fn test_rate_tracker() {
+        let rules = vec![AlertRule::parse("count>100 in 10m").unwrap()];
+        let mut tracker = RateTracker::new(&rules);
+
+        tracker.record(&issue("1", 50, 1), 0);
+        assert!(tracker.breached("1", &rules).is_none());
+
+        tracker.record(&issue("1", 120, 2), 300);
+        assert_eq!(tracker.increase("1", AlertMetric::Count, 600), 70);
+        assert!(tracker.breached("1", &rules).is_none());
+
+        tracker.record(&issue("1", 200, 3), 600);
+        assert_eq!(tracker.increase("1", AlertMetric::Count, 600), 150);
+        assert!(tracker.breached("1", &rules).is_some());
+
+        // Growth older than the window no longer counts
+        tracker.record(&issue("1", 210, 3), 1200);
+        assert_eq!(tracker.increase("1", AlertMetric::Count, 600), 10);
+        assert!(tracker.breached("1", &rules).is_none());
+
+        // Totals from before the monitor started are not growth
+        tracker.record(&issue("2", 500, 1), 1200);
+        assert!(tracker.breached("2", &rules).is_none());
+    }
+}
@@ -0,0 +1,231 @@
+use crate::config::APP_NAME;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CACHE_FILE: &str = "badges.json";
+/// tmux redraws its status line every `status-interval` seconds (15 by default) and
+/// runs `#()` commands on every redraw, so counts are never fetched more often than this.
+const TMUX_MIN_REFRESH: u64 = 15;
+/// Seconds a background refresh may take before the next prompt starts another one.
+const REFRESH_TIMEOUT: u64 = 60;
+
+/// Markup a badge is wrapped in for the status bar showing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BadgeStyle {
+    /// No color codes
+    Plain,
+    /// Colored `#[fg=...,bg=...]` tmux status line segment
+    Tmux,
+    /// `%{F...}` polybar format tags
+    Polybar,
+}
+
+/// Issue count shown by a badge. `capped` means there are at least `count` issues.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BadgeCount {
+    pub count: usize,
+    pub capped: bool,
+    /// Unix time the count was fetched
+    pub fetched_at: u64,
+}
+
+/// Renders a count as e.g. `⚠ 12`, red while there are issues and green without.
+pub fn render_badge(count: &BadgeCount, style: BadgeStyle) -> String {
+    let text = match (count.count, count.capped) {
+        (0, _) => "✓ 0".to_string(),
+        (n, true) => format!("⚠ {}+", n),
+        (n, false) => format!("⚠ {}", n),
+    };
+    let ok = count.count == 0;
+    match style {
+        BadgeStyle::Plain => text,
+        BadgeStyle::Tmux => {
+            let bg = if ok { "colour28" } else { "colour160" };
+            format!("#[fg=colour231,bg={}] {} #[default]", bg, text)
+        }
+        BadgeStyle::Polybar => {
+            let color = if ok { "#50fa7b" } else { "#ff5555" };
+            format!("%{{F{}}}{}%{{F-}}", color, text)
+        }
+    }
+}
+
+/// Counts kept between runs, so status bars polling every few seconds stay within
+/// API rate limits.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BadgeCache {
+    counts: HashMap<String, BadgeCount>,
+    /// Unix time a background refresh of a count started, until it stores the count
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    refreshing: HashMap<String, u64>,
+}
+
+impl BadgeCache {
+    /// Loads the cache, starting empty when it is missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create cache directory")?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?).context("Failed to write badge cache")
+    }
+
+    /// A count fetched less than `ttl` seconds before `now`.
+    pub fn get(&self, key: &str, ttl: u64, now: u64) -> Option<BadgeCount> {
+        self.counts
+            .get(key)
+            .filter(|count| now.saturating_sub(count.fetched_at) < ttl)
+            .copied()
+    }
+
+    /// The last count stored under a key, however old.
+    pub fn get_any(&self, key: &str) -> Option<BadgeCount> {
+        self.counts.get(key).copied()
+    }
+
+    pub fn insert(&mut self, key: String, count: BadgeCount) {
+        self.refreshing.remove(&key);
+        self.counts.insert(key, count);
+    }
+
+    /// Marks a count as being refreshed. Returns false while an earlier refresh started
+    /// less than [`REFRESH_TIMEOUT`] ago, so slow networks don't pile up refreshes.
+    pub fn start_refresh(&mut self, key: &str, now: u64) -> bool {
+        if let Some(started) = self.refreshing.get(key) {
+            if now.saturating_sub(*started) < REFRESH_TIMEOUT {
+                return false;
+            }
+        }
+        self.refreshing.insert(key.to_string(), now);
+        true
+    }
+}
+
+/// Cache keys of the error and warning counts shown by `prompt` for a project.
+pub fn prompt_keys(target: &str) -> (String, String) {
+    (
+        format!("{} prompt:errors", target),
+        format!("{} prompt:warnings", target),
+    )
+}
+
+/// Renders prompt counts as e.g. `E:3 W:12`, leaving out zero counts so that a clean
+/// project adds nothing to the prompt.
+pub fn render_prompt(errors: &BadgeCount, warnings: &BadgeCount) -> String {
+    let segment = |label: &str, count: &BadgeCount| match (count.count, count.capped) {
+        (0, _) => None,
+        (n, true) => Some(format!("{}:{}+", label, n)),
+        (n, false) => Some(format!("{}:{}", label, n)),
+    };
+    [segment("E", errors), segment("W", warnings)]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl BadgeStyle {
+    /// Seconds a cached count is reused for at the least, whatever TTL was asked for.
+    pub fn min_refresh(self) -> u64 {
+        match self {
+            BadgeStyle::Tmux => TMUX_MIN_REFRESH,
+            BadgeStyle::Plain | BadgeStyle::Polybar => 0,
+        }
+    }
+}
+
+pub fn cache_path() -> Result<PathBuf> {
+    Ok(dirs::cache_dir()
+        .context("Failed to determine cache directory")?
+        .join(APP_NAME)
+        .join(CACHE_FILE))
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+
+    fn count(count: usize, capped: bool) -> BadgeCount {
+        BadgeCount {
+            count,
+            capped,
+            fetched_at: 1000,
+        }
+    }
+
+    #[test]
+    fn test_render_badge() {
+        assert_eq!(render_badge(&count(12, false), BadgeStyle::Plain), "⚠ 12");
+        assert_eq!(render_badge(&count(100, true), BadgeStyle::Plain), "⚠ 100+");
+        assert_eq!(
+            render_badge(&count(0, false), BadgeStyle::Tmux),
+            "#[fg=colour231,bg=colour28] ✓ 0 #[default]"
+        );
+        assert_eq!(
+            render_badge(&count(12, false), BadgeStyle::Tmux),
+            "#[fg=colour231,bg=colour160] ⚠ 12 #[default]"
+        );
+        assert_eq!(
+            render_badge(&count(3, false), BadgeStyle::Polybar),
+            "%{F#ff5555}⚠ 3%{F-}"
+        );
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        assert_eq!(
+            render_prompt(&count(3, false), &count(12, false)),
+            "E:3 W:12"
+        );
+        assert_eq!(render_prompt(&count(0, false), &count(100, true)), "W:100+");
+        assert_eq!(render_prompt(&count(0, false), &count(0, false)), "");
+    }
+
+    #[test]
+    fn test_badge_cache() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("cache").join(CACHE_FILE);
+        assert!(BadgeCache::load(&path).get("acme/web", 60, 1000).is_none());
+
+        let mut cache = BadgeCache::default();
+        cache.insert("acme/web".to_string(), count(12, false));
+        cache.save(&path)?;
+
+        let cache = BadgeCache::load(&path);
+        assert_eq!(cache.get("acme/web", 60, 1059), Some(count(12, false)));
+        assert!(cache.get("acme/web", 60, 1060).is_none());
+        assert!(cache.get("acme/api", 60, 1000).is_none());
+        assert_eq!(cache.get_any("acme/web"), Some(count(12, false)));
+
+        let mut refreshed = BadgeCache::default();
+        assert!(refreshed.start_refresh("acme/web", 1000));
+        assert!(!refreshed.start_refresh("acme/web", 1059));
+        assert!(refreshed.start_refresh("acme/web", 1060));
+        // Storing the count ends the refresh
+        refreshed.insert("acme/web".to_string(), count(12, false));
+        assert!(refreshed.start_refresh("acme/web", 1061));
+
+        // tmux polls on every redraw, so a zero TTL still reuses counts for a while
+        let ttl = BadgeStyle::Tmux.min_refresh();
+        assert!(cache.get("acme/web", ttl, 1014).is_some());
+        assert!(cache.get("acme/web", 0, 1014).is_none());
+        Ok(())
+    }
+}
@@ -1,9 +1,47 @@
-use crate::config::{Config, Organization};
-use crate::dashboard::Dashboard;
+use crate::alert::AlertRule;
+use crate::badge::{self, render_badge, render_prompt, BadgeCache, BadgeCount, BadgeStyle};
+use crate::config::{Config, MonitorTarget, Organization, SentryCliSettings, TokenRole};
+use crate::dashboard::{run_tabs, Dashboard};
+use crate::debug_files::{find_debug_files, upload_debug_files, UploadOutcome};
+use crate::editor::{copy_to_clipboard, open_in_editor, SourceLocation};
+use crate::error::{auth_error, exit_code, not_found, CliError, ExitCode, SentryError};
+use crate::event_log::EventLog;
+use crate::export;
+use crate::fixtures::FixtureMode;
+use crate::git;
+use crate::issue_store::{self, IssueSnapshot, IssueStore};
 use crate::issue_viewer::{Issue as ViewerIssue, IssueViewer};
-use crate::sentry::SentryClient;
-use anyhow::Result;
-use clap::{CommandFactory, Parser, Subcommand};
+use crate::ownership::{
+    fetch_suggestions, glob_match, matching_owner_rule, pick_assignee, resolve_assignee,
+    AssigneeSuggestion,
+};
+use crate::progress::Progress;
+use crate::project_viewer::ProjectViewer;
+use crate::report::{
+    check_resolved, compare_totals, find_duplicates, gh_annotation, issue_trends, junit_report,
+    project_slug, release_diff, sarif_report, snapshot_diff, stats_csv, summarize, summarize_usage,
+    CanaryThresholds, CategoryUsage, Digest, IssueReport, IssueSummary, IssueTrend, MetricDelta,
+    ProjectBreakdown, ReleaseDiff, ResolutionState, SnapshotDiff,
+};
+use crate::scopes::check_scopes;
+use crate::secrets::SecretsBackend;
+use crate::sentry::{
+    crash_frame, parse_stats_period, period_minutes, Activity, AffectedUser, ClientKey,
+    Event as SentryEvent, EventDetails, Frame, GroupingInfo, IgnoreCondition, InboundFilter, Issue,
+    IssueQuery, IssueRef, KeyRateLimit, OrgUrl, OrganizationDetails, ProfileFunction,
+    ProfiledTransaction, Project, RateLimitStatus, ReleaseRef, SentryClient, TeamMember,
+    TransactionPerformance, INBOUND_FILTERS, MAX_PAGE_SIZE,
+};
+use crate::table::{
+    format_count, link, select_columns, set_exact_counts, set_hyperlinks, supports_hyperlinks,
+    terminal_width, Column, Table,
+};
+use crate::timing;
+use crate::trace_viewer::TraceViewer;
+use crate::triage::Triage;
+use crate::tui::sparkline;
+use anyhow::{Context, Result};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{generate, Shell};
 use crossterm::{
     cursor::{self, Hide, Show},
@@ -12,7 +50,11 @@ use crossterm::{
     style::{Color, Print, SetForegroundColor},
     terminal::{self, Clear, ClearType},
 };
-use std::io::{self, Write};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -27,6 +69,76 @@ use std::io::{self, Write};
 pub struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Link IDs, projects and releases to Sentry
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = HyperlinkMode::Auto,
+        help = "Make issue IDs, project names and release versions clickable links to Sentry (auto: in terminals known to support OSC 8 hyperlinks)"
+    )]
+    hyperlinks: HyperlinkMode,
+    /// Refuse commands that change data in Sentry
+    #[arg(
+        long,
+        global = true,
+        help = "Refuse commands that change data in Sentry (assign, resolve, bookmark, uploads, releases); see also 'config read-only'"
+    )]
+    read_only: bool,
+    /// Account to use for its organization
+    #[arg(
+        long = "as",
+        global = true,
+        value_name = "ACCOUNT",
+        help = "Configured organization entry to use when several share an organization slug (e.g. a personal and a bot account)"
+    )]
+    account: Option<String>,
+    /// Print API calls that would change data instead of making them
+    #[arg(
+        long,
+        global = true,
+        help = "Print the API calls (method, URL and payload) that would change data in Sentry instead of making them"
+    )]
+    dry_run: bool,
+    /// Save API responses to a directory
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        conflicts_with = "replay",
+        help = "Save every API response to DIR as a JSON fixture, e.g. to attach to a bug report (responses can hold issue and user data, but never the token)"
+    )]
+    record: Option<PathBuf>,
+    /// Serve API responses from a directory
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Answer API calls with responses saved by --record instead of contacting Sentry"
+    )]
+    replay: Option<PathBuf>,
+    /// Skip confirmation prompts
+    #[arg(
+        short = 'y',
+        long,
+        global = true,
+        help = "Change data in Sentry without asking for confirmation first (needed when not run from a terminal)"
+    )]
+    yes: bool,
+    /// Print counts in full
+    #[arg(
+        long,
+        global = true,
+        help = "Show event and user counts in full instead of e.g. 1.2k or 3.4M"
+    )]
+    exact: bool,
+    /// Time API calls and renders
+    #[arg(
+        long,
+        global = true,
+        help = "Print how long each API call and render took, with totals, to stderr when the command ends"
+    )]
+    timing: bool,
 }
 
 #[derive(Subcommand, Debug, PartialEq)]
@@ -52,16 +164,183 @@ enum Commands {
         #[command(subcommand)]
         command: IssueCommands,
     },
+    /// Inspect Sentry events
+    #[command(about = "Inspect raw Sentry events", alias = "e")]
+    Events {
+        #[command(subcommand)]
+        command: EventCommands,
+    },
+    /// Inspect distributed traces
+    #[command(about = "Inspect distributed traces across services", alias = "t")]
+    Trace {
+        #[command(subcommand)]
+        command: TraceCommands,
+    },
+    /// Summarize transaction performance
+    #[command(about = "Report Apdex, failure rate and latency of transactions")]
+    Perf {
+        #[command(subcommand)]
+        command: PerfCommands,
+    },
+    /// Summarize profiling data
+    #[command(about = "Summarize profiling data to see where CPU time goes")]
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+    /// Upload debug information files
+    #[command(about = "Upload debug symbols (dSYM, ELF, PDB) for native and mobile projects")]
+    DebugFiles {
+        #[command(subcommand)]
+        command: DebugFileCommands,
+    },
+    /// Manage releases
+    #[command(about = "Manage Sentry releases", alias = "r")]
+    Release {
+        #[command(subcommand)]
+        command: ReleaseCommands,
+    },
+    /// Inspect stored credentials
+    #[command(about = "Inspect the authentication tokens of organizations")]
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommands,
+    },
+    /// Inspect the Sentry API itself
+    #[command(about = "Inspect Sentry API usage for an organization")]
+    Api {
+        #[command(subcommand)]
+        command: ApiCommands,
+    },
+    /// Generate organization reports
+    #[command(about = "Generate periodic reports for sharing")]
+    Report {
+        #[command(subcommand)]
+        command: ReportCommands,
+    },
+    /// Interactive first-run setup
+    #[command(
+        about = "Interactively add an organization, authenticate and pick a default project"
+    )]
+    Init,
     /// Login to a Sentry organization
     #[command(about = "Authenticate with a Sentry organization")]
     Login {
         /// Use browser-based OAuth login instead of token
         #[arg(long, help = "Use browser-based OAuth login flow")]
         browser: bool,
+        /// Role to store the token under
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = TokenRole::Read,
+            help = "Store as the read token used for listing, or the write token used by mutating commands. A single token serves both roles."
+        )]
+        role: TokenRole,
         /// Organization name (optional, will be detected automatically if not provided)
         #[arg(help = "Name of the organization to authenticate with")]
         org: Option<String>,
     },
+    /// Fail when too many issues match a query
+    #[command(
+        about = "Exit with code 10 when more issues than allowed match a query, for gating CI/CD pipelines"
+    )]
+    Check {
+        /// Organization and project in format: [org/]project
+        #[arg(help = "Project to check in format: [org/]project")]
+        target: String,
+        /// Sentry search the issues must match
+        #[arg(
+            long,
+            default_value = "is:unresolved",
+            help = "Sentry search to count issues with (e.g. 'is:unresolved firstSeen:-1h')"
+        )]
+        query: String,
+        #[command(flatten)]
+        age: AgeFilters,
+        /// Number of matching issues still considered passing
+        #[arg(
+            long,
+            default_value_t = 0,
+            help = "Maximum number of matching issues to allow"
+        )]
+        max: usize,
+        /// Output format for matching issues
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = IssueFormat::Table,
+            help = "Print matching issues as a table, GitHub Actions annotations, a JUnit XML report, a SARIF log or one ID per line"
+        )]
+        format: IssueFormat,
+    },
+    /// Print a compact issue count for status bars
+    #[command(
+        about = "Print a compact issue count like '⚠ 12' for tmux, polybar and other status bars"
+    )]
+    Badge {
+        /// Organization and project in format: [org/]project
+        #[arg(help = "Project to count issues of in format: [org/]project")]
+        target: String,
+        /// Sentry search the issues must match
+        #[arg(
+            long,
+            default_value = "is:unresolved",
+            help = "Sentry search to count issues with (e.g. 'is:unresolved level:error')"
+        )]
+        query: String,
+        /// Color markup for the status bar
+        #[arg(
+            long,
+            visible_alias = "format",
+            value_enum,
+            default_value_t = BadgeStyle::Plain,
+            help = "Color markup: plain, tmux (colored status line segment) or polybar"
+        )]
+        style: BadgeStyle,
+        /// Seconds to reuse the last count for
+        #[arg(
+            long,
+            default_value_t = 60,
+            help = "Seconds to reuse the last count before asking Sentry again (at least 15 for tmux)"
+        )]
+        cache_ttl: u64,
+    },
+    /// Print error and warning counts for shell prompts
+    #[command(
+        about = "Print unresolved error and warning counts like 'E:3 W:12' for PS1 or starship prompts"
+    )]
+    Prompt {
+        /// Organization and project in format: [org/]project
+        #[arg(help = "Project to count issues of in format: [org/]project")]
+        target: String,
+        /// Seconds after which counts are refreshed
+        #[arg(
+            long,
+            default_value_t = 60,
+            help = "Seconds after which cached counts are refreshed in the background"
+        )]
+        cache_ttl: u64,
+        /// Fetch and cache counts without printing them
+        #[arg(long, hide = true)]
+        refresh: bool,
+    },
+    /// Step through unresolved issues one at a time
+    #[command(
+        about = "Step through unresolved issues one at a time with single-key resolve, ignore, assign, skip and open actions"
+    )]
+    Triage {
+        /// Organization and project in format: [org/]project
+        #[arg(help = "Project to triage in format: [org/]project")]
+        target: String,
+        /// Sentry search selecting the issues to triage
+        #[arg(
+            long,
+            default_value = "is:unresolved",
+            help = "Sentry search selecting the issues to triage (e.g. 'is:unresolved is:unassigned')"
+        )]
+        query: String,
+    },
     /// Monitor issues in real-time
     #[command(
         about = "Start a real-time dashboard for monitoring Sentry issues",
@@ -70,9 +349,80 @@ enum Commands {
     Monitor {
         /// Organization and project in format: [org/]project
         #[arg(
-            help = "Project to monitor in format: [org/]project (e.g. 'my-org/my-project' or just 'my-project')"
+            help = "Project to monitor in format: [org/]project (e.g. 'my-org/my-project' or just 'my-project'). Defaults to the last monitored target"
         )]
-        target: String,
+        target: Option<String>,
+        /// Organization to monitor with --all
+        #[arg(long, requires = "all", help = "Organization to monitor with --all")]
+        org: Option<String>,
+        /// Monitor every project of the organization
+        #[arg(
+            long,
+            conflicts_with = "target",
+            help = "Merge unresolved issues of all projects in the organization into one dashboard"
+        )]
+        all: bool,
+        /// Projects to monitor in their own tabs
+        #[arg(
+            long = "tab",
+            conflicts_with_all = ["target", "all", "resume"],
+            help = "Open a tab for a [org/]project pattern, where * merges the matching projects (e.g. 'acme/*' for a whole organization); a pattern matching several organizations opens a tab for each (repeatable)"
+        )]
+        tabs: Vec<String>,
+        /// One tab per organization
+        #[arg(
+            long,
+            conflicts_with_all = ["target", "all", "resume", "tabs"],
+            help = "Open a tab for every configured organization with all of its projects"
+        )]
+        all_orgs: bool,
+        /// Monitor the most recent target again
+        #[arg(
+            long,
+            conflicts_with_all = ["target", "all"],
+            help = "Monitor the most recently monitored project(s) again (the default without a target)"
+        )]
+        resume: bool,
+        /// Sentry search to poll instead of unresolved issues
+        #[arg(
+            long,
+            help = "Sentry search to poll instead of 'is:unresolved' (e.g. 'is:unresolved assigned:me')"
+        )]
+        query: Option<String>,
+        /// Only show issues of these levels
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_parser = LEVELS.to_vec(),
+            help = "Only show issues of these levels, comma-separated (fatal, error, warning, info, debug)"
+        )]
+        level: Vec<String>,
+        /// Alert when an issue grows faster than a threshold
+        #[arg(
+            long = "alert",
+            value_parser = alert_arg,
+            help = "Highlight issues and ring the bell when they cross a threshold, e.g. 'count>100 in 10m' or 'users>10 in 1h' (repeatable)"
+        )]
+        alerts: Vec<AlertRule>,
+        /// Send desktop notifications for regressions
+        #[arg(
+            long,
+            help = "Send a desktop notification when a resolved issue regresses or crosses an alert threshold"
+        )]
+        notify: bool,
+        /// Write change events to a file or named pipe
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Append issue_new, issue_spike (crossing an --alert) and issue_regressed events as JSON lines to a file or named pipe (which waits for a reader)"
+        )]
+        emit: Option<PathBuf>,
+    },
+    /// Manage local settings
+    #[command(about = "View and change local sex-cli settings")]
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
     },
     /// Generate shell completions
     #[command(about = "Generate shell completion scripts")]
@@ -83,6 +433,108 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand, Debug, PartialEq)]
+enum AuthCommands {
+    /// Show the scopes of an organization's token
+    #[command(
+        about = "Show the scopes of an organization's token and warn about missing or overly broad ones"
+    )]
+    Scopes {
+        /// Organization name
+        #[arg(help = "Name of the organization")]
+        org: String,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum ApiCommands {
+    /// Show rate limit status
+    #[command(
+        about = "Make a cheap request and show the rate limit, remaining requests and reset time"
+    )]
+    Limits {
+        /// Organization name
+        #[arg(help = "Name of the organization")]
+        org: String,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum ConfigCommands {
+    /// Show or set the source root
+    #[command(
+        about = "Show or set the local checkout that stack frames are resolved against when opening files"
+    )]
+    SourceRoot {
+        /// New source root
+        #[arg(help = "Path to the local checkout; omit to show the current value")]
+        path: Option<PathBuf>,
+    },
+    /// Show or set read-only mode
+    #[command(
+        about = "Show or set whether commands that change data in Sentry are refused by default"
+    )]
+    ReadOnly {
+        /// New setting
+        #[arg(
+            value_parser = clap::builder::BoolishValueParser::new(),
+            help = "on or off; omit to show the current setting"
+        )]
+        enabled: Option<bool>,
+    },
+    /// Encrypt the config file
+    #[command(
+        about = "Encrypt the whole config file at rest with the stored encryption key, so organization names and slugs are not kept in cleartext"
+    )]
+    Encrypt,
+    /// Decrypt the config file
+    #[command(about = "Store the config file in cleartext again")]
+    Decrypt,
+    /// Manage where tokens are stored
+    #[command(about = "Show or change where tokens and encryption keys are stored")]
+    Secrets {
+        #[command(subcommand)]
+        command: SecretsCommands,
+    },
+    /// Import settings from another tool
+    #[command(
+        about = "Bootstrap organization, server URL, token and default project from another tool's settings"
+    )]
+    Import {
+        /// Tool to import from
+        #[arg(value_enum, help = "Tool to import settings from")]
+        source: ImportSource,
+        /// Settings file to read
+        #[arg(
+            long,
+            help = "Settings file to read instead of ~/.sentryclirc; SENTRY_* variables still apply"
+        )]
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum ImportSource {
+    /// The official sentry-cli: ~/.sentryclirc and SENTRY_AUTH_TOKEN, SENTRY_URL, SENTRY_ORG, SENTRY_PROJECT
+    SentryCli,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum SecretsCommands {
+    /// Show the secrets backend
+    #[command(about = "Show the configured secrets backend")]
+    Backend,
+    /// Move secrets to another backend
+    #[command(
+        about = "Move tokens and keys to another backend (keyring, file or env) and switch to it"
+    )]
+    Migrate {
+        /// Backend to move to
+        #[arg(long, value_enum, help = "Backend to move secrets to")]
+        to: SecretsBackend,
+    },
+}
+
 #[derive(Subcommand, Debug, PartialEq)]
 enum OrgCommands {
     /// List configured organizations
@@ -92,13 +544,54 @@ enum OrgCommands {
     #[command(about = "Add a new Sentry organization to the configuration")]
     Add {
         /// Organization name (used for local reference)
-        #[arg(help = "Name to identify the organization locally")]
-        name: String,
+        #[arg(
+            required_unless_present = "from_url",
+            help = "Name to identify the organization locally (defaults to the slug with --from-url)"
+        )]
+        name: Option<String>,
         /// Organization slug (from Sentry URL)
         #[arg(
+            required_unless_present = "from_url",
+            conflicts_with = "from_url",
             help = "Organization slug from Sentry URL (e.g., 'my-org' from sentry.io/organizations/my-org/)"
         )]
-        slug: String,
+        slug: Option<String>,
+        /// Sentry web URL to take the slug and server from
+        #[arg(
+            long,
+            help = "Any Sentry page URL of the organization (e.g. https://sentry.io/organizations/my-org/issues/); self-hosted URLs also set the server, which all organizations share"
+        )]
+        from_url: Option<String>,
+        /// Skip checking the slug against the API
+        #[arg(
+            long,
+            help = "Do not check that the slug exists, even when a token of another organization is available"
+        )]
+        no_verify: bool,
+    },
+    /// Show event usage against quotas
+    #[command(
+        about = "Show event usage per category, with warnings as quotas approach exhaustion"
+    )]
+    Usage {
+        /// Organization name
+        #[arg(help = "Name of the organization")]
+        name: String,
+        /// Period to total, typically the billing period
+        #[arg(
+            long,
+            default_value = "30d",
+            value_parser = period_arg,
+            help = "Period to total usage over, typically the billing period (e.g. 30d)"
+        )]
+        period: String,
+        /// Quotas to compare usage against
+        #[arg(
+            long = "quota",
+            value_parser = quota_arg,
+            help = "Quota for a category as category=count (e.g. error=50000); repeatable"
+        )]
+        quotas: Vec<(String, u64)>,
     },
     /// List organization projects
     #[command(about = "List all projects in an organization")]
@@ -107,13 +600,32 @@ enum OrgCommands {
         #[arg(help = "Name of the organization")]
         name: String,
     },
+    /// Show organization settings
+    #[command(
+        about = "Show an organization's settings: default role, open membership, 2FA requirement, trusted relays and features"
+    )]
+    Info {
+        /// Organization name
+        #[arg(help = "Name of the organization")]
+        name: String,
+    },
 }
 
 #[derive(Subcommand, Debug, PartialEq)]
 enum ProjectCommands {
     /// List all projects across organizations
     #[command(about = "List all projects from all authenticated organizations")]
-    List,
+    List {
+        /// Columns to display
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Comma-separated columns to display, in order (access, name, platform, slug, id, status, bookmarked, member, teams)"
+        )]
+        columns: Vec<String>,
+        #[command(flatten)]
+        filters: ProjectFilters,
+    },
     /// Show project information
     #[command(about = "Show detailed project information including stats")]
     Info {
@@ -122,494 +634,7484 @@ enum ProjectCommands {
             help = "Project to show in format: [org/]project (e.g. 'my-org/my-project' or just 'my-project')"
         )]
         target: String,
+        /// Show project information in an interactive viewer
+        #[arg(
+            long,
+            short,
+            help = "Open an interactive viewer with event charts, top issues, releases and team members"
+        )]
+        interactive: bool,
+        /// Include the project's public DSNs
+        #[arg(long, help = "Include the project's public DSNs in the output")]
+        show_dsn: bool,
+    },
+    /// Export project event volume
+    #[command(about = "Export a project's received event counts over time")]
+    Stats {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project in format: [org/]project")]
+        target: String,
+        /// Period to cover
+        #[arg(
+            long,
+            default_value = "30d",
+            value_parser = period_arg,
+            help = "Period to cover (e.g. 24h, 7d, 30d); hourly buckets up to 7d, daily beyond"
+        )]
+        period: String,
+        /// Export format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Csv, help = "Export format")]
+        export: ExportFormat,
+    },
+    /// Compare a project against the previous period
+    #[command(
+        about = "Compare events, issues and affected users with the previous period of the same length"
+    )]
+    Compare {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project in format: [org/]project")]
+        target: String,
+        /// Length of each period
+        #[arg(
+            long,
+            default_value = "7d",
+            value_parser = period_arg,
+            help = "Length of each period (e.g. 24h, 7d, 2w)"
+        )]
+        period: String,
+    },
+    /// Show issue ownership rules
+    #[command(
+        about = "Show a project's issue ownership rules and which of them matches an issue's culprit"
+    )]
+    Owners {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project in format: [org/]project")]
+        target: String,
+        /// Issue to find the owners of
+        #[arg(
+            long,
+            help = "Issue (numeric ID, short ID or URL) whose culprit to match against the rules"
+        )]
+        issue: Option<String>,
+    },
+    /// Bookmark a project
+    #[command(about = "Bookmark a project in Sentry")]
+    Bookmark {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to bookmark in format: [org/]project")]
+        target: String,
+    },
+    /// Remove a project bookmark
+    #[command(about = "Remove a project bookmark in Sentry")]
+    Unbookmark {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to unbookmark in format: [org/]project")]
+        target: String,
+    },
+    /// Manage client keys (DSNs)
+    #[command(about = "List a project's client keys and manage their rate limits")]
+    Keys {
+        #[command(subcommand)]
+        command: KeyCommands,
+    },
+    /// Show or toggle inbound data filters
+    #[command(
+        about = "Show a project's inbound data filters and spike protection, or turn them on and off"
+    )]
+    Filters {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project in format: [org/]project")]
+        target: String,
+        /// Filters to turn on
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_parser = INBOUND_FILTERS.to_vec(),
+            help = "Filters to turn on, comma-separated (browser-extensions, legacy-browsers, localhost, web-crawlers, filtered-transaction)"
+        )]
+        enable: Vec<String>,
+        /// Filters to turn off
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_parser = INBOUND_FILTERS.to_vec(),
+            help = "Filters to turn off, comma-separated"
+        )]
+        disable: Vec<String>,
+        /// Turn spike protection on or off
+        #[arg(
+            long,
+            value_parser = clap::builder::BoolishValueParser::new(),
+            help = "Turn spike protection on or off"
+        )]
+        spike_protection: Option<bool>,
     },
 }
 
 #[derive(Subcommand, Debug, PartialEq)]
-enum IssueCommands {
-    /// List recent issues
-    #[command(about = "List recent unresolved issues from all authenticated organizations")]
-    List,
-    /// View detailed issue information
-    #[command(about = "View detailed information about a specific issue in an interactive viewer")]
-    View {
-        /// Issue ID
-        #[arg(help = "Issue ID from Sentry (found in issue URL or list command)")]
-        id: String,
+enum KeyCommands {
+    /// List client keys
+    #[command(about = "List a project's client keys with their rate limits and DSNs")]
+    List {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project in format: [org/]project")]
+        target: String,
+    },
+    /// View or set a client key's rate limit
+    #[command(
+        about = "Show a client key's rate limit, or set it to stop a runaway client from using up the quota"
+    )]
+    RateLimit {
+        /// Key ID or name
+        #[arg(help = "Client key ID or name (see 'project keys list')")]
+        key: String,
+        /// Organization and project in format: [org/]project
+        #[arg(long, help = "Project of the key in format: [org/]project")]
+        project: String,
+        /// Events allowed per window
+        #[arg(
+            long,
+            requires = "window",
+            help = "Most events the key may send per window"
+        )]
+        count: Option<u64>,
+        /// Window length in seconds
+        #[arg(long, requires = "count", help = "Window length in seconds (e.g. 60)")]
+        window: Option<u64>,
+        /// Remove the rate limit
+        #[arg(long, conflicts_with = "count", help = "Remove the key's rate limit")]
+        remove: bool,
     },
 }
 
-impl Cli {
-    pub fn run() -> Result<()> {
-        let cli = Self::parse();
-        let mut config = Config::load()?;
-        let mut client = SentryClient::new()?;
-
-        match cli.command {
-            Commands::Login { browser, org } => {
-                if browser {
-                    let sentry_org = client.login_with_browser()?;
-                    let org_name = org.unwrap_or_else(|| sentry_org.slug.clone());
-                    // Add organization if it doesn't exist
-                    if !config.organizations.contains_key(&org_name) {
-                        config.add_organization(org_name.clone(), sentry_org.slug);
-                        println!("Added new organization: {}", org_name);
-                    }
-
-                    let org_entry = config.get_organization_mut(&org_name).unwrap();
-                    if let Some(token) = client.get_current_token() {
-                        org_entry.set_auth_token(token)?;
-                        config.save()?;
-                        println!(
-                            "Successfully logged in to Sentry for organization: {}",
-                            org_name
-                        );
-                    }
-                } else {
-                    let org = org.ok_or_else(|| {
-                        anyhow::anyhow!("Organization name is required for token-based login")
-                    })?;
-                    let org_entry = config.get_organization_mut(&org).ok_or_else(|| {
-                        anyhow::anyhow!(
-                            "Organization '{}' not found. Add it first with 'org add'.",
-                            org
-                        )
-                    })?;
-
-                    client.login_with_prompt()?;
-                    if let Some(token) = client.get_current_token() {
-                        org_entry.set_auth_token(token)?;
-                        config.save()?;
-                        println!("Successfully logged in to Sentry for organization: {}", org);
-                    }
-                }
+#[derive(Subcommand, Debug, PartialEq)]
+enum IssueCommands {
+    /// List recent issues
+    #[command(about = "List recent issues from all authenticated organizations")]
+    List {
+        /// Projects to list issues for
+        #[arg(
+            help = "Projects in format [org/]project, where * and ? match any characters (e.g. 'my-org/api-*'). Lists all organizations when omitted."
+        )]
+        target: Option<String>,
+        /// Columns to display
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Comma-separated columns to display, in order (id, title, status, level, project, culprit, events, users, last_seen, url, events_24h, events_14d)"
+        )]
+        columns: Vec<String>,
+        /// Make issue IDs clickable
+        #[arg(
+            long,
+            help = "Make issue IDs clickable links to Sentry even where OSC 8 hyperlink support is not detected (same as --hyperlinks always)"
+        )]
+        links: bool,
+        /// Group issues under per-group headers
+        #[arg(
+            long,
+            value_enum,
+            help = "Group issues by project, level or culprit module, with totals per group"
+        )]
+        group_by: Option<GroupBy>,
+        /// Output format
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = IssueFormat::Table,
+            help = "Print a table, GitHub Actions annotations pointing at each issue's crash frame, a JUnit XML report, a SARIF log or one issue ID per line for piping"
+        )]
+        format: IssueFormat,
+        /// Period the event counts cover
+        #[arg(
+            long,
+            value_parser = clap::builder::PossibleValuesParser::new(STATS_PERIODS),
+            help = "Period of the event counts, adds an events column for it (24h or 14d, the periods the issues API supports; default 14d)"
+        )]
+        stats_period: Option<String>,
+        /// Only show issues with this status
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = StatusFilter::Unresolved,
+            help = "Only show issues with this status"
+        )]
+        status: StatusFilter,
+        /// Only show issues nobody is assigned to
+        #[arg(long, help = "Only show issues nobody is assigned to")]
+        unassigned: bool,
+        /// Only show issues assigned to a user or team
+        #[arg(
+            long,
+            conflicts_with = "unassigned",
+            help = "Only show issues assigned to 'me', a user email or a '#team'"
+        )]
+        assigned: Option<String>,
+        /// Only show issues introduced by the newest release
+        #[arg(
+            long,
+            help = "Only show issues first seen in each project's newest release"
+        )]
+        latest_release: bool,
+        /// Only show issues of these levels
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_parser = LEVELS.to_vec(),
+            help = "Only show issues of these levels, comma-separated (fatal, error, warning, info, debug)"
+        )]
+        level: Vec<String>,
+        #[command(flatten)]
+        age: AgeFilters,
+        /// List issues of a team's projects
+        #[arg(
+            long,
+            conflicts_with = "target",
+            help = "List issues of all projects a team owns, in format [org/]team"
+        )]
+        team: Option<String>,
+    },
+    /// Store unresolved issues locally for offline search
+    #[command(
+        about = "Store unresolved issues of every organization, or one, locally for 'issue grep'"
+    )]
+    Sync {
+        /// Organization to sync
+        #[arg(long, help = "Only sync this organization")]
+        org: Option<String>,
+    },
+    /// Search synced issues offline
+    #[command(
+        about = "Search titles, culprits and messages of issues stored by 'issue sync', without any API requests"
+    )]
+    Grep {
+        /// Text to search for
+        #[arg(help = "Words that must all appear in an issue's title, culprit or message")]
+        text: String,
+        /// Organization to search
+        #[arg(long, help = "Only search issues of this organization")]
+        org: Option<String>,
+    },
+    /// Find near-duplicate issues across projects
+    #[command(
+        about = "Flag clusters of unresolved issues with near-identical titles and culprits, e.g. a shared library bug raised in every service"
+    )]
+    Dedupe {
+        /// Organization name
+        #[arg(help = "Name of the organization")]
+        org: String,
+        /// Minimum similarity
+        #[arg(
+            long,
+            default_value = "90",
+            value_parser = percent_arg,
+            help = "Minimum similarity in percent of normalized titles and culprits (e.g. 85)"
+        )]
+        similarity: f64,
+    },
+    /// Summarize issues across an organization
+    #[command(about = "Summarize unresolved issues across all projects of an organization")]
+    Summary {
+        /// Organization name
+        #[arg(help = "Name of the organization")]
+        org: String,
+        /// Period to summarize
+        #[arg(
+            long,
+            default_value = "7d",
+            value_parser = period_arg,
+            help = "Period to summarize (e.g. 24h, 7d, 2w)"
+        )]
+        period: String,
+    },
+    /// Show fastest growing issues
+    #[command(
+        about = "List issues whose recent event rate is growing fastest compared to their baseline"
+    )]
+    Trends {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to analyze in format: [org/]project")]
+        target: String,
+        /// Histogram to analyze
+        #[arg(
+            long,
+            default_value = "14d",
+            value_parser = ["24h", "14d"],
+            help = "Histogram to analyze: 24h compares the last 3 hours, 14d compares the last day"
+        )]
+        period: String,
+        /// Maximum number of issues to show
+        #[arg(long, default_value_t = 20, help = "Maximum number of issues to show")]
+        limit: usize,
+    },
+    /// Export issues to a file for analysis
+    #[command(
+        about = "Write all matching issues of a project with their event histograms to a SQLite or Parquet file"
+    )]
+    Export {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to export in format: [org/]project")]
+        target: String,
+        /// File format
+        #[arg(long, value_enum, help = "File format")]
+        format: IssueExportFormat,
+        /// File to write
+        #[arg(
+            long,
+            help = "File to write, e.g. errors.db; SQLite files are added to, Parquet files replaced"
+        )]
+        output: PathBuf,
+        /// Sentry search selecting the issues
+        #[arg(
+            long,
+            default_value = "is:unresolved",
+            help = "Sentry search selecting the issues (e.g. 'is:unresolved level:error')"
+        )]
+        query: String,
+        /// Histogram to include
+        #[arg(
+            long,
+            default_value = "14d",
+            value_parser = ["24h", "14d"],
+            help = "Event histogram to include: hourly buckets for 24h or daily buckets for 14d"
+        )]
+        period: String,
+    },
+    /// Save the issues of a project to compare later
+    #[command(
+        about = "Save the issues of a project to a file, e.g. before a deploy, to compare with 'issue diff' later"
+    )]
+    Snapshot {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to snapshot in format: [org/]project")]
+        target: String,
+        /// File to write
+        #[arg(long, help = "File to write the snapshot to, e.g. snap.json")]
+        output: PathBuf,
+        /// Sentry search selecting the issues
+        #[arg(
+            long,
+            default_value = "is:unresolved",
+            help = "Sentry search selecting the issues (e.g. 'is:unresolved level:error')"
+        )]
+        query: String,
+    },
+    /// Compare the issues of a project with a snapshot
+    #[command(
+        about = "Show which issues are new, gone (e.g. resolved) or changed in events, users, status or assignee since a snapshot"
+    )]
+    Diff {
+        /// Snapshot file
+        #[arg(help = "Snapshot written by 'issue snapshot'")]
+        snapshot: PathBuf,
+    },
+    /// View detailed issue information
+    #[command(about = "View detailed information about a specific issue in an interactive viewer")]
+    View {
+        /// Issue ID, short ID or URL
+        #[arg(help = "Issue ID, short ID (e.g. BACKEND-4X3) or issue URL copied from Sentry")]
+        id: String,
+        /// Local checkout to resolve frames against
+        #[arg(
+            long,
+            help = "Path to the local checkout, enables 'o' to open the crashing file (defaults to the configured source root)"
+        )]
+        repo: Option<PathBuf>,
+        /// Print the issue instead of opening the viewer
+        #[arg(
+            long,
+            conflicts_with = "repo",
+            help = "Print metadata, tags, the latest stack trace and suspect commits as plain Markdown instead of opening the viewer"
+        )]
+        plain: bool,
+    },
+    /// Explain how an issue's events were grouped
+    #[command(
+        about = "Show an issue's fingerprint hashes, grouping config and metadata, to debug why errors did or didn't group together"
+    )]
+    Grouping {
+        /// Issue ID, short ID or URL
+        #[arg(help = "Issue ID, short ID (e.g. BACKEND-4X3) or issue URL copied from Sentry")]
+        id: String,
+    },
+    /// List the users an issue affected
+    #[command(about = "List the users an issue affected most, from its user tag")]
+    Users {
+        /// Issue ID, short ID or URL
+        #[arg(help = "Issue ID, short ID (e.g. BACKEND-4X3) or issue URL copied from Sentry")]
+        id: String,
+        /// Maximum number of users to show
+        #[arg(long, default_value_t = 10, help = "Maximum number of users to show")]
+        limit: usize,
+    },
+    /// Show the latest event of an issue
+    #[command(about = "Show the latest event of an issue with its stack traces, or its raw JSON")]
+    Event {
+        /// Issue ID, short ID or URL
+        #[arg(help = "Issue ID, short ID (e.g. BACKEND-4X3) or issue URL copied from Sentry")]
+        id: String,
+        /// Print the raw event
+        #[arg(
+            long,
+            help = "Print the event as pretty-printed JSON, as returned by Sentry"
+        )]
+        json: bool,
+        /// Local checkout to blame in-app frames against
+        #[arg(
+            long,
+            conflicts_with = "json",
+            help = "Path to a local checkout; in-app frames are annotated with git blame"
+        )]
+        repo: Option<PathBuf>,
+    },
+    /// View the newest issue of a project
+    #[command(
+        about = "Open the most recently created issue of a project in the interactive viewer"
+    )]
+    Latest {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to look in, in format: [org/]project")]
+        target: String,
+        /// Sentry search the issue must match
+        #[arg(
+            long,
+            default_value = "is:unresolved",
+            help = "Sentry search the issue must match (e.g. 'is:unresolved level:fatal')"
+        )]
+        query: String,
+        /// Local checkout to resolve frames against
+        #[arg(
+            long,
+            help = "Path to the local checkout, enables 'o' to open the crashing file (defaults to the configured source root)"
+        )]
+        repo: Option<PathBuf>,
+    },
+    /// Generate an incident report
+    #[command(
+        about = "Generate a shareable incident report with timeline, tags, stack trace and suspect commits"
+    )]
+    Report {
+        /// Issue ID, short ID or URL
+        #[arg(help = "Issue ID, short ID (e.g. BACKEND-4X3) or issue URL copied from Sentry")]
+        id: String,
+        /// Report format
+        #[arg(long, value_enum, default_value_t = ReportFormat::Markdown, help = "Report format")]
+        format: ReportFormat,
+    },
+    /// Print the web link of an issue
+    #[command(
+        about = "Print the Sentry web link of an issue, optionally copying it to the clipboard"
+    )]
+    Url {
+        /// Issue ID, short ID or URL
+        #[arg(help = "Issue ID, short ID (e.g. BACKEND-4X3) or issue URL copied from Sentry")]
+        id: String,
+        /// Copy the link to the clipboard
+        #[arg(long, help = "Also copy the link to the clipboard")]
+        copy: bool,
+    },
+    /// Share an issue publicly
+    #[command(
+        about = "Make an issue viewable by anyone with its link, e.g. a vendor without Sentry access, and print the public link"
+    )]
+    Share {
+        /// Issue IDs, short IDs or URLs, or `-` to read them from stdin
+        #[arg(
+            required = true,
+            help = "Issue IDs, short IDs (e.g. BACKEND-4X3) or issue URLs; '-' reads one per line from stdin (pass --yes)"
+        )]
+        ids: Vec<String>,
+        /// Copy the link to the clipboard
+        #[arg(long, help = "Also copy the public links to the clipboard")]
+        copy: bool,
+    },
+    /// Stop sharing an issue publicly
+    #[command(about = "Stop sharing an issue, so its public link no longer works")]
+    Unshare {
+        /// Issue IDs, short IDs or URLs, or `-` to read them from stdin
+        #[arg(
+            required = true,
+            help = "Issue IDs, short IDs (e.g. BACKEND-4X3) or issue URLs; '-' reads one per line from stdin (pass --yes)"
+        )]
+        ids: Vec<String>,
+    },
+    /// Open the crashing file in $EDITOR
+    #[command(about = "Open the file and line where an issue's latest event crashed in $EDITOR")]
+    OpenFile {
+        /// Issue ID, short ID or URL
+        #[arg(help = "Issue ID, short ID (e.g. BACKEND-4X3) or issue URL copied from Sentry")]
+        id: String,
+        /// Local checkout to resolve frames against
+        #[arg(
+            long,
+            help = "Path to the local checkout (defaults to the configured source root, then the current directory)"
+        )]
+        repo: Option<PathBuf>,
+    },
+    /// Follow changes to a single issue
+    #[command(
+        about = "Poll an issue and print a line whenever its events, users, status or assignee change"
+    )]
+    Watch {
+        /// Issue ID, short ID or URL
+        #[arg(help = "Issue ID, short ID (e.g. BACKEND-4X3) or issue URL copied from Sentry")]
+        id: String,
+        /// Seconds between polls
+        #[arg(
+            long,
+            default_value_t = 30,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Seconds between polls"
+        )]
+        interval: u64,
+    },
+    /// Assign an issue
+    #[command(
+        about = "Assign an issue to a user or team, optionally picking from owners and suspect committers"
+    )]
+    Assign {
+        /// Issue IDs, short IDs or URLs, or `-` to read them from stdin, then the assignee
+        #[arg(
+            required = true,
+            value_name = "ID... ASSIGNEE",
+            help = "Issue IDs, short IDs (e.g. BACKEND-4X3) or issue URLs ('-' reads one per line from stdin, pass --yes), then 'me', a user email or a '#team' unless --suggest"
+        )]
+        args: Vec<String>,
+        /// Pick from suggested assignees
+        #[arg(
+            long,
+            help = "Suggest assignees from ownership rules and suspect commits and pick one"
+        )]
+        suggest: bool,
+    },
+    /// Resolve an issue
+    #[command(
+        about = "Resolve an issue now, in the next release or in a given release; follow up with 'release check-resolved'"
+    )]
+    Resolve {
+        /// Issue IDs, short IDs or URLs, or `-` to read them from stdin
+        #[arg(
+            required = true,
+            help = "Issue IDs, short IDs (e.g. BACKEND-4X3) or issue URLs; '-' reads one per line from stdin (pass --yes)"
+        )]
+        ids: Vec<String>,
+        /// Resolve once the next release is out
+        #[arg(
+            long,
+            conflicts_with = "in_release",
+            help = "Resolve in the next release, so events of the current one don't reopen it"
+        )]
+        next_release: bool,
+        /// Resolve in a given release
+        #[arg(long, help = "Resolve in a release (e.g. 'my-app@1.2.0')")]
+        in_release: Option<String>,
+    },
+    /// Ignore an issue, optionally until a condition is met
+    #[command(
+        about = "Ignore an issue for good, for a while, or until it sees more events or users"
+    )]
+    Ignore {
+        /// Issue IDs, short IDs or URLs, or `-` to read them from stdin
+        #[arg(
+            required = true,
+            help = "Issue IDs, short IDs (e.g. BACKEND-4X3) or issue URLs; '-' reads one per line from stdin (pass --yes)"
+        )]
+        ids: Vec<String>,
+        /// Ignore for a period
+        #[arg(long, value_parser = period_arg, help = "Ignore for a period (e.g. 30m, 24h, 2w)")]
+        until: Option<String>,
+        /// Ignore until the issue sees this many more events
+        #[arg(long, help = "Ignore until the issue sees this many more events")]
+        until_count: Option<u64>,
+        /// Ignore until this many more users run into the issue
+        #[arg(long, help = "Ignore until this many more users run into the issue")]
+        until_users: Option<u64>,
+        /// Period the event and user counts must be reached within
+        #[arg(
+            long,
+            value_parser = period_arg,
+            help = "Only count events or users within a period (e.g. 1h)"
+        )]
+        window: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum EventCommands {
+    /// Stream new events as they arrive
+    #[command(about = "Print new events of a project as they arrive, like tail -f for errors")]
+    Tail {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to follow in format: [org/]project")]
+        target: String,
+        /// Sentry search query
+        #[arg(
+            long,
+            help = "Only show events matching a Sentry search query (e.g. 'browser:Chrome')"
+        )]
+        query: Option<String>,
+        /// Only show events of this level
+        #[arg(long, value_parser = LEVELS.to_vec(), help = "Only show events of a level")]
+        level: Option<String>,
+        /// Seconds between polls
+        #[arg(
+            long,
+            default_value_t = 5,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Seconds between polls"
+        )]
+        interval: u64,
+    },
+    /// Search individual events
+    #[command(about = "Search individual events of a project, e.g. to find one user's errors")]
+    Search {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to search in format: [org/]project")]
+        target: String,
+        /// Sentry search query
+        #[arg(
+            long,
+            help = "Sentry search query (e.g. 'user.email:jane@example.com' or 'user.id:42')"
+        )]
+        query: String,
+        /// Maximum number of events to show
+        #[arg(long, default_value_t = 50, help = "Maximum number of events to show")]
+        limit: usize,
+        /// Columns to display
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Comma-separated columns to display, in order (time, level, title, user, os, device, runtime, browser, link)"
+        )]
+        columns: Vec<String>,
+    },
+    /// Show an event with its stack trace
+    #[command(about = "Show an event's exceptions and stack traces")]
+    View {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project of the event in format: [org/]project")]
+        target: String,
+        /// Event ID
+        #[arg(help = "Event ID")]
+        event_id: String,
+        /// Local checkout to blame in-app frames against
+        #[arg(
+            long,
+            help = "Path to a local checkout; in-app frames are annotated with git blame"
+        )]
+        repo: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum TraceCommands {
+    /// View a trace as a waterfall
+    #[command(
+        about = "Show the transactions of a trace as a tree and waterfall in an interactive viewer"
+    )]
+    View {
+        /// Trace ID
+        #[arg(help = "Trace ID, as found in an event's trace context")]
+        trace_id: String,
+        /// Organization name
+        #[arg(
+            long,
+            help = "Organization the trace belongs to (defaults to searching all authenticated organizations)"
+        )]
+        org: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum ProfileCommands {
+    /// Show profiled transactions and slowest functions
+    #[command(
+        about = "List the most profiled transactions and the functions using the most CPU time"
+    )]
+    List {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to summarize in format: [org/]project")]
+        target: String,
+        /// Period to summarize
+        #[arg(
+            long,
+            default_value = "24h",
+            value_parser = period_arg,
+            help = "Period to summarize (e.g. 24h, 7d, 2w)"
+        )]
+        period: String,
+        /// Number of rows per table
+        #[arg(
+            long,
+            default_value_t = 10,
+            help = "Number of transactions and functions to show"
+        )]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum PerfCommands {
+    /// Show Apdex, failure rate and p95 latency
+    #[command(
+        about = "Show Apdex, failure rate and p95 latency of a project and its busiest transactions"
+    )]
+    Summary {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to summarize in format: [org/]project")]
+        target: String,
+        /// Period to summarize
+        #[arg(
+            long,
+            default_value = "24h",
+            value_parser = period_arg,
+            help = "Period to summarize (e.g. 24h, 7d, 2w)"
+        )]
+        period: String,
+        /// Number of transactions to show
+        #[arg(long, default_value_t = 10, help = "Number of transactions to show")]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum DebugFileCommands {
+    /// Upload debug files
+    #[command(
+        about = "Upload dSYM bundles, ELF binaries and PDB files; files Sentry already has are skipped"
+    )]
+    Upload {
+        /// Project identifier in format: [org/]project
+        #[arg(help = "Project to upload to in format: [org/]project")]
+        target: String,
+        /// File or directory to scan
+        #[arg(help = "Debug file or directory to scan for debug files (e.g. 'build/App.dSYM')")]
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum ReleaseCommands {
+    /// Associate commits with a release
+    #[command(about = "Associate commits with a release so Sentry can suggest suspect commits")]
+    SetCommits {
+        /// Release version
+        #[arg(help = "Release version (e.g. 'my-app@1.2.0')")]
+        version: String,
+        /// Organization name
+        #[arg(
+            long,
+            help = "Organization the release belongs to (defaults to the only configured organization)"
+        )]
+        org: Option<String>,
+        /// Use the local git checkout
+        #[arg(
+            long,
+            conflicts_with = "commit",
+            required_unless_present = "commit",
+            help = "Use HEAD of the local git checkout and its origin remote as the repository"
+        )]
+        auto: bool,
+        /// Explicit commit ranges
+        #[arg(
+            long,
+            value_parser = release_ref_arg,
+            help = "Commit range as repo@from..to or repo@to (can be repeated)"
+        )]
+        commit: Vec<ReleaseRef>,
+    },
+    /// Compare the issues of two releases
+    #[command(
+        about = "List issues first seen in a release but not the one before it, and issues resolved in between"
+    )]
+    Diff {
+        /// Older release version
+        #[arg(help = "Release to compare against (e.g. 'my-app@1.1.0')")]
+        from: String,
+        /// Newer release version
+        #[arg(help = "Release to compare (e.g. 'my-app@1.2.0')")]
+        to: String,
+        /// Organization and project in format: [org/]project
+        #[arg(long, help = "Project to compare in format: [org/]project")]
+        project: String,
+    },
+    /// Follow up on issues resolved in a release
+    #[command(
+        about = "List issues resolved in a release or pending the next one, and those that regressed after it shipped"
+    )]
+    CheckResolved {
+        /// Release version
+        #[arg(help = "Release version (e.g. 'my-app@1.2.0')")]
+        version: String,
+        /// Organization and project in format: [org/]project
+        #[arg(long, help = "Project to check in format: [org/]project")]
+        project: String,
+    },
+    /// Canary gate for a fresh deploy
+    #[command(
+        about = "Watch a freshly deployed release for new issues and crashes, exiting with code 10 when it exceeds the limits"
+    )]
+    Verify {
+        /// Release version
+        #[arg(help = "Release version (e.g. 'my-app@1.2.0')")]
+        version: String,
+        /// Organization and project in format: [org/]project
+        #[arg(long, help = "Project to watch in format: [org/]project")]
+        project: String,
+        /// How long to watch the release
+        #[arg(
+            long,
+            default_value = "30m",
+            help = "How long to watch the release (e.g. 10m, 1h)"
+        )]
+        window: String,
+        /// Number of new issues still considered passing
+        #[arg(
+            long,
+            default_value_t = 0,
+            help = "Maximum number of issues first seen in the release"
+        )]
+        max_new_issues: usize,
+        /// Highest acceptable crash rate
+        #[arg(
+            long,
+            value_parser = percent_arg,
+            help = "Maximum share of crashed sessions (e.g. 1%); requires release health"
+        )]
+        max_crash_rate: Option<f64>,
+        /// Seconds between checks
+        #[arg(long, default_value_t = 60, help = "Seconds between checks")]
+        interval: u64,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum ReportFormat {
+    Markdown,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum IssueFormat {
+    Table,
+    /// `::error file=...,line=...::title` workflow commands for GitHub Actions
+    GhAnnotations,
+    /// JUnit XML report with each issue as a failed test case
+    Junit,
+    /// SARIF log for code-scanning dashboards
+    Sarif,
+    /// One issue ID per line, e.g. to pipe into `xargs sex-cli issue resolve`
+    Ids,
+}
+
+impl IssueFormat {
+    /// Formats read by other programs, which must not be interleaved with progress output.
+    fn is_report(self) -> bool {
+        matches!(
+            self,
+            IssueFormat::Junit | IssueFormat::Sarif | IssueFormat::Ids
+        )
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum StatusFilter {
+    Unresolved,
+    Resolved,
+    Ignored,
+    All,
+}
+
+impl StatusFilter {
+    /// Sentry search selecting issues with this status, optionally only unassigned ones.
+    fn search(self, unassigned: bool) -> String {
+        let status = match self {
+            StatusFilter::Unresolved => "is:unresolved",
+            StatusFilter::Resolved => "is:resolved",
+            StatusFilter::Ignored => "is:ignored",
+            StatusFilter::All => "",
+        };
+        let assignment = if unassigned { "is:unassigned" } else { "" };
+        format!("{} {}", status, assignment).trim().to_string()
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum ExportFormat {
+    Csv,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum IssueExportFormat {
+    Sqlite,
+    Parquet,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum ReportCommands {
+    /// Summarize issue activity for a period
+    #[command(
+        about = "Summarize new and regressed issues, resolved issues seen in the period and top offenders per project, e.g. for a weekly email",
+        long_about = "Summarize new and regressed issues, resolved issues seen in the period and top offenders per project, e.g. for a weekly email.\n\nSentry's search cannot filter by when an issue was resolved, so the resolved section lists resolved issues that had events in the period: issues resolved in the period without new events are left out."
+    )]
+    Digest {
+        /// Organization name
+        #[arg(help = "Name of the organization")]
+        org: String,
+        /// Period to cover
+        #[arg(
+            long,
+            default_value = "7d",
+            value_parser = period_arg,
+            help = "Period to cover (e.g. 24h, 7d, 2w)"
+        )]
+        period: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = DigestFormat::Markdown, help = "Output format")]
+        format: DigestFormat,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum DigestFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(Args, Debug, Default, PartialEq)]
+struct ProjectFilters {
+    /// Only show projects for this platform
+    #[arg(
+        long,
+        help = "Only show projects for a platform (e.g. 'python' also matches 'python-django')"
+    )]
+    platform: Option<String>,
+    /// Only show projects owned by this team
+    #[arg(long, help = "Only show projects owned by a team (slug or name)")]
+    team: Option<String>,
+    /// Only show projects you are a member of
+    #[arg(long, help = "Only show projects you are a member of")]
+    member_only: bool,
+    /// Only show bookmarked projects
+    #[arg(long, help = "Only show bookmarked projects")]
+    bookmarked: bool,
+}
+
+impl ProjectFilters {
+    fn matches(&self, project: &Project) -> bool {
+        if let Some(platform) = &self.platform {
+            let platform = platform.to_lowercase();
+            let matches_platform = project.platform.as_ref().is_some_and(|p| {
+                let p = p.to_lowercase();
+                p == platform || p.starts_with(&format!("{}-", platform))
+            });
+            if !matches_platform {
+                return false;
+            }
+        }
+
+        if let Some(team) = &self.team {
+            let matches_team =
+                project.teams.iter().flatten().any(|t| {
+                    t.slug.eq_ignore_ascii_case(team) || t.name.eq_ignore_ascii_case(team)
+                });
+            if !matches_team {
+                return false;
+            }
+        }
+
+        if self.bookmarked && !project.is_bookmarked.unwrap_or(false) {
+            return false;
+        }
+
+        !self.member_only || project.is_member.unwrap_or(false)
+    }
+}
+
+#[derive(Args, Debug, Default, PartialEq)]
+struct AgeFilters {
+    /// Only issues first seen within this period
+    #[arg(
+        long,
+        value_parser = period_arg,
+        help = "Only issues first seen within a period (e.g. 2h, 1d)"
+    )]
+    first_seen_since: Option<String>,
+    /// Only issues last seen within this period
+    #[arg(
+        long,
+        value_parser = period_arg,
+        help = "Only issues last seen within a period (e.g. 2h, 1d)"
+    )]
+    last_seen_since: Option<String>,
+}
+
+impl AgeFilters {
+    /// Appends relative `firstSeen`/`lastSeen` filters to a Sentry search.
+    fn apply(&self, search: &str) -> String {
+        let mut search = search.to_string();
+        if let Some(period) = &self.first_seen_since {
+            search = format!("{} firstSeen:-{}", search, period);
+        }
+        if let Some(period) = &self.last_seen_since {
+            search = format!("{} lastSeen:-{}", search, period);
+        }
+        search.trim().to_string()
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum HyperlinkMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl HyperlinkMode {
+    fn enabled(self) -> bool {
+        match self {
+            HyperlinkMode::Auto => {
+                io::stdout().is_terminal() && supports_hyperlinks(|name| std::env::var(name).ok())
+            }
+            HyperlinkMode::Always => true,
+            HyperlinkMode::Never => false,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum GroupBy {
+    Project,
+    Level,
+    /// Module or file of the culprit, without function and line
+    Culprit,
+}
+
+impl Cli {
+    pub fn run() -> Result<()> {
+        let cli = Self::parse();
+        let account = cli.account.clone();
+        Self::execute(cli).map_err(|error| {
+            name_login_org(error, |slug| {
+                let mut config = Config::load().ok()?;
+                if let Some(account) = &account {
+                    config.select_account(account).ok()?;
+                }
+                Some(config.get_organization_by_slug(slug)?.name.clone())
+            })
+        })
+    }
+
+    fn execute(cli: Self) -> Result<()> {
+        set_hyperlinks(cli.hyperlinks.enabled());
+        set_exact_counts(cli.exact);
+        timing::set_enabled(cli.timing);
+        // Prompts render on every command, so cached counts skip loading the config
+        if let Commands::Prompt {
+            target,
+            cache_ttl,
+            refresh: false,
+        } = &cli.command
+        {
+            if print_cached_prompt(target, *cache_ttl)? {
+                return Ok(());
+            }
+        }
+        let mut config = Config::load()?;
+        if let Some(account) = &cli.account {
+            config.select_account(account)?;
+        }
+        if cli.read_only {
+            config.force_read_only();
+        }
+        let fixtures = match (cli.record, cli.replay) {
+            (Some(dir), _) => FixtureMode::Record(dir),
+            (None, Some(dir)) => FixtureMode::Replay(dir),
+            (None, None) => FixtureMode::Off,
+        };
+        let mut client = SentryClient::new()?
+            .with_dry_run(cli.dry_run)
+            .with_fixtures(fixtures);
+        // Nothing changes in a dry run, so there is nothing to confirm
+        let yes = cli.yes || cli.dry_run;
+        if let Some(url) = &config.url {
+            client = client.with_url(url);
+        }
+
+        match cli.command {
+            Commands::Init => run_init(&mut config, &mut client)?,
+            Commands::Badge {
+                target,
+                query,
+                style,
+                cache_ttl,
+            } => {
+                let cache_path = badge::cache_path()?;
+                let mut cache = BadgeCache::load(&cache_path);
+                let key = format!("{} {}", target, query);
+                let now = badge::unix_now();
+                let ttl = cache_ttl.max(style.min_refresh());
+                let count = match cache.get(&key, ttl, now) {
+                    Some(count) => count,
+                    None => {
+                        let (org_slug, project) =
+                            resolve_project_target(&mut config, &mut client, &target)?;
+                        let issue_query = IssueQuery {
+                            query,
+                            ..IssueQuery::default()
+                        };
+                        let issues =
+                            client.list_project_issues(&org_slug, &project, &issue_query)?;
+                        let count = BadgeCount {
+                            count: issues.len(),
+                            capped: issues.len() >= issue_query.limit,
+                            fetched_at: now,
+                        };
+                        cache.insert(key, count);
+                        // A stale badge is better than none, so cache failures are ignored
+                        let _ = cache.save(&cache_path);
+                        count
+                    }
+                };
+                println!("{}", render_badge(&count, style));
+            }
+            Commands::Prompt {
+                target, refresh, ..
+            } => {
+                let (org_slug, project) =
+                    resolve_project_target(&mut config, &mut client, &target)?;
+                let issue_query = IssueQuery::default();
+                let issues = client.list_project_issues(&org_slug, &project, &issue_query)?;
+                let capped = issues.len() >= issue_query.limit;
+                let now = badge::unix_now();
+                let count = |levels: &[&str]| BadgeCount {
+                    count: issues
+                        .iter()
+                        .filter(|i| levels.contains(&i.level.as_str()))
+                        .count(),
+                    capped,
+                    fetched_at: now,
+                };
+                let (errors, warnings) = (count(&["fatal", "error"]), count(&["warning"]));
+
+                let cache_path = badge::cache_path()?;
+                let mut cache = BadgeCache::load(&cache_path);
+                let (errors_key, warnings_key) = badge::prompt_keys(&target);
+                cache.insert(errors_key, errors);
+                cache.insert(warnings_key, warnings);
+                cache.save(&cache_path)?;
+                if !refresh {
+                    println!("{}", render_prompt(&errors, &warnings));
+                }
+            }
+            Commands::Triage { target, query } => {
+                let (org_slug, project) =
+                    resolve_project_target(&mut config, &mut client, &target)?;
+                let issue_query = IssueQuery {
+                    query,
+                    ..IssueQuery::default()
+                };
+                let issues = client.list_project_issues(&org_slug, &project, &issue_query)?;
+                if issues.is_empty() {
+                    println!("No issues to triage in {}/{}", org_slug, project);
+                    return Ok(());
+                }
+                require_terminal("Triage", "list the issues with 'issue list' instead")?;
+                login_for_write(&config, &mut client, &org_slug)?;
+                let mut triage = Triage::new(client, org_slug, project, issues)?
+                    .with_query(issue_query.query)
+                    .with_searches(config.searches.clone());
+                let result = triage.run();
+                config.set_searches(triage.searches().clone())?;
+                result?;
+                println!("Triage done: {}", triage.tally());
+            }
+            Commands::Check {
+                target,
+                query,
+                age,
+                max,
+                format,
+            } => {
+                let (org_slug, project) =
+                    resolve_project_target(&mut config, &mut client, &target)?;
+                let query = age.apply(&query);
+                let issues = fetch_check_issues(&client, &org_slug, &project, &query, max)?;
+                let table = Table::new(select_columns(
+                    issue_columns(),
+                    &[],
+                    &["id", "title", "level", "events", "last_seen"],
+                )?);
+                if issues.len() <= max {
+                    let summary = format!(
+                        "OK: {} issue(s) match '{}' in {}/{} (max {})",
+                        issues.len(),
+                        query,
+                        org_slug,
+                        project,
+                        max
+                    );
+                    if format.is_report() {
+                        // The report goes to stdout; a passing run has no findings
+                        eprintln!("{}", summary);
+                        print_issue_list(&client, &table, Vec::new(), None, format);
+                    } else {
+                        println!("{}", summary);
+                    }
+                    return Ok(());
+                }
+
+                // Only one issue past the maximum is fetched
+                print_issue_list(&client, &table, issues, None, format);
+                return Err(CliError::new(
+                    ExitCode::CheckFailed,
+                    format!(
+                        "More than the allowed {} issue(s) match '{}' in {}/{}",
+                        max, query, org_slug, project
+                    ),
+                )
+                .into());
+            }
+            Commands::Login { browser, role, org } => {
+                if browser {
+                    let sentry_org = client.login_with_browser()?;
+                    let org_name = org.unwrap_or_else(|| sentry_org.slug.clone());
+                    // Add organization if it doesn't exist
+                    if !config.organizations.contains_key(&org_name) {
+                        config.add_organization(org_name.clone(), sentry_org.slug);
+                        println!("Added new organization: {}", org_name);
+                    }
+
+                    let org_entry = config.get_organization_mut(&org_name).unwrap();
+                    if let Some(token) = client.get_current_token() {
+                        org_entry.set_token(role, token)?;
+                        config.save()?;
+                        println!(
+                            "Successfully logged in to Sentry for organization: {} ({} token)",
+                            org_name, role
+                        );
+                        warn_about_scopes(&client);
+                        discover_projects(&mut config, &client, &org_name);
+                    }
+                } else {
+                    let org = org.ok_or_else(|| {
+                        anyhow::anyhow!("Organization name is required for token-based login")
+                    })?;
+                    let org_entry = config.get_organization_mut(&org).ok_or_else(|| {
+                        not_found(format!(
+                            "Organization '{}' not found. Add it first with 'org add'.",
+                            org
+                        ))
+                    })?;
+
+                    client.login_with_prompt()?;
+                    if let Some(token) = client.get_current_token() {
+                        org_entry.set_token(role, token)?;
+                        config.save()?;
+                        println!(
+                            "Successfully logged in to Sentry for organization: {} ({} token)",
+                            org, role
+                        );
+                        warn_about_scopes(&client);
+                        discover_projects(&mut config, &client, &org);
+                    }
+                }
+            }
+            Commands::Monitor {
+                target,
+                org,
+                all,
+                resume: _,
+                tabs,
+                all_orgs,
+                query,
+                level,
+                alerts,
+                notify,
+                emit,
+            } => {
+                require_terminal(
+                    "The monitor",
+                    "use 'check' or 'issue list' in scripts and cron jobs",
+                )?;
+                let options = MonitorOptions {
+                    search: monitor_search(query, &level),
+                    alerts,
+                    notify,
+                    event_log: emit
+                        .as_deref()
+                        .map(EventLog::open)
+                        .transpose()?
+                        .map(Arc::new),
+                };
+                if all_orgs || !tabs.is_empty() {
+                    let patterns = if all_orgs {
+                        vec!["*".to_string()]
+                    } else {
+                        tabs
+                    };
+                    return start_tabbed_monitor(&mut config, &mut client, &patterns, &options);
+                }
+                let (org_slug, projects) = match target {
+                    Some(target) if !all => {
+                        let (org_slug, project) =
+                            resolve_project_target(&mut config, &mut client, &target)?;
+                        (org_slug, vec![project])
+                    }
+                    None if !all => match config.last_monitor.clone() {
+                        Some(last) => {
+                            let org_name = config
+                                .get_organization_by_slug(&last.org_slug)
+                                .map(|org| org.name.clone())
+                                .with_context(|| {
+                                    format!(
+                                        "Organization '{}' is no longer configured",
+                                        last.org_slug
+                                    )
+                                })?;
+                            login_org(&config, &mut client, &org_name)?;
+                            (last.org_slug, last.projects)
+                        }
+                        None => {
+                            let target = config.default_project.clone().context(
+                                "No previous monitor target. Run 'sex monitor <target>' first.",
+                            )?;
+                            let (org_slug, project) =
+                                resolve_project_target(&mut config, &mut client, &target)?;
+                            (org_slug, vec![project])
+                        }
+                    },
+                    _ => {
+                        let org_name = default_org_name(&config, org)?;
+                        let org = login_org(&config, &mut client, &org_name)?;
+                        let projects: Vec<String> = client
+                            .list_projects(&org.slug)?
+                            .into_iter()
+                            .map(|p| p.slug)
+                            .collect();
+                        if projects.is_empty() {
+                            anyhow::bail!("Organization '{}' has no projects", org.name);
+                        }
+                        (org.slug.clone(), projects)
+                    }
+                };
+                start_monitor(&mut config, &client, org_slug, projects, &options)?;
+            }
+            Commands::Events { command } => match command {
+                EventCommands::Tail {
+                    target,
+                    query,
+                    level,
+                    interval,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let mut search = query.unwrap_or_default();
+                    if let Some(level) = &level {
+                        search = format!("{} level:{}", search, level).trim().to_string();
+                    }
+
+                    println!(
+                        "Tailing events for {}/{}, press Ctrl+C to stop",
+                        org_slug, project
+                    );
+                    let mut seen: Option<HashSet<String>> = None;
+                    loop {
+                        match client.list_project_events(
+                            &org_slug,
+                            &project,
+                            &search,
+                            MAX_PAGE_SIZE,
+                        ) {
+                            Ok(events) => {
+                                for event in tail_new_events(events, &mut seen) {
+                                    println!("{}", format_event_line(&event));
+                                }
+                            }
+                            Err(e) => eprintln!("Failed to fetch events: {}", e),
+                        }
+                        std::thread::sleep(std::time::Duration::from_secs(interval));
+                    }
+                }
+                EventCommands::Search {
+                    target,
+                    query,
+                    limit,
+                    columns,
+                } => {
+                    let table = Table::new(select_columns(
+                        event_columns(),
+                        &columns,
+                        &["time", "level", "title", "user", "link"],
+                    )?);
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let events = client.list_project_events(&org_slug, &project, &query, limit)?;
+                    if events.is_empty() {
+                        println!("No events match '{}'", query);
+                        return Ok(());
+                    }
+
+                    let rows: Vec<(SentryEvent, String)> = events
+                        .into_iter()
+                        .map(|event| {
+                            let url = client.event_url(&org_slug, &event);
+                            (event, url)
+                        })
+                        .collect();
+                    print!("{}", table.render(&rows));
+                }
+                EventCommands::View {
+                    target,
+                    event_id,
+                    repo,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let event = client.get_project_event(&org_slug, &project, &event_id)?;
+                    print_event(&event, repo.as_deref());
+                }
+            },
+            Commands::Auth { command } => match command {
+                AuthCommands::Scopes { org } => {
+                    let org = login_org(&config, &mut client, &org)?;
+                    let mut scopes = client.get_token_scopes()?;
+                    scopes.sort();
+
+                    println!("Token scopes for organization: {}", org.name);
+                    for scope in &scopes {
+                        println!("  {}", scope);
+                    }
+                    let warnings = check_scopes(&scopes).warnings();
+                    if !warnings.is_empty() {
+                        println!();
+                        for warning in warnings {
+                            println!("Warning: {}", warning);
+                        }
+                    }
+                }
+            },
+            Commands::Api { command } => match command {
+                ApiCommands::Limits { org } => {
+                    let org = login_org(&config, &mut client, &org)?;
+                    let limits = client.get_rate_limits(&org.slug)?;
+                    print_rate_limits(&org.name, &limits);
+                }
+            },
+            Commands::Report { command } => match command {
+                ReportCommands::Digest {
+                    org,
+                    period,
+                    format,
+                } => {
+                    let org = login_org(&config, &mut client, &org)?;
+                    let search = |query: String| -> Result<Vec<Issue>> {
+                        client.list_org_issues(
+                            &org.slug,
+                            &IssueQuery {
+                                query,
+                                stats_period: period.clone(),
+                                limit: SUMMARY_ISSUE_LIMIT,
+                                ..IssueQuery::default()
+                            },
+                        )
+                    };
+
+                    let digest = Digest::new(
+                        &org.name,
+                        &period,
+                        search(format!("is:unresolved lastSeen:-{}", period))?,
+                        search(format!("firstSeen:-{}", period))?,
+                        search(format!("is:resolved lastSeen:-{}", period))?,
+                        search(format!("is:regressed lastSeen:-{}", period))?,
+                    );
+                    match format {
+                        DigestFormat::Markdown => print!("{}", digest.to_markdown()),
+                        DigestFormat::Html => print!("{}", digest.to_html()),
+                    }
+                }
+            },
+            Commands::Trace { command } => match command {
+                TraceCommands::View { trace_id, org } => {
+                    let transactions = match org {
+                        Some(name) => {
+                            let org = login_org(&config, &mut client, &name)?;
+                            client.get_trace(&org.slug, &trace_id)?
+                        }
+                        None => {
+                            // Tokens are per organization, so try each until one can see the trace
+                            let mut found = Vec::new();
+                            for org in config.accounts() {
+                                if let Some(token) = org.get_auth_token()? {
+                                    client.login(token)?;
+                                    if let Ok(transactions) = client.get_trace(&org.slug, &trace_id)
+                                    {
+                                        if !transactions.is_empty() {
+                                            found = transactions;
+                                            break;
+                                        }
+                                    }
+                                }
+                            }
+                            found
+                        }
+                    };
+
+                    if transactions.is_empty() {
+                        println!("Trace not found in any organization");
+                        return Ok(());
+                    }
+                    require_terminal("The trace viewer", "open the trace in Sentry instead")?;
+                    let mut viewer = TraceViewer::new(trace_id, &transactions)?;
+                    viewer.show()?;
+                }
+            },
+            Commands::Perf { command } => match command {
+                PerfCommands::Summary {
+                    target,
+                    period,
+                    limit,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let (transactions, overall) =
+                        client.get_transaction_performance(&org_slug, &project, &period, limit)?;
+
+                    println!(
+                        "Performance summary for {}/{} (last {})",
+                        org_slug, project, period
+                    );
+                    if overall.count == 0 {
+                        println!("\n  No transactions found");
+                        return Ok(());
+                    }
+                    println!(
+                        "\n  Apdex: {:.2}  Failure rate: {}  P95: {}  Transactions: {}",
+                        overall.apdex,
+                        format_percent(overall.failure_rate),
+                        format_nanos(overall.p95 * 1e6),
+                        overall.count
+                    );
+
+                    println!("\nBusiest transactions:");
+                    let table = Table::new(vec![
+                        Column::new(
+                            "transaction",
+                            "Transaction",
+                            |t: &TransactionPerformance| t.transaction.clone(),
+                        )
+                        .max_width(60),
+                        Column::new("count", "Count", |t: &TransactionPerformance| {
+                            format_count(t.count)
+                        })
+                        .align_right(),
+                        Column::new("apdex", "Apdex", |t: &TransactionPerformance| {
+                            format!("{:.2}", t.apdex)
+                        })
+                        .align_right(),
+                        Column::new(
+                            "failure_rate",
+                            "Failure Rate",
+                            |t: &TransactionPerformance| format_percent(t.failure_rate),
+                        )
+                        .align_right(),
+                        Column::new("p95", "P95", |t: &TransactionPerformance| {
+                            format_nanos(t.p95 * 1e6)
+                        })
+                        .align_right(),
+                    ])
+                    .indent(2);
+                    print!("{}", table.render(&transactions));
+                }
+            },
+            Commands::Profile { command } => match command {
+                ProfileCommands::List {
+                    target,
+                    period,
+                    limit,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let transactions =
+                        client.list_profiled_transactions(&org_slug, &project, &period, limit)?;
+                    let functions =
+                        client.list_profile_functions(&org_slug, &project, &period, limit)?;
+
+                    println!(
+                        "Profiling summary for {}/{} (last {})",
+                        org_slug, project, period
+                    );
+                    if transactions.is_empty() && functions.is_empty() {
+                        println!("\n  No profiles found");
+                        return Ok(());
+                    }
+
+                    println!("\nProfiled transactions:");
+                    let transaction_table = Table::new(vec![
+                        Column::new("transaction", "Transaction", |t: &ProfiledTransaction| {
+                            t.transaction.clone()
+                        })
+                        .max_width(60),
+                        Column::new("profiles", "Profiles", |t: &ProfiledTransaction| {
+                            format_count(t.count)
+                        })
+                        .align_right(),
+                        Column::new("p75", "P75", |t: &ProfiledTransaction| format_nanos(t.p75))
+                            .align_right(),
+                    ])
+                    .indent(2);
+                    print!("{}", transaction_table.render(&transactions));
+
+                    println!("\nSlowest functions (by total CPU time):");
+                    let function_table = Table::new(vec![
+                        Column::new("function", "Function", |f: &ProfileFunction| {
+                            f.function.clone()
+                        })
+                        .max_width(50),
+                        Column::new("package", "Package", |f: &ProfileFunction| {
+                            f.package.clone()
+                        })
+                        .max_width(30),
+                        Column::new("calls", "Samples", |f: &ProfileFunction| {
+                            format_count(f.count)
+                        })
+                        .align_right(),
+                        Column::new("p75", "P75", |f: &ProfileFunction| format_nanos(f.p75))
+                            .align_right(),
+                        Column::new("total", "Total", |f: &ProfileFunction| {
+                            format_nanos(f.total)
+                        })
+                        .align_right(),
+                    ])
+                    .indent(2);
+                    print!("{}", function_table.render(&functions));
+                }
+            },
+            Commands::DebugFiles { command } => match command {
+                DebugFileCommands::Upload { target, path } => {
+                    let files = find_debug_files(&path)?;
+                    if files.is_empty() {
+                        println!("No debug files found in {}", path.display());
+                        return Ok(());
+                    }
+
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    login_for_write(&config, &mut client, &org_slug)?;
+                    let summary = format!(
+                        "Upload {} debug file(s) to {}/{}",
+                        files.len(),
+                        org_slug,
+                        project
+                    );
+                    if !confirm(&summary, yes)? {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                    if client.is_dry_run() {
+                        println!(
+                            "[dry-run] Would upload {} debug file(s) to {}/{}",
+                            files.len(),
+                            org_slug,
+                            project
+                        );
+                        for (path, kind) in &files {
+                            println!("[dry-run]   {} [{}]", path.display(), kind);
+                        }
+                        return Ok(());
+                    }
+                    println!(
+                        "Uploading {} debug file(s) to {}/{}",
+                        files.len(),
+                        org_slug,
+                        project
+                    );
+                    let results = upload_debug_files(&client, &org_slug, &project, files)?;
+
+                    let mut failed = 0;
+                    for (file, outcome) in &results {
+                        let status = match outcome {
+                            UploadOutcome::AlreadyUploaded => "already uploaded".to_string(),
+                            UploadOutcome::Uploaded => "uploaded".to_string(),
+                            UploadOutcome::Failed(reason) => {
+                                failed += 1;
+                                format!("failed: {}", reason)
+                            }
+                        };
+                        println!("  {} [{}] {}", file.path.display(), file.kind, status);
+                    }
+                    if failed > 0 {
+                        return Err(anyhow::anyhow!("{} debug file(s) failed to upload", failed));
+                    }
+                }
+            },
+            Commands::Release { command } => match command {
+                ReleaseCommands::SetCommits {
+                    version,
+                    org,
+                    auto,
+                    commit,
+                } => {
+                    let org_name = default_org_name(&config, org)?;
+                    let org = login_org(&config, &mut client, &org_name)?;
+
+                    let refs = if auto {
+                        let repo = Path::new(".");
+                        vec![ReleaseRef {
+                            repository: git::origin_repository(repo)?,
+                            commit: git::head_commit(repo)?,
+                            previous_commit: None,
+                        }]
+                    } else {
+                        commit
+                    };
+
+                    login_for_write(&config, &mut client, &org.slug)?;
+                    let summary = format!(
+                        "Replace the commits of release {} with {} ref(s)",
+                        version,
+                        refs.len()
+                    );
+                    if !confirm(&summary, yes)? {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                    let Some(release) = client.set_release_refs(&org.slug, &version, &refs)? else {
+                        return Ok(());
+                    };
+                    println!(
+                        "Associated commits with release {}",
+                        link(
+                            &release.version,
+                            &client.release_url(&org.slug, &release.version)
+                        )
+                    );
+                    for r in &refs {
+                        match &r.previous_commit {
+                            Some(previous) => {
+                                println!("  {}@{}..{}", r.repository, previous, r.commit)
+                            }
+                            None => println!("  {}@{}", r.repository, r.commit),
+                        }
+                    }
+                    println!("  {} commit(s) in release", release.commit_count);
+                }
+                ReleaseCommands::Diff { from, to, project } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &project)?;
+                    let diff = fetch_release_diff(&client, &org_slug, &project, &from, &to)?;
+
+                    let table = Table::new(select_columns(
+                        issue_columns(),
+                        &[],
+                        &["id", "title", "level", "events", "users"],
+                    )?)
+                    .indent(2)
+                    .fit_width(terminal_width());
+                    let release =
+                        |version: &str| link(version, &client.release_url(&org_slug, version));
+                    println!("New in {} ({}):", release(&to), diff.new.len());
+                    print_issues(&table, diff.new, None);
+                    println!(
+                        "\nResolved since {} ({}):",
+                        release(&from),
+                        diff.resolved.len()
+                    );
+                    print_issues(&table, diff.resolved, None);
+                }
+                ReleaseCommands::CheckResolved { version, project } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &project)?;
+                    let states = resolution_states(&client, &org_slug, &project, &version)?;
+                    let release = link(&version, &client.release_url(&org_slug, &version));
+                    if states.is_empty() {
+                        println!("No issues resolved in {}", release);
+                        return Ok(());
+                    }
+                    println!("Issues resolved in {} ({}):", release, states.len());
+                    print!("{}", resolution_table().render(&states));
+                }
+                ReleaseCommands::Verify {
+                    version,
+                    project,
+                    window,
+                    max_new_issues,
+                    max_crash_rate,
+                    interval,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &project)?;
+                    let thresholds = CanaryThresholds {
+                        max_new_issues,
+                        max_crash_rate,
+                    };
+                    verify_release(
+                        &client, &org_slug, &project, &version, &window, thresholds, interval,
+                    )?;
+                }
+            },
+            Commands::Config { command } => match command {
+                ConfigCommands::SourceRoot { path: Some(path) } => {
+                    let path = path
+                        .canonicalize()
+                        .with_context(|| format!("Source root not found: {}", path.display()))?;
+                    println!("Source root set to {}", path.display());
+                    config.source_root = Some(path);
+                    config.save()?;
+                }
+                ConfigCommands::SourceRoot { path: None } => match &config.source_root {
+                    Some(path) => println!("{}", path.display()),
+                    None => println!("No source root configured"),
+                },
+                ConfigCommands::ReadOnly {
+                    enabled: Some(enabled),
+                } => {
+                    config.read_only = enabled;
+                    config.save()?;
+                    println!("Read-only mode is {}", if enabled { "on" } else { "off" });
+                }
+                ConfigCommands::ReadOnly { enabled: None } => {
+                    println!("{}", if config.read_only { "on" } else { "off" })
+                }
+                ConfigCommands::Encrypt => {
+                    config.secrets.encrypt_config = true;
+                    config.save()?;
+                    println!("Config file is now encrypted at rest");
+                }
+                ConfigCommands::Decrypt => {
+                    config.secrets.encrypt_config = false;
+                    config.save()?;
+                    println!("Config file is now stored in cleartext");
+                }
+                ConfigCommands::Import {
+                    source: ImportSource::SentryCli,
+                    file,
+                } => {
+                    let settings = SentryCliSettings::load(file)?;
+                    import_sentry_cli(&mut config, settings)?;
+                }
+                ConfigCommands::Secrets { command } => match command {
+                    SecretsCommands::Backend => println!("{}", config.secrets.backend),
+                    SecretsCommands::Migrate { to } => {
+                        let from = config.secrets.backend;
+                        if from == to {
+                            println!("Secrets are already stored in the {} backend", to);
+                            return Ok(());
+                        }
+                        let exports = config.migrate_secrets(to)?;
+                        println!("Switched secrets backend from {} to {}", from, to);
+                        if !exports.is_empty() {
+                            println!(
+                                "\nThe env backend is read-only. Export these variables; the {} backend still holds the secrets until you remove them:\n",
+                                from
+                            );
+                            for (name, value) in exports {
+                                println!("export {}={}", name, value);
+                            }
+                        }
+                    }
+                },
+            },
+            Commands::Org { command } => match command {
+                OrgCommands::List => {
+                    if config.organizations.is_empty() {
+                        println!("No organizations configured");
+                    } else {
+                        println!("Organizations:");
+                        for org in config.organizations.values() {
+                            let roles = org.token_roles()?;
+                            let auth_status = if roles.is_empty() {
+                                "not authenticated".to_string()
+                            } else {
+                                let roles: Vec<String> =
+                                    roles.iter().map(|r| r.to_string()).collect();
+                                format!("authenticated ({})", roles.join(", "))
+                            };
+                            println!("  {} ({}) - {}", org.name, org.slug, auth_status);
+
+                            // List cached projects
+                            for slug in org.projects.keys() {
+                                if let Some(Ok(name)) = org.get_project(slug) {
+                                    println!("    - {} ({})", name, slug);
+                                }
+                            }
+                        }
+                    }
+                }
+                OrgCommands::Add {
+                    name,
+                    slug,
+                    from_url,
+                    no_verify,
+                } => {
+                    let (name, slug) = match (from_url, name, slug) {
+                        (Some(url), name, _) => {
+                            let org_url = OrgUrl::parse(&url)?;
+                            config.use_server(org_url.server.clone())?;
+                            if let Some(server) = org_url.server {
+                                println!("Using Sentry server: {}", server);
+                                client = client.with_url(&server);
+                            }
+                            (name.unwrap_or_else(|| org_url.slug.clone()), org_url.slug)
+                        }
+                        (None, Some(name), Some(slug)) => (name, slug),
+                        _ => unreachable!("clap requires a name and slug without --from-url"),
+                    };
+                    // Any token can tell whether a slug exists, the new one has none yet
+                    let token = if no_verify {
+                        None
+                    } else {
+                        config
+                            .organizations
+                            .values()
+                            .find_map(|org| org.get_auth_token().ok().flatten())
+                    };
+                    config.add_organization(name.clone(), slug.clone());
+                    config.save()?;
+                    println!("Added organization: {} ({})", name, slug);
+                    if let Some(token) = token {
+                        client.login(token)?;
+                        verify_org_slug(&client, &slug);
+                    }
+                }
+                OrgCommands::Usage {
+                    name,
+                    period,
+                    quotas,
+                } => {
+                    let org = login_org(&config, &mut client, &name)?;
+                    let groups = client.get_org_usage(&org.slug, &period)?;
+                    let usage = summarize_usage(&groups, &quotas.into_iter().collect());
+                    print_usage(&org.name, &period, &usage);
+                }
+                OrgCommands::Projects { name } => {
+                    let org = config
+                        .get_organization(&name)
+                        .ok_or_else(|| not_found(format!("Organization '{}' not found", name)))?;
+                    println!("Projects in organization: {}", name);
+                    for project in org.projects.keys() {
+                        println!("  - {}", project);
+                    }
+                }
+                OrgCommands::Info { name } => {
+                    let org = login_org(&config, &mut client, &name)?;
+                    print_org_info(&client.get_organization_details(&org.slug)?);
+                }
+            },
+            Commands::Issue { command } => match command {
+                IssueCommands::List {
+                    target,
+                    columns,
+                    links,
+                    group_by,
+                    format,
+                    stats_period,
+                    status,
+                    unassigned,
+                    assigned,
+                    latest_release,
+                    level,
+                    age,
+                    team,
+                } => {
+                    if config.organizations.is_empty() {
+                        println!("No organizations configured. Add one first with 'org add'.");
+                        return Ok(());
+                    }
+
+                    let mut default_columns = DEFAULT_ISSUE_COLUMNS.to_vec();
+                    let period_column = stats_period.as_ref().map(|p| format!("events_{}", p));
+                    default_columns.extend(period_column.as_deref());
+                    let search = [Some(status.search(unassigned)), level_search(&level)]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let issue_query = IssueQuery {
+                        query: age.apply(search.trim()),
+                        stats_period: stats_period
+                            .unwrap_or_else(|| IssueQuery::default().stats_period),
+                        ..IssueQuery::default()
+                    };
+                    if links {
+                        set_hyperlinks(true);
+                    }
+                    let table =
+                        Table::new(select_columns(issue_columns(), &columns, &default_columns)?)
+                            .indent(if group_by.is_some() { 4 } else { 2 })
+                            .fit_width(terminal_width());
+                    // JUnit and SARIF reports are single documents covering every organization
+                    let mut report_issues = Vec::new();
+
+                    let expanded = match (target, team) {
+                        (Some(target), _) => {
+                            let expanded =
+                                expand_project_pattern(&mut config, &mut client, &target)?;
+                            if expanded.is_empty() {
+                                anyhow::bail!("No projects match '{}'", target);
+                            }
+                            Some(expanded)
+                        }
+                        (None, Some(team)) => {
+                            let expanded = team_projects(&config, &mut client, &team)?;
+                            if expanded.is_empty() {
+                                return Err(not_found(format!("Team '{}' owns no projects", team)));
+                            }
+                            Some(expanded)
+                        }
+                        (None, None) => None,
+                    };
+
+                    if let Some(expanded) = expanded {
+                        for (org_name, projects) in expanded {
+                            let org = login_org(&config, &mut client, &org_name)?;
+                            let issue_query =
+                                assignee_query(&client, &issue_query, assigned.as_deref())?;
+                            if !format.is_report() {
+                                println!(
+                                    "\nFetching issues for organization: {} ({})",
+                                    org.name,
+                                    projects.join(", ")
+                                );
+                            }
+                            let mut issues = Vec::new();
+                            let progress = Progress::bar(projects.len(), "");
+                            for project in &projects {
+                                progress.set_message(format!(
+                                    "Fetching issues of {}/{}",
+                                    org.slug, project
+                                ));
+                                let issue_query = if latest_release {
+                                    let release = client
+                                        .get_latest_release(&org.slug, project)?
+                                        .ok_or_else(|| {
+                                            not_found(format!(
+                                                "Project '{}' has no releases",
+                                                project
+                                            ))
+                                        })?;
+                                    if !format.is_report() {
+                                        progress.println(format!(
+                                            "Latest release of {}: {}",
+                                            project,
+                                            link(
+                                                &release.version,
+                                                &client.release_url(&org.slug, &release.version)
+                                            )
+                                        ));
+                                    }
+                                    first_release_query(&issue_query, &release.version)
+                                } else {
+                                    issue_query.clone()
+                                };
+                                issues.extend(client.list_project_issues(
+                                    &org.slug,
+                                    project,
+                                    &issue_query,
+                                )?);
+                                progress.inc();
+                            }
+                            drop(progress);
+                            issues.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+                            if format.is_report() {
+                                report_issues.extend(issues);
+                            } else {
+                                print_issue_list(&client, &table, issues, group_by, format);
+                            }
+                        }
+                    } else {
+                        for org in config.accounts() {
+                            if let Some(token) = org.get_auth_token()? {
+                                client.login(token)?;
+                                let mut issue_query =
+                                    assignee_query(&client, &issue_query, assigned.as_deref())?;
+                                if latest_release {
+                                    // Sentry resolves "latest" per project across the organization
+                                    issue_query = first_release_query(&issue_query, "latest");
+                                }
+                                let fetch_issues = || {
+                                    let _progress = Progress::spinner(format!(
+                                        "Fetching issues of {}",
+                                        org.name
+                                    ));
+                                    client.list_org_issues(&org.slug, &issue_query)
+                                };
+                                if format.is_report() {
+                                    report_issues.extend(fetch_issues()?);
+                                    continue;
+                                }
+                                println!("\nFetching issues for organization: {}", org.name);
+                                let issues = fetch_issues()?;
+                                print_issue_list(&client, &table, issues, group_by, format);
+                            }
+                        }
+                    }
+
+                    if format.is_report() {
+                        print_issue_list(&client, &table, report_issues, None, format);
+                    }
+                }
+                IssueCommands::Sync { org } => {
+                    let mut store = IssueStore::open(&issue_store::store_path()?)?;
+                    let org_names: Vec<String> = match org {
+                        Some(org) => vec![org],
+                        None => config.accounts().iter().map(|o| o.name.clone()).collect(),
+                    };
+                    let query = IssueQuery {
+                        limit: SUMMARY_ISSUE_LIMIT,
+                        ..IssueQuery::default()
+                    };
+                    let progress = Progress::bar(org_names.len(), "");
+                    for org_name in org_names {
+                        progress.set_message(format!("Syncing {}", org_name));
+                        let org = login_org(&config, &mut client, &org_name)?;
+                        let issues = client.list_org_issues(&org.slug, &query)?;
+                        progress.println(format!("Synced {} issues of {}", issues.len(), org.name));
+                        store.sync(&org.slug, &issues, badge::unix_now())?;
+                        progress.inc();
+                    }
+                }
+                IssueCommands::Grep { text, org } => {
+                    let store = IssueStore::open(&issue_store::store_path()?)?;
+                    // Synced issues are stored by slug, which usually matches the name
+                    let org_slug = org.map(|name| {
+                        config
+                            .get_organization(&name)
+                            .map(|o| o.slug.clone())
+                            .unwrap_or(name)
+                    });
+                    let synced: Vec<String> = match &org_slug {
+                        Some(slug) => vec![slug.clone()],
+                        None => store.org_slugs()?,
+                    };
+                    let now = badge::unix_now();
+                    let mut ages = Vec::new();
+                    for slug in &synced {
+                        if let Some(synced_at) = store.synced_at(slug)? {
+                            ages.push(format!(
+                                "{} {}",
+                                slug,
+                                format_age(now.saturating_sub(synced_at))
+                            ));
+                        }
+                    }
+                    if ages.is_empty() {
+                        return Err(not_found(
+                            "No synced issues. Run 'sex issue sync' first.".to_string(),
+                        ));
+                    }
+
+                    let found = store.search(&text, org_slug.as_deref())?;
+                    println!(
+                        "{} matching issues (synced: {})",
+                        found.len(),
+                        ages.join(", ")
+                    );
+                    print!(
+                        "{}",
+                        synced_issue_table()
+                            .fit_width(terminal_width())
+                            .render(&found)
+                    );
+                }
+                IssueCommands::Dedupe { org, similarity } => {
+                    let org = login_org(&config, &mut client, &org)?;
+                    let query = IssueQuery {
+                        limit: SUMMARY_ISSUE_LIMIT,
+                        ..IssueQuery::default()
+                    };
+                    let issues = client.list_org_issues(&org.slug, &query)?;
+                    let clusters = find_duplicates(issues, similarity / 100.0);
+                    if clusters.is_empty() {
+                        println!("No near-duplicate issues in {}", org.name);
+                        return Ok(());
+                    }
+                    println!(
+                        "Possible duplicates in {} ({} clusters):",
+                        org.name,
+                        clusters.len()
+                    );
+                    let table = duplicate_table().fit_width(terminal_width());
+                    for cluster in &clusters {
+                        println!(
+                            "\n  {} issues in {} project(s), {} events",
+                            cluster.issues.len(),
+                            cluster.projects().len(),
+                            format_count(cluster.events())
+                        );
+                        print!("{}", table.render(&cluster.issues));
+                    }
+                }
+                IssueCommands::Summary { org, period } => {
+                    let org = login_org(&config, &mut client, &org)?;
+                    let query = IssueQuery {
+                        stats_period: period.clone(),
+                        limit: SUMMARY_ISSUE_LIMIT,
+                        ..IssueQuery::default()
+                    };
+                    let issues = client.list_org_issues(&org.slug, &query)?;
+
+                    let new_query = IssueQuery {
+                        query: format!("is:unresolved firstSeen:-{}", period),
+                        ..query
+                    };
+                    let new_issue_ids: HashSet<String> = client
+                        .list_org_issues(&org.slug, &new_query)?
+                        .into_iter()
+                        .map(|i| i.id)
+                        .collect();
+
+                    print_summary(&org.name, &period, &summarize(issues, &new_issue_ids));
+                }
+                IssueCommands::Export {
+                    target,
+                    format,
+                    output,
+                    query,
+                    period,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let query = IssueQuery {
+                        query,
+                        stats_period: period.clone(),
+                        limit: usize::MAX,
+                        ..IssueQuery::default()
+                    };
+                    let issues = client.list_project_issues(&org_slug, &project, &query)?;
+                    match format {
+                        IssueExportFormat::Sqlite => {
+                            export::write_sqlite(&output, &issues, &period)?
+                        }
+                        IssueExportFormat::Parquet => {
+                            export::write_parquet(&output, &issues, &period)?
+                        }
+                    }
+                    println!(
+                        "Exported {} issues of {}/{} to {}",
+                        issues.len(),
+                        org_slug,
+                        project,
+                        output.display()
+                    );
+                }
+                IssueCommands::Snapshot {
+                    target,
+                    output,
+                    query,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let issue_query = IssueQuery {
+                        query: query.clone(),
+                        limit: usize::MAX,
+                        ..IssueQuery::default()
+                    };
+                    let issues = client.list_project_issues(&org_slug, &project, &issue_query)?;
+                    let count = issues.len();
+                    IssueSnapshot {
+                        target,
+                        org_slug: org_slug.clone(),
+                        project: project.clone(),
+                        query,
+                        taken_at: badge::unix_now(),
+                        issues,
+                    }
+                    .save(&output)?;
+                    println!(
+                        "Saved {} issues of {}/{} to {}",
+                        count,
+                        org_slug,
+                        project,
+                        output.display()
+                    );
+                }
+                IssueCommands::Diff { snapshot } => {
+                    let snapshot = IssueSnapshot::load(&snapshot)?;
+                    // The snapshot's own project, even if its target resolves elsewhere now
+                    let org_name = config
+                        .get_organization_by_slug(&snapshot.org_slug)
+                        .map(|org| org.name.clone())
+                        .unwrap_or_else(|| snapshot.org_slug.clone());
+                    login_org(&config, &mut client, &org_name)?;
+                    let (org_slug, project) = (&snapshot.org_slug, &snapshot.project);
+                    let issue_query = IssueQuery {
+                        query: snapshot.query.clone(),
+                        limit: usize::MAX,
+                        ..IssueQuery::default()
+                    };
+                    let now = client.list_project_issues(org_slug, project, &issue_query)?;
+                    let mut diff = snapshot_diff(snapshot.issues.clone(), now);
+                    // Issues that stopped matching show how they left, e.g. resolved
+                    let mut unavailable = HashSet::new();
+                    for issue in diff.gone.iter_mut() {
+                        match client.get_issue(&issue.id) {
+                            Ok(current) => *issue = current,
+                            Err(e) => {
+                                eprintln!(
+                                    "Warning: could not fetch the current status of issue {}: {}",
+                                    issue.id, e
+                                );
+                                unavailable.insert(issue.id.clone());
+                            }
+                        }
+                    }
+                    println!(
+                        "Issues of {}/{} since the snapshot {} (query: {})",
+                        org_slug,
+                        project,
+                        format_age(badge::unix_now().saturating_sub(snapshot.taken_at)),
+                        snapshot.query
+                    );
+                    print_snapshot_diff(&diff, &unavailable);
+                }
+                IssueCommands::Trends {
+                    target,
+                    period,
+                    limit,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let query = IssueQuery {
+                        stats_period: period.clone(),
+                        limit: SUMMARY_ISSUE_LIMIT,
+                        ..IssueQuery::default()
+                    };
+                    let issues = client.list_project_issues(&org_slug, &project, &query)?;
+
+                    let recent_buckets = if period == "24h" { 3 } else { 1 };
+                    let mut trends = issue_trends(issues, &period, recent_buckets);
+                    trends.truncate(limit);
+
+                    if trends.is_empty() {
+                        println!("No issues with recent events in {}/{}", org_slug, project);
+                    } else {
+                        let unit = if period == "24h" { "hour" } else { "day" };
+                        println!(
+                            "Fastest growing issues in {}/{} (events per {})",
+                            org_slug, project, unit
+                        );
+                        print!("{}", trend_table().render(&trends));
+                    }
+                }
+                IssueCommands::View { id, repo, plain } => {
+                    let issue = find_issue(&config, &mut client, &id)?;
+                    match issue {
+                        // Pipes and cron jobs get the report instead of the viewer
+                        Some((org_slug, issue)) if plain || !is_interactive() => {
+                            print!(
+                                "{}",
+                                fetch_issue_report(&client, &org_slug, issue)?.to_markdown()
+                            )
+                        }
+                        Some((org_slug, issue)) => {
+                            view_issue(&config, &client, &org_slug, issue, repo)?
+                        }
+                        None => println!("Issue not found in any organization"),
+                    }
+                }
+                IssueCommands::Grouping { id } => {
+                    let Some((org_slug, issue)) = find_issue(&config, &mut client, &id)? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+                    let event = client.get_latest_event(&issue.id).ok();
+                    for line in grouping_lines(&client, &org_slug, &issue, event.as_ref())? {
+                        println!("{}", line);
+                    }
+                }
+                IssueCommands::Users { id, limit } => {
+                    let Some((_, issue)) = find_issue(&config, &mut client, &id)? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+                    let mut users = client.list_issue_users(&issue.id)?;
+                    if users.is_empty() {
+                        println!("No affected users recorded for issue {}", issue.id);
+                        return Ok(());
+                    }
+                    users.truncate(limit);
+                    println!(
+                        "Users affected by {}: {} ({} in total)",
+                        issue.id,
+                        issue.title,
+                        format_count(issue.user_count)
+                    );
+                    print!("{}", affected_user_table().render(&users));
+                }
+                IssueCommands::Event { id, json, repo } => {
+                    let Some((_, issue)) = find_issue(&config, &mut client, &id)? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+                    let event = client.get_latest_event_json(&issue.id)?;
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&event)?);
+                    } else {
+                        let event: EventDetails = serde_json::from_value(event)?;
+                        print_event(&event, repo.as_deref());
+                    }
+                }
+                IssueCommands::Latest {
+                    target,
+                    query,
+                    repo,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let issue_query = IssueQuery {
+                        query,
+                        sort: "new".to_string(),
+                        limit: 1,
+                        ..IssueQuery::default()
+                    };
+                    let Some(issue) = client
+                        .list_project_issues(&org_slug, &project, &issue_query)?
+                        .into_iter()
+                        .next()
+                    else {
+                        println!("No issues found in {}/{}", org_slug, project);
+                        return Ok(());
+                    };
+                    view_issue(&config, &client, &org_slug, issue, repo)?;
+                }
+                // Without an assignee, --suggest is set and the picker below runs
+                IssueCommands::Assign { args, suggest } => {
+                    let (ids, assignee) = split_assign_args(args, suggest)?;
+                    let ids = read_issue_ids(ids)?;
+                    if ids.len() > 1 {
+                        let assignee = assignee
+                            .context("--suggest picks an assignee for one issue at a time")?;
+                        if !confirm(&format!("Assign {} issues to {}", ids.len(), assignee), yes)? {
+                            println!("Cancelled");
+                            return Ok(());
+                        }
+                        let results =
+                            change_issues(&config, &client, &ids, |client, org_slug, issue| {
+                                // Teams are looked up in each issue's project
+                                let teams = match &issue.project {
+                                    Some(project) if assignee.starts_with('#') => client
+                                        .get_project(org_slug, &project.slug)?
+                                        .teams
+                                        .unwrap_or_default(),
+                                    _ => Vec::new(),
+                                };
+                                let assignee = resolve_assignee(client, &assignee, &teams)?;
+                                client.update_issue(
+                                    &issue.id,
+                                    &serde_json::json!({ "assignedTo": assignee }),
+                                )?;
+                                Ok(None)
+                            });
+                        return bulk_summary(&client, "Assigned", &results);
+                    }
+                    let Some((org_slug, issue)) = find_issue(&config, &mut client, &ids[0])? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+                    login_for_write(&config, &mut client, &org_slug)?;
+                    let (teams, suggestions) = fetch_suggestions(&client, &org_slug, &issue)?;
+
+                    let (assignee, label) = match assignee {
+                        Some(assignee) => {
+                            let resolved = resolve_assignee(&client, &assignee, &teams)?;
+                            let summary = format!(
+                                "Assign {} ({}) to {}",
+                                issue.short_id.as_deref().unwrap_or(&issue.id),
+                                issue.title,
+                                assignee
+                            );
+                            if !confirm(&summary, yes)? {
+                                println!("Cancelled");
+                                return Ok(());
+                            }
+                            (resolved, assignee.clone())
+                        }
+                        None => {
+                            require_terminal(
+                                "Picking a suggested assignee",
+                                "pass the assignee instead",
+                            )?;
+                            println!("{}: {}", issue.id, issue.title);
+                            println!("Currently assigned to {}", assignee_name(&issue));
+                            if suggestions.is_empty() {
+                                println!("No suggestions from ownership rules or suspect commits");
+                            }
+                            for (index, suggestion) in suggestions.iter().enumerate() {
+                                println!(
+                                    "  {}) {} - {}",
+                                    index + 1,
+                                    suggestion.label,
+                                    suggestion.reason
+                                );
+                            }
+                            let input = prompt_line(
+                                "Assign to (number, 'me', email or #team; empty to cancel)",
+                                None,
+                            )?;
+                            if input.is_empty() {
+                                println!("Cancelled");
+                                return Ok(());
+                            }
+                            pick_assignee(&client, &input, &teams, &suggestions)?
+                        }
+                    };
+                    client
+                        .update_issue(&issue.id, &serde_json::json!({ "assignedTo": assignee }))?;
+                    if !client.is_dry_run() {
+                        println!("Assigned {} to {}", issue.id, label);
+                    }
+                }
+                IssueCommands::Resolve {
+                    ids,
+                    next_release,
+                    in_release,
+                } => {
+                    let when = match (&in_release, next_release) {
+                        (Some(version), _) => format!(" in {}", version),
+                        (None, true) => " in the next release".to_string(),
+                        (None, false) => String::new(),
+                    };
+                    let changes = resolve_changes(next_release, in_release.as_deref());
+                    let ids = read_issue_ids(ids)?;
+                    if ids.len() > 1 {
+                        if !confirm(&format!("Resolve {} issues{}", ids.len(), when), yes)? {
+                            println!("Cancelled");
+                            return Ok(());
+                        }
+                        let results = change_issues(&config, &client, &ids, |client, _, issue| {
+                            client.update_issue(&issue.id, &changes).map(|_| None)
+                        });
+                        return bulk_summary(&client, "Resolved", &results);
+                    }
+                    let Some((org_slug, issue)) = find_issue(&config, &mut client, &ids[0])? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+                    login_for_write(&config, &mut client, &org_slug)?;
+                    let summary = format!(
+                        "Resolve {} ({}){}",
+                        issue.short_id.as_deref().unwrap_or(&issue.id),
+                        issue.title,
+                        when
+                    );
+                    if !confirm(&summary, yes)? {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                    client.update_issue(&issue.id, &changes)?;
+                    if !client.is_dry_run() {
+                        println!("Resolved {}{}", issue.id, when);
+                    }
+                }
+                IssueCommands::Ignore {
+                    ids,
+                    until,
+                    until_count,
+                    until_users,
+                    window,
+                } => {
+                    if window.is_some() && until_count.is_none() && until_users.is_none() {
+                        anyhow::bail!("--window needs --until-count or --until-users");
+                    }
+                    let condition = IgnoreCondition {
+                        duration: until.as_deref().map(period_minutes).transpose()?,
+                        count: until_count,
+                        user_count: until_users,
+                        window: window.as_deref().map(period_minutes).transpose()?,
+                    };
+                    let ids = read_issue_ids(ids)?;
+                    if ids.len() > 1 {
+                        if !confirm(&format!("Ignore {} issues {}", ids.len(), condition), yes)? {
+                            println!("Cancelled");
+                            return Ok(());
+                        }
+                        let changes = condition.changes();
+                        let results = change_issues(&config, &client, &ids, |client, _, issue| {
+                            client.update_issue(&issue.id, &changes).map(|_| None)
+                        });
+                        return bulk_summary(&client, "Ignored", &results);
+                    }
+                    let Some((org_slug, issue)) = find_issue(&config, &mut client, &ids[0])? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+                    login_for_write(&config, &mut client, &org_slug)?;
+                    let summary = format!(
+                        "Ignore {} ({}) {}",
+                        issue.short_id.as_deref().unwrap_or(&issue.id),
+                        issue.title,
+                        condition
+                    );
+                    if !confirm(&summary, yes)? {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                    client.update_issue(&issue.id, &condition.changes())?;
+                    if !client.is_dry_run() {
+                        println!("Ignored {} {}", issue.id, condition);
+                    }
+                }
+                IssueCommands::Report { id, format } => {
+                    let Some((org_slug, issue)) = find_issue(&config, &mut client, &id)? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+
+                    let report = fetch_issue_report(&client, &org_slug, issue)?;
+                    match format {
+                        ReportFormat::Markdown => print!("{}", report.to_markdown()),
+                    }
+                }
+                IssueCommands::Url { id, copy } => {
+                    let Some((org_slug, issue)) = find_issue(&config, &mut client, &id)? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+                    let url = client.issue_url(&org_slug, &issue);
+                    println!("{}", url);
+                    if copy {
+                        copy_to_clipboard(&url)?;
+                        eprintln!("Copied to clipboard");
+                    }
+                }
+                IssueCommands::Share { ids, copy } => {
+                    let ids = read_issue_ids(ids)?;
+                    if ids.len() > 1 {
+                        let summary = format!(
+                            "Share {} issues publicly; anyone with the links can see their latest events",
+                            ids.len()
+                        );
+                        if !confirm(&summary, yes)? {
+                            println!("Cancelled");
+                            return Ok(());
+                        }
+                        let results = change_issues(&config, &client, &ids, |client, _, issue| {
+                            let Some(shared) = client.set_issue_public(&issue.id, true)? else {
+                                return Ok(None);
+                            };
+                            let share_id = shared
+                                .share_id
+                                .context("Sentry did not return a share link for the issue")?;
+                            Ok(Some(client.share_url(&share_id)))
+                        });
+                        if copy {
+                            let links: Vec<&str> = results
+                                .iter()
+                                .filter_map(|(_, result)| result.as_ref().ok())
+                                .filter_map(|line| line.split_once("  ").map(|(_, url)| url))
+                                .collect();
+                            if !links.is_empty() {
+                                copy_to_clipboard(&links.join("\n"))?;
+                                eprintln!("Copied {} links to clipboard", links.len());
+                            }
+                        }
+                        return bulk_summary(&client, "Shared", &results);
+                    }
+                    let Some((org_slug, issue)) = find_issue(&config, &mut client, &ids[0])? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+                    login_for_write(&config, &mut client, &org_slug)?;
+                    let summary = format!(
+                        "Share {} ({}) publicly; anyone with the link can see its latest event",
+                        issue.short_id.as_deref().unwrap_or(&issue.id),
+                        issue.title
+                    );
+                    if !confirm(&summary, yes)? {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                    let Some(shared) = client.set_issue_public(&issue.id, true)? else {
+                        return Ok(());
+                    };
+                    let share_id = shared
+                        .share_id
+                        .context("Sentry did not return a share link for the issue")?;
+                    let url = client.share_url(&share_id);
+                    println!("{}", url);
+                    if copy {
+                        copy_to_clipboard(&url)?;
+                        eprintln!("Copied to clipboard");
+                    }
+                }
+                IssueCommands::Unshare { ids } => {
+                    let ids = read_issue_ids(ids)?;
+                    if ids.len() > 1 {
+                        if !confirm(&format!("Stop sharing {} issues", ids.len()), yes)? {
+                            println!("Cancelled");
+                            return Ok(());
+                        }
+                        let results = change_issues(&config, &client, &ids, |client, _, issue| {
+                            client.set_issue_public(&issue.id, false).map(|_| None)
+                        });
+                        return bulk_summary(&client, "Stopped sharing", &results);
+                    }
+                    let Some((org_slug, issue)) = find_issue(&config, &mut client, &ids[0])? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+                    login_for_write(&config, &mut client, &org_slug)?;
+                    let summary = format!(
+                        "Stop sharing {} ({})",
+                        issue.short_id.as_deref().unwrap_or(&issue.id),
+                        issue.title
+                    );
+                    if !confirm(&summary, yes)? {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                    client.set_issue_public(&issue.id, false)?;
+                    if !client.is_dry_run() {
+                        println!("Stopped sharing {}", issue.id);
+                    }
+                }
+                IssueCommands::OpenFile { id, repo } => {
+                    let Some((_, issue)) = find_issue(&config, &mut client, &id)? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+                    let root = repo
+                        .or_else(|| config.source_root.clone())
+                        .unwrap_or_else(|| PathBuf::from("."));
+                    let event = client.get_latest_event(&issue.id)?;
+                    match crash_location(&event, &root) {
+                        Some(location) => open_in_editor(&location)?,
+                        None => println!(
+                            "Could not find the crashing file of issue {} in {}",
+                            issue.id,
+                            root.display()
+                        ),
+                    }
+                }
+                IssueCommands::Watch { id, interval } => {
+                    let Some((_, mut issue)) = find_issue(&config, &mut client, &id)? else {
+                        println!("Issue not found in any organization");
+                        return Ok(());
+                    };
+
+                    println!("Watching {}: {}", issue.id, issue.title);
+                    println!(
+                        "  {} events, {} users, {}, assigned to {}",
+                        issue.count,
+                        issue.user_count,
+                        issue.status,
+                        assignee_name(&issue)
+                    );
+                    println!("Polling every {}s, press Ctrl+C to stop", interval);
+
+                    loop {
+                        std::thread::sleep(std::time::Duration::from_secs(interval));
+                        let current = match client.get_issue(&issue.id) {
+                            Ok(current) => current,
+                            Err(e) => {
+                                eprintln!("{} Failed to refresh issue: {}", clock_time(), e);
+                                continue;
+                            }
+                        };
+                        let changes = issue_changes(&issue, &current);
+                        if !changes.is_empty() {
+                            println!("{} {}", clock_time(), changes.join(", "));
+                        }
+                        issue = current;
+                    }
+                }
+            },
+            Commands::Project { command } => match command {
+                ProjectCommands::List { columns, filters } => {
+                    if config.organizations.is_empty() {
+                        println!("No organizations configured. Add one first with 'org add'.");
+                        return Ok(());
+                    }
+
+                    let table = Table::new(select_columns(
+                        project_columns(),
+                        &columns,
+                        DEFAULT_PROJECT_COLUMNS,
+                    )?)
+                    .indent(2);
+
+                    for org in config.accounts() {
+                        if let Some(token) = org.get_auth_token()? {
+                            client.login(token)?;
+                            println!("\nProjects in organization: {}", org.name);
+                            let progress =
+                                Progress::spinner(format!("Fetching projects of {}", org.name));
+                            let fetched = client.list_projects(&org.slug)?;
+                            drop(progress);
+                            let projects: Vec<(Project, String)> = fetched
+                                .into_iter()
+                                .filter(|p| filters.matches(p))
+                                .map(|p| {
+                                    let url = client.project_url(&org.slug, &p.slug);
+                                    (p, url)
+                                })
+                                .collect();
+
+                            if projects.is_empty() {
+                                println!("  No projects found");
+                            } else {
+                                print!("{}", table.render(&projects));
+                            }
+                        }
+                    }
+                }
+                ProjectCommands::Info {
+                    target,
+                    interactive,
+                    show_dsn,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    // Without a terminal the viewer falls back to the plain listing
+                    if interactive && is_interactive() {
+                        start_project_viewer(&client, &org_slug, &project)?;
+                    } else {
+                        start_project_info(&client, org_slug, project, show_dsn)?;
+                    }
+                }
+                ProjectCommands::Stats {
+                    target,
+                    period,
+                    export,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let seconds = parse_stats_period(&period)?;
+                    let now = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or(0);
+                    let resolution = if seconds <= HOURLY_STATS_MAX_PERIOD {
+                        "1h"
+                    } else {
+                        "1d"
+                    };
+                    let buckets = client.get_project_event_stats(
+                        &org_slug,
+                        &project,
+                        now.saturating_sub(seconds),
+                        resolution,
+                    )?;
+                    match export {
+                        ExportFormat::Csv => print!("{}", stats_csv(&buckets)),
+                    }
+                }
+                ProjectCommands::Compare { target, period } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let seconds = parse_stats_period(&period)?;
+                    let current = client.get_project_totals(&org_slug, &project, &period, None)?;
+                    let previous = client.get_project_totals(
+                        &org_slug,
+                        &project,
+                        &format!("{}s", seconds * 2),
+                        Some(&period),
+                    )?;
+
+                    println!(
+                        "{}/{}: last {} vs the {} before\n",
+                        org_slug, project, period, period
+                    );
+                    for delta in compare_totals(&current, &previous) {
+                        print_delta(&delta)?;
+                    }
+                }
+                ProjectCommands::Owners { target, issue } => {
+                    let issue = match issue {
+                        Some(id) => Some(
+                            find_issue(&config, &mut client, &id)?
+                                .ok_or_else(|| not_found(format!("Issue '{}' not found", id)))?
+                                .1,
+                        ),
+                        None => None,
+                    };
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    let ownership = client.get_project_ownership(&org_slug, &project)?;
+                    let rules = ownership.rules();
+
+                    println!("Ownership rules for {}/{}:", org_slug, project);
+                    if rules.is_empty() {
+                        println!("  No rules");
+                    }
+                    let width = rules
+                        .iter()
+                        .map(|r| r.kind.len() + r.pattern.len() + 1)
+                        .max()
+                        .unwrap_or(0);
+                    for rule in &rules {
+                        println!(
+                            "  {:<width$}  -> {}",
+                            format!("{}:{}", rule.kind, rule.pattern),
+                            rule.owners.join(", "),
+                            width = width
+                        );
+                    }
+                    println!(
+                        "\nUnmatched issues: {}",
+                        if ownership.fallthrough {
+                            "assigned to all project members"
+                        } else {
+                            "unowned"
+                        }
+                    );
+                    if let Some(mode) = &ownership.auto_assignment {
+                        println!("Auto-assignment: {}", mode);
+                    }
+
+                    if let Some(issue) = issue {
+                        println!("\nCulprit of {}: {}", issue.id, issue.culprit);
+                        match matching_owner_rule(&rules, &issue.culprit) {
+                            Some(rule) => println!(
+                                "  Matches {}:{} -> {}",
+                                rule.kind,
+                                rule.pattern,
+                                rule.owners.join(", ")
+                            ),
+                            None => println!("  No rule matches"),
+                        }
+                    }
+                }
+                ProjectCommands::Bookmark { target } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    login_for_write(&config, &mut client, &org_slug)?;
+                    if !confirm(&format!("Bookmark {}/{}", org_slug, project), yes)? {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                    if client
+                        .set_project_bookmark(&org_slug, &project, true)?
+                        .is_some()
+                    {
+                        println!("Bookmarked project: {}/{}", org_slug, project);
+                    }
+                }
+                ProjectCommands::Keys { command } => match command {
+                    KeyCommands::List { target } => {
+                        let (org_slug, project) =
+                            resolve_project_target(&mut config, &mut client, &target)?;
+                        let keys = client.list_client_keys(&org_slug, &project)?;
+                        println!("Client keys of {}/{}:", org_slug, project);
+                        print!("{}", client_key_table().render(&keys));
+                    }
+                    KeyCommands::RateLimit {
+                        key,
+                        project,
+                        count,
+                        window,
+                        remove,
+                    } => {
+                        let (org_slug, project) =
+                            resolve_project_target(&mut config, &mut client, &project)?;
+                        let keys = client.list_client_keys(&org_slug, &project)?;
+                        let key = find_client_key(keys, &key)?;
+                        let rate_limit = count
+                            .zip(window)
+                            .map(|(count, window)| KeyRateLimit { count, window });
+                        if rate_limit.is_none() && !remove {
+                            println!(
+                                "Rate limit of key '{}': {}",
+                                key.name,
+                                key.rate_limit
+                                    .map(|limit| limit.to_string())
+                                    .unwrap_or_else(|| "none".to_string())
+                            );
+                            return Ok(());
+                        }
+
+                        login_for_write(&config, &mut client, &org_slug)?;
+                        let change = match &rate_limit {
+                            Some(limit) => format!("to {}", limit),
+                            None => "to none".to_string(),
+                        };
+                        let summary = format!(
+                            "Set the rate limit of key '{}' of {}/{} {}",
+                            key.name, org_slug, project, change
+                        );
+                        if !confirm(&summary, yes)? {
+                            println!("Cancelled");
+                            return Ok(());
+                        }
+                        if client
+                            .set_key_rate_limit(&org_slug, &project, &key.id, rate_limit.as_ref())?
+                            .is_some()
+                        {
+                            println!("Rate limit of key '{}' set {}", key.name, change);
+                        }
+                    }
+                },
+                ProjectCommands::Filters {
+                    target,
+                    enable,
+                    disable,
+                    spike_protection,
+                } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    if let Some(id) = enable.iter().find(|id| disable.contains(id)) {
+                        return Err(anyhow::anyhow!(
+                            "Filter '{}' can't be both enabled and disabled",
+                            id
+                        ));
+                    }
+                    let changes = filter_changes(&enable, &disable, spike_protection);
+                    if !changes.is_empty() {
+                        login_for_write(&config, &mut client, &org_slug)?;
+                        let summary =
+                            format!("{} for {}/{}", changes.join(", "), org_slug, project);
+                        if !confirm(&summary, yes)? {
+                            println!("Cancelled");
+                            return Ok(());
+                        }
+                        for id in &enable {
+                            client.set_inbound_filter(&org_slug, &project, id, true)?;
+                        }
+                        for id in &disable {
+                            client.set_inbound_filter(&org_slug, &project, id, false)?;
+                        }
+                        if let Some(enabled) = spike_protection {
+                            client.set_spike_protection(&org_slug, &project, enabled)?;
+                        }
+                        if client.is_dry_run() {
+                            return Ok(());
+                        }
+                    }
+
+                    let filters = client.list_inbound_filters(&org_slug, &project)?;
+                    let details = client.get_project(&org_slug, &project)?;
+                    println!("Inbound filters of {}/{}:", org_slug, project);
+                    print!("{}", inbound_filter_table().render(&filters));
+                    println!(
+                        "Spike protection: {}",
+                        if details.spike_protection() {
+                            "on"
+                        } else {
+                            "off"
+                        }
+                    );
+                }
+                ProjectCommands::Unbookmark { target } => {
+                    let (org_slug, project) =
+                        resolve_project_target(&mut config, &mut client, &target)?;
+                    login_for_write(&config, &mut client, &org_slug)?;
+                    let summary = format!("Remove the bookmark from {}/{}", org_slug, project);
+                    if !confirm(&summary, yes)? {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                    if client
+                        .set_project_bookmark(&org_slug, &project, false)?
+                        .is_some()
+                    {
+                        println!("Removed bookmark from project: {}/{}", org_slug, project);
+                    }
+                }
+            },
+            Commands::Completion { shell } => {
+                let mut cmd = Self::command();
+                let bin_name = cmd.get_name().to_string();
+                generate(shell, &mut cmd, bin_name, &mut io::stdout());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub fn parse_from(args: &[&str]) -> Self {
+        Self::try_parse_from(args).unwrap()
+    }
+}
+
+/// Upper bound on issues fetched for org-wide reports.
+const SUMMARY_ISSUE_LIMIT: usize = 1000;
+/// Longest period, in seconds, exported with hourly rather than daily stats buckets.
+const HOURLY_STATS_MAX_PERIOD: u64 = 7 * 86_400;
+/// Issues changed or looked up at the same time by bulk commands.
+const BULK_CONCURRENCY: usize = 4;
+
+fn period_arg(value: &str) -> std::result::Result<String, String> {
+    parse_stats_period(value)
+        .map(|_| value.to_string())
+        .map_err(|e| e.to_string())
+}
+
+fn print_summary(org_name: &str, period: &str, summary: &IssueSummary) {
+    println!(
+        "Issue summary for organization: {} (last {})",
+        org_name, period
+    );
+    println!("\n  Unresolved issues: {}", summary.unresolved);
+    println!("  New this period:   {}", summary.new_issues);
+
+    let issue_table = |metric: Column<Issue>| {
+        Table::new(vec![
+            Column::new("id", "ID", |i: &Issue| i.id.clone()),
+            Column::new("project", "Project", project_slug),
+            Column::new("title", "Title", |i: &Issue| i.title.clone()).max_width(60),
+            metric,
+        ])
+        .indent(2)
+    };
+
+    println!("\nTop {} by events:", summary.top_by_events.len());
+    let events = Column::new("events", "Events", |i: &Issue| format_count(i.count)).align_right();
+    print!("{}", issue_table(events).render(&summary.top_by_events));
+
+    println!("\nTop {} by users:", summary.top_by_users.len());
+    let users = Column::new("users", "Users", |i: &Issue| format_count(i.user_count)).align_right();
+    print!("{}", issue_table(users).render(&summary.top_by_users));
+
+    println!("\nBy project:");
+    let projects = Table::new(vec![
+        Column::new("project", "Project", |p: &ProjectBreakdown| {
+            p.project.clone()
+        }),
+        Column::new("issues", "Issues", |p: &ProjectBreakdown| {
+            p.issues.to_string()
+        })
+        .align_right(),
+        Column::new("new", "New", |p: &ProjectBreakdown| {
+            p.new_issues.to_string()
+        })
+        .align_right(),
+        Column::new("events", "Events", |p: &ProjectBreakdown| {
+            p.events.to_string()
+        })
+        .align_right(),
+        Column::new("users", "Users", |p: &ProjectBreakdown| p.users.to_string()).align_right(),
+    ])
+    .indent(2);
+    print!("{}", projects.render(&summary.projects));
+}
+
+/// Formats a 0..1 ratio such as a failure rate as a percentage.
+fn format_percent(ratio: f64) -> String {
+    format!("{:.1}%", ratio * 100.0)
+}
+
+/// Prints the cached prompt counts of a project, if there are any. Stale counts are
+/// still printed, while a detached `prompt --refresh` fetches fresh ones for next time.
+fn print_cached_prompt(target: &str, cache_ttl: u64) -> Result<bool> {
+    let cache_path = badge::cache_path()?;
+    let mut cache = BadgeCache::load(&cache_path);
+    let (errors_key, warnings_key) = badge::prompt_keys(target);
+    let (Some(errors), Some(warnings)) = (cache.get_any(&errors_key), cache.get_any(&warnings_key))
+    else {
+        return Ok(false);
+    };
+    println!("{}", render_prompt(&errors, &warnings));
+
+    let now = badge::unix_now();
+    // Marked before spawning, so prompts drawn meanwhile don't start refreshes of their
+    // own; without a saved mark no refresh is started at all
+    if cache.get(&errors_key, cache_ttl, now).is_none()
+        && cache.start_refresh(&errors_key, now)
+        && cache.save(&cache_path).is_ok()
+    {
+        // A failed refresh only means the counts stay stale a little longer
+        let _ = std::process::Command::new(std::env::current_exe()?)
+            .args(["prompt", target, "--refresh"])
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn();
+    }
+    Ok(true)
+}
+
+/// Formats a nanosecond duration with the largest fitting unit.
+fn format_nanos(nanos: f64) -> String {
+    if nanos >= 1e9 {
+        format!("{:.2}s", nanos / 1e9)
+    } else if nanos >= 1e6 {
+        format!("{:.1}ms", nanos / 1e6)
+    } else if nanos >= 1e3 {
+        format!("{:.1}µs", nanos / 1e3)
+    } else {
+        format!("{:.0}ns", nanos)
+    }
+}
+
+fn affected_user_table() -> Table<AffectedUser> {
+    Table::new(vec![
+        Column::new("user", "User", |u: &AffectedUser| u.label().to_string()).max_width(40),
+        Column::new("value", "Tag value", |u: &AffectedUser| u.value.clone()).max_width(40),
+        Column::new("events", "Events", |u: &AffectedUser| format_count(u.count)).align_right(),
+        Column::new("last_seen", "Last seen", |u: &AffectedUser| {
+            u.last_seen.clone().unwrap_or_default()
+        }),
+    ])
+    .indent(2)
+}
+
+fn synced_issue_table() -> Table<(String, Issue)> {
+    Table::new(vec![
+        Column::new("org", "Org", |(org, _): &(String, Issue)| org.clone()),
+        Column::new("project", "Project", |(_, i): &(String, Issue)| {
+            project_slug(i)
+        })
+        .max_width(20),
+        Column::new("id", "ID", |(_, i): &(String, Issue)| {
+            i.short_id.clone().unwrap_or_else(|| i.id.clone())
+        }),
+        Column::new("title", "Title", |(_, i): &(String, Issue)| i.title.clone())
+            .max_width(60)
+            .flex(),
+        Column::new("culprit", "Culprit", |(_, i): &(String, Issue)| {
+            i.culprit.clone()
+        })
+        .max_width(40),
+        Column::new("events", "Events", |(_, i): &(String, Issue)| {
+            format_count(i.count)
+        })
+        .align_right(),
+    ])
+    .indent(2)
+}
+
+/// Age in seconds as e.g. `5m ago`.
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+fn duplicate_table() -> Table<Issue> {
+    Table::new(vec![
+        Column::new("project", "Project", project_slug).max_width(20),
+        Column::new("id", "ID", |i: &Issue| {
+            i.short_id.clone().unwrap_or_else(|| i.id.clone())
+        }),
+        Column::new("title", "Title", |i: &Issue| i.title.clone())
+            .max_width(60)
+            .flex(),
+        Column::new("culprit", "Culprit", |i: &Issue| i.culprit.clone()).max_width(40),
+        Column::new("events", "Events", |i: &Issue| format_count(i.count)).align_right(),
+    ])
+    .indent(4)
+}
+
+fn resolution_table() -> Table<(Issue, ResolutionState)> {
+    Table::new(vec![
+        Column::new("id", "ID", |(i, _): &(Issue, ResolutionState)| i.id.clone()),
+        Column::new("title", "Title", |(i, _): &(Issue, ResolutionState)| {
+            i.title.clone()
+        })
+        .max_width(50)
+        .flex(),
+        Column::new("state", "State", |(_, s): &(Issue, ResolutionState)| {
+            s.to_string()
+        }),
+        Column::new(
+            "last_seen",
+            "Last seen",
+            |(i, _): &(Issue, ResolutionState)| i.last_seen.clone(),
+        ),
+    ])
+    .indent(2)
+    .fit_width(terminal_width())
+}
+
+/// Issue IDs of a bulk command, read one per line from stdin when given as `-`.
+fn read_issue_ids(ids: Vec<String>) -> Result<Vec<String>> {
+    if ids != ["-"] {
+        return Ok(ids);
+    }
+    let ids = parse_id_lines(io::stdin().lock())?;
+    if ids.is_empty() {
+        anyhow::bail!("No issue IDs on stdin");
+    }
+    Ok(ids)
+}
+
+/// Non-empty lines of `reader`, trimmed.
+fn parse_id_lines(reader: impl io::BufRead) -> Result<Vec<String>> {
+    let mut ids = Vec::new();
+    for line in reader.lines() {
+        let line = line.context("Failed to read issue IDs")?;
+        if !line.trim().is_empty() {
+            ids.push(line.trim().to_string());
+        }
+    }
+    Ok(ids)
+}
+
+/// Looks up each issue and applies `change` with the write token of its organization,
+/// [`BULK_CONCURRENCY`] issues at a time. `change` gets the organization slug and may
+/// return a detail to show, e.g. a link. Returns the short ID and detail or the error of
+/// each issue, in the order given.
+fn change_issues(
+    config: &Config,
+    client: &SentryClient,
+    ids: &[String],
+    change: impl Fn(&SentryClient, &str, &Issue) -> Result<Option<String>> + Sync,
+) -> Vec<(String, Result<String>)> {
+    let next = AtomicUsize::new(0);
+    let change_one = |client: &mut SentryClient, id: &str| -> Result<String> {
+        let (org_slug, issue) = find_issue(config, client, id)?
+            .ok_or_else(|| not_found("Issue not found in any organization"))?;
+        login_for_write(config, client, &org_slug)?;
+        let detail = change(client, &org_slug, &issue)?;
+        let short_id = issue.short_id.unwrap_or(issue.id);
+        Ok(match detail {
+            Some(detail) => format!("{}  {}", short_id, detail),
+            None => short_id,
+        })
+    };
+    let done: Vec<(usize, Result<String>)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..BULK_CONCURRENCY.min(ids.len()))
+            .map(|_| {
+                let mut client = client.clone();
+                let (next, change_one) = (&next, &change_one);
+                scope.spawn(move || {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(id) = ids.get(index) else {
+                            return done;
+                        };
+                        done.push((index, change_one(&mut client, id)));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    });
+    let mut results: Vec<Option<Result<String>>> = ids.iter().map(|_| None).collect();
+    for (index, result) in done {
+        results[index] = Some(result);
+    }
+    // The results of a panicked worker are lost, so its issues count as failed
+    ids.iter()
+        .zip(results)
+        .map(|(id, result)| {
+            let result = result.unwrap_or_else(|| {
+                Err(anyhow::anyhow!(
+                    "Worker crashed; the change may or may not have been applied"
+                ))
+            });
+            (id.clone(), result)
+        })
+        .collect()
+}
+
+/// Splits `issue assign` arguments into the issue IDs and the assignee after them,
+/// which --suggest replaces with a picker for a single issue.
+fn split_assign_args(
+    mut args: Vec<String>,
+    suggest: bool,
+) -> Result<(Vec<String>, Option<String>)> {
+    if suggest {
+        if args.len() > 1 {
+            anyhow::bail!(
+                "--suggest picks an assignee for one issue; pass either an assignee or --suggest"
+            );
+        }
+        return Ok((args, None));
+    }
+    if args.len() < 2 {
+        anyhow::bail!(
+            "Pass the assignee after the issue IDs ('me', an email or a '#team'), or --suggest"
+        );
+    }
+    let assignee = args.pop();
+    Ok((args, assignee))
+}
+
+/// Prints how each issue of a bulk change went, failing when any of them did.
+fn bulk_summary(
+    client: &SentryClient,
+    done: &str,
+    results: &[(String, Result<String>)],
+) -> Result<()> {
+    let mut failed = 0;
+    for (id, result) in results {
+        match result {
+            Ok(short_id) if !client.is_dry_run() => println!("  ok      {}  {}", id, short_id),
+            Ok(_) => {}
+            Err(e) => {
+                failed += 1;
+                println!("  failed  {}  {:#}", id, e);
+            }
+        }
+    }
+    if !client.is_dry_run() {
+        println!(
+            "{} {} of {} issues",
+            done,
+            results.len() - failed,
+            results.len()
+        );
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} issues failed", failed, results.len());
+    }
+    Ok(())
+}
+
+/// Changes resolving an issue, now or once a release is out.
+fn resolve_changes(next_release: bool, in_release: Option<&str>) -> serde_json::Value {
+    match (in_release, next_release) {
+        (Some(version), _) => {
+            serde_json::json!({"status": "resolved", "statusDetails": {"inRelease": version}})
+        }
+        (None, true) => {
+            serde_json::json!({"status": "resolved", "statusDetails": {"inNextRelease": true}})
+        }
+        (None, false) => serde_json::json!({"status": "resolved"}),
+    }
+}
+
+fn client_key_table() -> Table<ClientKey> {
+    Table::new(vec![
+        Column::new("name", "Name", |k: &ClientKey| k.name.clone()),
+        Column::new("id", "ID", |k: &ClientKey| k.id.clone()),
+        Column::new("active", "Active", |k: &ClientKey| {
+            if k.is_active { "yes" } else { "no" }.to_string()
+        }),
+        Column::new("rate_limit", "Rate limit", |k: &ClientKey| {
+            k.rate_limit
+                .as_ref()
+                .map(|limit| limit.to_string())
+                .unwrap_or_else(|| "none".to_string())
+        }),
+        Column::new("dsn", "DSN", |k: &ClientKey| k.dsn.public.clone()),
+    ])
+    .indent(2)
+}
+
+fn inbound_filter_table() -> Table<InboundFilter> {
+    Table::new(vec![
+        Column::new("filter", "Filter", |f: &InboundFilter| f.id.clone()),
+        Column::new("active", "Active", |f: &InboundFilter| {
+            match f.active.as_array() {
+                Some(browsers) if !browsers.is_empty() => format!(
+                    "on ({})",
+                    browsers
+                        .iter()
+                        .filter_map(|b| b.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                _ => if f.is_active() { "on" } else { "off" }.to_string(),
+            }
+        }),
+    ])
+    .indent(2)
+}
+
+/// Describes the requested filter and spike protection changes, e.g. "turn on localhost".
+fn filter_changes(enable: &[String], disable: &[String], spike: Option<bool>) -> Vec<String> {
+    let mut changes = Vec::new();
+    if !enable.is_empty() {
+        changes.push(format!("turn on {}", enable.join(", ")));
+    }
+    if !disable.is_empty() {
+        changes.push(format!("turn off {}", disable.join(", ")));
+    }
+    if let Some(enabled) = spike {
+        changes.push(format!(
+            "turn spike protection {}",
+            if enabled { "on" } else { "off" }
+        ));
+    }
+    changes
+}
+
+/// Finds a client key by ID or, ignoring case, by name.
+fn find_client_key(keys: Vec<ClientKey>, key: &str) -> Result<ClientKey> {
+    let names: Vec<String> = keys.iter().map(|k| k.name.clone()).collect();
+    keys.into_iter()
+        .find(|k| k.id == key || k.name.eq_ignore_ascii_case(key))
+        .ok_or_else(|| {
+            not_found(format!(
+                "No client key '{}'. Keys: {}",
+                key,
+                names.join(", ")
+            ))
+        })
+}
+
+fn trend_table() -> Table<IssueTrend> {
+    Table::new(vec![
+        Column::new("id", "ID", |t: &IssueTrend| t.issue.id.clone()),
+        Column::new("title", "Title", |t: &IssueTrend| t.issue.title.clone()).max_width(50),
+        Column::new("recent", "Recent", |t: &IssueTrend| {
+            format!("{:.1}", t.recent_rate)
+        })
+        .align_right(),
+        Column::new("baseline", "Baseline", |t: &IssueTrend| {
+            format!("{:.1}", t.baseline_rate)
+        })
+        .align_right(),
+        Column::new("growth", "Growth", |t: &IssueTrend| {
+            format!("x{:.1}", t.growth)
+        })
+        .align_right(),
+        Column::new("trend", "Trend", |t: &IssueTrend| sparkline(&t.buckets, 24)),
+    ])
+    .indent(2)
+}
+
+const DEFAULT_ISSUE_COLUMNS: &[&str] = &["id", "title", "status"];
+/// Stats periods `issue list` can show event counts for, the only ones the issues
+/// endpoint accepts.
+const STATS_PERIODS: &[&str] = &["24h", "14d"];
+const DEFAULT_PROJECT_COLUMNS: &[&str] = &["access", "name", "platform", "slug"];
+
+fn flag(value: Option<bool>) -> String {
+    if value.unwrap_or(false) { "✓" } else { "✗" }.to_string()
+}
+
+fn issue_columns() -> Vec<Column<Issue>> {
+    vec![
+        Column::new("id", "ID", |i: &Issue| match &i.permalink {
+            Some(url) => link(&i.id, url),
+            None => i.id.clone(),
+        }),
+        Column::new("title", "Title", |i: &Issue| i.title.clone())
+            .max_width(60)
+            .flex(),
+        Column::new("status", "Status", |i: &Issue| i.status.clone()),
+        Column::new("level", "Level", |i: &Issue| i.level.clone()).priority(2),
+        Column::new("project", "Project", |i: &Issue| {
+            i.project
+                .as_ref()
+                .map(|p| p.slug.clone())
+                .unwrap_or_else(|| "-".to_string())
+        })
+        .priority(1),
+        Column::new("culprit", "Culprit", |i: &Issue| i.culprit.clone())
+            .max_width(40)
+            .priority(3),
+        Column::new("events", "Events", |i: &Issue| format_count(i.count)).align_right(),
+        Column::new("users", "Users", |i: &Issue| format_count(i.user_count))
+            .align_right()
+            .priority(1),
+        Column::new("last_seen", "Last Seen", |i: &Issue| i.last_seen.clone()).priority(2),
+        Column::new("url", "URL", |i: &Issue| {
+            i.permalink.clone().unwrap_or_else(|| "-".to_string())
+        })
+        .priority(4),
+        Column::new("events_24h", "Events (24h)", |i: &Issue| {
+            period_events(i, "24h")
+        })
+        .align_right()
+        .priority(3),
+        Column::new("events_14d", "Events (14d)", |i: &Issue| {
+            period_events(i, "14d")
+        })
+        .align_right()
+        .priority(3),
+    ]
+}
+
+/// Events within a stats period, summed from the issue's histogram for it.
+fn period_events(issue: &Issue, period: &str) -> String {
+    issue
+        .stats
+        .as_ref()
+        .and_then(|stats| stats.get(period))
+        .map(|buckets| {
+            buckets
+                .iter()
+                .map(|(_, count)| count)
+                .sum::<i64>()
+                .to_string()
+        })
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Severity order used when grouping by level, most severe first.
+const LEVELS: &[&str] = &["fatal", "error", "warning", "info", "debug"];
+
+/// Number of already-recorded events shown when `events tail` starts.
+const TAIL_LINES: usize = 10;
+
+/// Events of a poll, newest first as the API returns them, that were not printed yet,
+/// oldest first like tail. `seen` is `None` before the first poll, which only shows the
+/// last [`TAIL_LINES`]; it then keeps the IDs of the latest page, the only events a
+/// later page can repeat.
+fn tail_new_events(
+    events: Vec<SentryEvent>,
+    seen: &mut Option<HashSet<String>>,
+) -> Vec<SentryEvent> {
+    let ids = events.iter().map(|e| e.event_id.clone()).collect();
+    let mut new_events: Vec<SentryEvent> = match seen.replace(ids) {
+        Some(seen) => events
+            .into_iter()
+            .filter(|e| !seen.contains(&e.event_id))
+            .collect(),
+        None => events.into_iter().take(TAIL_LINES).collect(),
+    };
+    new_events.reverse();
+    new_events
+}
+
+fn event_columns() -> Vec<Column<(SentryEvent, String)>> {
+    vec![
+        Column::new("time", "Time", |(e, _): &(SentryEvent, String)| {
+            e.date_created.clone()
+        }),
+        Column::new("level", "Level", |(e, _): &(SentryEvent, String)| {
+            e.level().to_string()
+        }),
+        Column::new("title", "Title", |(e, _): &(SentryEvent, String)| {
+            e.title.clone()
+        })
+        .max_width(50),
+        Column::new("user", "User", |(e, _): &(SentryEvent, String)| {
+            e.user_label().unwrap_or("-").to_string()
+        })
+        .max_width(30),
+        Column::new("os", "OS", |(e, _): &(SentryEvent, String)| {
+            e.context("os").unwrap_or("-").to_string()
+        }),
+        Column::new("device", "Device", |(e, _): &(SentryEvent, String)| {
+            e.context("device").unwrap_or("-").to_string()
+        }),
+        Column::new("runtime", "Runtime", |(e, _): &(SentryEvent, String)| {
+            e.context("runtime").unwrap_or("-").to_string()
+        }),
+        Column::new("browser", "Browser", |(e, _): &(SentryEvent, String)| {
+            e.context("browser").unwrap_or("-").to_string()
+        }),
+        Column::new("link", "Link", |(_, url): &(SentryEvent, String)| {
+            url.clone()
+        }),
+    ]
+}
+
+fn print_event(event: &EventDetails, repo: Option<&Path>) {
+    println!("Event {}: {}", event.event_id, event.title);
+    println!("  {}  {}", event.date_created, event.level());
+
+    if let Some(user) = &event.user {
+        println!("  User: {}", user.summary());
+    }
+    let contexts: Vec<String> = event
+        .context_summary()
+        .into_iter()
+        .map(|(label, value)| format!("{}: {}", label, value))
+        .collect();
+    if !contexts.is_empty() {
+        println!("  {}", contexts.join("  |  "));
+    }
+
+    if let Some(request) = event.request() {
+        println!("\nRequest:");
+        for line in request.lines() {
+            println!("  {}", line);
+        }
+    }
+
+    for exception in event.exceptions().iter().rev() {
+        println!(
+            "\n{}: {}",
+            exception.kind.as_deref().unwrap_or("Error"),
+            exception.value.as_deref().unwrap_or("")
+        );
+        let Some(stacktrace) = &exception.stacktrace else {
+            continue;
+        };
+        // Most recent call first
+        for frame in stacktrace.frames.iter().rev() {
+            println!("  {}", frame);
+            if let (Some(repo), true) = (repo, frame.in_app == Some(true)) {
+                if let Some(blame) = blame_frame(repo, frame) {
+                    println!("      {}", blame);
+                }
+            }
+        }
+    }
+}
+
+/// Describes who last touched a frame's line in the local checkout.
+fn blame_frame(repo: &Path, frame: &Frame) -> Option<String> {
+    let line = frame.line_no?;
+    let file = [frame.abs_path.as_deref(), frame.filename.as_deref()]
+        .into_iter()
+        .flatten()
+        .find_map(|path| git::resolve_source_path(repo, path))?;
+    match git::blame_line(repo, &file, line) {
+        Ok(blame) => Some(format!(
+            "{} {} - {} ({}:{})",
+            blame.short_commit(),
+            blame.author,
+            blame.summary,
+            file.display(),
+            line
+        )),
+        Err(e) => Some(format!("blame unavailable: {}", e)),
+    }
+}
+
+fn format_event_line(event: &SentryEvent) -> String {
+    format!(
+        "{}  {:<7}  {}  [{}]",
+        event.date_created,
+        event.level(),
+        event.title,
+        event.user_label().unwrap_or("-")
+    )
+}
+
+fn print_issue_list(
+    client: &SentryClient,
+    table: &Table<Issue>,
+    issues: Vec<Issue>,
+    group_by: Option<GroupBy>,
+    format: IssueFormat,
+) {
+    match format {
+        IssueFormat::Table => print_issues(table, issues, group_by),
+        IssueFormat::GhAnnotations => {
+            for issue in &issues {
+                println!("{}", issue_annotation(client, issue));
+            }
+        }
+        IssueFormat::Junit => print!("{}", junit_report(&issues)),
+        IssueFormat::Sarif => {
+            let findings: Vec<(Issue, Vec<Frame>)> = issues
+                .into_iter()
+                .map(|issue| {
+                    let frames = issue_frames(client, &issue);
+                    (issue, frames)
+                })
+                .collect();
+            println!("{}", sarif_report(&findings));
+        }
+        IssueFormat::Ids => {
+            for issue in &issues {
+                println!("{}", issue.id);
+            }
+        }
+    }
+}
+
+/// Annotation at the crash frame of the issue's latest event, or without a location
+/// when there is no usable frame.
+fn issue_annotation(client: &SentryClient, issue: &Issue) -> String {
+    let frames = issue_frames(client, issue);
+    let frame = crash_frame(&frames);
+    gh_annotation(
+        issue,
+        frame.and_then(|f| f.filename.as_deref()),
+        frame.and_then(|f| f.line_no),
+    )
+}
+
+/// Frames of the last exception in the issue's latest event, innermost last, with
+/// `filename` made relative to the current checkout where the file can be found there.
+fn issue_frames(client: &SentryClient, issue: &Issue) -> Vec<Frame> {
+    let Ok(event) = client.get_latest_event(&issue.id) else {
+        return Vec::new();
+    };
+    event
+        .crash_frames()
+        .into_iter()
+        .map(|mut frame| {
+            let resolved = [frame.abs_path.as_deref(), frame.filename.as_deref()]
+                .into_iter()
+                .flatten()
+                .find_map(|p| git::resolve_source_path(Path::new("."), p));
+            match resolved {
+                Some(path) => frame.filename = Some(path.to_string_lossy().replace('\\', "/")),
+                None => {
+                    frame.filename = frame
+                        .filename
+                        .or(frame.abs_path.clone())
+                        .map(|p| p.trim_start_matches('/').to_string())
+                }
+            }
+            frame
+        })
+        .collect()
+}
+
+fn print_issues(table: &Table<Issue>, issues: Vec<Issue>, group_by: Option<GroupBy>) {
+    if issues.is_empty() {
+        println!("  No issues found");
+    } else if let Some(group_by) = group_by {
+        for (group, issues) in group_issues(issues, group_by) {
+            println!(
+                "\n  {} ({} issues, {} events, {} users)",
+                group,
+                issues.len(),
+                format_count(issues.iter().map(|i| u64::from(i.count)).sum::<u64>()),
+                format_count(issues.iter().map(|i| u64::from(i.user_count)).sum::<u64>())
+            );
+            print!("{}", table.render(&issues));
+        }
+    } else {
+        print!("{}", table.render(&issues));
+    }
+}
+
+/// Expands a `[org/]pattern` target to the matching project slugs per organization
+/// name. Cached projects are used when any match; otherwise the organization's
+/// projects are fetched and cached.
+fn expand_project_pattern(
+    config: &mut Config,
+    client: &mut SentryClient,
+    target: &str,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let (org_names, pattern) = match target.split_once('/') {
+        Some((org, pattern)) => {
+            login_org(config, client, org)?;
+            (vec![org.to_string()], pattern)
+        }
+        None => (
+            config
+                .accounts()
+                .iter()
+                .map(|org| org.name.clone())
+                .collect(),
+            target,
+        ),
+    };
+
+    let mut expanded = Vec::new();
+    for org_name in org_names {
+        let Some(org) = config.get_organization(&org_name) else {
+            continue;
+        };
+        let Some(token) = org.get_auth_token()? else {
+            continue;
+        };
+
+        let mut projects: Vec<String> = org
+            .projects
+            .keys()
+            .filter(|slug| glob_match(pattern, slug))
+            .cloned()
+            .collect();
+        if projects.is_empty() {
+            let org_slug = org.slug.clone();
+            client.login(token)?;
+            let fetched = client.list_projects(&org_slug)?;
+            projects = fetched
+                .iter()
+                .map(|p| p.slug.clone())
+                .filter(|slug| glob_match(pattern, slug))
+                .collect();
+            config.cache_projects(
+                &org_name,
+                fetched.into_iter().map(|p| (p.slug, p.name)).collect(),
+            )?;
+        }
+
+        if !projects.is_empty() {
+            projects.sort();
+            expanded.push((org_name, projects));
+        }
+    }
+    expanded.sort();
+    Ok(expanded)
+}
+
+/// Resolves a `[org/]team` to the slugs of the projects the team owns per organization
+/// name, looking in every configured organization when none is given.
+fn team_projects(
+    config: &Config,
+    client: &mut SentryClient,
+    target: &str,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let (org_names, team, explicit) = match target.split_once('/') {
+        Some((org, team)) => (vec![org.to_string()], team, true),
+        None => (
+            config
+                .accounts()
+                .iter()
+                .map(|org| org.name.clone())
+                .collect(),
+            target,
+            false,
+        ),
+    };
+    let filters = ProjectFilters {
+        team: Some(team.to_string()),
+        ..ProjectFilters::default()
+    };
+
+    let mut expanded = Vec::new();
+    for org_name in org_names {
+        let org = match login_org(config, client, &org_name) {
+            Ok(org) => org,
+            // Organizations without a token are skipped unless asked for by name
+            Err(_) if !explicit => continue,
+            Err(e) => return Err(e),
+        };
+        let mut projects: Vec<String> = client
+            .list_projects(&org.slug)?
+            .into_iter()
+            .filter(|project| filters.matches(project))
+            .map(|project| project.slug)
+            .collect();
+        if !projects.is_empty() {
+            projects.sort();
+            expanded.push((org_name, projects));
+        }
+    }
+    expanded.sort();
+    Ok(expanded)
+}
+
+fn group_issues(issues: Vec<Issue>, group_by: GroupBy) -> Vec<(String, Vec<Issue>)> {
+    let mut groups: Vec<(String, Vec<Issue>)> = Vec::new();
+    for issue in issues {
+        let key = match group_by {
+            GroupBy::Project => project_slug(&issue),
+            GroupBy::Level => issue.level.clone(),
+            GroupBy::Culprit => culprit_module(&issue.culprit),
+        };
+        match groups.iter_mut().find(|(name, _)| *name == key) {
+            Some((_, group)) => group.push(issue),
+            None => groups.push((key, vec![issue])),
+        }
+    }
+
+    match group_by {
+        GroupBy::Project => {
+            groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)))
+        }
+        GroupBy::Level => groups.sort_by_key(|(level, _)| {
+            LEVELS
+                .iter()
+                .position(|l| l == level)
+                .unwrap_or(LEVELS.len())
+        }),
+        // The module behind the most events first
+        GroupBy::Culprit => groups.sort_by_key(|(module, issues)| {
+            (
+                std::cmp::Reverse(issues.iter().map(|i| u64::from(i.count)).sum::<u64>()),
+                module.clone(),
+            )
+        }),
+    }
+    groups
+}
+
+/// The module or file of a culprit like `app.payments in charge`, `charge(app/pay.js)`
+/// or `app/pay.js:42`, so issues raised at different places of one module cluster.
+fn culprit_module(culprit: &str) -> String {
+    let culprit = culprit.trim();
+    let location = match culprit.split_once(" in ") {
+        Some((location, _)) => location,
+        None => match culprit.strip_suffix(')').and_then(|c| c.split_once('(')) {
+            Some((_, location)) if !location.is_empty() => location,
+            _ => culprit,
+        },
+    };
+    let module = location.trim_end_matches(|c: char| c.is_ascii_digit() || c == ':');
+    if module.is_empty() {
+        "(no culprit)".to_string()
+    } else {
+        module.to_string()
+    }
+}
+
+fn project_columns() -> Vec<Column<(Project, String)>> {
+    vec![
+        Column::new("access", "Access", |(p, _): &(Project, String)| {
+            flag(p.has_access)
+        }),
+        Column::new("name", "Name", |(p, url): &(Project, String)| {
+            link(&p.name, url)
+        })
+        .max_width(40),
+        Column::new("platform", "Platform", |(p, _): &(Project, String)| {
+            p.platform.clone().unwrap_or_else(|| "-".to_string())
+        }),
+        Column::new("slug", "Slug", |(p, _): &(Project, String)| p.slug.clone()),
+        Column::new("id", "ID", |(p, _): &(Project, String)| {
+            p.id.clone().unwrap_or_else(|| "-".to_string())
+        }),
+        Column::new("status", "Status", |(p, _): &(Project, String)| {
+            p.status.clone()
+        }),
+        Column::new("bookmarked", "Bookmarked", |(p, _): &(Project, String)| {
+            flag(p.is_bookmarked)
+        }),
+        Column::new("member", "Member", |(p, _): &(Project, String)| {
+            flag(p.is_member)
+        }),
+        Column::new("teams", "Teams", |(p, _): &(Project, String)| {
+            p.teams
+                .as_ref()
+                .map(|teams| {
+                    teams
+                        .iter()
+                        .map(|t| t.slug.as_str())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .unwrap_or_default()
+        })
+        .max_width(30),
+    ]
+}
+
+/// Looks up an issue by numeric ID, short ID or URL across the configured organizations,
+/// returning it with the slug of the organization it belongs to.
+fn find_issue(
+    config: &Config,
+    client: &mut SentryClient,
+    id: &str,
+) -> Result<Option<(String, Issue)>> {
+    let issue = match IssueRef::parse(id)? {
+        IssueRef::Id(id) => {
+            // Tokens are per organization, so try each until one can see the issue
+            let mut found = None;
+            for org in config.accounts() {
+                if let Some(token) = org.get_auth_token()? {
+                    client.login(token)?;
+                    if let Ok(issue) = client.get_issue(&id) {
+                        found = Some((org.slug.clone(), issue));
+                        break;
+                    }
+                }
+            }
+            found
+        }
+        IssueRef::ShortId(short_id) => {
+            let mut found = None;
+            for org in config.accounts() {
+                if let Some(token) = org.get_auth_token()? {
+                    client.login(token)?;
+                    if let Ok(issue) = client.resolve_short_id(&org.slug, &short_id) {
+                        found = Some((org.slug.clone(), issue));
+                        break;
+                    }
+                }
+            }
+            found
+        }
+        IssueRef::Url { org_slug, id } => {
+            let org = config.get_organization_by_slug(&org_slug).ok_or_else(|| {
+                not_found(format!(
+                    "Organization with slug '{}' not found. Add it first with 'org add'.",
+                    org_slug
+                ))
+            })?;
+            let token = org.get_auth_token()?.ok_or_else(|| {
+                auth_error(format!(
+                    "Not logged in for organization '{}'. Use 'login' first.",
+                    org.name
+                ))
+            })?;
+            client.login(token)?;
+            Some((org.slug.clone(), client.get_issue(&id)?))
+        }
+    };
+    Ok(issue)
+}
+
+fn assignee_name(issue: &Issue) -> String {
+    issue
+        .assigned_to
+        .as_ref()
+        .map(|a| a.to_string())
+        .unwrap_or_else(|| "nobody".to_string())
+}
+
+/// Prints a snapshot diff; `unavailable` are gone issues whose current status could
+/// not be fetched.
+fn print_snapshot_diff(diff: &SnapshotDiff, unavailable: &HashSet<String>) {
+    let label = |issue: &Issue| {
+        format!(
+            "{}  {}",
+            issue.short_id.as_deref().unwrap_or(&issue.id),
+            issue.title
+        )
+    };
+    let changed: Vec<(&Issue, Vec<String>)> = diff
+        .kept
+        .iter()
+        .map(|(before, now)| (now, issue_changes(before, now)))
+        .filter(|(_, changes)| !changes.is_empty())
+        .collect();
+
+    if !diff.new.is_empty() {
+        println!("\nNew ({}):", diff.new.len());
+        for issue in &diff.new {
+            println!(
+                "  + {}  ({} events)",
+                label(issue),
+                format_count(issue.count)
+            );
+        }
+    }
+    if !diff.gone.is_empty() {
+        println!("\nGone ({}):", diff.gone.len());
+        for issue in &diff.gone {
+            if unavailable.contains(&issue.id) {
+                println!(
+                    "  - {}  (current status unavailable, was {})",
+                    label(issue),
+                    issue.status
+                );
+            } else {
+                println!("  - {}  ({})", label(issue), issue.status);
+            }
+        }
+    }
+    if !changed.is_empty() {
+        println!("\nChanged ({}):", changed.len());
+        for (issue, changes) in &changed {
+            println!("  ~ {}  {}", label(issue), changes.join(", "));
+        }
+    }
+    println!(
+        "\n{} new, {} gone, {} changed, {} unchanged",
+        diff.new.len(),
+        diff.gone.len(),
+        changed.len(),
+        diff.kept.len() - changed.len()
+    );
+}
+
+/// Describes what changed between two snapshots of the same issue.
+fn issue_changes(previous: &Issue, current: &Issue) -> Vec<String> {
+    let mut changes = Vec::new();
+    if current.count != previous.count {
+        changes.push(format!(
+            "events {} -> {} (+{})",
+            previous.count,
+            current.count,
+            current.count.saturating_sub(previous.count)
+        ));
+    }
+    if current.user_count != previous.user_count {
+        changes.push(format!(
+            "users {} -> {}",
+            previous.user_count, current.user_count
+        ));
+    }
+    if current.status != previous.status {
+        changes.push(format!("status {} -> {}", previous.status, current.status));
+    }
+    if current.assigned_to != previous.assigned_to {
+        changes.push(format!(
+            "assignee {} -> {}",
+            assignee_name(previous),
+            assignee_name(current)
+        ));
+    }
+    changes
+}
+
+/// Current UTC time of day as HH:MM:SS.
+fn clock_time() -> String {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+        % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Warns after login when a token's scopes do not fit what the CLI uses. Failing to
+/// look the scopes up does not fail the login.
+fn warn_about_scopes(client: &SentryClient) {
+    match client.get_token_scopes() {
+        Ok(scopes) => {
+            for warning in check_scopes(&scopes).warnings() {
+                println!("Warning: {}", warning);
+            }
+        }
+        Err(e) => eprintln!("Could not determine token scopes: {}", e),
+    }
+}
+
+fn print_org_info(org: &OrganizationDetails) {
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+    println!("{} ({})", org.name, org.slug);
+    if let Some(created) = &org.date_created {
+        println!("  Created:          {}", created);
+    }
+    println!(
+        "  Default role:     {}",
+        org.default_role.as_deref().unwrap_or("-")
+    );
+    println!("  Open membership:  {}", yes_no(org.open_membership));
+    println!("  Require 2FA:      {}", yes_no(org.require_2fa));
+    println!("  Trusted relays:   {}", org.trusted_relays.len());
+    for relay in &org.trusted_relays {
+        match &relay.description {
+            Some(description) if !description.is_empty() => {
+                println!(
+                    "    - {} ({}): {}",
+                    relay.name, relay.public_key, description
+                )
+            }
+            _ => println!("    - {} ({})", relay.name, relay.public_key),
+        }
+    }
+    println!("  Features:         {}", org.features.len());
+    let mut features = org.features.clone();
+    features.sort();
+    for feature in features {
+        println!("    - {}", feature);
+    }
+}
+
+fn print_usage(org_name: &str, period: &str, usage: &[CategoryUsage]) {
+    println!("Usage for organization: {} (last {})\n", org_name, period);
+    if usage.is_empty() {
+        println!("  No usage recorded");
+        return;
+    }
+
+    let table = Table::new(vec![
+        Column::new("category", "Category", |u: &CategoryUsage| {
+            u.category.clone()
+        }),
+        Column::new("accepted", "Accepted", |u: &CategoryUsage| {
+            u.accepted.to_string()
+        })
+        .align_right(),
+        Column::new("quota", "Quota", |u: &CategoryUsage| {
+            u.quota.map_or("-".to_string(), |q| q.to_string())
+        })
+        .align_right(),
+        Column::new("used", "Used", |u: &CategoryUsage| {
+            u.used_percent()
+                .map_or("-".to_string(), |p| format!("{:.0}%", p))
+        })
+        .align_right(),
+        Column::new("dropped", "Rate limited", |u: &CategoryUsage| {
+            u.rate_limited.to_string()
+        })
+        .align_right(),
+    ])
+    .indent(2);
+    print!("{}", table.render(usage));
+
+    let warnings: Vec<String> = usage.iter().filter_map(CategoryUsage::warning).collect();
+    if !warnings.is_empty() {
+        println!();
+        for warning in warnings {
+            println!("  Warning: {}", warning);
+        }
+    }
+}
+
+/// Parses a percentage such as `1%` or `0.5`.
+fn percent_arg(value: &str) -> std::result::Result<f64, String> {
+    value
+        .trim_end_matches('%')
+        .parse::<f64>()
+        .ok()
+        .filter(|p| (0.0..=100.0).contains(p))
+        .ok_or_else(|| format!("expected a percentage between 0 and 100, got '{}'", value))
+}
+
+fn alert_arg(value: &str) -> std::result::Result<AlertRule, String> {
+    AlertRule::parse(value).map_err(|e| e.to_string())
+}
+
+fn quota_arg(value: &str) -> std::result::Result<(String, u64), String> {
+    let (category, count) = value
+        .split_once('=')
+        .filter(|(category, _)| !category.is_empty())
+        .ok_or_else(|| format!("expected category=count, got '{}'", value))?;
+    let count = count
+        .parse()
+        .map_err(|_| format!("invalid quota '{}' for {}", count, category))?;
+    Ok((category.to_string(), count))
+}
+
+fn print_rate_limits(org_name: &str, limits: &RateLimitStatus) {
+    println!("Rate limits for organization: {}", org_name);
+    if limits.is_empty() {
+        println!("\n  The server did not report any rate limits");
+        return;
+    }
+
+    let value = |v: Option<u64>| v.map_or("-".to_string(), |v| v.to_string());
+    println!(
+        "\n  Limit:      {} requests per window",
+        value(limits.limit)
+    );
+    println!("  Remaining:  {}", value(limits.remaining));
+    if let Some(reset) = limits.reset {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        println!("  Reset:      in {}s", reset.saturating_sub(now));
+    }
+    if limits.concurrent_limit.is_some() || limits.concurrent_remaining.is_some() {
+        println!(
+            "  Concurrent: {} of {} available",
+            value(limits.concurrent_remaining),
+            value(limits.concurrent_limit)
+        );
+    }
+}
+
+/// Prints a metric with its change, red when it went up since more errors is worse.
+fn print_delta(delta: &MetricDelta) -> Result<()> {
+    let (arrow, color) = match delta.current.cmp(&delta.previous) {
+        std::cmp::Ordering::Greater => ("▲", Color::Red),
+        std::cmp::Ordering::Less => ("▼", Color::Green),
+        std::cmp::Ordering::Equal => ("=", Color::Reset),
+    };
+    let change = match delta.percent_change() {
+        Some(percent) => format!("{:+.1}%", percent),
+        None if delta.current == 0 => "0.0%".to_string(),
+        None => "new".to_string(),
+    };
+
+    let metric = format!(
+        "  {:<16}{:>10}  (was {})  ",
+        delta.label, delta.current, delta.previous
+    );
+    // Colors would end up as escape codes in files and pipes
+    if !io::stdout().is_terminal() {
+        println!("{}{} {}", metric, arrow, change);
+        return Ok(());
+    }
+    execute!(
+        io::stdout(),
+        Print(metric),
+        SetForegroundColor(color),
+        Print(format!("{} {}", arrow, change)),
+        SetForegroundColor(Color::Reset),
+        Print("\n")
+    )?;
+    Ok(())
+}
+
+/// Opens an issue in the interactive viewer, with its crashing file and, while nobody
+/// is assigned, assignee suggestions.
+fn view_issue(
+    config: &Config,
+    client: &SentryClient,
+    org_slug: &str,
+    issue: Issue,
+    repo: Option<PathBuf>,
+) -> Result<()> {
+    // Issues without events (e.g. after deletion) still open, without the raw JSON view
+    let raw_event = client.get_latest_event_json(&issue.id).ok();
+    let event: Option<EventDetails> = raw_event
+        .clone()
+        .and_then(|json| serde_json::from_value(json).ok());
+    // Only look up the crashing file when there is a checkout to open it in
+    let source = match (repo.or_else(|| config.source_root.clone()), &event) {
+        (Some(root), Some(event)) => crash_location(event, &root),
+        _ => None,
+    };
+    let suggestions = if issue.assigned_to.is_none() {
+        fetch_suggestions(client, org_slug, &issue)
+            .map(|(_, suggestions)| suggestions)
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let affected = client.list_issue_users(&issue.id).unwrap_or_default();
+    let grouping = grouping_lines(client, org_slug, &issue, event.as_ref()).unwrap_or_default();
+    show_issue(
+        issue,
+        source,
+        suggestions,
+        affected,
+        grouping,
+        event.as_ref(),
+        raw_event,
+    )
+}
+
+/// Gathers tags, the latest event and suspect commits of an issue into a report.
+fn fetch_issue_report(client: &SentryClient, org_slug: &str, issue: Issue) -> Result<IssueReport> {
+    let tags = client.list_issue_tags(&issue.id)?;
+    // Issues without events (e.g. after deletion) still get a report
+    let event = client.get_latest_event(&issue.id).ok();
+    let committers = match (&event, &issue.project) {
+        (Some(event), Some(project)) => client
+            .list_event_committers(org_slug, &project.slug, &event.event_id)
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    Ok(IssueReport {
+        issue,
+        tags,
+        event,
+        committers,
+    })
+}
+
+/// Describes how an issue's events were grouped, with the variants of its latest event
+/// when it has one.
+fn grouping_lines(
+    client: &SentryClient,
+    org_slug: &str,
+    issue: &Issue,
+    event: Option<&EventDetails>,
+) -> Result<Vec<String>> {
+    let hashes = client.list_issue_hashes(&issue.id)?;
+    let (variants, unavailable) = match (event, &issue.project) {
+        (Some(event), Some(project)) => {
+            match client.get_grouping_info(org_slug, &project.slug, &event.event_id) {
+                Ok(variants) => (variants, None),
+                // The hashes still tell what merged into the issue
+                Err(e) => (
+                    Default::default(),
+                    Some(format!("Variants of the latest event unavailable: {}", e)),
+                ),
+            }
+        }
+        _ => Default::default(),
+    };
+    let mut lines = GroupingInfo {
+        issue,
+        hashes,
+        variants,
+    }
+    .lines();
+    lines.extend(unavailable);
+    Ok(lines)
+}
+
+/// Finds the crashing frame of an issue's latest event in a local checkout.
+fn crash_location(event: &EventDetails, root: &Path) -> Option<SourceLocation> {
+    let frame = event.crash_frame()?;
+
+    let path = [frame.abs_path.as_deref(), frame.filename.as_deref()]
+        .into_iter()
+        .flatten()
+        .find_map(|path| git::resolve_source_path(root, path))
+        .or_else(|| {
+            frame
+                .module
+                .as_deref()
+                .and_then(|module| git::resolve_module_path(root, module))
+        });
+    path.map(|path| SourceLocation {
+        path: root.join(path),
+        line: frame.line_no.unwrap_or(1),
+    })
+}
+
+fn show_issue(
+    issue: Issue,
+    source: Option<SourceLocation>,
+    suggestions: Vec<AssigneeSuggestion>,
+    affected: Vec<AffectedUser>,
+    grouping: Vec<String>,
+    event: Option<&EventDetails>,
+    raw_event: Option<serde_json::Value>,
+) -> Result<()> {
+    let viewer_issue = ViewerIssue {
+        id: issue.id,
+        title: issue.title,
+        status: issue.status,
+        level: issue.level,
+        culprit: issue.culprit,
+        last_seen: issue.last_seen,
+        events: issue.count,
+        users: issue.user_count,
+    };
+
+    let mut viewer = IssueViewer::new(viewer_issue)?
+        .with_source(source)
+        .with_suggestions(suggestions)
+        .with_contexts(
+            event
+                .map(|event| event.context_summary())
+                .unwrap_or_default(),
+        )
+        .with_users(event.and_then(|event| event.user.as_ref()), &affected)
+        .with_request(event.and_then(|event| event.request()))
+        .with_grouping(grouping)
+        .with_raw_event(raw_event.as_ref());
+    viewer.show()
+}
+
+/// Filters and outputs shared by every dashboard of a monitor.
+struct MonitorOptions {
+    search: String,
+    alerts: Vec<AlertRule>,
+    notify: bool,
+    event_log: Option<Arc<EventLog>>,
+}
+
+impl MonitorOptions {
+    fn apply(&self, dashboard: Dashboard) -> Dashboard {
+        dashboard
+            .with_query(self.search.clone())
+            .with_alerts(self.alerts.clone())
+            .with_notifications(self.notify)
+            .with_event_log(self.event_log.clone())
+    }
+}
+
+fn start_monitor(
+    config: &mut Config,
+    client: &SentryClient,
+    org_slug: String,
+    projects: Vec<String>,
+    options: &MonitorOptions,
+) -> Result<()> {
+    println!(
+        "Starting monitor for organization: {} project(s): {}",
+        org_slug,
+        projects.join(", ")
+    );
+    let target = MonitorTarget {
+        org_slug: org_slug.clone(),
+        projects: projects.clone(),
+    };
+    if config.last_monitor.as_ref() != Some(&target) {
+        config.last_monitor = Some(target);
+        config.save()?;
+    }
+    let muted = config
+        .get_organization_by_slug(&org_slug)
+        .map(|org| org.muted_issues.clone())
+        .unwrap_or_default();
+    let mut dashboard = options
+        .apply(Dashboard::new(client.clone(), org_slug.clone(), projects))
+        .with_muted(muted)
+        .with_searches(config.searches.clone());
+    let result = dashboard.run();
+    config.set_muted_issues(&org_slug, dashboard.muted_issues().clone())?;
+    config.set_searches(dashboard.searches().clone())?;
+    result
+}
+
+/// Monitors `[org/]project` patterns in tabs, one per pattern and organization, each
+/// polled with the organization's own token.
+fn start_tabbed_monitor(
+    config: &mut Config,
+    client: &mut SentryClient,
+    patterns: &[String],
+    options: &MonitorOptions,
+) -> Result<()> {
+    let mut tabs = Vec::new();
+    for pattern in patterns {
+        let expanded = expand_project_pattern(config, client, pattern)?;
+        if expanded.is_empty() {
+            return Err(not_found(format!("No projects match '{}'", pattern)));
+        }
+        let project_pattern = pattern.split_once('/').map_or(pattern.as_str(), |(_, p)| p);
+        for (org_name, projects) in expanded {
+            let org = login_org(config, client, &org_name)?;
+            let label = match projects.as_slice() {
+                [project] => format!("{}/{}", org.slug, project),
+                _ => format!("{}/{}", org.slug, project_pattern),
+            };
+            let muted = org.muted_issues.clone();
+            tabs.push(
+                options
+                    .apply(Dashboard::new(client.clone(), org.slug.clone(), projects))
+                    .with_label(label)
+                    .with_muted(muted)
+                    .with_searches(config.searches.clone()),
+            );
+        }
+    }
+    println!("Starting monitor with {} tab(s)", tabs.len());
+
+    let result = run_tabs(&mut tabs);
+    // Tabs of one organization each own the mutes of their projects
+    let mut muted: HashMap<String, HashMap<String, Vec<String>>> = HashMap::new();
+    for tab in &tabs {
+        let org_muted = muted.entry(tab.org_slug().to_string()).or_insert_with(|| {
+            config
+                .get_organization_by_slug(tab.org_slug())
+                .map(|org| org.muted_issues.clone())
+                .unwrap_or_default()
+        });
+        for project in tab.projects() {
+            match tab.muted_issues().get(project) {
+                Some(ids) => org_muted.insert(project.clone(), ids.clone()),
+                None => org_muted.remove(project),
+            };
+        }
+    }
+    for (org_slug, org_muted) in muted {
+        config.set_muted_issues(&org_slug, org_muted)?;
+    }
+    // Every tab holds the latest searches once the monitor exits
+    if let Some(tab) = tabs.first() {
+        config.set_searches(tab.searches().clone())?;
+    }
+    result
+}
+
+/// Narrows an issue search to an assignee, resolving "me" to the logged-in user.
+fn assignee_query(
+    client: &SentryClient,
+    query: &IssueQuery,
+    assignee: Option<&str>,
+) -> Result<IssueQuery> {
+    let Some(assignee) = assignee else {
+        return Ok(query.clone());
+    };
+    let assignee = if assignee == "me" {
+        client
+            .get_current_user()
+            .context("Cannot resolve 'me'; pass an email instead")?
+            .handle()
+            .to_string()
+    } else {
+        assignee.to_string()
+    };
+    Ok(IssueQuery {
+        query: format!("{} assigned:{}", query.query, assignee)
+            .trim()
+            .to_string(),
+        ..query.clone()
+    })
+}
+
+/// Narrows an issue search to issues first seen in a release.
+fn first_release_query(query: &IssueQuery, version: &str) -> IssueQuery {
+    IssueQuery {
+        query: format!("{} firstRelease:\"{}\"", query.query, version)
+            .trim()
+            .to_string(),
+        ..query.clone()
+    }
+}
+
+/// Search polled by the monitor: the given query, or unresolved issues, narrowed by level.
+fn monitor_search(query: Option<String>, levels: &[String]) -> String {
+    let mut search = query.unwrap_or_else(|| "is:unresolved".to_string());
+    if let Some(level) = level_search(levels) {
+        search = format!("{} {}", search, level).trim().to_string();
+    }
+    search
+}
+
+/// Search filter for issues of any of the given levels, e.g. `level:[fatal,error]`.
+fn level_search(levels: &[String]) -> Option<String> {
+    let mut levels: Vec<&str> = levels.iter().map(String::as_str).collect();
+    levels.sort_by_key(|level| LEVELS.iter().position(|known| known == level));
+    levels.dedup();
+    match levels.as_slice() {
+        [] => None,
+        [level] => Some(format!("level:{}", level)),
+        levels => Some(format!("level:[{}]", levels.join(","))),
+    }
+}
+
+/// Warns when a slug does not name an existing organization, suggesting similar
+/// slugs, so typos surface now instead of as 404s in later commands.
+fn verify_org_slug(client: &SentryClient, slug: &str) {
+    match client.get_organization(slug) {
+        Ok(org) => println!("  Verified on Sentry as '{}'", org.name),
+        Err(e) if exit_code(&e) == ExitCode::NotFound => {
+            eprintln!(
+                "Warning: organization '{}' does not exist on {}",
+                slug,
+                client.web_url()
+            );
+            let slugs: Vec<String> = client
+                .list_organizations()
+                .map(|orgs| orgs.into_iter().map(|org| org.slug).collect())
+                .unwrap_or_default();
+            let similar = similar_slugs(slug, &slugs);
+            if !similar.is_empty() {
+                eprintln!("  Did you mean: {}?", similar.join(", "));
+            }
+        }
+        Err(e) => eprintln!("Warning: could not verify organization '{}': {}", slug, e),
+    }
+}
+
+/// Slugs within two edits of the given one, or containing it, closest first.
+fn similar_slugs<'a>(slug: &str, candidates: &'a [String]) -> Vec<&'a str> {
+    let mut similar: Vec<(usize, &str)> = candidates
+        .iter()
+        .map(|candidate| (edit_distance(slug, candidate), candidate.as_str()))
+        .filter(|(distance, candidate)| *distance <= 2 || candidate.contains(slug))
+        .collect();
+    similar.sort();
+    similar
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Uses the given organization name, or the only configured organization when omitted.
+fn default_org_name(config: &Config, name: Option<String>) -> Result<String> {
+    if let Some(name) = name {
+        return Ok(name);
+    }
+    let accounts = config.accounts();
+    let mut names = accounts.iter().map(|org| &org.name);
+    match (names.next(), names.next()) {
+        (Some(name), None) => Ok(name.clone()),
+        (None, _) => Err(anyhow::anyhow!(
+            "No organizations configured. Add one first with 'org add'."
+        )),
+        _ => Err(anyhow::anyhow!(
+            "Multiple organizations configured. Choose one with --org."
+        )),
+    }
+}
+
+/// Parses `repo@from..to` or `repo@to` into a release commit range.
+fn release_ref_arg(value: &str) -> std::result::Result<ReleaseRef, String> {
+    let (repository, range) = value
+        .rsplit_once('@')
+        .filter(|(repo, range)| !repo.is_empty() && !range.is_empty())
+        .ok_or_else(|| format!("expected repo@from..to or repo@to, got '{}'", value))?;
+    let (previous_commit, commit) = match range.split_once("..") {
+        Some((from, to)) if !from.is_empty() && !to.is_empty() => {
+            (Some(from.to_string()), to.to_string())
+        }
+        Some(_) => return Err(format!("invalid commit range '{}'", range)),
+        None => (None, range.to_string()),
+    };
+    Ok(ReleaseRef {
+        repository: repository.to_string(),
+        commit,
+        previous_commit,
+    })
+}
+
+/// Looks up a configured organization by name and logs the client in with its token.
+fn login_org<'a>(
+    config: &'a Config,
+    client: &mut SentryClient,
+    name: &str,
+) -> Result<&'a Organization> {
+    let org = config.get_organization(name).ok_or_else(|| {
+        not_found(format!(
+            "Organization '{}' not found. Add it first with 'org add'.",
+            name
+        ))
+    })?;
+    let org = config.account_for(org);
+
+    let token = org.get_auth_token()?.ok_or_else(|| {
+        auth_error(format!(
+            "Not logged in for organization '{}'. Use 'login' first.",
+            org.name
+        ))
+    })?;
+
+    client.login(token)?;
+    Ok(org)
+}
+
+/// Caches an organization's projects after login so bare project names resolve
+/// without live lookups. Failures are reported but do not fail the login.
+fn discover_projects(config: &mut Config, client: &SentryClient, org_name: &str) {
+    let Some(org_slug) = config.get_organization(org_name).map(|o| o.slug.clone()) else {
+        return;
+    };
+    print!("Discovering projects...");
+    let _ = io::stdout().flush();
+
+    let result = client.list_projects(&org_slug).and_then(|projects| {
+        let count = projects.len();
+        config.cache_projects(
+            org_name,
+            projects.into_iter().map(|p| (p.slug, p.name)).collect(),
+        )?;
+        Ok(count)
+    });
+    match result {
+        Ok(count) => println!(" cached {} project(s)", count),
+        Err(e) => {
+            println!(" failed");
+            eprintln!("Could not discover projects: {}", e);
+        }
+    }
+}
+
+/// Adds the organization, token, server and default project sentry-cli is set up with.
+fn import_sentry_cli(config: &mut Config, settings: SentryCliSettings) -> Result<()> {
+    let org_slug = settings
+        .org
+        .clone()
+        .context("No organization found in ~/.sentryclirc ([defaults] org) or SENTRY_ORG")?;
+    let org_name = config
+        .get_organization_by_slug(&org_slug)
+        .map(|org| org.name.clone())
+        .unwrap_or_else(|| org_slug.clone());
+    if !config.organizations.contains_key(&org_name) {
+        config.add_organization(org_name.clone(), org_slug.clone());
+        println!("Added organization: {} ({})", org_name, org_slug);
+    }
+
+    if let Some(url) = settings.self_hosted_url() {
+        println!("Using Sentry server: {}", url);
+        config.url = Some(url);
+    }
+    if let Some(project) = &settings.project {
+        if config.default_project.is_none() {
+            let target = format!("{}/{}", org_name, project);
+            println!("Default project set to {}", target);
+            config.default_project = Some(target);
+        }
+    }
+    config.save()?;
+
+    match settings.token {
+        Some(token) => {
+            config
+                .get_organization_mut(&org_name)
+                .context("Organization was not added")?
+                .set_token(TokenRole::Read, token)?;
+            println!("Imported auth token for organization: {}", org_name);
+        }
+        None => println!(
+            "No auth token found; run 'sex login {}' to authenticate",
+            org_name
+        ),
+    }
+    Ok(())
+}
+
+/// Issues matching the query of `check`, one more than `max` at most: enough to tell
+/// whether the gate fails, over as many pages as that takes.
+fn fetch_check_issues(
+    client: &SentryClient,
+    org_slug: &str,
+    project: &str,
+    query: &str,
+    max: usize,
+) -> Result<Vec<Issue>> {
+    let issue_query = IssueQuery {
+        query: query.to_string(),
+        limit: max.saturating_add(1),
+        ..IssueQuery::default()
+    };
+    client.list_project_issues(org_slug, project, &issue_query)
+}
+
+/// Every issue of a project matching `query`, not only the first page of the last 14
+/// days; an empty stats period leaves the window open.
+fn all_project_issues(
+    client: &SentryClient,
+    org_slug: &str,
+    project: &str,
+    query: String,
+) -> Result<Vec<Issue>> {
+    client.list_project_issues(
+        org_slug,
+        project,
+        &IssueQuery {
+            query,
+            stats_period: String::new(),
+            limit: usize::MAX,
+            ..IssueQuery::default()
+        },
+    )
+}
+
+/// Compares the issues of two releases. Every page is fetched: an issue of the older
+/// release missing from a partial list of the newer one would count as resolved.
+fn fetch_release_diff(
+    client: &SentryClient,
+    org_slug: &str,
+    project: &str,
+    from: &str,
+    to: &str,
+) -> Result<ReleaseDiff> {
+    let search = |query: String| all_project_issues(client, org_slug, project, query);
+    let seen_in_new = search(format!("release:\"{}\"", to))?;
+    Ok(release_diff(
+        search(format!("firstRelease:\"{}\"", to))?,
+        search(format!("release:\"{}\"", from))?,
+        &seen_in_new,
+    ))
+}
+
+/// Issues resolved in `version` and whether the fixes held. The searches leave out
+/// `release:`, which only matches issues with events in the release, while a fix leaves
+/// none there; the release each issue was resolved in is checked instead.
+fn resolution_states(
+    client: &SentryClient,
+    org_slug: &str,
+    project: &str,
+    version: &str,
+) -> Result<Vec<(Issue, ResolutionState)>> {
+    let resolved = all_project_issues(client, org_slug, project, "is:resolved".to_string())?;
+    let regressed = all_project_issues(client, org_slug, project, "is:regressed".to_string())?;
+    let chunk_size = regressed.len().div_ceil(BULK_CONCURRENCY).max(1);
+    let regressed: Vec<(Issue, Vec<Activity>)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = regressed
+            .chunks(chunk_size)
+            .map(|issues| {
+                scope.spawn(|| {
+                    issues
+                        .iter()
+                        .map(|issue| {
+                            let activities = client.list_issue_activities(&issue.id)?;
+                            Ok((issue.clone(), activities))
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("Activity fetch panicked")))
+            })
+            .collect::<Result<Vec<_>>>()
+    })?
+    .into_iter()
+    .flatten()
+    .collect();
+    // Issues waiting for the next release are only pending on the latest
+    let is_latest = client
+        .get_latest_release(org_slug, project)?
+        .is_some_and(|release| release.version == version);
+    Ok(check_resolved(version, is_latest, resolved, regressed))
+}
+
+/// Polls a release's new issues and crash rate until the window ends, failing as soon
+/// as a threshold is exceeded.
+fn verify_release(
+    client: &SentryClient,
+    org_slug: &str,
+    project: &str,
+    version: &str,
+    window: &str,
+    thresholds: CanaryThresholds,
+    interval: u64,
+) -> Result<()> {
+    let window_secs = parse_stats_period(window)?;
+    let project_id = match thresholds.max_crash_rate {
+        Some(_) => Some(
+            client
+                .get_project(org_slug, project)?
+                .id
+                .context("Project has no ID")?,
+        ),
+        None => None,
+    };
+    let new_issues_query = IssueQuery {
+        query: format!("firstRelease:\"{}\"", version),
+        ..IssueQuery::default()
+    };
+
+    println!(
+        "Verifying release {} of {}/{} for {}, press Ctrl+C to stop",
+        link(version, &client.release_url(org_slug, version)),
+        org_slug,
+        link(project, &client.project_url(org_slug, project)),
+        window
+    );
+    let started = std::time::Instant::now();
+    loop {
+        let new_issues = client.list_project_issues(org_slug, project, &new_issues_query)?;
+        let crash_free_rate = match &project_id {
+            Some(id) => client.get_release_crash_free_rate(org_slug, id, version, window)?,
+            None => None,
+        };
+
+        let elapsed = started.elapsed().as_secs();
+        let crash_free = crash_free_rate
+            .map(|rate| format!("{:.2}%", rate * 100.0))
+            .unwrap_or_else(|| "n/a".to_string());
+        println!(
+            "  [{:>4}s] new issues: {}, crash-free sessions: {}",
+            elapsed,
+            new_issues.len(),
+            crash_free
+        );
+
+        let failures = thresholds.failures(new_issues.len(), crash_free_rate);
+        if !failures.is_empty() {
+            if !new_issues.is_empty() {
+                let table = Table::new(select_columns(
+                    issue_columns(),
+                    &[],
+                    &["id", "title", "level", "events"],
+                )?);
+                print_issues(&table, new_issues, None);
+            }
+            return Err(CliError::new(
+                ExitCode::CheckFailed,
+                format!(
+                    "Release {} failed verification: {}",
+                    version,
+                    failures.join("; ")
+                ),
+            )
+            .into());
+        }
+
+        if elapsed >= window_secs {
+            println!(
+                "PASS: release {} stayed within limits for {}",
+                version, window
+            );
+            return Ok(());
+        }
+        std::thread::sleep(std::time::Duration::from_secs(
+            interval.min(window_secs - elapsed).max(1),
+        ));
+    }
+}
+
+/// First-run wizard: adds an organization, stores and verifies a token, then caches
+/// the organization's projects and offers one as the default target.
+fn run_init(config: &mut Config, client: &mut SentryClient) -> Result<()> {
+    require_terminal(
+        "Setup",
+        "add organizations with 'org add' and 'login' instead",
+    )?;
+    println!("Welcome to sex-cli! Let's connect to Sentry.\n");
+    println!("How do you want to authenticate?");
+    println!("  1) Paste an auth token (https://sentry.io/settings/account/api/auth-tokens/)");
+    println!("  2) Log in with the browser");
+    let browser = loop {
+        match prompt_line("Choice", Some("1"))?.as_str() {
+            "1" => break false,
+            "2" => break true,
+            other => println!("Please enter 1 or 2, not '{}'", other),
+        }
+    };
+
+    let (org_name, org_slug) = if browser {
+        let sentry_org = client.login_with_browser()?;
+        let name = prompt_line("Name for this organization", Some(&sentry_org.slug))?;
+        (name, sentry_org.slug)
+    } else {
+        let slug = loop {
+            let slug = prompt_line(
+                "Organization slug (as in sentry.io/organizations/<slug>/)",
+                None,
+            )?;
+            if !slug.is_empty() {
+                break slug;
+            }
+        };
+        let name = prompt_line("Name for this organization", Some(&slug))?;
+        client.login_with_prompt()?;
+        (name, slug)
+    };
+
+    print!("Verifying token...");
+    io::stdout().flush()?;
+    let projects = match client.list_projects(&org_slug) {
+        Ok(projects) => {
+            println!(" ok");
+            projects
+        }
+        Err(e) => {
+            println!(" failed");
+            return Err(e).context(format!(
+                "Could not access organization '{}' with this token",
+                org_slug
+            ));
+        }
+    };
+
+    if !config.organizations.contains_key(&org_name) {
+        config.add_organization(org_name.clone(), org_slug.clone());
+    }
+    let token = client
+        .get_current_token()
+        .context("No token was obtained")?;
+    config
+        .get_organization_mut(&org_name)
+        .context("Organization was not added")?
+        .set_token(TokenRole::Read, token)?;
+    config.save()?;
+    println!("Saved organization '{}' ({})", org_name, org_slug);
+    warn_about_scopes(client);
+
+    config.cache_projects(
+        &org_name,
+        projects
+            .iter()
+            .map(|p| (p.slug.clone(), p.name.clone()))
+            .collect(),
+    )?;
+    if projects.is_empty() {
+        println!("\nThe organization has no projects yet.");
+    } else {
+        println!("\nProjects:");
+        for (i, project) in projects.iter().enumerate() {
+            println!("  {}) {} ({})", i + 1, project.name, project.slug);
+        }
+        let choice = prompt_line("Default project (number, Enter to skip)", None)?;
+        if let Some(index) = parse_choice(&choice, projects.len()) {
+            let target = format!("{}/{}", org_name, projects[index].slug);
+            config.default_project = Some(target.clone());
+            config.save()?;
+            println!("Default project set to {}", target);
+        }
+    }
+
+    println!("\nAll set. Try 'sex issue list' or 'sex monitor'.");
+    Ok(())
+}
+
+/// Whether someone is at a terminal to answer prompts and use full-screen views. Pipes,
+/// redirects and cron jobs get plain output instead.
+fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Refuses to start a full-screen view without a terminal, pointing at an alternative.
+fn require_terminal(what: &str, alternative: &str) -> Result<()> {
+    if !is_interactive() {
+        anyhow::bail!("{} needs a terminal; {}", what, alternative);
+    }
+    Ok(())
+}
+
+/// Names the organization in the login hint of an auth failure as configured, since
+/// the API only knows its slug and `login` takes the configured name.
+fn name_login_org(
+    mut error: anyhow::Error,
+    org_name: impl FnOnce(&str) -> Option<String>,
+) -> anyhow::Error {
+    if let Some(SentryError::Unauthorized { org: Some(org), .. }) =
+        error.downcast_mut::<SentryError>()
+    {
+        if let Some(name) = org_name(org) {
+            *org = name;
+        }
+    }
+    error
+}
+
+/// Reads a line from stdin, returning the default for an empty answer. Fails at the
+/// end of input, e.g. Ctrl-D, so callers asking again until answered stop.
+fn prompt_line(question: &str, default: Option<&str>) -> Result<String> {
+    match default {
+        Some(default) => print!("{} [{}]: ", question, default),
+        None => print!("{}: ", question),
+    }
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    let read = io::stdin()
+        .read_line(&mut answer)
+        .context("Failed to read answer")?;
+    if read == 0 {
+        println!();
+        anyhow::bail!("No answer given: input ended");
+    }
+    let answer = answer.trim();
+    Ok(match default {
+        Some(default) if answer.is_empty() => default.to_string(),
+        _ => answer.to_string(),
+    })
+}
+
+/// Shows what a mutating command is about to do and asks before going ahead. Every
+/// handler that changes data in Sentry calls this; `yes` (from `-y/--yes`) skips the
+/// question. Without a terminal to ask on, the command is refused instead of hanging.
+fn confirm(summary: &str, yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    if !is_interactive() {
+        anyhow::bail!("{}: confirmation needed, pass --yes to proceed", summary);
+    }
+    Ok(is_confirmed(&prompt_line(
+        &format!("{}. Continue? [y/N]", summary),
+        None,
+    )?))
+}
+
+fn is_confirmed(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Parses a 1-based menu choice into an index, if it names one of `count` entries.
+fn parse_choice(answer: &str, count: usize) -> Option<usize> {
+    answer
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n >= 1 && n <= count)
+        .map(|n| n - 1)
+}
+
+/// Switches the client to an organization's write token ahead of a mutating request.
+/// Every command that changes data in Sentry goes through here, so read-only mode
+/// refuses them before anything is sent.
+fn login_for_write(config: &Config, client: &mut SentryClient, org_slug: &str) -> Result<()> {
+    // A dry run changes nothing, so it is allowed in read-only mode
+    if config.is_read_only() && !client.is_dry_run() {
+        anyhow::bail!(
+            "Refusing to change data in Sentry: read-only mode is on (--read-only or 'config read-only off')"
+        );
+    }
+    let org = config
+        .get_organization_by_slug(org_slug)
+        .ok_or_else(|| not_found(format!("Organization '{}' is not configured", org_slug)))?;
+    let token = org.get_token(TokenRole::Write)?.ok_or_else(|| {
+        auth_error(format!(
+            "No write token for organization '{}'. Use 'login --role write' first.",
+            org.name
+        ))
+    })?;
+    client.login(token)
+}
+
+/// Resolves a `[org/]project` target to organization and project slugs and logs
+/// the client in with the matching token. Without an organization, the project
+/// is looked up in the cache and then live across all authenticated organizations.
+fn resolve_project_target(
+    config: &mut Config,
+    client: &mut SentryClient,
+    target: &str,
+) -> Result<(String, String)> {
+    if let Some((org, project)) = target.split_once('/') {
+        let org_entry = login_org(config, client, org)?;
+        return Ok((org_entry.slug.clone(), project.to_string()));
+    }
+
+    let project = target;
+    let mut matches = Vec::new();
+    let mut to_cache = Vec::new();
+
+    // Projects cached at login or by earlier lookups resolve without any requests
+    for org in config.accounts() {
+        if org.has_project(project) {
+            if let Some(token) = org.get_auth_token()? {
+                matches.push((org.name.clone(), token));
+            }
+        }
+    }
+
+    // Otherwise look the project up live and collect it for caching
+    if matches.is_empty() {
+        for org in config.accounts() {
+            if let Some(token) = org.get_auth_token()? {
+                client.login(token.clone())?;
+                if let Ok(projects) = client.list_projects(&org.slug) {
+                    if let Some(found_project) = projects.iter().find(|p| p.slug == project) {
+                        to_cache.push((org.name.clone(), found_project.name.clone()));
+                        matches.push((org.name.clone(), token));
+                    }
+                }
+            }
+        }
+    }
+
+    // Second pass: cache projects
+    for (org_name, project_name) in to_cache {
+        config.cache_project(&org_name, project.to_string(), project_name)?;
+    }
+
+    let (org_name, token) = match matches.len() {
+        0 => {
+            return Err(not_found(format!(
+                "Project '{}' not found in any organization",
+                project
+            )))
+        }
+        1 => matches.remove(0),
+        _ => {
+            let orgs: Vec<&Organization> = matches
+                .iter()
+                .filter_map(|(name, _)| config.get_organization(name))
+                .collect();
+            if !is_interactive() {
+                let names: Vec<&str> = orgs.iter().map(|o| o.name.as_str()).collect();
+                anyhow::bail!(
+                    "Project '{}' is in several organizations ({}); pass it as org/project",
+                    project,
+                    names.join(", ")
+                );
+            }
+            let selected = select_organization(&orgs)?;
+            matches.remove(selected)
+        }
+    };
+
+    let org = config
+        .get_organization(&org_name)
+        .ok_or_else(|| not_found(format!("Organization '{}' not found", org_name)))?;
+    if let Some(Ok(project_name)) = org.get_project(project) {
+        println!("Found project: {} ({})", project_name, project);
+    }
+    client.login(token)?;
+    Ok((org.slug.clone(), project.to_string()))
+}
+
+fn select_organization(orgs: &[&Organization]) -> Result<usize> {
+    println!("\nMultiple organizations have this project. Please select one:");
+
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), Hide)?;
+
+    let mut selected = 0;
+    let mut result = None;
+
+    loop {
+        execute!(
+            io::stdout(),
+            Clear(ClearType::All),
+            cursor::MoveTo(0, 0),
+            Print("Use arrow keys to select an organization and press Enter:\n\n")
+        )?;
+
+        for (i, org) in orgs.iter().enumerate() {
+            let prefix = if i == selected { "> " } else { "  " };
+            let color = if i == selected {
+                Color::Green
+            } else {
+                Color::Reset
+            };
+
+            execute!(
+                io::stdout(),
+                SetForegroundColor(color),
+                Print(format!("{}{} ({})\n", prefix, org.name, org.slug)),
+                SetForegroundColor(Color::Reset)
+            )?;
+        }
+
+        io::stdout().flush()?;
+
+        if let Event::Key(key) = event::read()? {
+            match key.code {
+                KeyCode::Up if selected > 0 => selected -= 1,
+                KeyCode::Down if selected < orgs.len() - 1 => selected += 1,
+                KeyCode::Enter => {
+                    result = Some(selected);
+                    break;
+                }
+                KeyCode::Esc => {
+                    println!("Operation cancelled");
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    terminal::disable_raw_mode()?;
+    execute!(io::stdout(), Show)?;
+    println!();
+
+    result.ok_or_else(|| anyhow::anyhow!("No organization selected"))
+}
+
+fn start_project_info(
+    client: &SentryClient,
+    org_slug: String,
+    project_slug: String,
+    show_dsn: bool,
+) -> Result<()> {
+    println!(
+        "Starting project info for organization: {} project: {}",
+        org_slug, project_slug
+    );
+    let project_info = client.get_project_info(&org_slug, &project_slug, show_dsn)?;
+    println!("Project Info:");
+    for (key, value) in project_info {
+        println!("  {}: {}", key, value);
+    }
+    Ok(())
+}
+
+fn start_project_viewer(client: &SentryClient, org_slug: &str, project_slug: &str) -> Result<()> {
+    let project = client.get_project(org_slug, project_slug)?;
+
+    let mut issues = client.list_issues(org_slug, project_slug)?;
+    issues.sort_by_key(|i| std::cmp::Reverse(i.count));
+    issues.truncate(10);
+
+    let releases = client.list_releases(org_slug, project_slug)?;
+
+    let mut members = Vec::new();
+    for team in project.teams.iter().flatten() {
+        for member in client.list_team_members(org_slug, &team.slug)? {
+            if !members.iter().any(|m: &TeamMember| m.id == member.id) {
+                members.push(member);
+            }
+        }
+    }
+
+    let mut viewer = ProjectViewer::new(project, issues, releases, members)?;
+    viewer.show()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentry::Assignee;
+
+    #[test]
+    fn test_org_list_command() {
+        let cli = Cli::parse_from(&["sex-cli", "org", "list"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Org {
+                command: OrgCommands::List
+            }
+        ));
+    }
+
+    #[test]
+    fn test_org_add_command() {
+        let cli = Cli::parse_from(&["sex-cli", "org", "add", "test", "test-slug"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Org {
+                command: OrgCommands::Add {
+                    name: Some(name),
+                    slug: Some(slug),
+                    from_url: None,
+                    no_verify: false,
+                }
+            } if name == "test" && slug == "test-slug"
+        ));
+    }
+
+    #[test]
+    fn test_org_add_from_url() {
+        let url = "https://sentry.io/organizations/acme/issues/";
+        let cli = Cli::parse_from(&["sex-cli", "org", "add", "--from-url", url]);
+        assert!(matches!(
+            cli.command,
+            Commands::Org {
+                command: OrgCommands::Add { name: None, slug: None, from_url: Some(from_url), .. }
+            } if from_url == url
+        ));
+        let cli = Cli::parse_from(&["sex-cli", "org", "add", "work", "--from-url", url]);
+        assert!(matches!(
+            cli.command,
+            Commands::Org {
+                command: OrgCommands::Add { name: Some(name), slug: None, .. }
+            } if name == "work"
+        ));
+
+        assert!(Cli::try_parse_from(["sex-cli", "org", "add", "acme"]).is_err());
+        assert!(
+            Cli::try_parse_from(["sex-cli", "org", "add", "acme", "acme", "--from-url", url])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_similar_slugs() {
+        assert_eq!(edit_distance("acme", "acme"), 0);
+        assert_eq!(edit_distance("acmee", "acme"), 1);
+        assert_eq!(edit_distance("amce", "acme"), 2);
+
+        let slugs = vec![
+            "acme".to_string(),
+            "acme-labs".to_string(),
+            "globex".to_string(),
+        ];
+        assert_eq!(similar_slugs("acmee", &slugs), vec!["acme"]);
+        assert_eq!(similar_slugs("acme", &slugs), vec!["acme", "acme-labs"]);
+        assert!(similar_slugs("initech", &slugs).is_empty());
+    }
+
+    #[test]
+    fn test_issue_list_command() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::List { columns, .. }
+            } if columns.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_issue_list_pattern() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list", "my-org/api-*"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::List { target: Some(target), .. }
+            } if target == "my-org/api-*"
+        ));
+    }
+
+    #[test]
+    fn test_issue_list_stats_period() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list", "--stats-period", "24h"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::List { stats_period: Some(period), .. }
+            } if period == "24h"
+        ));
+        // The issues API rejects other periods
+        for period in ["1h", "7d", "90d"] {
+            assert!(
+                Cli::try_parse_from(["sex-cli", "issue", "list", "--stats-period", period])
+                    .is_err()
+            );
+        }
+
+        let issue: Issue = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "title": "Test Issue",
+            "status": "unresolved",
+            "level": "error",
+            "culprit": "test.js:42",
+            "lastSeen": "2024-01-01T00:00:00Z",
+            "count": 100,
+            "userCount": 3,
+            "stats": {"24h": [[1704067200, 4], [1704070800, 6]]}
+        }))
+        .unwrap();
+        assert_eq!(period_events(&issue, "24h"), "10");
+        assert_eq!(period_events(&issue, "14d"), "-");
+    }
+
+    #[test]
+    fn test_issue_list_status() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::List {
+                    status: StatusFilter::Unresolved,
+                    unassigned: false,
+                    ..
+                }
+            }
+        ));
+
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "issue",
+            "list",
+            "--status",
+            "all",
+            "--unassigned",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::List {
+                    status: StatusFilter::All,
+                    unassigned: true,
+                    ..
+                }
+            }
+        ));
+
+        assert_eq!(StatusFilter::Unresolved.search(false), "is:unresolved");
+        assert_eq!(
+            StatusFilter::Ignored.search(true),
+            "is:ignored is:unassigned"
+        );
+        assert_eq!(StatusFilter::All.search(true), "is:unassigned");
+        assert_eq!(StatusFilter::All.search(false), "");
+    }
+
+    #[test]
+    fn test_issue_list_team() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list", "--team", "acme/payments"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::List { target: None, team: Some(team), .. }
+            } if team == "acme/payments"
+        ));
+        assert!(
+            Cli::try_parse_from(["sex-cli", "issue", "list", "web", "--team", "payments"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_issue_list_links() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list", "--links", "--columns", "id,url"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::List { links: true, columns, .. }
+            } if columns == ["id", "url"]
+        ));
+    }
+
+    #[test]
+    fn test_as_flag() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list", "acme/web", "--as", "acme-bot"]);
+        assert_eq!(cli.account.as_deref(), Some("acme-bot"));
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list"]);
+        assert_eq!(cli.account, None);
+    }
+
+    #[test]
+    fn test_hyperlinks_flag() {
+        let cli = Cli::parse_from(&["sex-cli", "project", "list", "--hyperlinks", "never"]);
+        assert_eq!(cli.hyperlinks, HyperlinkMode::Never);
+        assert!(!cli.hyperlinks.enabled());
+        let cli = Cli::parse_from(&["sex-cli", "--hyperlinks", "always", "project", "list"]);
+        assert!(cli.hyperlinks.enabled());
+    }
+
+    #[test]
+    fn test_issue_latest_command() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "latest", "acme/web"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Latest { target, query, repo: None }
+            } if target == "acme/web" && query == "is:unresolved"
+        ));
+    }
+
+    #[test]
+    fn test_issue_url_command() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "url", "BACKEND-4X3", "--copy"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Url { id, copy: true }
+            } if id == "BACKEND-4X3"
+        ));
+    }
+
+    #[test]
+    fn test_issue_share_command() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "share", "BACKEND-4X3", "--copy"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Share { ids, copy: true }
+            } if ids == ["BACKEND-4X3"]
+        ));
+        let cli = Cli::parse_from(&["sex-cli", "issue", "unshare", "BACKEND-4X3", "BACKEND-4X4"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Unshare { ids }
+            } if ids == ["BACKEND-4X3", "BACKEND-4X4"]
+        ));
+    }
+
+    #[test]
+    fn test_issue_list_age_filters() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "issue",
+            "list",
+            "--first-seen-since",
+            "2h",
+            "--last-seen-since",
+            "1d",
+        ]);
+        let Commands::Issue {
+            command: IssueCommands::List { age, .. },
+        } = cli.command
+        else {
+            panic!("expected issue list command");
+        };
+        assert_eq!(
+            age.apply("is:unresolved"),
+            "is:unresolved firstSeen:-2h lastSeen:-1d"
+        );
+        assert_eq!(
+            AgeFilters::default().apply("is:unresolved"),
+            "is:unresolved"
+        );
+    }
+
+    #[test]
+    fn test_issue_list_assigned() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list", "--assigned", "#backend"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::List { assigned: Some(assignee), .. }
+            } if assignee == "#backend"
+        ));
+        assert!(Cli::try_parse_from([
+            "sex-cli",
+            "issue",
+            "list",
+            "--assigned",
+            "me",
+            "--unassigned"
+        ])
+        .is_err());
+
+        // Explicit assignees need no lookup
+        let client = SentryClient::new().unwrap();
+        let query =
+            assignee_query(&client, &IssueQuery::default(), Some("jane@example.com")).unwrap();
+        assert_eq!(query.query, "is:unresolved assigned:jane@example.com");
+        assert_eq!(
+            first_release_query(&query, "app@1.2.0").query,
+            "is:unresolved assigned:jane@example.com firstRelease:\"app@1.2.0\""
+        );
+        assert_eq!(
+            assignee_query(&client, &IssueQuery::default(), None).unwrap(),
+            IssueQuery::default()
+        );
+    }
+
+    #[test]
+    fn test_badge_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "badge",
+            "acme/web",
+            "--query",
+            "is:unresolved level:error",
+            "--style",
+            "tmux",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Badge { target, query, style: BadgeStyle::Tmux, cache_ttl: 60 }
+            if target == "acme/web" && query == "is:unresolved level:error"
+        ));
+
+        let cli = Cli::parse_from(&["sex-cli", "badge", "web", "--format", "tmux"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Badge {
+                style: BadgeStyle::Tmux,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_prompt_command() {
+        let cli = Cli::parse_from(&["sex-cli", "prompt", "acme/web"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Prompt { target, cache_ttl: 60, refresh: false }
+            if target == "acme/web"
+        ));
+    }
+
+    #[test]
+    fn test_triage_command() {
+        let cli = Cli::parse_from(&["sex-cli", "triage", "acme/web"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Triage { target, query }
+            if target == "acme/web" && query == "is:unresolved"
+        ));
+    }
+
+    #[test]
+    fn test_check_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "check",
+            "acme/web",
+            "--query",
+            "is:unresolved firstSeen:-1h",
+            "--max",
+            "2",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Check { target, query, max: 2, .. }
+            if target == "acme/web" && query == "is:unresolved firstSeen:-1h"
+        ));
+
+        let cli = Cli::parse_from(&["sex-cli", "check", "web"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Check { query, max: 0, format: IssueFormat::Table, .. }
+            if query == "is:unresolved"
+        ));
+
+        let cli = Cli::parse_from(&["sex-cli", "check", "web", "--first-seen-since", "2h"]);
+        let Commands::Check { query, age, .. } = cli.command else {
+            panic!("expected check command");
+        };
+        assert_eq!(age.apply(&query), "is:unresolved firstSeen:-2h");
+        assert!(
+            Cli::try_parse_from(["sex-cli", "check", "web", "--last-seen-since", "lunch"]).is_err()
+        );
+
+        let cli = Cli::parse_from(&["sex-cli", "check", "web", "--format", "gh-annotations"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Check {
+                format: IssueFormat::GhAnnotations,
+                ..
+            }
+        ));
+
+        let cli = Cli::parse_from(&["sex-cli", "check", "web", "--format", "ids"]);
+        let Commands::Check { format, .. } = cli.command else {
+            panic!("expected check command");
+        };
+        assert_eq!(format, IssueFormat::Ids);
+        assert!(format.is_report());
+    }
+
+    #[test]
+    fn test_init_command() {
+        let cli = Cli::parse_from(&["sex-cli", "init"]);
+        assert!(matches!(cli.command, Commands::Init));
+
+        assert_eq!(parse_choice("2", 3), Some(1));
+        assert_eq!(parse_choice(" 1 ", 3), Some(0));
+        assert_eq!(parse_choice("0", 3), None);
+        assert_eq!(parse_choice("4", 3), None);
+        assert_eq!(parse_choice("", 3), None);
+    }
+
+    #[test]
+    fn test_monitor_search() {
+        let levels = |levels: &[&str]| levels.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        assert_eq!(monitor_search(None, &[]), "is:unresolved");
+        assert_eq!(
+            monitor_search(None, &levels(&["error"])),
+            "is:unresolved level:error"
+        );
+        assert_eq!(
+            monitor_search(Some("assigned:me".to_string()), &levels(&["fatal"])),
+            "assigned:me level:fatal"
+        );
+        assert_eq!(
+            level_search(&levels(&["warning", "fatal", "error", "fatal"])).unwrap(),
+            "level:[fatal,error,warning]"
+        );
+    }
+
+    #[test]
+    fn test_issue_list_columns() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list", "--columns", "title,id,events"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::List { columns, .. }
+            } if columns == vec!["title", "id", "events"]
+        ));
+    }
+
+    #[test]
+    fn test_issue_list_group_by() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list", "--group-by", "project"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::List {
+                    group_by: Some(GroupBy::Project),
+                    ..
+                }
+            }
+        ));
+    }
+
+    fn test_issue(id: &str, level: &str, project: &str) -> Issue {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": "Test Issue",
+            "status": "unresolved",
+            "level": level,
+            "culprit": "test.js:42",
+            "lastSeen": "2024-01-01T00:00:00Z",
+            "count": 1,
+            "userCount": 1,
+            "project": {"id": "1", "slug": project, "name": project}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_group_issues() {
+        let issues = vec![
+            test_issue("1", "warning", "web"),
+            test_issue("2", "error", "api"),
+            test_issue("3", "error", "api"),
+            test_issue("4", "fatal", "web"),
+            test_issue("5", "info", "worker"),
+        ];
+
+        let by_level = group_issues(issues, GroupBy::Level);
+        let levels: Vec<&str> = by_level.iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(levels, vec!["fatal", "error", "warning", "info"]);
+
+        let issues = by_level.into_iter().flat_map(|(_, g)| g).collect();
+        let by_project = group_issues(issues, GroupBy::Project);
+        let projects: Vec<(&str, usize)> = by_project
+            .iter()
+            .map(|(p, g)| (p.as_str(), g.len()))
+            .collect();
+        assert_eq!(projects, vec![("api", 2), ("web", 2), ("worker", 1)]);
+    }
+
+    #[test]
+    fn test_group_issues_by_culprit() {
+        let issue = |id: &str, culprit: &str, count: u32| {
+            let mut issue = test_issue(id, "error", "api");
+            issue.culprit = culprit.to_string();
+            issue.count = count;
+            issue
+        };
+        let issues = vec![
+            issue("1", "app.payments in charge", 5),
+            issue("2", "app.payments in refund", 5),
+            issue("3", "render(src/views/cart.js)", 20),
+            issue("4", "src/views/cart.js:42:7", 1),
+            issue("5", "", 1),
+        ];
+        let groups: Vec<(String, usize)> = group_issues(issues, GroupBy::Culprit)
+            .into_iter()
+            .map(|(module, issues)| (module, issues.len()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("src/views/cart.js".to_string(), 2),
+                ("app.payments".to_string(), 2),
+                ("(no culprit)".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_issue_summary_command() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "summary", "acme"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Summary { org, period }
+            } if org == "acme" && period == "7d"
+        ));
+
+        let result = Cli::try_parse_from(["sex-cli", "issue", "summary", "acme", "--period", "7x"]);
+        assert!(result.is_err());
+
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "issue",
+            "grep",
+            "pool exhausted",
+            "--org",
+            "acme",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Grep { text, org: Some(org) }
+            } if text == "pool exhausted" && org == "acme"
+        ));
+        assert_eq!(format_age(30), "just now");
+        assert_eq!(format_age(300), "5m ago");
+        assert_eq!(format_age(2 * 86400), "2d ago");
+
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "issue",
+            "export",
+            "acme/web",
+            "--format",
+            "parquet",
+            "--output",
+            "errors.parquet",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Export {
+                    format: IssueExportFormat::Parquet,
+                    query,
+                    period,
+                    ..
+                }
+            } if query == "is:unresolved" && period == "14d"
+        ));
+        assert!(Cli::try_parse_from([
+            "sex-cli", "issue", "export", "acme/web", "--format", "parquet"
+        ])
+        .is_err());
+
+        let cli = Cli::parse_from(&["sex-cli", "issue", "dedupe", "acme", "--similarity", "85"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Dedupe { org, similarity }
+            } if org == "acme" && similarity == 85.0
+        ));
+
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "issue",
+            "snapshot",
+            "acme/web",
+            "--output",
+            "snap.json",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Snapshot { target, output, query }
+            } if target == "acme/web" && output == Path::new("snap.json") && query == "is:unresolved"
+        ));
+        assert!(Cli::try_parse_from(["sex-cli", "issue", "snapshot", "acme/web"]).is_err());
+        let cli = Cli::parse_from(&["sex-cli", "issue", "diff", "snap.json"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Diff { snapshot }
+            } if snapshot == Path::new("snap.json")
+        ));
+    }
+
+    #[test]
+    fn test_issue_trends_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "issue",
+            "trends",
+            "my-project",
+            "--period",
+            "24h",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Trends { target, period, limit: 20 }
+            } if target == "my-project" && period == "24h"
+        ));
+
+        let result = Cli::try_parse_from(["sex-cli", "issue", "trends", "p", "--period", "7d"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_issue_view_command() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "view", "test-id"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::View {
+                    id,
+                    plain: false,
+                    ..
+                }
+            } if id == "test-id"
+        ));
+
+        let cli = Cli::parse_from(&["sex-cli", "issue", "view", "WEB-1", "--plain"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::View { plain: true, .. }
+            }
+        ));
+    }
+
+    #[test]
+    fn test_issue_grouping_command() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "grouping", "WEB-12"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Grouping { id }
+            } if id == "WEB-12"
+        ));
+    }
+
+    #[test]
+    fn test_grouping_lines_without_grouping_info() -> Result<()> {
+        let mut server = mockito::Server::new();
+        let _hashes = server
+            .mock("GET", "/issues/123/hashes/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"id": "a1b2"}]"#)
+            .create();
+        let _grouping = server
+            .mock("GET", "/projects/acme/api/events/abc123/grouping-info/")
+            .with_status(500)
+            .create();
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let issue: Issue = serde_json::from_value(serde_json::json!({
+            "id": "123",
+            "title": "Checkout failed",
+            "status": "unresolved",
+            "level": "error",
+            "culprit": "app.views",
+            "lastSeen": "2024-01-02T00:00:00Z",
+            "count": 5,
+            "userCount": 1,
+            "project": {"id": "1", "name": "API", "slug": "api"}
+        }))?;
+        let event: EventDetails = serde_json::from_value(serde_json::json!({
+            "eventID": "abc123",
+            "dateCreated": "2024-01-02T00:00:00Z"
+        }))?;
+
+        let lines = grouping_lines(&client, "acme", &issue, Some(&event))?;
+        assert!(lines.contains(&"  a1b2".to_string()));
+        assert!(lines
+            .last()
+            .unwrap()
+            .starts_with("Variants of the latest event unavailable: API request failed: 500"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_issue_users_command() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "users", "WEB-12", "--limit", "5"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Users { id, limit: 5 }
+            } if id == "WEB-12"
+        ));
+    }
+
+    #[test]
+    fn test_issue_event_command() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "event", "WEB-12", "--json"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Event { id, json: true, repo: None }
+            } if id == "WEB-12"
+        ));
+
+        let result = Cli::try_parse_from([
+            "sex-cli", "issue", "event", "WEB-12", "--json", "--repo", ".",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_issue_assign_command() -> Result<()> {
+        let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        let cli = Cli::parse_from(&["sex-cli", "issue", "assign", "WEB-12", "#backend"]);
+        let Commands::Issue {
+            command:
+                IssueCommands::Assign {
+                    args: parsed,
+                    suggest: false,
+                },
+        } = cli.command
+        else {
+            panic!("expected assign command");
+        };
+        assert_eq!(
+            split_assign_args(parsed, false)?,
+            (args(&["WEB-12"]), Some("#backend".to_string()))
+        );
+        assert_eq!(
+            split_assign_args(args(&["WEB-12", "WEB-13", "me"]), false)?,
+            (args(&["WEB-12", "WEB-13"]), Some("me".to_string()))
+        );
+
+        let cli = Cli::parse_from(&["sex-cli", "issue", "assign", "WEB-12", "--suggest"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Assign { suggest: true, .. }
             }
-            Commands::Monitor { target } => {
-                let (org, project) = if let Some((org_part, project_part)) = target.split_once('/')
-                {
-                    (org_part.to_string(), project_part.to_string())
-                } else {
-                    (String::new(), target)
-                };
+        ));
+        assert_eq!(
+            split_assign_args(args(&["WEB-12"]), true)?,
+            (args(&["WEB-12"]), None)
+        );
 
-                if !org.is_empty() {
-                    let org_entry = config.get_organization(&org).ok_or_else(|| {
-                        anyhow::anyhow!(
-                            "Organization '{}' not found. Add it first with 'org add'.",
-                            org
-                        )
-                    })?;
+        assert!(Cli::try_parse_from(["sex-cli", "issue", "assign"]).is_err());
+        assert!(split_assign_args(args(&["WEB-12"]), false).is_err());
+        assert!(split_assign_args(args(&["WEB-12", "me"]), true).is_err());
+        Ok(())
+    }
 
-                    let token = org_entry.get_auth_token()?.ok_or_else(|| {
-                        anyhow::anyhow!(
-                            "Not logged in for organization '{}'. Use 'login' first.",
-                            org
-                        )
-                    })?;
+    #[test]
+    fn test_issue_watch_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "issue",
+            "watch",
+            "BACKEND-4X3",
+            "--interval",
+            "10",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Watch { id, interval: 10 }
+            } if id == "BACKEND-4X3"
+        ));
 
-                    client.login(token)?;
-                    start_monitor(&client, org_entry.slug.clone(), project)?;
-                } else {
-                    let mut matches = Vec::new();
-                    let mut to_cache = Vec::new();
+        let result = Cli::try_parse_from(["sex-cli", "issue", "watch", "1", "--interval", "0"]);
+        assert!(result.is_err());
+    }
 
-                    // First pass: collect projects to cache
-                    for org in config.organizations.values() {
-                        if let Some(token) = org.get_auth_token()? {
-                            client.login(token.clone())?;
-
-                            if org.has_project(&project) {
-                                matches.push((org.clone(), token));
-                            } else if let Ok(projects) = client.list_projects(&org.slug) {
-                                if let Some(found_project) =
-                                    projects.iter().find(|p| p.slug == project)
-                                {
-                                    to_cache.push((
-                                        org.name.clone(),
-                                        project.clone(),
-                                        found_project.name.clone(),
-                                    ));
-                                    matches.push((org.clone(), token));
-                                }
-                            }
-                        }
-                    }
+    #[test]
+    fn test_issue_changes() {
+        let previous = test_issue("1", "error", "api");
+        let mut current = previous.clone();
+        assert!(issue_changes(&previous, &current).is_empty());
 
-                    // Second pass: cache projects
-                    for (org_name, project_slug, project_name) in to_cache {
-                        config.cache_project(&org_name, project_slug, project_name)?;
-                    }
+        current.count = 5;
+        current.status = "resolved".to_string();
+        current.assigned_to = Some(Assignee {
+            kind: "team".to_string(),
+            id: "2".to_string(),
+            name: "backend".to_string(),
+        });
+        assert_eq!(
+            issue_changes(&previous, &current),
+            vec![
+                "events 1 -> 5 (+4)",
+                "status unresolved -> resolved",
+                "assignee nobody -> #backend"
+            ]
+        );
+    }
 
-                    match matches.len() {
-                        0 => {
-                            println!("Project '{}' not found in any organization", project);
-                            return Ok(());
-                        }
-                        1 => {
-                            let (org, token) = &matches[0];
-                            if let Some(Ok(project_name)) = org.get_project(&project) {
-                                println!("Found project: {} ({})", project_name, project);
-                            }
-                            client.login(token.clone())?;
-                            start_monitor(&client, org.slug.clone(), project)?;
-                        }
-                        _ => {
-                            let matches_owned: Vec<(Organization, String)> = matches
-                                .into_iter()
-                                .map(|(org, token)| (org.clone(), token.clone()))
-                                .collect();
-                            let org = select_organization(&matches_owned[..])?;
-                            if let Some(Ok(project_name)) = org.0.get_project(&project) {
-                                println!("Selected project: {} ({})", project_name, project);
-                            }
-                            client.login(org.1.clone())?;
-                            start_monitor(&client, org.0.slug.clone(), project)?;
-                        }
-                    }
-                }
-            }
-            Commands::Org { command } => match command {
-                OrgCommands::List => {
-                    if config.organizations.is_empty() {
-                        println!("No organizations configured");
-                    } else {
-                        println!("Organizations:");
-                        for org in config.organizations.values() {
-                            let auth_status = if org.get_auth_token()?.is_some() {
-                                "authenticated"
-                            } else {
-                                "not authenticated"
-                            };
-                            println!("  {} ({}) - {}", org.name, org.slug, auth_status);
+    #[test]
+    fn test_events_tail_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "events",
+            "tail",
+            "my-org/api",
+            "--query",
+            "browser:Chrome",
+            "--level",
+            "error",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Events {
+                command: EventCommands::Tail { target, query: Some(query), level: Some(level), interval: 5 }
+            } if target == "my-org/api" && query == "browser:Chrome" && level == "error"
+        ));
 
-                            // List cached projects
-                            for (slug, _) in &org.projects {
-                                if let Some(Ok(name)) = org.get_project(slug) {
-                                    println!("    - {} ({})", name, slug);
-                                }
-                            }
-                        }
-                    }
-                }
-                OrgCommands::Add { name, slug } => {
-                    config.add_organization(name.clone(), slug.clone());
-                    config.save()?;
-                    println!("Added organization: {} ({})", name, slug);
-                }
-                OrgCommands::Projects { name } => {
-                    let org = config
-                        .get_organization(&name)
-                        .ok_or_else(|| anyhow::anyhow!("Organization '{}' not found", name))?;
-                    println!("Projects in organization: {}", name);
-                    for project in org.projects.keys() {
-                        println!("  - {}", project);
-                    }
-                }
-            },
-            Commands::Issue { command } => match command {
-                IssueCommands::List => {
-                    if config.organizations.is_empty() {
-                        println!("No organizations configured. Add one first with 'org add'.");
-                        return Ok(());
-                    }
+        let result = Cli::try_parse_from(["sex-cli", "events", "tail", "api", "--level", "loud"]);
+        assert!(result.is_err());
+    }
 
-                    for org in config.organizations.values() {
-                        if let Some(token) = org.get_auth_token()? {
-                            client.login(token)?;
-                            println!("\nFetching issues for organization: {}", org.name);
-                            let issues = client.list_issues(&org.slug, "default")?;
+    #[test]
+    fn test_tail_new_events() {
+        let events = |ids: std::ops::Range<usize>| -> Vec<SentryEvent> {
+            ids.rev()
+                .map(|id| {
+                    serde_json::from_value(serde_json::json!({
+                        "eventID": id.to_string(),
+                        "dateCreated": "2024-01-02T00:00:00Z"
+                    }))
+                    .unwrap()
+                })
+                .collect()
+        };
+        let ids = |events: Vec<SentryEvent>| -> Vec<String> {
+            events.into_iter().map(|e| e.event_id).collect()
+        };
+        let mut seen = None;
 
-                            if issues.is_empty() {
-                                println!("  No issues found");
-                            } else {
-                                for issue in issues {
-                                    println!("  {}: {} ({})", issue.id, issue.title, issue.status);
-                                }
-                            }
-                        }
-                    }
+        // Only the last lines of what was recorded before the start, oldest first
+        let first = ids(tail_new_events(events(0..30), &mut seen));
+        assert_eq!(first.len(), TAIL_LINES);
+        assert_eq!(first.first().map(String::as_str), Some("20"));
+        assert_eq!(first.last().map(String::as_str), Some("29"));
+
+        // A later burst shows in full
+        let burst = ids(tail_new_events(events(10..55), &mut seen));
+        assert_eq!(burst.len(), 25);
+        assert_eq!(burst.first().map(String::as_str), Some("30"));
+        assert_eq!(seen.as_ref().map(HashSet::len), Some(45));
+
+        assert!(tail_new_events(events(10..55), &mut seen).is_empty());
+    }
+
+    #[test]
+    fn test_events_search_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "events",
+            "search",
+            "api",
+            "--query",
+            "user.email:jane@example.com",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Events {
+                command: EventCommands::Search { target, query, limit: 50, .. }
+            } if target == "api" && query == "user.email:jane@example.com"
+        ));
+
+        // A query is required
+        assert!(Cli::try_parse_from(["sex-cli", "events", "search", "api"]).is_err());
+    }
+
+    #[test]
+    fn test_trace_view_command() {
+        let cli = Cli::parse_from(&["sex-cli", "trace", "view", "abc123", "--org", "test-org"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Trace {
+                command: TraceCommands::View { trace_id, org: Some(org) }
+            } if trace_id == "abc123" && org == "test-org"
+        ));
+    }
+
+    #[test]
+    fn test_profile_list_command() {
+        let cli = Cli::parse_from(&["sex-cli", "profile", "list", "my-org/api", "--period", "7d"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Profile {
+                command: ProfileCommands::List { target, period, limit: 10 }
+            } if target == "my-org/api" && period == "7d"
+        ));
+    }
+
+    #[test]
+    fn test_perf_summary_command() {
+        let cli = Cli::parse_from(&["sex-cli", "perf", "summary", "my-org/api", "--period", "7d"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Perf {
+                command: PerfCommands::Summary { target, period, limit: 10 }
+            } if target == "my-org/api" && period == "7d"
+        ));
+        assert_eq!(format_percent(0.0512), "5.1%");
+    }
+
+    #[test]
+    fn test_format_nanos() {
+        assert_eq!(format_nanos(512.0), "512ns");
+        assert_eq!(format_nanos(1_500.0), "1.5µs");
+        assert_eq!(format_nanos(1_500_000.0), "1.5ms");
+        assert_eq!(format_nanos(2_345_000_000.0), "2.35s");
+    }
+
+    #[test]
+    fn test_debug_files_upload_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "debug-files",
+            "upload",
+            "my-org/ios",
+            "build/App.dSYM",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::DebugFiles {
+                command: DebugFileCommands::Upload { target, path }
+            } if target == "my-org/ios" && path.as_path() == std::path::Path::new("build/App.dSYM")
+        ));
+    }
+
+    #[test]
+    fn test_release_set_commits_command() {
+        let cli = Cli::parse_from(&["sex-cli", "release", "set-commits", "app@1.0", "--auto"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Release {
+                command: ReleaseCommands::SetCommits { version, org: None, auto: true, .. }
+            } if version == "app@1.0"
+        ));
+
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "release",
+            "set-commits",
+            "app@1.0",
+            "--commit",
+            "acme/app@abc..def",
+            "--commit",
+            "acme/lib@fff",
+        ]);
+        let Commands::Release {
+            command: ReleaseCommands::SetCommits { commit, .. },
+        } = cli.command
+        else {
+            panic!("expected release set-commits");
+        };
+        assert_eq!(
+            commit,
+            vec![
+                ReleaseRef {
+                    repository: "acme/app".to_string(),
+                    commit: "def".to_string(),
+                    previous_commit: Some("abc".to_string()),
+                },
+                ReleaseRef {
+                    repository: "acme/lib".to_string(),
+                    commit: "fff".to_string(),
+                    previous_commit: None,
+                },
+            ]
+        );
+
+        // One of --auto or --commit is required, and they cannot be combined
+        assert!(Cli::try_parse_from(["sex-cli", "release", "set-commits", "app@1.0"]).is_err());
+        assert!(Cli::try_parse_from([
+            "sex-cli",
+            "release",
+            "set-commits",
+            "app@1.0",
+            "--auto",
+            "--commit",
+            "acme/app@abc"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_release_diff_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "release",
+            "diff",
+            "app@1.1.0",
+            "app@1.2.0",
+            "--project",
+            "acme/web",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Release {
+                command: ReleaseCommands::Diff { from, to, project }
+            } if from == "app@1.1.0" && to == "app@1.2.0" && project == "acme/web"
+        ));
+        assert!(Cli::try_parse_from(["sex-cli", "release", "diff", "1.1.0", "1.2.0"]).is_err());
+    }
+
+    fn resolved_issue(id: &str, in_release: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "title": "Checkout failed",
+            "status": "resolved",
+            "level": "error",
+            "culprit": "app.views",
+            "lastSeen": "2024-01-02T00:00:00Z",
+            "count": 5,
+            "userCount": 1,
+            "statusDetails": {"inRelease": in_release}
+        })
+    }
+
+    #[test]
+    fn test_fetch_check_issues_past_a_page() -> Result<()> {
+        let mut server = mockito::Server::new();
+        let issues = |ids: std::ops::Range<usize>| -> String {
+            let issues: Vec<serde_json::Value> = ids
+                .map(|id| {
+                    serde_json::json!({
+                        "id": id.to_string(),
+                        "title": "Checkout failed",
+                        "status": "unresolved",
+                        "level": "error",
+                        "culprit": "app.views",
+                        "lastSeen": "2024-01-02T00:00:00Z",
+                        "count": 5,
+                        "userCount": 1
+                    })
+                })
+                .collect();
+            serde_json::Value::Array(issues).to_string()
+        };
+        let second_page = server
+            .mock("GET", "/projects/acme/web/issues/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "cursor".into(),
+                "0:100:0".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(issues(100..101))
+            .create();
+        let first_page = server
+            .mock("GET", "/projects/acme/web/issues/")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header(
+                "link",
+                "<http://x/?cursor=0:100:0>; rel=\"next\"; results=\"true\"; cursor=\"0:100:0\"",
+            )
+            .with_body(issues(0..100))
+            .create();
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        // With --max 100, the 101st issue fails the gate
+        let found = fetch_check_issues(&client, "acme", "web", "is:unresolved", 100)?;
+        assert_eq!(found.len(), 101);
+        first_page.assert();
+        second_page.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_release_diff_pages() -> Result<()> {
+        let mut server = mockito::Server::new();
+        let issue = |id: &str, status: &str| {
+            serde_json::json!({
+                "id": id,
+                "title": "Checkout failed",
+                "status": status,
+                "level": "error",
+                "culprit": "app.views",
+                "lastSeen": "2024-01-02T00:00:00Z",
+                "count": 5,
+                "userCount": 1
+            })
+        };
+        let json = |value: serde_json::Value| value.to_string();
+        // Issue 2 is still seen in the new release, but only on the second page
+        let _second_page = server
+            .mock("GET", "/projects/acme/web/issues/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("query".into(), "release:\"app@1.2.0\"".into()),
+                mockito::Matcher::UrlEncoded("cursor".into(), "0:100:0".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json(serde_json::json!([issue("2", "resolved")])))
+            .create();
+        let _seen_in_new = server
+            .mock("GET", "/projects/acme/web/issues/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "query".into(),
+                "release:\"app@1.2.0\"".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header(
+                "link",
+                "<http://x/?cursor=0:100:0>; rel=\"next\"; results=\"true\"; cursor=\"0:100:0\"",
+            )
+            .with_body(json(serde_json::json!([issue("3", "unresolved")])))
+            .create();
+        let _first_seen = server
+            .mock("GET", "/projects/acme/web/issues/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "query".into(),
+                "firstRelease:\"app@1.2.0\"".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json(serde_json::json!([issue("3", "unresolved")])))
+            .create();
+        let _seen_in_old = server
+            .mock("GET", "/projects/acme/web/issues/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "query".into(),
+                "release:\"app@1.1.0\"".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json(serde_json::json!([
+                issue("1", "resolved"),
+                issue("2", "resolved")
+            ])))
+            .create();
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let diff = fetch_release_diff(&client, "acme", "web", "app@1.1.0", "app@1.2.0")?;
+        let ids =
+            |issues: &[Issue]| -> Vec<String> { issues.iter().map(|i| i.id.clone()).collect() };
+        assert_eq!(ids(&diff.new), vec!["3"]);
+        assert_eq!(ids(&diff.resolved), vec!["1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolution_states() -> Result<()> {
+        let mut server = mockito::Server::new();
+        // The fixed issue has no events in the release, so the search must not ask for them
+        let resolved = server
+            .mock("GET", "/projects/acme/web/issues/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "query".into(),
+                "is:resolved".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    resolved_issue("1", "app@1.2.0"),
+                    resolved_issue("2", "app@1.1.0")
+                ])
+                .to_string(),
+            )
+            .create();
+        let _regressed = server
+            .mock("GET", "/projects/acme/web/issues/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "query".into(),
+                "is:regressed".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create();
+        let _releases = server
+            .mock("GET", "/projects/acme/web/releases/")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"version": "app@1.2.0", "dateCreated": "2024-01-01T00:00:00Z"}]"#)
+            .create();
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let states = resolution_states(&client, "acme", "web", "app@1.2.0")?;
+        let states: Vec<(&str, String)> = states
+            .iter()
+            .map(|(issue, state)| (issue.id.as_str(), state.to_string()))
+            .collect();
+        assert_eq!(states, vec![("1", "resolved".to_string())]);
+        resolved.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_issue_ignore_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "issue",
+            "ignore",
+            "WEB-12",
+            "--until",
+            "2w",
+            "--until-count",
+            "1000",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Ignore {
+                    ids,
+                    until: Some(until),
+                    until_count: Some(1000),
+                    until_users: None,
+                    window: None,
                 }
-                IssueCommands::View { id } => {
-                    let mut found = false;
-                    for org in config.organizations.values() {
-                        if let Some(token) = org.get_auth_token()? {
-                            client.login(token)?;
-                            if let Ok(issues) = client.list_issues(&org.slug, "default") {
-                                if let Some(issue) = issues.into_iter().find(|i| i.id == id) {
-                                    found = true;
-                                    let viewer_issue = ViewerIssue {
-                                        id: issue.id,
-                                        title: issue.title,
-                                        status: issue.status,
-                                        level: issue.level,
-                                        culprit: issue.culprit,
-                                        last_seen: issue.last_seen,
-                                        events: issue.count,
-                                        users: issue.user_count,
-                                    };
-
-                                    let mut viewer = IssueViewer::new(viewer_issue)?;
-                                    viewer.show()?;
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                    if !found {
-                        println!("Issue not found in any organization");
-                    }
+            } if ids == ["WEB-12"] && until == "2w"
+        ));
+        assert!(
+            Cli::try_parse_from(["sex-cli", "issue", "ignore", "WEB-12", "--until", "soon"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_bulk_issue_ids() -> Result<()> {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "resolve", "WEB-1", "WEB-2", "42"]);
+        let Commands::Issue {
+            command: IssueCommands::Resolve { ids, .. },
+        } = cli.command
+        else {
+            panic!("expected resolve command");
+        };
+        assert_eq!(read_issue_ids(ids.clone())?, ids);
+        assert!(Cli::try_parse_from(["sex-cli", "issue", "ignore"]).is_err());
+
+        let stdin = "WEB-1\n  WEB-2 \n\n42\n";
+        assert_eq!(
+            parse_id_lines(stdin.as_bytes())?,
+            vec!["WEB-1", "WEB-2", "42"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_in_release() {
+        let cli = Cli::parse_from(&["sex-cli", "issue", "resolve", "WEB-12", "--next-release"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Resolve { ids, next_release: true, in_release: None }
+            } if ids == ["WEB-12"]
+        ));
+        assert!(Cli::try_parse_from([
+            "sex-cli",
+            "issue",
+            "resolve",
+            "WEB-12",
+            "--next-release",
+            "--in-release",
+            "app@1.2.0"
+        ])
+        .is_err());
+
+        assert_eq!(
+            resolve_changes(true, None),
+            serde_json::json!({"status": "resolved", "statusDetails": {"inNextRelease": true}})
+        );
+        assert_eq!(
+            resolve_changes(false, Some("app@1.2.0")),
+            serde_json::json!({"status": "resolved", "statusDetails": {"inRelease": "app@1.2.0"}})
+        );
+        assert_eq!(
+            resolve_changes(false, None),
+            serde_json::json!({"status": "resolved"})
+        );
+
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "release",
+            "check-resolved",
+            "app@1.2.0",
+            "--project",
+            "acme/web",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Release {
+                command: ReleaseCommands::CheckResolved { version, project }
+            } if version == "app@1.2.0" && project == "acme/web"
+        ));
+    }
+
+    #[test]
+    fn test_release_verify_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "release",
+            "verify",
+            "app@1.2.0",
+            "--project",
+            "acme/web",
+            "--window",
+            "30m",
+            "--max-new-issues",
+            "0",
+            "--max-crash-rate",
+            "1%",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Release {
+                command: ReleaseCommands::Verify {
+                    version,
+                    project,
+                    max_new_issues: 0,
+                    max_crash_rate: Some(rate),
+                    interval: 60,
+                    ..
                 }
-            },
-            Commands::Project { command } => match command {
-                ProjectCommands::List => {
-                    if config.organizations.is_empty() {
-                        println!("No organizations configured. Add one first with 'org add'.");
-                        return Ok(());
-                    }
+            } if version == "app@1.2.0" && project == "acme/web" && rate == 1.0
+        ));
 
-                    for org in config.organizations.values() {
-                        if let Some(token) = org.get_auth_token()? {
-                            client.login(token)?;
-                            println!("\nProjects in organization: {}", org.name);
-                            let projects = client.list_projects(&org.slug)?;
+        assert_eq!(percent_arg("0.5"), Ok(0.5));
+        assert!(percent_arg("150%").is_err());
+        assert!(percent_arg("lots").is_err());
+    }
 
-                            if projects.is_empty() {
-                                println!("  No projects found");
-                            } else {
-                                for project in projects {
-                                    let platform =
-                                        project.platform.unwrap_or_else(|| "-".to_string());
-                                    let access = if project.hasAccess.unwrap_or(false) {
-                                        "✓"
-                                    } else {
-                                        "✗"
-                                    };
-                                    println!(
-                                        "  {} {} [{}] {}",
-                                        access, project.name, platform, project.slug
-                                    );
-                                }
-                            }
-                        }
-                    }
+    #[test]
+    fn test_release_ref_arg_errors() {
+        assert!(release_ref_arg("no-range").is_err());
+        assert!(release_ref_arg("acme/app@abc..").is_err());
+        assert!(release_ref_arg("@abc").is_err());
+    }
+
+    #[test]
+    fn test_events_view_command() {
+        let cli = Cli::parse_from(&["sex-cli", "events", "view", "api", "abc123", "--repo", "."]);
+        assert!(matches!(
+            cli.command,
+            Commands::Events {
+                command: EventCommands::View { target, event_id, repo: Some(_) }
+            } if target == "api" && event_id == "abc123"
+        ));
+    }
+
+    #[test]
+    fn test_format_frame() {
+        let frame: Frame = serde_json::from_value(serde_json::json!({
+            "filename": "app/views.py",
+            "absPath": "/srv/app/views.py",
+            "function": "checkout",
+            "module": "app.views",
+            "lineNo": 12,
+            "inApp": true
+        }))
+        .unwrap();
+        assert_eq!(frame.to_string(), "at checkout (app/views.py:12) [in app]");
+    }
+
+    #[test]
+    fn test_issue_open_file_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "issue",
+            "open-file",
+            "BACKEND-4X3",
+            "--repo",
+            "../app",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::OpenFile { id, repo: Some(repo) }
+            } if id == "BACKEND-4X3" && repo == std::path::Path::new("../app")
+        ));
+    }
+
+    #[test]
+    fn test_config_source_root_command() {
+        let cli = Cli::parse_from(&["sex-cli", "config", "source-root"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Config {
+                command: ConfigCommands::SourceRoot { path: None }
+            }
+        ));
+    }
+
+    #[test]
+    fn test_read_only() {
+        let cli = Cli::parse_from(&["sex-cli", "config", "read-only", "on"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Config {
+                command: ConfigCommands::ReadOnly {
+                    enabled: Some(true)
                 }
-                ProjectCommands::Info { target } => {
-                    let (org, project) =
-                        if let Some((org_part, project_part)) = target.split_once('/') {
-                            (org_part.to_string(), project_part.to_string())
-                        } else {
-                            (String::new(), target)
-                        };
+            }
+        ));
+        let cli = Cli::parse_from(&["sex-cli", "issue", "assign", "42", "me", "--read-only"]);
+        assert!(cli.read_only);
+
+        let mut config = Config::default();
+        config.add_organization("acme".to_string(), "acme".to_string());
+        config.force_read_only();
+        let mut client = SentryClient::new().unwrap();
+        let error = login_for_write(&config, &mut client, "acme").unwrap_err();
+        assert!(error.to_string().contains("read-only mode is on"));
+    }
+
+    #[test]
+    fn test_name_login_org() {
+        let mut config = Config::default();
+        config.add_organization("acme-prod".to_string(), "acme".to_string());
+        let org_name = |slug: &str| Some(config.get_organization_by_slug(slug)?.name.clone());
+
+        let error: anyhow::Error = SentryError::new(
+            reqwest::StatusCode::UNAUTHORIZED,
+            Some("acme".to_string()),
+            None,
+            "",
+        )
+        .into();
+        let error = name_login_org(error.context("Failed to list issues"), org_name);
+        assert!(format!("{:#}", error).ends_with("run 'login acme-prod' to sign in again"));
+
+        let error = name_login_org(anyhow::anyhow!("boom"), |_| unreachable!());
+        assert_eq!(error.to_string(), "boom");
+    }
+
+    #[test]
+    fn test_confirm() {
+        let cli = Cli::parse_from(&["sex-cli", "project", "unbookmark", "web", "-y"]);
+        assert!(cli.yes);
+        let cli = Cli::parse_from(&["sex-cli", "--yes", "debug-files", "upload", "web", "."]);
+        assert!(cli.yes);
+
+        assert!(is_confirmed("y"));
+        assert!(is_confirmed(" Yes\n"));
+        assert!(!is_confirmed(""));
+        assert!(!is_confirmed("n"));
+        assert!(!is_confirmed("yep"));
+        assert!(confirm("Bookmark acme/web", true).unwrap());
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let cli = Cli::parse_from(&["sex-cli", "project", "bookmark", "web", "--dry-run"]);
+        assert!(cli.dry_run);
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "--dry-run",
+            "release",
+            "set-commits",
+            "1.0",
+            "--auto",
+        ]);
+        assert!(cli.dry_run);
 
-                    if !org.is_empty() {
-                        let org_entry = config.get_organization(&org).ok_or_else(|| {
-                            anyhow::anyhow!(
-                                "Organization '{}' not found. Add it first with 'org add'.",
-                                org
-                            )
-                        })?;
+        let mut config = Config::default();
+        config.add_organization("acme".to_string(), "acme".to_string());
+        config.force_read_only();
+        let mut client = SentryClient::new().unwrap().with_dry_run(true);
+        // Gets past the read-only check and fails only for the missing token
+        let error = login_for_write(&config, &mut client, "acme").unwrap_err();
+        assert!(!error.to_string().contains("read-only"));
+    }
 
-                        let token = org_entry.get_auth_token()?.ok_or_else(|| {
-                            anyhow::anyhow!(
-                                "Not logged in for organization '{}'. Use 'login' first.",
-                                org
-                            )
-                        })?;
+    #[test]
+    fn test_config_encrypt_commands() {
+        let cli = Cli::parse_from(&["sex-cli", "config", "encrypt"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Config {
+                command: ConfigCommands::Encrypt
+            }
+        ));
+    }
 
-                        client.login(token)?;
-                        start_project_info(&client, org_entry.slug.clone(), project)?;
-                    } else {
-                        println!("Project identifier must include organization");
+    #[test]
+    fn test_config_import_command() {
+        let cli = Cli::parse_from(&["sex-cli", "config", "import", "sentry-cli"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Config {
+                command: ConfigCommands::Import {
+                    source: ImportSource::SentryCli,
+                    file: None
+                }
+            }
+        ));
+        assert!(Cli::try_parse_from(["sex-cli", "config", "import", "other-cli"]).is_err());
+    }
+
+    #[test]
+    fn test_config_secrets_commands() {
+        let cli = Cli::parse_from(&["sex-cli", "config", "secrets", "migrate", "--to", "file"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Config {
+                command: ConfigCommands::Secrets {
+                    command: SecretsCommands::Migrate {
+                        to: SecretsBackend::File
                     }
                 }
-            },
-            Commands::Completion { shell } => {
-                let mut cmd = Self::command();
-                let bin_name = cmd.get_name().to_string();
-                generate(shell, &mut cmd, bin_name, &mut io::stdout());
             }
-        }
+        ));
+        assert!(
+            Cli::try_parse_from(["sex-cli", "config", "secrets", "migrate", "--to", "vault"])
+                .is_err()
+        );
+    }
 
-        Ok(())
+    #[test]
+    fn test_issue_report_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "issue",
+            "report",
+            "BACKEND-4X3",
+            "--format",
+            "markdown",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue {
+                command: IssueCommands::Report { id, format: ReportFormat::Markdown }
+            } if id == "BACKEND-4X3"
+        ));
     }
 
-    #[cfg(test)]
-    pub fn parse_from(args: &[&str]) -> Self {
-        Self::try_parse_from(args).unwrap()
+    #[test]
+    fn test_report_digest_command() {
+        let cli = Cli::parse_from(&["sex-cli", "report", "digest", "acme", "--format", "html"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Report {
+                command: ReportCommands::Digest { org, period, format: DigestFormat::Html }
+            } if org == "acme" && period == "7d"
+        ));
     }
-}
 
-fn start_monitor(client: &SentryClient, org_slug: String, project_slug: String) -> Result<()> {
-    println!(
-        "Starting monitor for organization: {} project: {}",
-        org_slug, project_slug
-    );
-    let mut dashboard = Dashboard::new(client.clone(), org_slug, project_slug);
-    dashboard.run()
-}
+    #[test]
+    fn test_format_event_line() {
+        let event: SentryEvent = serde_json::from_value(serde_json::json!({
+            "eventID": "abc",
+            "title": "ValueError: bad input",
+            "dateCreated": "2024-01-02T00:00:00Z",
+            "tags": [{"key": "level", "value": "fatal"}],
+            "user": null
+        }))
+        .unwrap();
+        assert_eq!(
+            format_event_line(&event),
+            "2024-01-02T00:00:00Z  fatal    ValueError: bad input  [-]"
+        );
+    }
 
-fn select_organization(matches: &[(Organization, String)]) -> Result<(&Organization, String)> {
-    println!("\nMultiple organizations have this project. Please select one:");
+    #[test]
+    fn test_event_context_columns() {
+        let event: SentryEvent = serde_json::from_value(serde_json::json!({
+            "eventID": "abc",
+            "title": "TypeError",
+            "dateCreated": "2024-01-02T00:00:00Z",
+            "tags": [
+                {"key": "browser", "value": "Chrome 120.0"},
+                {"key": "os.name", "value": "Windows"}
+            ],
+            "user": null
+        }))
+        .unwrap();
+        let columns = ["os", "runtime", "browser"].map(String::from);
+        let table = Table::new(select_columns(event_columns(), &columns, &[]).unwrap());
+        let output = table.render(&[(event, String::new())]);
+        let row = output.lines().nth(1).unwrap();
+        assert_eq!(
+            row.split_whitespace().collect::<Vec<_>>(),
+            ["Windows", "-", "Chrome", "120.0"]
+        );
+    }
 
-    terminal::enable_raw_mode()?;
-    execute!(io::stdout(), Hide)?;
+    #[test]
+    fn test_login_command() {
+        let cli = Cli::parse_from(&["sex-cli", "login", "test-org"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Login { org, .. }
+            if org.as_deref() == Some("test-org")
+        ));
+    }
 
-    let mut selected = 0;
-    let mut result = None;
+    #[test]
+    fn test_login_role() {
+        let cli = Cli::parse_from(&["sex-cli", "login", "--role", "write", "test-org"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Login {
+                role: TokenRole::Write,
+                ..
+            }
+        ));
 
-    loop {
-        execute!(
-            io::stdout(),
-            Clear(ClearType::All),
-            cursor::MoveTo(0, 0),
-            Print("Use arrow keys to select an organization and press Enter:\n\n")
-        )?;
+        let cli = Cli::parse_from(&["sex-cli", "login", "test-org"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Login {
+                role: TokenRole::Read,
+                ..
+            }
+        ));
+    }
 
-        for (i, (org, _)) in matches.iter().enumerate() {
-            let prefix = if i == selected { "> " } else { "  " };
-            let color = if i == selected {
-                Color::Green
-            } else {
-                Color::Reset
-            };
+    #[test]
+    fn test_monitor_command() {
+        // Test project-only format
+        let cli = Cli::parse_from(&["sex-cli", "monitor", "my-project"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Monitor { target, .. }
+            if target.as_deref() == Some("my-project")
+        ));
 
-            execute!(
-                io::stdout(),
-                SetForegroundColor(color),
-                Print(format!("{}{} ({})\n", prefix, org.name, org.slug)),
-                SetForegroundColor(Color::Reset)
-            )?;
-        }
+        // Test org/project format
+        let cli = Cli::parse_from(&["sex-cli", "monitor", "test-org/my-project"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Monitor { target, .. }
+            if target.as_deref() == Some("test-org/my-project")
+        ));
 
-        io::stdout().flush()?;
+        // Whole organization
+        let cli = Cli::parse_from(&["sex-cli", "monitor", "--org", "test-org", "--all"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Monitor { target: None, org: Some(org), all: true, .. }
+            if org == "test-org"
+        ));
+        // Without a target the last one is resumed
+        let cli = Cli::parse_from(&["sex-cli", "monitor"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Monitor {
+                target: None,
+                all: false,
+                resume: false,
+                ..
+            }
+        ));
+        let cli = Cli::parse_from(&["sex-cli", "monitor", "--resume"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Monitor { resume: true, .. }
+        ));
+        assert!(Cli::try_parse_from(["sex-cli", "monitor", "my-project", "--resume"]).is_err());
 
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Up if selected > 0 => selected -= 1,
-                KeyCode::Down if selected < matches.len() - 1 => selected += 1,
-                KeyCode::Enter => {
-                    result = Some((&matches[selected].0, matches[selected].1.clone()));
-                    break;
-                }
-                KeyCode::Esc => {
-                    println!("Operation cancelled");
-                    break;
-                }
-                _ => {}
+        // Tabs
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "monitor",
+            "--tab",
+            "acme/*",
+            "--tab",
+            "globex/web",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Monitor { tabs, all_orgs: false, .. } if tabs == ["acme/*", "globex/web"]
+        ));
+        assert!(Cli::try_parse_from(["sex-cli", "monitor", "my-project", "--tab", "web"]).is_err());
+        assert!(Cli::try_parse_from(["sex-cli", "monitor", "--all-orgs", "--tab", "web"]).is_err());
+
+        // Server-side filters
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "monitor",
+            "my-project",
+            "--query",
+            "is:unresolved assigned:me",
+            "--level",
+            "error",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Monitor { query: Some(query), level, .. }
+            if query == "is:unresolved assigned:me" && level == ["error"]
+        ));
+        assert!(
+            Cli::try_parse_from(["sex-cli", "monitor", "my-project", "--level", "loud"]).is_err()
+        );
+        assert!(
+            Cli::try_parse_from(["sex-cli", "issue", "list", "--level", "fatal,error,loud"])
+                .is_err()
+        );
+        let cli = Cli::parse_from(&["sex-cli", "issue", "list", "--level", "fatal,error"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Issue { command: IssueCommands::List { level, .. } }
+            if level == ["fatal", "error"]
+        ));
+
+        // Threshold alerts
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "monitor",
+            "my-project",
+            "--alert",
+            "count>100 in 10m",
+            "--alert",
+            "users>10 in 1h",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Monitor { alerts, .. }
+            if alerts.len() == 2 && alerts[0].threshold == 100 && alerts[1].window == 3600
+        ));
+        assert!(
+            Cli::try_parse_from(["sex-cli", "monitor", "my-project", "--alert", "count>100"])
+                .is_err()
+        );
+        assert!(Cli::try_parse_from(["sex-cli", "monitor", "--org", "test-org"]).is_err());
+
+        let cli = Cli::parse_from(&["sex-cli", "monitor", "web", "--emit", "events.jsonl"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Monitor { emit: Some(path), .. } if path == Path::new("events.jsonl")
+        ));
+    }
+
+    #[test]
+    fn test_project_list_command() {
+        let cli = Cli::parse_from(&["sex-cli", "project", "list"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Project {
+                command: ProjectCommands::List { .. }
             }
-        }
+        ));
     }
 
-    terminal::disable_raw_mode()?;
-    execute!(io::stdout(), Show)?;
-    println!();
+    #[test]
+    fn test_project_list_filters() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "project",
+            "list",
+            "--platform",
+            "python",
+            "--team",
+            "backend",
+            "--member-only",
+        ]);
+        let Commands::Project {
+            command: ProjectCommands::List { filters, .. },
+        } = cli.command
+        else {
+            panic!("expected project list command");
+        };
 
-    result.ok_or_else(|| anyhow::anyhow!("No organization selected"))
-}
+        let project = |platform: &str, team: &str, member: bool| -> Project {
+            serde_json::from_value(serde_json::json!({
+                "slug": "p",
+                "name": "P",
+                "platform": platform,
+                "isMember": member,
+                "teams": [{"id": "1", "name": team, "slug": team}]
+            }))
+            .unwrap()
+        };
 
-fn start_project_info(client: &SentryClient, org_slug: String, project_slug: String) -> Result<()> {
-    println!(
-        "Starting project info for organization: {} project: {}",
-        org_slug, project_slug
-    );
-    let project_info = client.get_project_info(&org_slug, &project_slug)?;
-    println!("Project Info:");
-    for (key, value) in project_info {
-        println!("  {}: {}", key, value);
+        assert!(filters.matches(&project("python-django", "backend", true)));
+        assert!(!filters.matches(&project("pythonista", "backend", true)));
+        assert!(!filters.matches(&project("python", "frontend", true)));
+        assert!(!filters.matches(&project("python", "backend", false)));
+        assert!(ProjectFilters::default().matches(&project("go", "infra", false)));
     }
-    Ok(())
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_project_info_show_dsn() {
+        let cli = Cli::parse_from(&["sex-cli", "project", "info", "test-org/p", "--show-dsn"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Project {
+                command: ProjectCommands::Info { show_dsn: true, .. }
+            }
+        ));
+    }
 
     #[test]
-    fn test_org_list_command() {
-        let cli = Cli::parse_from(&["sex-cli", "org", "list"]);
+    fn test_project_stats_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "project",
+            "stats",
+            "my-org/web",
+            "--export",
+            "csv",
+            "--period",
+            "14d",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Project {
+                command: ProjectCommands::Stats { target, period, export: ExportFormat::Csv }
+            } if target == "my-org/web" && period == "14d"
+        ));
+        assert!(
+            Cli::try_parse_from(["sex-cli", "project", "stats", "web", "--period", "30"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_auth_scopes_command() {
+        let cli = Cli::parse_from(&["sex-cli", "auth", "scopes", "acme"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Auth {
+                command: AuthCommands::Scopes { org }
+            } if org == "acme"
+        ));
+    }
+
+    #[test]
+    fn test_org_usage_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "org",
+            "usage",
+            "acme",
+            "--quota",
+            "error=50000",
+            "--quota",
+            "transaction=100000",
+        ]);
         assert!(matches!(
             cli.command,
             Commands::Org {
-                command: OrgCommands::List
-            }
+                command: OrgCommands::Usage { name, period, quotas }
+            } if name == "acme"
+                && period == "30d"
+                && quotas == vec![("error".to_string(), 50000), ("transaction".to_string(), 100000)]
         ));
+        assert!(
+            Cli::try_parse_from(["sex-cli", "org", "usage", "acme", "--quota", "error"]).is_err()
+        );
+        assert!(
+            Cli::try_parse_from(["sex-cli", "org", "usage", "acme", "--quota", "error=lots"])
+                .is_err()
+        );
     }
 
     #[test]
-    fn test_org_add_command() {
-        let cli = Cli::parse_from(&["sex-cli", "org", "add", "test", "test-slug"]);
+    fn test_org_info_command() {
+        let cli = Cli::parse_from(&["sex-cli", "org", "info", "acme"]);
         assert!(matches!(
             cli.command,
             Commands::Org {
-                command: OrgCommands::Add {
-                    name,
-                    slug,
-                }
-            } if name == "test" && slug == "test-slug"
+                command: OrgCommands::Info { name }
+            } if name == "acme"
         ));
+        assert!(Cli::try_parse_from(["sex-cli", "org", "info"]).is_err());
     }
 
     #[test]
-    fn test_issue_list_command() {
-        let cli = Cli::parse_from(&["sex-cli", "issue", "list"]);
+    fn test_api_limits_command() {
+        let cli = Cli::parse_from(&["sex-cli", "api", "limits", "acme"]);
         assert!(matches!(
             cli.command,
-            Commands::Issue {
-                command: IssueCommands::List
-            }
+            Commands::Api {
+                command: ApiCommands::Limits { org }
+            } if org == "acme"
         ));
     }
 
     #[test]
-    fn test_issue_view_command() {
-        let cli = Cli::parse_from(&["sex-cli", "issue", "view", "test-id"]);
+    fn test_project_compare_command() {
+        let cli = Cli::parse_from(&["sex-cli", "project", "compare", "my-org/web"]);
         assert!(matches!(
             cli.command,
-            Commands::Issue {
-                command: IssueCommands::View {
-                    id,
-                }
-            } if id == "test-id"
+            Commands::Project {
+                command: ProjectCommands::Compare { target, period }
+            } if target == "my-org/web" && period == "7d"
         ));
     }
 
     #[test]
-    fn test_login_command() {
-        let cli = Cli::parse_from(&["sex-cli", "login", "test-org"]);
+    fn test_project_keys_rate_limit() {
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "project",
+            "keys",
+            "rate-limit",
+            "Default",
+            "--project",
+            "acme/api",
+            "--count",
+            "1000",
+            "--window",
+            "60",
+        ]);
         assert!(matches!(
             cli.command,
-            Commands::Login { org }
-            if org == "test-org"
+            Commands::Project {
+                command: ProjectCommands::Keys {
+                    command: KeyCommands::RateLimit {
+                        key,
+                        count: Some(1000),
+                        window: Some(60),
+                        remove: false,
+                        ..
+                    }
+                }
+            } if key == "Default"
         ));
+        // A count needs a window
+        assert!(Cli::try_parse_from([
+            "sex-cli",
+            "project",
+            "keys",
+            "rate-limit",
+            "Default",
+            "--project",
+            "acme/api",
+            "--count",
+            "1000"
+        ])
+        .is_err());
+
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "project",
+            "filters",
+            "acme/web",
+            "--enable",
+            "localhost,web-crawlers",
+            "--spike-protection",
+            "off",
+        ]);
+        match cli.command {
+            Commands::Project {
+                command:
+                    ProjectCommands::Filters {
+                        enable,
+                        disable,
+                        spike_protection,
+                        ..
+                    },
+            } => {
+                assert_eq!(
+                    filter_changes(&enable, &disable, spike_protection),
+                    vec![
+                        "turn on localhost, web-crawlers",
+                        "turn spike protection off"
+                    ]
+                );
+            }
+            _ => panic!("expected project filters"),
+        }
+        assert!(Cli::try_parse_from([
+            "sex-cli", "project", "filters", "acme/web", "--enable", "adblock"
+        ])
+        .is_err());
+
+        let keys: Vec<ClientKey> = serde_json::from_value(serde_json::json!([
+            {"id": "abc", "name": "Default", "dsn": {"public": "https://abc@o1.ingest.sentry.io/1"}},
+            {"id": "def", "name": "Mobile", "dsn": {"public": "https://def@o1.ingest.sentry.io/1"}}
+        ]))
+        .unwrap();
+        assert_eq!(find_client_key(keys, "mobile").unwrap().id, "def");
     }
 
     #[test]
-    fn test_monitor_command() {
-        // Test project-only format
-        let cli = Cli::parse_from(&["sex-cli", "monitor", "my-project"]);
+    fn test_project_bookmark_commands() {
+        let cli = Cli::parse_from(&["sex-cli", "project", "bookmark", "test-org/my-project"]);
         assert!(matches!(
             cli.command,
-            Commands::Monitor { target }
-            if target == "my-project"
+            Commands::Project {
+                command: ProjectCommands::Bookmark { target }
+            } if target == "test-org/my-project"
         ));
 
-        // Test org/project format
-        let cli = Cli::parse_from(&["sex-cli", "monitor", "test-org/my-project"]);
+        let cli = Cli::parse_from(&["sex-cli", "project", "unbookmark", "my-project"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Project {
+                command: ProjectCommands::Unbookmark { target }
+            } if target == "my-project"
+        ));
+
+        let cli = Cli::parse_from(&["sex-cli", "project", "list", "--bookmarked"]);
         assert!(matches!(
             cli.command,
-            Commands::Monitor { target }
-            if target == "test-org/my-project"
+            Commands::Project {
+                command: ProjectCommands::List { filters, .. }
+            } if filters.bookmarked
         ));
     }
 
     #[test]
-    fn test_project_list_command() {
-        let cli = Cli::parse_from(&["sex-cli", "project", "list"]);
+    fn test_project_owners_command() {
+        let cli = Cli::parse_from(&[
+            "sex-cli", "project", "owners", "acme/web", "--issue", "WEB-12",
+        ]);
         assert!(matches!(
             cli.command,
             Commands::Project {
-                command: ProjectCommands::List
-            }
+                command: ProjectCommands::Owners { target, issue: Some(issue) }
+            } if target == "acme/web" && issue == "WEB-12"
         ));
     }
 
@@ -621,8 +8123,27 @@ mod tests {
             Commands::Project {
                 command: ProjectCommands::Info {
                     target,
+                    interactive: false,
+                    show_dsn: false,
                 }
             } if target == "test-org/my-project"
         ));
+
+        let cli = Cli::parse_from(&[
+            "sex-cli",
+            "project",
+            "info",
+            "test-org/my-project",
+            "--interactive",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Commands::Project {
+                command: ProjectCommands::Info {
+                    interactive: true,
+                    ..
+                }
+            }
+        ));
     }
 }
@@ -1,6 +1,7 @@
+use crate::history::SearchHistory;
+use crate::secrets::{env_var_name, SecretsBackend};
 use anyhow::{Context, Result};
 use base64::Engine;
-use keyring::Entry;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use sodiumoxide::crypto::secretbox;
@@ -8,10 +9,9 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
-const KEYRING_SERVICE: &str = "sex-cli";
-const KEYRING_USERNAME: &str = "project-encryption-key";
+const PROJECT_KEY_ACCOUNT: &str = "project-encryption-key";
 const PROJECT_KEY_LENGTH: usize = 32;
-const APP_NAME: &str = "sex-cli";
+pub const APP_NAME: &str = "sex-cli";
 const CONFIG_FILE: &str = "config.json";
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -21,20 +21,112 @@ pub struct EncryptedProject {
     pub slug: String,
 }
 
+/// Role of a stored token. Listing uses the read token and mutating commands the
+/// write token, so a leaked read-only token cannot change anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TokenRole {
+    Read,
+    Write,
+}
+
+impl TokenRole {
+    fn keyring_user(self) -> &'static str {
+        match self {
+            // The read token keeps the entry name used before roles existed
+            TokenRole::Read => "auth-token",
+            TokenRole::Write => "auth-token-write",
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            TokenRole::Read => TokenRole::Write,
+            TokenRole::Write => TokenRole::Read,
+        }
+    }
+}
+
+impl std::fmt::Display for TokenRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenRole::Read => write!(f, "read"),
+            TokenRole::Write => write!(f, "write"),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Organization {
     pub name: String,
     pub slug: String,
     #[serde(skip)]
-    keyring: Option<Entry>,
+    secrets: SecretsBackend,
     #[serde(default)]
     #[serde(with = "encrypted_projects")]
     pub(crate) projects: HashMap<String, EncryptedProject>,
+    /// Issue IDs hidden from the monitor, keyed by project slug
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub muted_issues: HashMap<String, Vec<String>>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Config {
     pub organizations: HashMap<String, Organization>,
+    /// Local checkout that stack frame paths are resolved against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_root: Option<PathBuf>,
+    #[serde(default)]
+    pub secrets: SecretsSettings,
+    /// Sentry server for self-hosted installs; sentry.io when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Project used when a command's target is omitted, as `org/project`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_project: Option<String>,
+    /// Target of the most recent `monitor` run, for `monitor --resume`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_monitor: Option<MonitorTarget>,
+    /// Refuse commands that change data in Sentry, e.g. for shared automation
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+    /// Searches used in the monitor and triage, and filters saved from them
+    #[serde(default, skip_serializing_if = "SearchHistory::is_empty")]
+    pub searches: SearchHistory,
+    /// Entry chosen with `--as` for its organization slug, for this run only
+    #[serde(skip)]
+    account: Option<String>,
+    /// Read-only mode turned on with `--read-only`, for this run only
+    #[serde(skip)]
+    read_only_flag: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorTarget {
+    pub org_slug: String,
+    pub projects: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SecretsSettings {
+    #[serde(default)]
+    pub backend: SecretsBackend,
+    /// Encrypt the whole config file with the project encryption key
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypt_config: bool,
+}
+
+/// On-disk form of an encrypted config. The backend stays readable so the key can be
+/// found before anything is decrypted.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedConfig {
+    backend: SecretsBackend,
+    #[serde(with = "encrypted_data")]
+    data: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedConfigFile {
+    encrypted: EncryptedConfig,
 }
 
 mod encrypted_data {
@@ -96,8 +188,105 @@ impl Config {
         let content = fs::read_to_string(&config_path)
             .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
 
-        serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse config file: {}", config_path.display()))
+        let content = match serde_json::from_str::<EncryptedConfigFile>(&content) {
+            Ok(file) => {
+                let key = Self::get_project_key(file.encrypted.backend)?;
+                decrypt_config(&file.encrypted, &key).with_context(|| {
+                    format!("Failed to decrypt config file: {}", config_path.display())
+                })?
+            }
+            Err(_) => content,
+        };
+
+        let mut config: Config = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", config_path.display()))?;
+        config.set_secrets_backend(config.secrets.backend);
+        Ok(config)
+    }
+
+    fn set_secrets_backend(&mut self, backend: SecretsBackend) {
+        self.secrets.backend = backend;
+        for org in self.organizations.values_mut() {
+            org.secrets = backend;
+        }
+    }
+
+    /// Moves all tokens and the project key to another backend and switches to it.
+    /// The env backend cannot be written, so moving there keeps the secrets where they
+    /// are and returns the variables to export instead.
+    ///
+    /// Every secret is copied and the config saved before anything is deleted from the
+    /// old backend, so a failure part way leaves the old backend in use and complete.
+    pub fn migrate_secrets(&mut self, to: SecretsBackend) -> Result<Vec<(String, String)>> {
+        let from = self.secrets.backend;
+        let mut moves: Vec<(String, SecretsBackend, SecretsBackend)> = Vec::new();
+        for org in self.organizations.values() {
+            for role in [TokenRole::Read, TokenRole::Write] {
+                moves.push((org.token_account(role), from, to));
+            }
+        }
+        moves.push((
+            PROJECT_KEY_ACCOUNT.to_string(),
+            from.generated_secrets(),
+            to.generated_secrets(),
+        ));
+
+        let mut exports = Vec::new();
+        let mut copied: Vec<(String, SecretsBackend, SecretsBackend)> = Vec::new();
+        for (account, source, target) in moves {
+            if source == target {
+                continue;
+            }
+            let value = match source.get(&account) {
+                Ok(Some(value)) => value,
+                Ok(None) => continue,
+                Err(e) => return Err(roll_back_copies(&copied, e)),
+            };
+            if target == SecretsBackend::Env {
+                exports.push((env_var_name(&account), value));
+                continue;
+            }
+            if let Err(e) = target.set(&account, &value) {
+                return Err(roll_back_copies(&copied, e));
+            }
+            copied.push((account, source, target));
+        }
+
+        self.set_secrets_backend(to);
+        if let Err(e) = self.save() {
+            self.set_secrets_backend(from);
+            return Err(roll_back_copies(&copied, e));
+        }
+        for (account, source, _) in &copied {
+            source.delete(account).with_context(|| {
+                format!(
+                    "Switched to the {} backend but could not remove '{}' from the {} backend",
+                    to, account, source
+                )
+            })?;
+        }
+        Ok(exports)
+    }
+
+    /// Points the config at the server of an organization being added, `None` being
+    /// sentry.io. All organizations share one server, so switching is refused while
+    /// others are configured for a different one.
+    pub fn use_server(&mut self, server: Option<String>) -> Result<()> {
+        let normalize =
+            |url: &Option<String>| url.as_deref().map(|u| u.trim_end_matches('/').to_string());
+        if normalize(&self.url) == normalize(&server) {
+            return Ok(());
+        }
+        if !self.organizations.is_empty() {
+            let name = |url: &Option<String>| url.clone().unwrap_or_else(|| "sentry.io".into());
+            anyhow::bail!(
+                "The configured organizations use {}, not {}; all organizations must be on the same Sentry server",
+                name(&self.url),
+                name(&server)
+            );
+        }
+        self.url = server;
+        Ok(())
     }
 
     pub fn save(&self) -> Result<()> {
@@ -108,7 +297,15 @@ impl Config {
             })?;
         }
 
-        let content = serde_json::to_string_pretty(self).context("Failed to serialize config")?;
+        let mut content =
+            serde_json::to_string_pretty(self).context("Failed to serialize config")?;
+        if self.secrets.encrypt_config {
+            let key = Self::get_project_key(self.secrets.backend)?;
+            let file = EncryptedConfigFile {
+                encrypted: encrypt_config(&content, self.secrets.backend, &key),
+            };
+            content = serde_json::to_string_pretty(&file).context("Failed to serialize config")?;
+        }
 
         fs::write(&config_path, content)
             .with_context(|| format!("Failed to write config file: {}", config_path.display()))
@@ -120,8 +317,9 @@ impl Config {
             Organization {
                 name,
                 slug,
-                keyring: None,
+                secrets: self.secrets.backend,
                 projects: HashMap::new(),
+                muted_issues: HashMap::new(),
             },
         );
     }
@@ -134,11 +332,71 @@ impl Config {
         self.organizations.get_mut(name)
     }
 
-    fn get_project_key() -> Result<[u8; PROJECT_KEY_LENGTH]> {
-        let keyring = Entry::new(KEYRING_SERVICE, KEYRING_USERNAME)?;
+    /// The account used for an organization slug. Several entries may share a slug with
+    /// different tokens, e.g. a personal and a bot account; the one selected with `--as`
+    /// wins, then the entry named like the slug, then the first by name.
+    pub fn get_organization_by_slug(&self, slug: &str) -> Option<&Organization> {
+        let mut entries: Vec<&Organization> = self
+            .organizations
+            .values()
+            .filter(|org| org.slug == slug)
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+            .iter()
+            .find(|org| Some(&org.name) == self.account.as_ref())
+            .or_else(|| entries.iter().find(|org| org.name == org.slug))
+            .or(entries.first())
+            .copied()
+    }
+
+    /// Turns on read-only mode for this run without saving it, as with `--read-only`.
+    pub fn force_read_only(&mut self) {
+        self.read_only_flag = true;
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only || self.read_only_flag
+    }
+
+    /// Selects the entry to use for its organization slug, as with `--as`.
+    pub fn select_account(&mut self, name: &str) -> Result<()> {
+        if !self.organizations.contains_key(name) {
+            anyhow::bail!("Account '{}' not found. Add it first with 'org add'.", name);
+        }
+        self.account = Some(name.to_string());
+        Ok(())
+    }
+
+    /// One entry per organization slug, sorted by name, for commands that go through
+    /// every organization.
+    pub fn accounts(&self) -> Vec<&Organization> {
+        let mut accounts: Vec<&Organization> = self
+            .organizations
+            .values()
+            .filter(|org| {
+                self.get_organization_by_slug(&org.slug)
+                    .is_some_and(|account| account.name == org.name)
+            })
+            .collect();
+        accounts.sort_by(|a, b| a.name.cmp(&b.name));
+        accounts
+    }
 
-        match keyring.get_password() {
-            Ok(key_str) => {
+    /// The entry selected with `--as` when it shares the organization of `org`.
+    pub fn account_for<'a>(&'a self, org: &'a Organization) -> &'a Organization {
+        self.account
+            .as_ref()
+            .and_then(|name| self.organizations.get(name))
+            .filter(|account| account.slug == org.slug)
+            .unwrap_or(org)
+    }
+
+    fn get_project_key(secrets: SecretsBackend) -> Result<[u8; PROJECT_KEY_LENGTH]> {
+        let secrets = secrets.generated_secrets();
+
+        match secrets.get(PROJECT_KEY_ACCOUNT)? {
+            Some(key_str) => {
                 let key_bytes = base64::engine::general_purpose::STANDARD
                     .decode(key_str)
                     .context("Failed to decode project key")?;
@@ -146,12 +404,12 @@ impl Config {
                 key.copy_from_slice(&key_bytes);
                 Ok(key)
             }
-            Err(_) => {
+            None => {
                 // Generate new key if not exists
                 let mut key = [0u8; PROJECT_KEY_LENGTH];
                 rand::thread_rng().fill_bytes(&mut key);
                 let key_str = base64::engine::general_purpose::STANDARD.encode(key);
-                keyring.set_password(&key_str)?;
+                secrets.set(PROJECT_KEY_ACCOUNT, &key_str)?;
                 Ok(key)
             }
         }
@@ -180,71 +438,135 @@ impl Config {
         matches
     }
 
+    /// Replaces the search history and saved filters, saving when they changed.
+    pub fn set_searches(&mut self, searches: SearchHistory) -> Result<()> {
+        if self.searches == searches {
+            return Ok(());
+        }
+        self.searches = searches;
+        self.save()
+    }
+
+    /// Replaces the monitor's persistently muted issues of an organization and saves.
+    pub fn set_muted_issues(
+        &mut self,
+        org_slug: &str,
+        muted: HashMap<String, Vec<String>>,
+    ) -> Result<()> {
+        if self.replace_muted_issues(org_slug, muted) {
+            self.save()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Replaces the muted issues of the account used for an organization slug, the one
+    /// they are read from, returning whether they changed.
+    fn replace_muted_issues(
+        &mut self,
+        org_slug: &str,
+        muted: HashMap<String, Vec<String>>,
+    ) -> bool {
+        let Some(name) = self
+            .get_organization_by_slug(org_slug)
+            .map(|org| org.name.clone())
+        else {
+            return false;
+        };
+        let Some(org) = self.organizations.get_mut(&name) else {
+            return false;
+        };
+        if org.muted_issues == muted {
+            return false;
+        }
+        org.muted_issues = muted;
+        true
+    }
+
     pub fn cache_project(
         &mut self,
         org_name: &str,
         project_slug: String,
         project_name: String,
+    ) -> Result<()> {
+        self.cache_projects(org_name, vec![(project_slug, project_name)])
+    }
+
+    /// Caches `(slug, name)` pairs for an organization, saving the config once.
+    pub fn cache_projects(
+        &mut self,
+        org_name: &str,
+        projects: Vec<(String, String)>,
     ) -> Result<()> {
         if let Some(org) = self.organizations.get_mut(org_name) {
-            let key = Self::get_project_key()?;
-            let nonce = secretbox::gen_nonce();
-            let encrypted_name =
-                secretbox::seal(project_name.as_bytes(), &nonce, &secretbox::Key(key));
-
-            let mut combined = nonce.as_ref().to_vec();
-            combined.extend(encrypted_name);
-
-            org.projects.insert(
-                project_slug.clone(),
-                EncryptedProject {
-                    name: combined,
-                    slug: project_slug,
-                },
-            );
+            let key = Self::get_project_key(self.secrets.backend)?;
+            for (project_slug, project_name) in projects {
+                org.projects.insert(
+                    project_slug.clone(),
+                    EncryptedProject {
+                        name: seal(project_name.as_bytes(), &key),
+                        slug: project_slug,
+                    },
+                );
+            }
             self.save()?;
         }
         Ok(())
     }
 }
 
+/// Removes secrets copied by an unfinished migration, keeping the error that stopped it.
+fn roll_back_copies(
+    copied: &[(String, SecretsBackend, SecretsBackend)],
+    error: anyhow::Error,
+) -> anyhow::Error {
+    for (account, _, target) in copied {
+        let _ = target.delete(account);
+    }
+    error.context("Secrets were not migrated; the current backend is unchanged")
+}
+
 impl Organization {
-    pub fn new(name: String, slug: String) -> Self {
-        let keyring = Entry::new(&format!("{}-{}", APP_NAME, name), "auth-token").ok();
-        Self {
-            name,
-            slug,
-            keyring,
-            projects: HashMap::new(),
+    fn token_account(&self, role: TokenRole) -> String {
+        format!("{}/{}", self.name, role.keyring_user())
+    }
+
+    /// Token stored for exactly this role.
+    pub fn get_stored_token(&self, role: TokenRole) -> Result<Option<String>> {
+        self.secrets.get(&self.token_account(role))
+    }
+
+    /// Token to use for a role. With a single stored token, it serves both roles.
+    pub fn get_token(&self, role: TokenRole) -> Result<Option<String>> {
+        match self.get_stored_token(role)? {
+            Some(token) => Ok(Some(token)),
+            None => self.get_stored_token(role.other()),
         }
     }
 
     pub fn get_auth_token(&self) -> Result<Option<String>> {
-        Ok(self.keyring.as_ref().and_then(|k| k.get_password().ok()))
+        self.get_token(TokenRole::Read)
     }
 
-    pub fn set_auth_token(&mut self, token: String) -> Result<()> {
-        if let Some(keyring) = &self.keyring {
-            keyring.set_password(&token)?;
+    pub fn set_token(&mut self, role: TokenRole, token: String) -> Result<()> {
+        self.secrets.set(&self.token_account(role), &token)
+    }
+
+    /// Roles that have a token of their own.
+    pub fn token_roles(&self) -> Result<Vec<TokenRole>> {
+        let mut roles = Vec::new();
+        for role in [TokenRole::Read, TokenRole::Write] {
+            if self.get_stored_token(role)?.is_some() {
+                roles.push(role);
+            }
         }
-        Ok(())
+        Ok(roles)
     }
 
     pub fn get_project(&self, slug: &str) -> Option<Result<String>> {
         self.projects.get(slug).map(|project| {
-            let key = Config::get_project_key()?;
-            let combined = &project.name;
-            if combined.len() < secretbox::NONCEBYTES {
-                return Err(anyhow::anyhow!("Invalid encrypted project data"));
-            }
-
-            let (nonce_bytes, encrypted) = combined.split_at(secretbox::NONCEBYTES);
-            let nonce =
-                secretbox::Nonce::from_slice(nonce_bytes).context("Invalid nonce length")?;
-
-            let decrypted = secretbox::open(encrypted, &nonce, &secretbox::Key(key))
-                .map_err(|_| anyhow::anyhow!("Failed to decrypt project name"))?;
-
+            let key = Config::get_project_key(self.secrets)?;
+            let decrypted = open(&project.name, &key).context("Failed to decrypt project name")?;
             String::from_utf8(decrypted).context("Invalid UTF-8 in decrypted project name")
         })
     }
@@ -265,11 +587,123 @@ impl Organization {
     }
 }
 
-fn get_config_path() -> Result<PathBuf> {
-    let config_dir = dirs::config_dir()
+/// Encrypts data with secretbox and prepends the nonce.
+fn seal(data: &[u8], key: &[u8; PROJECT_KEY_LENGTH]) -> Vec<u8> {
+    let nonce = secretbox::gen_nonce();
+    let mut combined = nonce.as_ref().to_vec();
+    combined.extend(secretbox::seal(data, &nonce, &secretbox::Key(*key)));
+    combined
+}
+
+fn open(combined: &[u8], key: &[u8; PROJECT_KEY_LENGTH]) -> Result<Vec<u8>> {
+    if combined.len() < secretbox::NONCEBYTES {
+        return Err(anyhow::anyhow!("Invalid encrypted data"));
+    }
+    let (nonce_bytes, encrypted) = combined.split_at(secretbox::NONCEBYTES);
+    let nonce = secretbox::Nonce::from_slice(nonce_bytes).context("Invalid nonce length")?;
+    secretbox::open(encrypted, &nonce, &secretbox::Key(*key))
+        .map_err(|_| anyhow::anyhow!("Wrong key or corrupted data"))
+}
+
+fn encrypt_config(
+    content: &str,
+    backend: SecretsBackend,
+    key: &[u8; PROJECT_KEY_LENGTH],
+) -> EncryptedConfig {
+    EncryptedConfig {
+        backend,
+        data: seal(content.as_bytes(), key),
+    }
+}
+
+fn decrypt_config(config: &EncryptedConfig, key: &[u8; PROJECT_KEY_LENGTH]) -> Result<String> {
+    String::from_utf8(open(&config.data, key)?).context("Invalid UTF-8 in decrypted config")
+}
+
+pub fn config_dir() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
         .context("Failed to determine config directory")?
-        .join("sex-cli");
-    Ok(config_dir.join("config.json"))
+        .join(APP_NAME))
+}
+
+fn get_config_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join(CONFIG_FILE))
+}
+
+/// Settings of the official sentry-cli, from `~/.sentryclirc` and `SENTRY_*` variables.
+#[derive(Debug, Default, PartialEq)]
+pub struct SentryCliSettings {
+    pub token: Option<String>,
+    pub url: Option<String>,
+    pub org: Option<String>,
+    pub project: Option<String>,
+}
+
+impl SentryCliSettings {
+    /// Reads the given rc file (or `~/.sentryclirc`), then applies environment overrides
+    /// the way sentry-cli does.
+    pub fn load(path: Option<PathBuf>) -> Result<Self> {
+        let path = match path {
+            Some(path) => Some(path),
+            None => dirs::home_dir().map(|home| home.join(".sentryclirc")),
+        };
+        let mut settings = match path.filter(|path| path.exists()) {
+            Some(path) => Self::parse(
+                &fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?,
+            ),
+            None => Self::default(),
+        };
+        settings.apply_env(|name| std::env::var(name).ok());
+        Ok(settings)
+    }
+
+    /// Parses the INI format of `.sentryclirc`.
+    pub fn parse(content: &str) -> Self {
+        let mut settings = Self::default();
+        let mut section = String::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_lowercase();
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+            match (section.as_str(), key.trim()) {
+                ("auth", "token") => settings.token = value,
+                ("defaults", "url") => settings.url = value,
+                ("defaults", "org") => settings.org = value,
+                ("defaults", "project") => settings.project = value,
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) {
+        for (name, field) in [
+            ("SENTRY_AUTH_TOKEN", &mut self.token),
+            ("SENTRY_URL", &mut self.url),
+            ("SENTRY_ORG", &mut self.org),
+            ("SENTRY_PROJECT", &mut self.project),
+        ] {
+            if let Some(value) = var(name).filter(|v| !v.is_empty()) {
+                *field = Some(value);
+            }
+        }
+    }
+
+    /// Server URL, unless it is sentry.io itself.
+    pub fn self_hosted_url(&self) -> Option<String> {
+        let url = self.url.as_deref()?.trim_end_matches('/');
+        let host = url.split("://").last().unwrap_or(url);
+        (host != "sentry.io" && !host.ends_with(".sentry.io")).then(|| url.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -285,22 +719,139 @@ mod tests {
         let org = config.get_organization("test").unwrap();
         assert_eq!(org.name, "test");
         assert_eq!(org.slug, "test-slug");
-        assert!(org.keyring.is_none());
+        assert!(org.projects.is_empty());
+    }
+
+    #[test]
+    fn test_accounts_sharing_a_slug() -> Result<()> {
+        let mut config = Config::default();
+        config.add_organization("acme".to_string(), "acme".to_string());
+        config.add_organization("acme-bot".to_string(), "acme".to_string());
+        config.add_organization("globex".to_string(), "globex-inc".to_string());
+
+        let names = |config: &Config| -> Vec<String> {
+            config.accounts().iter().map(|o| o.name.clone()).collect()
+        };
+        assert_eq!(names(&config), vec!["acme", "globex"]);
+        assert_eq!(
+            config.get_organization_by_slug("acme").unwrap().name,
+            "acme"
+        );
+
+        config.select_account("acme-bot")?;
+        assert_eq!(names(&config), vec!["acme-bot", "globex"]);
+        assert_eq!(
+            config.get_organization_by_slug("acme").unwrap().name,
+            "acme-bot"
+        );
+        let acme = config.get_organization("acme").unwrap();
+        assert_eq!(config.account_for(acme).name, "acme-bot");
+        let globex = config.get_organization("globex").unwrap();
+        assert_eq!(config.account_for(globex).name, "globex");
+
+        assert!(config.select_account("initech").is_err());
+
+        // Mutes go to the selected account, where the monitor reads them back
+        let muted = HashMap::from([("web".to_string(), vec!["42".to_string()])]);
+        assert!(config.replace_muted_issues("acme", muted.clone()));
+        assert!(!config.replace_muted_issues("acme", muted.clone()));
+        assert_eq!(
+            config
+                .get_organization_by_slug("acme")
+                .unwrap()
+                .muted_issues,
+            muted
+        );
+        assert!(config
+            .get_organization("acme")
+            .unwrap()
+            .muted_issues
+            .is_empty());
+        Ok(())
     }
 
     #[test]
+    fn test_read_only_flag_is_not_saved() -> Result<()> {
+        let mut config = Config::default();
+        assert!(!config.is_read_only());
+        config.force_read_only();
+        assert!(config.is_read_only());
+
+        let saved: Config = serde_json::from_str(&serde_json::to_string(&config)?)?;
+        assert!(!saved.is_read_only());
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "needs a keyring service"]
     fn test_organization_auth_token() -> Result<()> {
         let mut config = Config::default();
         config.add_organization("test".to_string(), "test-slug".to_string());
 
         let org = config.get_organization_mut("test").unwrap();
-        org.set_auth_token("secret-token".to_string())?;
+        org.set_token(TokenRole::Read, "secret-token".to_string())?;
 
         let token = org.get_auth_token()?.unwrap();
         assert_eq!(token, "secret-token");
         Ok(())
     }
 
+    #[test]
+    fn test_sentry_cli_settings() {
+        let mut settings = SentryCliSettings::parse(
+            "# sentry-cli\n[auth]\ntoken = sntrys_abc\n\n[defaults]\nurl=https://sentry.example.com/\norg=acme\nproject=\n",
+        );
+        assert_eq!(
+            settings,
+            SentryCliSettings {
+                token: Some("sntrys_abc".to_string()),
+                url: Some("https://sentry.example.com/".to_string()),
+                org: Some("acme".to_string()),
+                project: None,
+            }
+        );
+        assert_eq!(
+            settings.self_hosted_url().as_deref(),
+            Some("https://sentry.example.com")
+        );
+
+        // Environment variables win over the rc file
+        settings.apply_env(|name| match name {
+            "SENTRY_ORG" => Some("other-org".to_string()),
+            "SENTRY_PROJECT" => Some("web".to_string()),
+            "SENTRY_URL" => Some("https://sentry.io/".to_string()),
+            _ => None,
+        });
+        assert_eq!(settings.org.as_deref(), Some("other-org"));
+        assert_eq!(settings.project.as_deref(), Some("web"));
+        assert_eq!(settings.token.as_deref(), Some("sntrys_abc"));
+        assert_eq!(settings.self_hosted_url(), None);
+    }
+
+    #[test]
+    fn test_token_role_keyring_entries() {
+        assert_eq!(TokenRole::Read.keyring_user(), "auth-token");
+        assert_eq!(TokenRole::Write.keyring_user(), "auth-token-write");
+        assert_eq!(TokenRole::Read.other(), TokenRole::Write);
+        assert_eq!(TokenRole::Write.to_string(), "write");
+    }
+
+    #[test]
+    fn test_use_server() -> Result<()> {
+        let mut config = Config::default();
+        config.use_server(Some("https://sentry.example.com".to_string()))?;
+        assert_eq!(config.url.as_deref(), Some("https://sentry.example.com"));
+        config.add_organization("acme".to_string(), "acme".to_string());
+
+        config.use_server(Some("https://sentry.example.com/".to_string()))?;
+        assert!(config.use_server(None).is_err());
+        assert!(config
+            .use_server(Some("https://other.example.com".to_string()))
+            .is_err());
+        assert_eq!(config.url.as_deref(), Some("https://sentry.example.com"));
+        Ok(())
+    }
+
     #[test]
     fn test_save_and_load() -> Result<()> {
         let temp = assert_fs::TempDir::new()?;
@@ -315,8 +866,37 @@ mod tests {
 
         // Load config
         let loaded: Config = serde_json::from_str(&fs::read_to_string(config_file.path())?)?;
-        assert_eq!(config, loaded);
+        assert_eq!(
+            serde_json::to_value(&config)?,
+            serde_json::to_value(&loaded)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encrypted_config_roundtrip() -> Result<()> {
+        let mut config = Config::default();
+        config.add_organization("client-a".to_string(), "client-a-slug".to_string());
+        config.secrets.encrypt_config = true;
+        let content = serde_json::to_string_pretty(&config)?;
 
+        let key = [7u8; PROJECT_KEY_LENGTH];
+        let file = EncryptedConfigFile {
+            encrypted: encrypt_config(&content, SecretsBackend::File, &key),
+        };
+        let on_disk = serde_json::to_string_pretty(&file)?;
+        assert!(!on_disk.contains("client-a"));
+
+        let parsed: EncryptedConfigFile = serde_json::from_str(&on_disk)?;
+        assert_eq!(parsed.encrypted.backend, SecretsBackend::File);
+        let decrypted: Config = serde_json::from_str(&decrypt_config(&parsed.encrypted, &key)?)?;
+        assert_eq!(decrypted.organizations["client-a"].slug, "client-a-slug");
+        assert!(decrypted.secrets.encrypt_config);
+
+        assert!(decrypt_config(&parsed.encrypted, &[8u8; PROJECT_KEY_LENGTH]).is_err());
+        // A plain config is not mistaken for an encrypted one
+        assert!(serde_json::from_str::<EncryptedConfigFile>(&content).is_err());
         Ok(())
     }
 
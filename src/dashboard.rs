@@ -1,86 +1,481 @@
-use crate::sentry::{Issue, SentryClient};
+use crate::alert::{AlertRule, RateTracker};
+use crate::event_log::{ChangeEvent, ChangeKind, EventLog};
+use crate::history::{FilterInput, InputState, SearchHistory};
+use crate::keys::NavKeys;
+use crate::notify::desktop_notification;
+use crate::report::project_slug;
+use crate::sentry::{Issue, IssueQuery, SentryClient};
+use crate::table::{format_count, Column, Table};
+use crate::timing;
 use anyhow::Result;
 use crossterm::{
     cursor,
-    event::{self, Event, KeyCode},
+    event::{self, Event, KeyCode, KeyEvent},
     execute,
     style::{Color, Print, SetForegroundColor},
     terminal::{self, ClearType},
 };
+use std::collections::{HashMap, HashSet};
 use std::io::{self, Write};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Issues listed at once
+const VISIBLE_ISSUES: usize = 10;
+
+/// How often each tab polls its issues
+const UPDATE_INTERVAL: Duration = Duration::from_secs(5);
 
 pub struct Dashboard {
     client: SentryClient,
     org_slug: String,
-    project_slug: String,
+    projects: Vec<String>,
     issues: Vec<Issue>,
     selected_index: usize,
+    resolved_ids: HashSet<String>,
+    regressions: HashSet<String>,
+    /// Every issue listed so far, to tell new ones apart; `None` before the first poll
+    seen_ids: Option<HashSet<String>>,
+    notify: bool,
+    event_log: Option<Arc<EventLog>>,
+    query: IssueQuery,
+    alert_rules: Vec<AlertRule>,
+    rates: RateTracker,
+    /// Issues currently breaking an alert rule, with the rule they break
+    alerts: HashMap<String, String>,
+    started: Instant,
+    /// Issues hidden until the dashboard exits
+    session_muted: HashSet<String>,
+    /// Issues hidden across sessions, keyed by project slug
+    persistent_muted: HashMap<String, Vec<String>>,
+    show_muted: bool,
+    searches: SearchHistory,
+    /// Result of the last filter change, shown below the query
+    message: Option<String>,
+    /// Whether `message` holds the error of the last update, cleared once one succeeds
+    update_failed: bool,
+    keys: NavKeys,
+    /// Name in the tab bar when several dashboards are shown as tabs
+    label: String,
+    last_update: Option<Instant>,
+    /// Poll right away instead of waiting for the interval, e.g. after a filter change
+    refresh: bool,
 }
 
 impl Dashboard {
-    pub fn new(client: SentryClient, org_slug: String, project_slug: String) -> Self {
+    /// Projects are merged into one list, with a project column when there are several.
+    pub fn new(client: SentryClient, org_slug: String, projects: Vec<String>) -> Self {
         Self {
             client,
-            org_slug,
-            project_slug,
             issues: Vec::new(),
             selected_index: 0,
+            resolved_ids: HashSet::new(),
+            regressions: HashSet::new(),
+            seen_ids: None,
+            notify: false,
+            event_log: None,
+            query: IssueQuery::default(),
+            alert_rules: Vec::new(),
+            rates: RateTracker::default(),
+            alerts: HashMap::new(),
+            started: Instant::now(),
+            session_muted: HashSet::new(),
+            persistent_muted: HashMap::new(),
+            show_muted: false,
+            searches: SearchHistory::default(),
+            message: None,
+            update_failed: false,
+            keys: NavKeys::default(),
+            label: org_slug.clone(),
+            org_slug,
+            projects,
+            last_update: None,
+            refresh: true,
         }
     }
 
+    /// Names the dashboard in the tab bar.
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = label;
+        self
+    }
+
+    pub fn org_slug(&self) -> &str {
+        &self.org_slug
+    }
+
+    pub fn projects(&self) -> &[String] {
+        &self.projects
+    }
+
+    /// Starts with searches and saved filters from earlier sessions.
+    pub fn with_searches(mut self, searches: SearchHistory) -> Self {
+        self.searches = searches;
+        self
+    }
+
+    /// Searches used and filters saved, to be kept once the dashboard exits.
+    pub fn searches(&self) -> &SearchHistory {
+        &self.searches
+    }
+
+    /// Starts with issues muted in an earlier session, keyed by project slug.
+    pub fn with_muted(mut self, muted: HashMap<String, Vec<String>>) -> Self {
+        self.persistent_muted = muted;
+        self
+    }
+
+    /// Persistently muted issues, to be saved once the dashboard exits.
+    pub fn muted_issues(&self) -> &HashMap<String, Vec<String>> {
+        &self.persistent_muted
+    }
+
+    /// Highlights issues growing faster than a rule allows and rings the bell.
+    pub fn with_alerts(mut self, rules: Vec<AlertRule>) -> Self {
+        self.rates = RateTracker::new(&rules);
+        self.alert_rules = rules;
+        self
+    }
+
+    /// Polls issues matching a Sentry search instead of `is:unresolved`.
+    pub fn with_query(mut self, search: String) -> Self {
+        self.query.query = search;
+        self
+    }
+
+    /// Sends a desktop notification when a resolved issue regresses.
+    pub fn with_notifications(mut self, notify: bool) -> Self {
+        self.notify = notify;
+        self
+    }
+
+    /// Writes new, spiking and regressed issues to an event log.
+    pub fn with_event_log(mut self, event_log: Option<Arc<EventLog>>) -> Self {
+        self.event_log = event_log;
+        self
+    }
+
     pub fn run(&mut self) -> Result<()> {
-        self.setup_terminal()?;
+        run_tabs(std::slice::from_mut(self))
+    }
+
+    /// Logs a change, showing a failing write below the query instead of stopping.
+    fn emit(&mut self, event: ChangeEvent) {
+        if let Some(log) = &self.event_log {
+            if let Err(e) = log.emit(&event) {
+                self.message = Some(format!("{:#}", e));
+            }
+        }
+    }
+
+    fn is_due(&self) -> bool {
+        self.refresh
+            || self
+                .last_update
+                .is_none_or(|last| last.elapsed() >= UPDATE_INTERVAL)
+    }
 
-        let mut last_update = std::time::Instant::now();
-        let update_interval = Duration::from_secs(5);
+    /// Handles a key for the shown tab. Returns false when the monitor should quit.
+    fn handle_key(&mut self, key: &KeyEvent) -> Result<bool> {
+        if let Some(nav) = self.keys.handle(key) {
+            let visible = self.visible_issues().len();
+            self.selected_index = nav.apply(self.selected_index, visible, VISIBLE_ISSUES);
+            return Ok(true);
+        }
+        match key.code {
+            KeyCode::Char('q') => return Ok(false),
+            KeyCode::Char('m') => self.toggle_mute(false),
+            KeyCode::Char('M') => self.toggle_mute(true),
+            KeyCode::Char('v') => {
+                self.show_muted = !self.show_muted;
+                self.selected_index = 0;
+            }
+            KeyCode::Char('/') => self.refresh = self.change_filter()?,
+            KeyCode::Char('S') => self.save_filter()?,
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    /// Regressions and firing alerts, flagged on the tab when it is not shown.
+    fn attention_count(&self) -> usize {
+        self.regressions.len() + self.alerts.len()
+    }
 
+    /// Asks for a new search, where `@name` picks a saved filter. Returns whether the
+    /// search changed, so issues are fetched again right away.
+    fn change_filter(&mut self) -> Result<bool> {
+        let recent = self.searches.recent.clone();
+        let current = self.query.query.clone();
+        let Some(input) = self.read_line(
+            "Filter (Up/Down: history, @name: saved): ",
+            &recent,
+            &current,
+        )?
+        else {
+            return Ok(false);
+        };
+        self.message = None;
+        let query = match self.searches.expand(&input) {
+            Ok(query) if query.is_empty() => IssueQuery::default().query,
+            Ok(query) => query,
+            Err(e) => {
+                self.message = Some(e.to_string());
+                return Ok(false);
+            }
+        };
+        self.searches.record(&query);
+        self.query.query = query;
+        self.selected_index = 0;
+        Ok(true)
+    }
+
+    /// Saves the current search under a name, to be recalled as `@name`.
+    fn save_filter(&mut self) -> Result<()> {
+        let Some(name) = self.read_line("Save filter as: ", &[], "")? else {
+            return Ok(());
+        };
+        if name.is_empty() {
+            return Ok(());
+        }
+        self.searches.save_filter(&name, &self.query.query);
+        self.message = Some(format!("Saved filter @{}", name.trim_start_matches('@')));
+        Ok(())
+    }
+
+    /// Reads a line on the bottom row. Returns `None` when cancelled with Esc.
+    fn read_line(&self, label: &str, recent: &[String], text: &str) -> Result<Option<String>> {
+        let mut input = FilterInput::new(recent, text);
         loop {
-            if last_update.elapsed() >= update_interval {
-                self.update_issues()?;
-                last_update = std::time::Instant::now();
+            let (_, height) = terminal::size()?;
+            execute!(
+                io::stdout(),
+                cursor::MoveTo(0, height.saturating_sub(1)),
+                terminal::Clear(ClearType::CurrentLine),
+                Print(format!("{}{}", label, input.text))
+            )?;
+            if let Event::Key(key) = event::read()? {
+                match input.handle(key.code) {
+                    InputState::Editing => {}
+                    InputState::Submitted(text) => return Ok(Some(text)),
+                    InputState::Cancelled => return Ok(None),
+                }
             }
+        }
+    }
 
-            self.render()?;
+    fn is_multi_project(&self) -> bool {
+        self.projects.len() > 1
+    }
 
-            if event::poll(Duration::from_millis(100))? {
-                if let Event::Key(key) = event::read()? {
-                    match key.code {
-                        KeyCode::Char('q') => break,
-                        KeyCode::Up => self.move_selection_up(),
-                        KeyCode::Down => self.move_selection_down(),
-                        _ => {}
-                    }
+    /// Fetches unresolved and resolved issues. Several projects are fetched from the
+    /// organization's issues, keeping those of the listed projects, so every poll takes
+    /// two requests however many projects there are.
+    fn fetch_issues(&self) -> Result<(Vec<Issue>, Vec<Issue>)> {
+        let resolved_query = IssueQuery {
+            query: "is:resolved".to_string(),
+            ..IssueQuery::default()
+        };
+        let fetch = |query: &IssueQuery| -> Result<Vec<Issue>> {
+            match self.projects.as_slice() {
+                [project] => self
+                    .client
+                    .list_project_issues(&self.org_slug, project, query),
+                projects => Ok(self
+                    .client
+                    .list_org_issues(&self.org_slug, query)?
+                    .into_iter()
+                    .filter(|issue| {
+                        issue
+                            .project
+                            .as_ref()
+                            .is_some_and(|project| projects.contains(&project.slug))
+                    })
+                    .collect()),
+            }
+        };
+        Ok((fetch(&self.query)?, fetch(&resolved_query)?))
+    }
+
+    fn update_issues(&mut self) -> Result<()> {
+        let (mut issues, resolved) = self.fetch_issues()?;
+
+        let regressed = self.detect_regressions(&issues, &resolved);
+        for issue in &regressed {
+            if self.notify {
+                // A failing notifier should not take the dashboard down
+                let _ = desktop_notification(
+                    &format!("Sentry regression in {}", project_slug(issue)),
+                    &issue.title,
+                );
+            }
+        }
+        let regressed: HashSet<String> = regressed.iter().map(|i| i.id.clone()).collect();
+        if self.event_log.is_some() {
+            let org_slug = self.org_slug.clone();
+            for issue in self.detect_new_issues(&issues) {
+                // A regression is an old issue coming back, not a new one
+                if !regressed.contains(&issue.id) {
+                    self.emit(ChangeEvent::new(ChangeKind::New, &org_slug, issue));
                 }
             }
+            for issue in issues.iter().filter(|i| regressed.contains(&i.id)) {
+                self.emit(ChangeEvent::new(ChangeKind::Regressed, &org_slug, issue));
+            }
         }
 
-        self.cleanup_terminal()?;
+        self.check_alerts(&issues)?;
+
+        // Regressions and alerts are pinned above the busiest issues, or above the most
+        // recently seen ones when several projects are merged
+        let multi_project = self.is_multi_project();
+        issues.sort_by(|a, b| {
+            let a_regressed = self.regressions.contains(&a.id);
+            let b_regressed = self.regressions.contains(&b.id);
+            let a_alerted = self.alerts.contains_key(&a.id);
+            let b_alerted = self.alerts.contains_key(&b.id);
+            b_regressed
+                .cmp(&a_regressed)
+                .then_with(|| b_alerted.cmp(&a_alerted))
+                .then_with(|| {
+                    if multi_project {
+                        b.last_seen.cmp(&a.last_seen)
+                    } else {
+                        b.count.cmp(&a.count)
+                    }
+                })
+        });
+        self.issues = issues;
+        self.clamp_selection();
         Ok(())
     }
 
-    fn setup_terminal(&self) -> Result<()> {
-        terminal::enable_raw_mode()?;
-        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
-        Ok(())
+    /// Project an issue belongs to, for persistent mutes.
+    fn issue_project(&self, issue: &Issue) -> String {
+        match &issue.project {
+            Some(project) => project.slug.clone(),
+            None => self.projects[0].clone(),
+        }
     }
 
-    fn cleanup_terminal(&self) -> Result<()> {
-        execute!(io::stdout(), terminal::LeaveAlternateScreen, cursor::Show)?;
-        terminal::disable_raw_mode()?;
-        Ok(())
+    fn is_muted(&self, issue: &Issue) -> bool {
+        self.session_muted.contains(&issue.id)
+            || self
+                .persistent_muted
+                .get(&self.issue_project(issue))
+                .is_some_and(|ids| ids.contains(&issue.id))
     }
 
-    fn update_issues(&mut self) -> Result<()> {
-        let mut issues = self
-            .client
-            .list_issues(&self.org_slug, &self.project_slug)?;
-        issues.sort_by(|a, b| b.count.cmp(&a.count));
-        self.issues = issues.into_iter().take(10).collect();
+    fn muted_count(&self) -> usize {
+        self.session_muted.len() + self.persistent_muted.values().map(Vec::len).sum::<usize>()
+    }
+
+    /// Top issues, leaving out muted ones unless they are revealed.
+    fn visible_issues(&self) -> Vec<&Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| self.show_muted || !self.is_muted(issue))
+            .take(VISIBLE_ISSUES)
+            .collect()
+    }
+
+    /// Mutes the selected issue, or unmutes it when it already is.
+    fn toggle_mute(&mut self, persistent: bool) {
+        let Some(issue) = self.visible_issues().get(self.selected_index).copied() else {
+            return;
+        };
+        let id = issue.id.clone();
+        let project = self.issue_project(issue);
+
+        if self.is_muted(issue) {
+            self.session_muted.remove(&id);
+            if let Some(ids) = self.persistent_muted.get_mut(&project) {
+                ids.retain(|muted| muted != &id);
+                if ids.is_empty() {
+                    self.persistent_muted.remove(&project);
+                }
+            }
+        } else if persistent {
+            self.persistent_muted.entry(project).or_default().push(id);
+        } else {
+            self.session_muted.insert(id);
+        }
+        self.clamp_selection();
+    }
+
+    fn clamp_selection(&mut self) {
+        let visible = self.visible_issues().len();
+        self.selected_index = self.selected_index.min(visible.saturating_sub(1));
+    }
+
+    /// Records issue counters and rings the bell for issues newly breaking an alert rule.
+    fn check_alerts(&mut self, issues: &[Issue]) -> Result<()> {
+        if self.alert_rules.is_empty() {
+            return Ok(());
+        }
+
+        let now = self.started.elapsed().as_secs();
+        let mut alerts = HashMap::new();
+        for issue in issues {
+            self.rates.record(issue, now);
+            let Some(rule) = self.rates.breached(&issue.id, &self.alert_rules) else {
+                continue;
+            };
+            let rule = rule.to_string();
+            if !self.alerts.contains_key(&issue.id) {
+                execute!(io::stdout(), Print("\x07"))?;
+                if self.notify {
+                    let _ = desktop_notification(
+                        &format!("Sentry alert in {}", project_slug(issue)),
+                        &format!("{} ({})", issue.title, rule),
+                    );
+                }
+                let org_slug = self.org_slug.clone();
+                self.emit(
+                    ChangeEvent::new(ChangeKind::Spike, &org_slug, issue).with_rule(rule.clone()),
+                );
+            }
+            alerts.insert(issue.id.clone(), rule);
+        }
+        self.alerts = alerts;
         Ok(())
     }
 
-    fn render(&self) -> Result<()> {
+    /// Remembers listed issues and returns those not listed by earlier polls. The first
+    /// poll only takes note, so starting the monitor does not report every issue as new.
+    fn detect_new_issues<'a>(&mut self, issues: &'a [Issue]) -> Vec<&'a Issue> {
+        let Some(seen) = self.seen_ids.as_mut() else {
+            self.seen_ids = Some(issues.iter().map(|issue| issue.id.clone()).collect());
+            return Vec::new();
+        };
+        issues
+            .iter()
+            .filter(|issue| seen.insert(issue.id.clone()))
+            .collect()
+    }
+
+    /// Remembers resolved issues and returns those previously seen as resolved
+    /// that are now unresolved again.
+    fn detect_regressions<'a>(
+        &mut self,
+        unresolved: &'a [Issue],
+        resolved: &[Issue],
+    ) -> Vec<&'a Issue> {
+        let regressed: Vec<&Issue> = unresolved
+            .iter()
+            .filter(|issue| self.resolved_ids.remove(&issue.id))
+            .collect();
+        for issue in &regressed {
+            self.regressions.insert(issue.id.clone());
+        }
+
+        self.resolved_ids
+            .extend(resolved.iter().map(|issue| issue.id.clone()));
+        regressed
+    }
+
+    /// Draws the dashboard below the tab bar, which is empty for a single dashboard.
+    fn render(&self, tab_bar: &str) -> Result<()> {
         execute!(
             io::stdout(),
             terminal::Clear(ClearType::All),
@@ -91,43 +486,150 @@ impl Dashboard {
         execute!(
             io::stdout(),
             SetForegroundColor(Color::Cyan),
-            Print("Sentry Issue Monitor - Press 'q' to quit\n\n"),
+            Print(
+                "Sentry Issue Monitor - 'q' quit, 'm' mute, 'M' mute for project, 'v' show muted, '/' filter, 'S' save filter, j/k gg/G ^u/^d move\n"
+            ),
             SetForegroundColor(Color::Reset)
         )?;
-
-        // Column headers
+        if !tab_bar.is_empty() {
+            execute!(
+                io::stdout(),
+                SetForegroundColor(Color::Cyan),
+                Print(format!("{}  (1-9 or [/]: switch tab)\n", tab_bar)),
+                SetForegroundColor(Color::Reset)
+            )?;
+        }
         execute!(
             io::stdout(),
-            SetForegroundColor(Color::Yellow),
-            Print(format!(
-                "{:<10} {:<40} {:<12} {:<8} {:<8}\n",
-                "ID", "Title", "Status", "Events", "Users"
-            )),
-            SetForegroundColor(Color::Reset)
+            Print(format!("Query: {}\n", self.query.query))
         )?;
+        if let Some(message) = &self.message {
+            execute!(
+                io::stdout(),
+                SetForegroundColor(Color::Yellow),
+                Print(format!("{}\n", message)),
+                SetForegroundColor(Color::Reset)
+            )?;
+        }
+        if !self.alert_rules.is_empty() {
+            let rules: Vec<String> = self.alert_rules.iter().map(|r| r.to_string()).collect();
+            execute!(
+                io::stdout(),
+                SetForegroundColor(Color::Magenta),
+                Print(format!(
+                    "Alerts: {} ({} firing)\n",
+                    rules.join(", "),
+                    self.alerts.len()
+                )),
+                SetForegroundColor(Color::Reset)
+            )?;
+        }
+        if self.regressions.is_empty() {
+            execute!(io::stdout(), Print("\n"))?;
+        } else {
+            execute!(
+                io::stdout(),
+                SetForegroundColor(Color::Red),
+                Print(format!("Regressions: {}\n", self.regressions.len())),
+                SetForegroundColor(Color::Reset)
+            )?;
+        }
+
+        let muted = self.muted_count();
+        if muted > 0 {
+            let hint = if self.show_muted {
+                "press 'v' to hide"
+            } else {
+                "press 'v' to show"
+            };
+            execute!(
+                io::stdout(),
+                SetForegroundColor(Color::DarkGrey),
+                Print(format!("muted ({}) - {}\n", muted, hint)),
+                SetForegroundColor(Color::Reset)
+            )?;
+        }
+
+        // Issues, one color per row
+        let mut columns: Vec<Column<(Issue, String)>> = Vec::new();
+        if self.is_multi_project() {
+            columns.push(
+                Column::new("project", "Project", |(issue, _): &(Issue, String)| {
+                    project_slug(issue)
+                })
+                .max_width(16)
+                .priority(1),
+            );
+        }
+        columns.extend([
+            Column::new("id", "ID", |(issue, _): &(Issue, String)| {
+                issue.id.chars().take(10).collect()
+            }),
+            Column::new("title", "Title", |(_, title): &(Issue, String)| {
+                title.clone()
+            })
+            .max_width(40)
+            .flex(),
+            Column::new("status", "Status", |(issue, _): &(Issue, String)| {
+                issue.status.clone()
+            }),
+            Column::new("events", "Events", |(issue, _): &(Issue, String)| {
+                format_count(issue.count)
+            })
+            .align_right(),
+            Column::new("users", "Users", |(issue, _): &(Issue, String)| {
+                format_count(issue.user_count)
+            })
+            .align_right()
+            .priority(1),
+        ]);
 
-        // Issues
-        for (index, issue) in self.issues.iter().enumerate() {
-            let color = if index == self.selected_index {
+        let mut rows = Vec::new();
+        let mut colors = Vec::new();
+        for (index, issue) in self.visible_issues().into_iter().enumerate() {
+            let regressed = self.regressions.contains(&issue.id);
+            let alert = self.alerts.get(&issue.id);
+            let muted = self.is_muted(issue);
+            colors.push(if index == self.selected_index {
                 Color::Green
+            } else if muted {
+                Color::DarkGrey
+            } else if regressed {
+                Color::Red
+            } else if alert.is_some() {
+                Color::Magenta
             } else {
                 Color::Reset
-            };
+            });
 
-            let id_short = &issue.id[..10.min(issue.id.len())];
-            let title_short = if issue.title.len() > 40 {
-                format!("{}...", &issue.title[..37])
+            let title = if muted {
+                format!("[MUTED] {}", issue.title)
+            } else if regressed {
+                format!("[REGRESSED] {}", issue.title)
+            } else if let Some(rule) = alert {
+                format!("[{}] {}", rule, issue.title)
             } else {
                 issue.title.clone()
             };
+            rows.push((issue.clone(), title));
+        }
 
+        let width = terminal::size().ok().map(|(columns, _)| columns as usize);
+        let output = Table::new(columns).fit_width(width).render(&rows);
+        let mut lines = output.lines();
+        if let Some(header) = lines.next() {
+            execute!(
+                io::stdout(),
+                SetForegroundColor(Color::Yellow),
+                Print(format!("{}\n", header)),
+                SetForegroundColor(Color::Reset)
+            )?;
+        }
+        for (line, color) in lines.zip(colors) {
             execute!(
                 io::stdout(),
                 SetForegroundColor(color),
-                Print(format!(
-                    "{:<10} {:<40} {:<12} {:<8} {:<8}\n",
-                    id_short, title_short, issue.status, issue.count, issue.user_count
-                )),
+                Print(format!("{}\n", line)),
                 SetForegroundColor(Color::Reset)
             )?;
         }
@@ -135,20 +637,142 @@ impl Dashboard {
         io::stdout().flush()?;
         Ok(())
     }
+}
+
+/// Shows dashboards as tabs, switched with number keys or `[` and `]`. Every tab polls
+/// its own issues on its own schedule, so regressions and alerts of tabs in the
+/// background still show up in the tab bar. Searches follow the shown tab.
+pub fn run_tabs(tabs: &mut [Dashboard]) -> Result<()> {
+    if tabs.is_empty() {
+        return Ok(());
+    }
+    setup_terminal()?;
+    let result = poll_tabs(tabs);
+    cleanup_terminal()?;
+    result
+}
 
-    fn move_selection_up(&mut self) {
-        if self.selected_index > 0 {
-            self.selected_index -= 1;
+fn poll_tabs(tabs: &mut [Dashboard]) -> Result<()> {
+    let mut active = 0;
+    loop {
+        update_due_tabs(tabs);
+        timing::time_repeated(
+            "render",
+            || format!("dashboard {}", tabs[active].label),
+            || tabs[active].render(&tab_bar(tabs, active)),
+        )?;
+
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        // A single dashboard keeps digits and brackets for its own keys
+        let next = match key.code {
+            _ if tabs.len() < 2 => None,
+            KeyCode::Char(digit @ '1'..='9') => Some(digit as usize - '1' as usize),
+            KeyCode::Char(']') => Some((active + 1) % tabs.len()),
+            KeyCode::Char('[') => Some((active + tabs.len() - 1) % tabs.len()),
+            _ => None,
+        };
+        match next {
+            Some(next) if next < tabs.len() && next != active => {
+                let searches = tabs[active].searches.clone();
+                tabs[next].searches = searches;
+                if !tabs[next].update_failed {
+                    tabs[next].message = None;
+                }
+                active = next;
+            }
+            Some(_) => {}
+            None => {
+                if !tabs[active].handle_key(&key)? {
+                    let searches = tabs[active].searches.clone();
+                    for tab in tabs.iter_mut() {
+                        tab.searches = searches.clone();
+                    }
+                    return Ok(());
+                }
+            }
         }
     }
+}
 
-    fn move_selection_down(&mut self) {
-        if !self.issues.is_empty() && self.selected_index < self.issues.len() - 1 {
-            self.selected_index += 1;
+/// Updates every tab whose interval has passed, one thread per tab. A failing tab, e.g.
+/// with a revoked token, shows its error and is retried after the interval while the
+/// other tabs keep polling.
+fn update_due_tabs(tabs: &mut [Dashboard]) {
+    let due: Vec<usize> = (0..tabs.len()).filter(|&i| tabs[i].is_due()).collect();
+    let results: Vec<Result<()>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = tabs
+            .iter_mut()
+            .filter(|tab| tab.is_due())
+            .map(|tab| scope.spawn(move || tab.update_issues()))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("Issue fetch panicked")))
+            })
+            .collect()
+    });
+    for (index, result) in due.into_iter().zip(results) {
+        let tab = &mut tabs[index];
+        tab.last_update = Some(Instant::now());
+        tab.refresh = false;
+        match result {
+            Ok(()) if tab.update_failed => {
+                tab.update_failed = false;
+                tab.message = None;
+            }
+            Ok(()) => {}
+            Err(e) => {
+                tab.update_failed = true;
+                tab.message = Some(format!("Update failed: {:#}", e));
+            }
         }
     }
 }
 
+/// Numbered tab names with the shown one in brackets and a `!N` flag for tabs with
+/// regressions or firing alerts. Empty for a single dashboard.
+fn tab_bar(tabs: &[Dashboard], active: usize) -> String {
+    if tabs.len() < 2 {
+        return String::new();
+    }
+    tabs.iter()
+        .enumerate()
+        .map(|(index, tab)| {
+            let mut name = format!("{}:{}", index + 1, tab.label);
+            let attention = tab.attention_count();
+            if attention > 0 {
+                name.push_str(&format!(" !{}", attention));
+            }
+            if index == active {
+                format!("[{}]", name)
+            } else {
+                format!(" {} ", name)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn setup_terminal() -> Result<()> {
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+    Ok(())
+}
+
+fn cleanup_terminal() -> Result<()> {
+    execute!(io::stdout(), terminal::LeaveAlternateScreen, cursor::Show)?;
+    terminal::disable_raw_mode()?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,8 +780,223 @@ mod tests {
     #[test]
     fn test_dashboard_creation() {
         let client = SentryClient::new().unwrap();
-        let dashboard = Dashboard::new(client, "test-org".to_string(), "test-project".to_string());
+        let dashboard = Dashboard::new(
+            client,
+            "test-org".to_string(),
+            vec!["test-project".to_string()],
+        );
         assert_eq!(dashboard.selected_index, 0);
         assert!(dashboard.issues.is_empty());
     }
+
+    fn create_test_issue(id: &str) -> Issue {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": "Test Issue",
+            "status": "unresolved",
+            "level": "error",
+            "culprit": "test.js:42",
+            "lastSeen": "2024-01-01T00:00:00Z",
+            "count": 1,
+            "userCount": 1
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_detect_regressions() {
+        let client = SentryClient::new().unwrap();
+        let mut dashboard = Dashboard::new(
+            client,
+            "test-org".to_string(),
+            vec!["test-project".to_string()],
+        );
+
+        // First poll: issue 2 is resolved
+        let unresolved = vec![create_test_issue("1")];
+        let resolved = vec![create_test_issue("2")];
+        assert!(dashboard
+            .detect_regressions(&unresolved, &resolved)
+            .is_empty());
+
+        // Second poll: issue 2 is back
+        let unresolved = vec![create_test_issue("1"), create_test_issue("2")];
+        let regressed = dashboard.detect_regressions(&unresolved, &[]);
+        assert_eq!(regressed.len(), 1);
+        assert_eq!(regressed[0].id, "2");
+        assert!(dashboard.regressions.contains("2"));
+
+        // The same regression is only reported once
+        assert!(dashboard.detect_regressions(&unresolved, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_detect_new_issues() {
+        let client = SentryClient::new().unwrap();
+        let mut dashboard = Dashboard::new(
+            client,
+            "test-org".to_string(),
+            vec!["test-project".to_string()],
+        );
+
+        // Issues listed when the monitor starts are not new
+        let issues = vec![create_test_issue("1"), create_test_issue("2")];
+        assert!(dashboard.detect_new_issues(&issues).is_empty());
+
+        let issues = vec![create_test_issue("2"), create_test_issue("3")];
+        let new: Vec<&str> = dashboard
+            .detect_new_issues(&issues)
+            .iter()
+            .map(|i| i.id.as_str())
+            .collect();
+        assert_eq!(new, vec!["3"]);
+
+        // Issue 1 dropped out of the list for a while, it is not new when it is back
+        let issues = vec![create_test_issue("1"), create_test_issue("3")];
+        assert!(dashboard.detect_new_issues(&issues).is_empty());
+    }
+
+    #[test]
+    fn test_mute_issues() {
+        let client = SentryClient::new().unwrap();
+        let mut dashboard = Dashboard::new(
+            client,
+            "test-org".to_string(),
+            vec!["test-project".to_string()],
+        )
+        .with_muted(HashMap::from([(
+            "test-project".to_string(),
+            vec!["3".to_string()],
+        )]));
+        dashboard.issues = vec![
+            create_test_issue("1"),
+            create_test_issue("2"),
+            create_test_issue("3"),
+        ];
+        assert_eq!(dashboard.visible_issues().len(), 2);
+        assert_eq!(dashboard.muted_count(), 1);
+
+        // Session mute of the selected issue
+        dashboard.toggle_mute(false);
+        let visible: Vec<&str> = dashboard
+            .visible_issues()
+            .iter()
+            .map(|i| i.id.as_str())
+            .collect();
+        assert_eq!(visible, vec!["2"]);
+
+        // Persistent mute is kept per project
+        dashboard.toggle_mute(true);
+        assert!(dashboard.visible_issues().is_empty());
+        assert_eq!(
+            dashboard.muted_issues().get("test-project"),
+            Some(&vec!["3".to_string(), "2".to_string()])
+        );
+
+        // Revealed muted issues can be unmuted again
+        dashboard.show_muted = true;
+        assert_eq!(dashboard.visible_issues().len(), 3);
+        dashboard.toggle_mute(false);
+        assert_eq!(dashboard.muted_count(), 2);
+        assert!(!dashboard.session_muted.contains("1"));
+    }
+
+    #[test]
+    fn test_tab_bar() {
+        let tab = |org: &str| {
+            Dashboard::new(
+                SentryClient::new().unwrap(),
+                org.to_string(),
+                vec!["web".to_string()],
+            )
+        };
+        let mut tabs = vec![
+            tab("acme"),
+            tab("globex").with_label("globex/web".to_string()),
+        ];
+        assert_eq!(tab_bar(&tabs[..1], 0), "");
+        tabs[1].regressions.insert("7".to_string());
+        assert_eq!(tab_bar(&tabs, 0), "[1:acme]  2:globex/web !1 ");
+        assert!(tabs.iter().all(Dashboard::is_due));
+    }
+
+    #[test]
+    fn test_fetch_issues_of_several_projects() -> Result<()> {
+        let mut server = mockito::Server::new();
+        let issue = |id: &str, project: &str| {
+            serde_json::json!({
+                "id": id,
+                "title": "Test Issue",
+                "status": "unresolved",
+                "level": "error",
+                "culprit": "test.js:42",
+                "lastSeen": "2024-01-01T00:00:00Z",
+                "count": 1,
+                "userCount": 1,
+                "project": {"id": "1", "slug": project, "name": project}
+            })
+        };
+        // One request per query for the whole organization, none per project
+        let org_issues = server
+            .mock("GET", "/organizations/acme/issues/")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([issue("1", "web"), issue("2", "api"), issue("3", "billing")])
+                    .to_string(),
+            )
+            .expect(2)
+            .create();
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("token".to_string())?;
+        let dashboard = Dashboard::new(
+            client,
+            "acme".to_string(),
+            vec!["web".to_string(), "api".to_string()],
+        );
+
+        let (unresolved, resolved) = dashboard.fetch_issues()?;
+        let ids: Vec<&str> = unresolved.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        assert_eq!(resolved.len(), 2);
+        org_issues.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_due_tabs_keeps_polling_after_a_failure() {
+        let mut ok_server = mockito::Server::new();
+        ok_server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create();
+        let mut revoked_server = mockito::Server::new();
+        revoked_server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(401)
+            .with_body(r#"{"detail": "Invalid token"}"#)
+            .create();
+
+        let tab = |url: &str| {
+            let mut client = SentryClient::new().unwrap().with_url(url);
+            client.login("token".to_string()).unwrap();
+            Dashboard::new(client, "acme".to_string(), vec!["web".to_string()])
+        };
+        let mut tabs = vec![tab(&ok_server.url()), tab(&revoked_server.url())];
+        tabs[0].message = Some("Saved filter @mine".to_string());
+        update_due_tabs(&mut tabs);
+
+        assert!(!tabs[0].update_failed);
+        assert_eq!(tabs[0].message.as_deref(), Some("Saved filter @mine"));
+        assert!(tabs[1].update_failed);
+        assert!(tabs[1]
+            .message
+            .as_deref()
+            .is_some_and(|m| m.starts_with("Update failed")));
+        // Both are retried after the interval instead of on every redraw
+        assert!(!tabs.iter().any(Dashboard::is_due));
+    }
 }
@@ -0,0 +1,271 @@
+use crate::sentry::{AssembleRequest, AssembleStatus, SentryClient};
+use anyhow::{Context, Result};
+use sha1_smol::Sha1;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+const PDB_MAGIC: &[u8] = b"Microsoft C/C++ MSF 7.00\r\n\x1aDS\0\0\0";
+const MACHO_MAGICS: &[[u8; 4]] = &[
+    [0xfe, 0xed, 0xfa, 0xce],
+    [0xce, 0xfa, 0xed, 0xfe],
+    [0xfe, 0xed, 0xfa, 0xcf],
+    [0xcf, 0xfa, 0xed, 0xfe],
+    // Universal (fat) binaries
+    [0xca, 0xfe, 0xba, 0xbe],
+];
+const ASSEMBLE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const ASSEMBLE_POLL_ATTEMPTS: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DebugFileKind {
+    /// Mach-O debug companion, as found inside a dSYM bundle
+    MachO,
+    Elf,
+    Pdb,
+}
+
+impl std::fmt::Display for DebugFileKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DebugFileKind::MachO => write!(f, "dSYM"),
+            DebugFileKind::Elf => write!(f, "ELF"),
+            DebugFileKind::Pdb => write!(f, "PDB"),
+        }
+    }
+}
+
+/// A debug file ready for upload, split into chunks of the server's chunk size.
+pub struct DebugFile {
+    pub path: PathBuf,
+    pub kind: DebugFileKind,
+    pub checksum: String,
+    pub chunks: Vec<(String, Vec<u8>)>,
+}
+
+impl DebugFile {
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// Outcome of uploading a single debug file.
+#[derive(Debug, PartialEq)]
+pub enum UploadOutcome {
+    /// The server already had this file
+    AlreadyUploaded,
+    Uploaded,
+    Failed(String),
+}
+
+/// Detects the debug file format from the first bytes of a file.
+pub fn detect_kind(header: &[u8]) -> Option<DebugFileKind> {
+    if header.starts_with(ELF_MAGIC) {
+        Some(DebugFileKind::Elf)
+    } else if header.starts_with(PDB_MAGIC) {
+        Some(DebugFileKind::Pdb)
+    } else if MACHO_MAGICS.iter().any(|magic| header.starts_with(magic)) {
+        Some(DebugFileKind::MachO)
+    } else {
+        None
+    }
+}
+
+/// Finds debug files at a path, descending into directories such as dSYM bundles.
+pub fn find_debug_files(path: &Path) -> Result<Vec<(PathBuf, DebugFileKind)>> {
+    let mut found = Vec::new();
+    if path.is_dir() {
+        let mut entries: Vec<PathBuf> = fs::read_dir(path)
+            .with_context(|| format!("Failed to read directory {}", path.display()))?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<_>>()?;
+        entries.sort();
+        for entry in entries {
+            found.extend(find_debug_files(&entry)?);
+        }
+    } else {
+        let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        if let Some(kind) = detect_kind(&data) {
+            found.push((path.to_path_buf(), kind));
+        }
+    }
+    Ok(found)
+}
+
+/// Splits data into chunks and returns the whole-file SHA-1 with `(sha1, chunk)` pairs.
+pub fn chunk_data(data: &[u8], chunk_size: usize) -> (String, Vec<(String, Vec<u8>)>) {
+    let checksum = Sha1::from(data).digest().to_string();
+    let chunks = data
+        .chunks(chunk_size.max(1))
+        .map(|chunk| (Sha1::from(chunk).digest().to_string(), chunk.to_vec()))
+        .collect();
+    (checksum, chunks)
+}
+
+/// Uploads debug files with the chunked upload and assemble endpoints. Files the server
+/// already knows, and duplicates within the upload, are skipped by checksum.
+pub fn upload_debug_files(
+    client: &SentryClient,
+    org_slug: &str,
+    project_slug: &str,
+    paths: Vec<(PathBuf, DebugFileKind)>,
+) -> Result<Vec<(DebugFile, UploadOutcome)>> {
+    let options = client.get_chunk_upload_options(org_slug)?;
+
+    let mut files: Vec<DebugFile> = Vec::new();
+    for (path, kind) in paths {
+        let data = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let (checksum, chunks) = chunk_data(&data, options.chunk_size);
+        if files.iter().any(|f| f.checksum == checksum) {
+            continue;
+        }
+        files.push(DebugFile {
+            path,
+            kind,
+            checksum,
+            chunks,
+        });
+    }
+
+    let requests: HashMap<String, AssembleRequest> = files
+        .iter()
+        .map(|file| {
+            (
+                file.checksum.clone(),
+                AssembleRequest {
+                    name: file.name(),
+                    chunks: file.chunks.iter().map(|(sha, _)| sha.clone()).collect(),
+                },
+            )
+        })
+        .collect();
+
+    let initial = client.assemble_difs(org_slug, project_slug, &requests)?;
+    let already_uploaded: Vec<String> = initial
+        .iter()
+        .filter(|(_, status)| status.state == "OK")
+        .map(|(checksum, _)| checksum.clone())
+        .collect();
+
+    // Upload only the chunks the server is missing, batched to the server limits
+    let missing: Vec<&(String, Vec<u8>)> = files
+        .iter()
+        .flat_map(|file| {
+            let status = initial.get(&file.checksum);
+            file.chunks
+                .iter()
+                .filter(move |(sha, _)| status.is_some_and(|s| s.missing_chunks.contains(sha)))
+        })
+        .collect();
+    let mut batch: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut batch_size = 0;
+    for (sha, data) in missing {
+        if !batch.is_empty()
+            && (batch.len() >= options.chunks_per_request
+                || batch_size + data.len() > options.max_request_size)
+        {
+            client.upload_chunks(&options.url, &batch)?;
+            batch.clear();
+            batch_size = 0;
+        }
+        batch_size += data.len();
+        batch.push((sha.clone(), data.clone()));
+    }
+    if !batch.is_empty() {
+        client.upload_chunks(&options.url, &batch)?;
+    }
+
+    let statuses = wait_for_assembly(client, org_slug, project_slug, &requests)?;
+    Ok(files
+        .into_iter()
+        .map(|file| {
+            let outcome = if already_uploaded.contains(&file.checksum) {
+                UploadOutcome::AlreadyUploaded
+            } else {
+                match statuses.get(&file.checksum) {
+                    Some(status) if status.state == "OK" => UploadOutcome::Uploaded,
+                    Some(status) => UploadOutcome::Failed(
+                        status
+                            .detail
+                            .clone()
+                            .unwrap_or_else(|| status.state.clone()),
+                    ),
+                    None => UploadOutcome::Failed("missing from server response".to_string()),
+                }
+            };
+            (file, outcome)
+        })
+        .collect())
+}
+
+/// Polls the assemble endpoint until every file is processed or the attempts run out.
+fn wait_for_assembly(
+    client: &SentryClient,
+    org_slug: &str,
+    project_slug: &str,
+    requests: &HashMap<String, AssembleRequest>,
+) -> Result<HashMap<String, AssembleStatus>> {
+    let mut statuses = client.assemble_difs(org_slug, project_slug, requests)?;
+    for _ in 0..ASSEMBLE_POLL_ATTEMPTS {
+        let pending = statuses
+            .values()
+            .any(|s| s.state == "CREATED" || s.state == "ASSEMBLING");
+        if !pending {
+            break;
+        }
+        std::thread::sleep(ASSEMBLE_POLL_INTERVAL);
+        statuses = client.assemble_difs(org_slug, project_slug, requests)?;
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::*;
+
+    #[test]
+    fn test_detect_kind() {
+        assert_eq!(detect_kind(b"\x7fELF\x02\x01"), Some(DebugFileKind::Elf));
+        assert_eq!(
+            detect_kind(&[0xcf, 0xfa, 0xed, 0xfe, 0x07]),
+            Some(DebugFileKind::MachO)
+        );
+        assert_eq!(
+            detect_kind(b"Microsoft C/C++ MSF 7.00\r\n\x1aDS\0\0\0\0\x10"),
+            Some(DebugFileKind::Pdb)
+        );
+        assert_eq!(detect_kind(b"#!/bin/sh"), None);
+    }
+
+    #[test]
+    fn test_chunk_data() {
+        let (checksum, chunks) = chunk_data(b"hello world", 5);
+        assert_eq!(checksum, "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed");
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].1, b"hello");
+        assert_eq!(chunks[2].1, b"d");
+        assert_eq!(chunks[0].0, Sha1::from("hello").digest().to_string());
+    }
+
+    #[test]
+    fn test_find_debug_files_in_dsym_bundle() -> Result<()> {
+        let dir = assert_fs::TempDir::new()?;
+        dir.child("App.dSYM/Contents/Info.plist")
+            .write_str("<plist/>")?;
+        dir.child("App.dSYM/Contents/Resources/DWARF/App")
+            .write_binary(&[0xcf, 0xfa, 0xed, 0xfe, 0, 0, 0, 0])?;
+        dir.child("libapp.so")
+            .write_binary(b"\x7fELF\x02\x01\x01")?;
+
+        let found = find_debug_files(dir.path())?;
+        let kinds: Vec<DebugFileKind> = found.iter().map(|(_, kind)| *kind).collect();
+        assert_eq!(kinds, vec![DebugFileKind::MachO, DebugFileKind::Elf]);
+        Ok(())
+    }
+}
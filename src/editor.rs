@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const DEFAULT_EDITOR: &str = "vi";
+
+/// A line in a local source file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLocation {
+    pub path: PathBuf,
+    pub line: u32,
+}
+
+/// Editor from `$VISUAL` or `$EDITOR`, split into program and arguments.
+fn configured_editor() -> Vec<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| DEFAULT_EDITOR.to_string());
+    editor.split_whitespace().map(str::to_string).collect()
+}
+
+/// Builds the arguments that make an editor jump to a line, since every family
+/// of editors spells it differently.
+pub fn editor_args(editor: &str, location: &SourceLocation) -> Vec<String> {
+    let program = Path::new(editor)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let path = location.path.display().to_string();
+
+    match program.as_str() {
+        "code" | "code-insiders" | "codium" | "cursor" => {
+            vec!["-g".to_string(), format!("{}:{}", path, location.line)]
+        }
+        "subl" | "zed" | "hx" | "helix" => vec![format!("{}:{}", path, location.line)],
+        "idea" | "pycharm" | "rubymine" | "webstorm" | "goland" | "clion" => {
+            vec!["--line".to_string(), location.line.to_string(), path]
+        }
+        // vi, vim, nvim, nano, emacs, micro, kak and most terminal editors
+        _ => vec![format!("+{}", location.line), path],
+    }
+}
+
+/// Opens a file at a line in the user's editor and waits for it to exit.
+pub fn open_in_editor(location: &SourceLocation) -> Result<()> {
+    let editor = configured_editor();
+    let (program, extra_args) = editor
+        .split_first()
+        .context("No editor configured. Set $EDITOR.")?;
+
+    let status = Command::new(program)
+        .args(extra_args)
+        .args(editor_args(program, location))
+        .status()
+        .with_context(|| format!("Failed to start editor '{}'", program))?;
+
+    if !status.success() {
+        return Err(anyhow::anyhow!(
+            "Editor '{}' exited with {}",
+            program,
+            status
+        ));
+    }
+    Ok(())
+}
+
+/// Opens a URL in the default browser without waiting for it.
+pub fn open_in_browser(url: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    Command::new("open").arg(url).spawn()?;
+    #[cfg(target_os = "linux")]
+    Command::new("xdg-open").arg(url).spawn()?;
+    #[cfg(target_os = "windows")]
+    Command::new("cmd").args(["/C", "start", url]).spawn()?;
+    Ok(())
+}
+
+/// Clipboard programs tried in order, with their arguments.
+#[cfg(target_os = "macos")]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(target_os = "windows")]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["clip"]];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+/// Copies text to the system clipboard with the first clipboard program available.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    for command in CLIPBOARD_COMMANDS {
+        let Ok(mut child) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    let programs: Vec<&str> = CLIPBOARD_COMMANDS.iter().map(|c| c[0]).collect();
+    anyhow::bail!("No clipboard program found (tried {})", programs.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location() -> SourceLocation {
+        SourceLocation {
+            path: PathBuf::from("/src/app/views.py"),
+            line: 12,
+        }
+    }
+
+    #[test]
+    fn test_editor_args() {
+        assert_eq!(
+            editor_args("vim", &location()),
+            vec!["+12", "/src/app/views.py"]
+        );
+        assert_eq!(
+            editor_args("/usr/local/bin/nvim", &location()),
+            vec!["+12", "/src/app/views.py"]
+        );
+        assert_eq!(
+            editor_args("code", &location()),
+            vec!["-g", "/src/app/views.py:12"]
+        );
+        assert_eq!(
+            editor_args("subl", &location()),
+            vec!["/src/app/views.py:12"]
+        );
+    }
+}
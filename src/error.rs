@@ -0,0 +1,249 @@
+use reqwest::StatusCode;
+use std::fmt;
+
+/// Process exit codes, stable so shell scripts can branch on outcomes. Success is 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Any failure without a more specific code
+    Error = 1,
+    /// Missing, invalid or insufficient credentials
+    Auth = 2,
+    /// Organization, project, issue or other resource does not exist
+    NotFound = 3,
+    /// The API rejected the request because of rate limits
+    RateLimited = 4,
+    /// A check or canary gate failed, e.g. issues matched in check mode
+    CheckFailed = 10,
+}
+
+impl ExitCode {
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    fn from_status(status: u16) -> Self {
+        match status {
+            401 | 403 => ExitCode::Auth,
+            404 => ExitCode::NotFound,
+            429 => ExitCode::RateLimited,
+            _ => ExitCode::Error,
+        }
+    }
+}
+
+/// Error that ends the process with a specific exit code.
+#[derive(Debug)]
+pub struct CliError {
+    pub code: ExitCode,
+    message: String,
+}
+
+impl CliError {
+    pub fn new(code: ExitCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Failure reported by the Sentry API, sorted by what the user can do about it. Messages
+/// start with "API request failed: <status>" followed by the detail Sentry gave.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SentryError {
+    /// 401 or 403: the token is invalid, expired or lacks a scope
+    Unauthorized {
+        status: StatusCode,
+        /// Organization the request was for, by slug until renamed as configured, to
+        /// name in the login hint
+        org: Option<String>,
+        detail: String,
+    },
+    NotFound {
+        detail: String,
+    },
+    /// Seconds to wait come from the `Retry-After` header when Sentry sends one
+    RateLimited {
+        retry_after: Option<u64>,
+    },
+    /// 400: the request was understood but rejected, e.g. an invalid search query
+    Validation {
+        detail: String,
+    },
+    Api {
+        status: StatusCode,
+        detail: String,
+    },
+}
+
+impl SentryError {
+    pub fn new(
+        status: StatusCode,
+        org: Option<String>,
+        retry_after: Option<u64>,
+        body: &str,
+    ) -> Self {
+        // Sentry explains most failures in a JSON `detail` field
+        let detail = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|value| value.get("detail")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| body.trim().to_string());
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => SentryError::Unauthorized {
+                status,
+                org,
+                detail,
+            },
+            StatusCode::NOT_FOUND => SentryError::NotFound { detail },
+            StatusCode::TOO_MANY_REQUESTS => SentryError::RateLimited { retry_after },
+            StatusCode::BAD_REQUEST => SentryError::Validation { detail },
+            _ => SentryError::Api { status, detail },
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            SentryError::Unauthorized { status, .. } | SentryError::Api { status, .. } => *status,
+            SentryError::NotFound { .. } => StatusCode::NOT_FOUND,
+            SentryError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            SentryError::Validation { .. } => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+impl fmt::Display for SentryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "API request failed: {}", self.status())?;
+        match self {
+            SentryError::Unauthorized {
+                status,
+                org,
+                detail,
+            } => {
+                let login = match org {
+                    Some(org) => format!("login {}", org),
+                    None => "login".to_string(),
+                };
+                if *status == StatusCode::UNAUTHORIZED {
+                    write!(
+                        f,
+                        " - {}; the token is invalid or expired, run '{}' to sign in again",
+                        detail, login
+                    )
+                } else {
+                    write!(
+                        f,
+                        " - {}; the token lacks access, run '{}' with a token that has the needed scopes",
+                        detail, login
+                    )
+                }
+            }
+            SentryError::RateLimited {
+                retry_after: Some(seconds),
+            } => write!(f, " - rate limited, retry in {}s", seconds),
+            SentryError::RateLimited { retry_after: None } => {
+                write!(f, " - rate limited, retry later")
+            }
+            SentryError::NotFound { detail }
+            | SentryError::Validation { detail }
+            | SentryError::Api { detail, .. } => write!(f, " - {}", detail),
+        }
+    }
+}
+
+impl std::error::Error for SentryError {}
+
+pub fn auth_error(message: impl Into<String>) -> anyhow::Error {
+    CliError::new(ExitCode::Auth, message).into()
+}
+
+pub fn not_found(message: impl Into<String>) -> anyhow::Error {
+    CliError::new(ExitCode::NotFound, message).into()
+}
+
+/// Maps an error to the exit code of the most specific cause in its chain.
+pub fn exit_code(error: &anyhow::Error) -> ExitCode {
+    for cause in error.chain() {
+        if let Some(e) = cause.downcast_ref::<CliError>() {
+            return e.code;
+        }
+        if let Some(e) = cause.downcast_ref::<SentryError>() {
+            return ExitCode::from_status(e.status().as_u16());
+        }
+        if let Some(status) = cause
+            .downcast_ref::<reqwest::Error>()
+            .and_then(|e| e.status())
+        {
+            return ExitCode::from_status(status.as_u16());
+        }
+    }
+    ExitCode::Error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    fn api_error(status: u16, body: &str) -> anyhow::Error {
+        let status = StatusCode::from_u16(status).unwrap();
+        SentryError::new(status, Some("acme".to_string()), None, body).into()
+    }
+
+    #[test]
+    fn test_sentry_error() {
+        let error = SentryError::new(
+            StatusCode::UNAUTHORIZED,
+            Some("acme".to_string()),
+            None,
+            r#"{"detail": "Invalid token"}"#,
+        );
+        assert_eq!(
+            error.to_string(),
+            "API request failed: 401 Unauthorized - Invalid token; the token is invalid or expired, run 'login acme' to sign in again"
+        );
+        assert_eq!(
+            SentryError::new(StatusCode::TOO_MANY_REQUESTS, None, Some(30), ""),
+            SentryError::RateLimited {
+                retry_after: Some(30)
+            }
+        );
+        assert_eq!(
+            SentryError::new(StatusCode::BAD_REQUEST, None, None, "bad query\n").to_string(),
+            "API request failed: 400 Bad Request - bad query"
+        );
+        assert!(matches!(
+            SentryError::new(StatusCode::BAD_GATEWAY, None, None, ""),
+            SentryError::Api { .. }
+        ));
+    }
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(exit_code(&anyhow::anyhow!("boom")), ExitCode::Error);
+        assert_eq!(exit_code(&api_error(401, "{}")), ExitCode::Auth);
+        assert_eq!(exit_code(&api_error(403, "")), ExitCode::Auth);
+        assert_eq!(exit_code(&api_error(429, "")), ExitCode::RateLimited);
+        assert_eq!(exit_code(&api_error(500, "")), ExitCode::Error);
+        assert_eq!(
+            exit_code(&api_error(404, "").context("Failed to load issue")),
+            ExitCode::NotFound
+        );
+
+        // Context added on top keeps the code of the underlying cause
+        let error = Err::<(), _>(not_found("Project 'web' not found"))
+            .context("Failed to open project")
+            .unwrap_err();
+        assert_eq!(exit_code(&error), ExitCode::NotFound);
+        assert_eq!(exit_code(&auth_error("Not logged in")).code(), 2);
+        assert_eq!(ExitCode::CheckFailed.code(), 10);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::badge::unix_now;
+use crate::report::project_slug;
+use crate::sentry::Issue;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// What the monitor noticed about an issue.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum ChangeKind {
+    /// An issue the monitor had not listed before
+    #[serde(rename = "issue_new")]
+    New,
+    /// An issue crossing an `--alert` threshold
+    #[serde(rename = "issue_spike")]
+    Spike,
+    /// A resolved issue that is unresolved again
+    #[serde(rename = "issue_regressed")]
+    Regressed,
+}
+
+/// One line of the event log, shaped like a webhook payload.
+#[derive(Debug, Serialize)]
+pub struct ChangeEvent<'a> {
+    pub event: ChangeKind,
+    /// Unix time the monitor noticed the change
+    pub timestamp: u64,
+    pub org: &'a str,
+    pub project: String,
+    pub issue_id: &'a str,
+    pub short_id: Option<&'a str>,
+    pub title: &'a str,
+    pub level: &'a str,
+    pub count: u32,
+    pub user_count: u32,
+    pub permalink: Option<&'a str>,
+    /// The alert rule crossed, for spikes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+}
+
+impl<'a> ChangeEvent<'a> {
+    pub fn new(kind: ChangeKind, org: &'a str, issue: &'a Issue) -> Self {
+        Self {
+            event: kind,
+            timestamp: unix_now(),
+            org,
+            project: project_slug(issue),
+            issue_id: &issue.id,
+            short_id: issue.short_id.as_deref(),
+            title: &issue.title,
+            level: &issue.level,
+            count: issue.count,
+            user_count: issue.user_count,
+            permalink: issue.permalink.as_deref(),
+            rule: None,
+        }
+    }
+
+    pub fn with_rule(mut self, rule: String) -> Self {
+        self.rule = Some(rule);
+        self
+    }
+}
+
+/// JSON lines appended to a file or named pipe for other tools to consume, shared by
+/// all monitor tabs.
+pub struct EventLog {
+    file: Mutex<File>,
+}
+
+impl EventLog {
+    /// Opens the log for appending. Opening a named pipe waits until something reads it.
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open event log {}", path.display()))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Writes an event as one line, flushed right away so readers see it live.
+    pub fn emit(&self, event: &ChangeEvent) -> Result<()> {
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Event log is poisoned"))?;
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.flush())
+            .context("Failed to write to event log")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+
+    #[test]
+    fn test_event_log() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("events.jsonl");
+        let issue: Issue = serde_json::from_value(serde_json::json!({
+            "id": "42",
+            "shortId": "WEB-7",
+            "title": "TypeError",
+            "status": "unresolved",
+            "level": "error",
+            "culprit": "app.js in render",
+            "lastSeen": "2024-01-01T00:00:00Z",
+            "count": "120",
+            "userCount": 8,
+            "project": {"id": "1", "slug": "web", "name": "Web"}
+        }))?;
+
+        let log = EventLog::open(&path)?;
+        log.emit(&ChangeEvent::new(ChangeKind::New, "acme", &issue))?;
+        log.emit(
+            &ChangeEvent::new(ChangeKind::Spike, "acme", &issue)
+                .with_rule("count>100 in 10m".to_string()),
+        )?;
+        // Reopening appends instead of truncating
+        EventLog::open(&path)?.emit(&ChangeEvent::new(ChangeKind::Regressed, "acme", &issue))?;
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["event"], "issue_new");
+        assert_eq!(lines[0]["project"], "web");
+        assert_eq!(lines[0]["short_id"], "WEB-7");
+        assert_eq!(lines[0]["count"], 120);
+        assert!(lines[0].get("rule").is_none());
+        assert_eq!(lines[1]["event"], "issue_spike");
+        assert_eq!(lines[1]["rule"], "count>100 in 10m");
+        assert_eq!(lines[2]["event"], "issue_regressed");
+        Ok(())
+    }
+}
@@ -0,0 +1,299 @@
+use crate::report::project_slug;
+use crate::sentry::Issue;
+use anyhow::{Context, Result};
+use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedColumnWriter, SerializedFileWriter};
+use parquet::schema::parser::parse_message_type;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Issues keep one row each, updated on every export; histogram buckets accumulate, so
+/// exporting into the same file over time keeps the longer history.
+const SQLITE_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS issues (
+    id TEXT PRIMARY KEY,
+    short_id TEXT,
+    project TEXT NOT NULL,
+    title TEXT NOT NULL,
+    culprit TEXT NOT NULL,
+    level TEXT NOT NULL,
+    status TEXT NOT NULL,
+    first_seen TEXT,
+    last_seen TEXT NOT NULL,
+    count INTEGER NOT NULL,
+    user_count INTEGER NOT NULL,
+    exported_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE TABLE IF NOT EXISTS issue_stats (
+    issue_id TEXT NOT NULL REFERENCES issues(id),
+    period TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    count INTEGER NOT NULL,
+    PRIMARY KEY (issue_id, period, timestamp)
+);
+";
+
+/// Histogram buckets are repeated columns, read as lists by e.g. pandas or DuckDB.
+const PARQUET_SCHEMA: &str = "
+message issue {
+    REQUIRED BYTE_ARRAY id (UTF8);
+    OPTIONAL BYTE_ARRAY short_id (UTF8);
+    REQUIRED BYTE_ARRAY project (UTF8);
+    REQUIRED BYTE_ARRAY title (UTF8);
+    REQUIRED BYTE_ARRAY culprit (UTF8);
+    REQUIRED BYTE_ARRAY level (UTF8);
+    REQUIRED BYTE_ARRAY status (UTF8);
+    OPTIONAL BYTE_ARRAY first_seen (UTF8);
+    REQUIRED BYTE_ARRAY last_seen (UTF8);
+    REQUIRED INT64 count;
+    REQUIRED INT64 user_count;
+    REPEATED INT64 stats_timestamp;
+    REPEATED INT64 stats_count;
+}
+";
+
+/// Writes issues with their `stats_period` histograms to a SQLite database, adding to
+/// the tables when the file already has them.
+pub fn write_sqlite(path: &Path, issues: &[Issue], stats_period: &str) -> Result<()> {
+    let mut db =
+        Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    db.execute_batch(SQLITE_SCHEMA)
+        .context("Failed to create export tables")?;
+
+    let tx = db.transaction()?;
+    {
+        let mut insert_issue = tx.prepare(
+            "INSERT OR REPLACE INTO issues (id, short_id, project, title, culprit, level, status,
+                first_seen, last_seen, count, user_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )?;
+        let mut insert_bucket = tx.prepare(
+            "INSERT OR REPLACE INTO issue_stats (issue_id, period, timestamp, count)
+             VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for issue in issues {
+            insert_issue.execute(params![
+                issue.id,
+                issue.short_id,
+                project_slug(issue),
+                issue.title,
+                issue.culprit,
+                issue.level,
+                issue.status,
+                issue.first_seen,
+                issue.last_seen,
+                issue.count,
+                issue.user_count,
+            ])?;
+            for (timestamp, count) in buckets(issue, stats_period) {
+                insert_bucket.execute(params![issue.id, stats_period, timestamp, count])?;
+            }
+        }
+    }
+    tx.commit().context("Failed to write issues")?;
+    Ok(())
+}
+
+/// Writes issues with their `stats_period` histograms to a Parquet file, replacing it.
+pub fn write_parquet(path: &Path, issues: &[Issue], stats_period: &str) -> Result<()> {
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let schema = Arc::new(parse_message_type(PARQUET_SCHEMA)?);
+    let props = Arc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(file, schema, props)?;
+
+    let text = |value: fn(&Issue) -> String| -> Column {
+        Column::Text(issues.iter().map(|i| Some(value(i))).collect(), false)
+    };
+    let optional = |value: fn(&Issue) -> Option<String>| -> Column {
+        Column::Text(issues.iter().map(value).collect(), true)
+    };
+    let stats: Vec<Vec<(i64, i64)>> = issues.iter().map(|i| buckets(i, stats_period)).collect();
+    let columns = [
+        text(|i| i.id.clone()),
+        optional(|i| i.short_id.clone()),
+        text(project_slug),
+        text(|i| i.title.clone()),
+        text(|i| i.culprit.clone()),
+        text(|i| i.level.clone()),
+        text(|i| i.status.clone()),
+        optional(|i| i.first_seen.clone()),
+        text(|i| i.last_seen.clone()),
+        Column::Int(issues.iter().map(|i| i64::from(i.count)).collect()),
+        Column::Int(issues.iter().map(|i| i64::from(i.user_count)).collect()),
+        Column::Repeated(
+            stats
+                .iter()
+                .map(|b| b.iter().map(|(t, _)| *t).collect())
+                .collect(),
+        ),
+        Column::Repeated(
+            stats
+                .iter()
+                .map(|b| b.iter().map(|(_, c)| *c).collect())
+                .collect(),
+        ),
+    ];
+
+    let mut row_group = writer.next_row_group()?;
+    let mut columns = columns.iter();
+    while let Some(mut column_writer) = row_group.next_column()? {
+        let column = columns
+            .next()
+            .context("Parquet schema has more columns than written")?;
+        column.write(&mut column_writer)?;
+        column_writer.close()?;
+    }
+    row_group.close()?;
+    writer.close().context("Failed to write Parquet file")?;
+    Ok(())
+}
+
+/// Values of one Parquet column, one entry per issue.
+enum Column {
+    /// Strings and whether the column is optional; only optional ones hold `None`
+    Text(Vec<Option<String>>, bool),
+    Int(Vec<i64>),
+    /// A list per issue, possibly empty
+    Repeated(Vec<Vec<i64>>),
+}
+
+impl Column {
+    fn write(&self, writer: &mut SerializedColumnWriter) -> Result<()> {
+        match self {
+            Column::Text(values, optional) => {
+                let present: Vec<ByteArray> = values
+                    .iter()
+                    .flatten()
+                    .map(|v| ByteArray::from(v.as_str()))
+                    .collect();
+                let def_levels: Vec<i16> = values.iter().map(|v| i16::from(v.is_some())).collect();
+                let def_levels = optional.then_some(def_levels.as_slice());
+                writer
+                    .typed::<ByteArrayType>()
+                    .write_batch(&present, def_levels, None)?;
+            }
+            Column::Int(values) => {
+                writer
+                    .typed::<Int64Type>()
+                    .write_batch(values, None, None)?;
+            }
+            Column::Repeated(lists) => {
+                let mut values = Vec::new();
+                let mut def_levels = Vec::new();
+                let mut rep_levels = Vec::new();
+                for list in lists {
+                    if list.is_empty() {
+                        def_levels.push(0);
+                        rep_levels.push(0);
+                    }
+                    for (index, value) in list.iter().enumerate() {
+                        values.push(*value);
+                        def_levels.push(1);
+                        rep_levels.push(i16::from(index > 0));
+                    }
+                }
+                writer.typed::<Int64Type>().write_batch(
+                    &values,
+                    Some(&def_levels),
+                    Some(&rep_levels),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Event count buckets of an issue's histogram for a stats period.
+fn buckets(issue: &Issue, stats_period: &str) -> Vec<(i64, i64)> {
+    issue
+        .stats
+        .as_ref()
+        .and_then(|stats| stats.get(stats_period))
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    fn issues() -> Vec<Issue> {
+        serde_json::from_value(serde_json::json!([
+            {
+                "id": "1",
+                "shortId": "WEB-1",
+                "title": "TypeError",
+                "status": "unresolved",
+                "level": "error",
+                "culprit": "app.js in render",
+                "lastSeen": "2024-01-02T00:00:00Z",
+                "count": "12",
+                "userCount": 3,
+                "project": {"id": "1", "slug": "web", "name": "Web"},
+                "stats": {"24h": [[1000, 5], [4600, 7]]}
+            },
+            {
+                "id": "2",
+                "title": "KeyError",
+                "status": "unresolved",
+                "level": "warning",
+                "culprit": "views in show",
+                "lastSeen": "2024-01-01T00:00:00Z",
+                "count": 1,
+                "userCount": 1
+            }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_write_sqlite() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("errors.db");
+        write_sqlite(&path, &issues(), "24h")?;
+        // Exporting again updates issues instead of duplicating them
+        write_sqlite(&path, &issues(), "24h")?;
+
+        let db = Connection::open(&path)?;
+        let issues: i64 = db.query_row("SELECT COUNT(*) FROM issues", [], |r| r.get(0))?;
+        assert_eq!(issues, 2);
+        let (project, count): (String, i64) = db.query_row(
+            "SELECT project, count FROM issues WHERE id = '1'",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+        assert_eq!((project.as_str(), count), ("web", 12));
+        let events: i64 = db.query_row(
+            "SELECT SUM(count) FROM issue_stats WHERE issue_id = '1' AND period = '24h'",
+            [],
+            |r| r.get(0),
+        )?;
+        assert_eq!(events, 12);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_parquet() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("errors.parquet");
+        write_parquet(&path, &issues(), "24h")?;
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&path)?)?;
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 2);
+        assert_eq!(metadata.file_metadata().schema_descr().num_columns(), 13);
+
+        let rows: Vec<String> = reader
+            .get_row_iter(None)?
+            .map(|row| Ok(row?.to_string()))
+            .collect::<Result<_>>()?;
+        assert!(rows[0].contains("stats_count: [5, 7]"), "{}", rows[0]);
+        assert!(rows[1].contains("short_id: null"), "{}", rows[1]);
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Runs a git command in `repo` and returns its trimmed standard output.
+fn git(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn head_commit(repo: &Path) -> Result<String> {
+    git(repo, &["rev-parse", "HEAD"])
+}
+
+/// Repository name as Sentry's repository integrations know it, e.g. `owner/repo`,
+/// derived from the `origin` remote.
+pub fn origin_repository(repo: &Path) -> Result<String> {
+    let url = git(repo, &["remote", "get-url", "origin"])?;
+    repository_name(&url)
+        .ok_or_else(|| anyhow::anyhow!("Cannot derive a repository name from remote '{}'", url))
+}
+
+/// Extracts `owner/repo` from SSH (`git@host:owner/repo.git`) and HTTPS remote URLs.
+pub fn repository_name(url: &str) -> Option<String> {
+    let path = if let Some((_, rest)) = url.split_once("://") {
+        rest.split_once('/')?.1
+    } else {
+        url.split_once(':')?.1
+    };
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    if path.split('/').filter(|s| !s.is_empty()).count() < 2 {
+        return None;
+    }
+    Some(path.to_string())
+}
+
+/// Last change to a line according to `git blame`.
+#[derive(Debug, PartialEq)]
+pub struct BlameInfo {
+    pub commit: String,
+    pub author: String,
+    pub summary: String,
+}
+
+impl BlameInfo {
+    pub fn short_commit(&self) -> &str {
+        &self.commit[..self.commit.len().min(8)]
+    }
+}
+
+/// Blames a single line of a file relative to the repository root.
+pub fn blame_line(repo: &Path, file: &Path, line: u32) -> Result<BlameInfo> {
+    let range = format!("{},{}", line, line);
+    let file = file.to_string_lossy();
+    let output = git(repo, &["blame", "--porcelain", "-L", &range, "--", &file])?;
+    parse_blame_porcelain(&output)
+        .ok_or_else(|| anyhow::anyhow!("Unexpected git blame output for {}:{}", file, line))
+}
+
+fn parse_blame_porcelain(output: &str) -> Option<BlameInfo> {
+    let mut lines = output.lines();
+    let commit = lines.next()?.split_whitespace().next()?.to_string();
+    let mut author = None;
+    let mut summary = None;
+    for line in lines {
+        if let Some(value) = line.strip_prefix("author ") {
+            author = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("summary ") {
+            summary = Some(value.to_string());
+        }
+    }
+    Some(BlameInfo {
+        commit,
+        author: author?,
+        summary: summary.unwrap_or_default(),
+    })
+}
+
+/// Maps a stack frame path to a file in the checkout. Frame paths are often absolute
+/// paths on the build or production host, or carry bundler prefixes, so leading
+/// components are dropped until the remainder matches a file in the repository.
+pub fn resolve_source_path(repo: &Path, frame_path: &str) -> Option<PathBuf> {
+    let path = frame_path
+        .split_once(":///")
+        .map_or(frame_path, |(_, rest)| rest)
+        .trim_start_matches("~/");
+    let components: Vec<&str> = path
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect();
+
+    (0..components.len()).find_map(|start| {
+        let candidate: PathBuf = components[start..].iter().collect();
+        repo.join(&candidate).is_file().then_some(candidate)
+    })
+}
+
+/// Maps a dotted module name (e.g. `app.views` or `com.example.Checkout`) to a
+/// source file under `root`, trying the usual extensions of module-based languages.
+pub fn resolve_module_path(root: &Path, module: &str) -> Option<PathBuf> {
+    const EXTENSIONS: &[&str] = &["py", "java", "kt", "scala", "groovy", "cs"];
+
+    let base: PathBuf = module.split('.').collect();
+    EXTENSIONS.iter().find_map(|ext| {
+        let candidate = base.with_extension(ext);
+        root.join(&candidate).is_file().then_some(candidate)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_name() {
+        assert_eq!(
+            repository_name("git@github.com:amkisko/sex-cli.git"),
+            Some("amkisko/sex-cli".to_string())
+        );
+        assert_eq!(
+            repository_name("https://github.com/amkisko/sex-cli"),
+            Some("amkisko/sex-cli".to_string())
+        );
+        assert_eq!(
+            repository_name("ssh://git@gitlab.example.com/group/sub/app.git"),
+            Some("group/sub/app".to_string())
+        );
+        assert_eq!(repository_name("https://example.com/"), None);
+    }
+
+    #[test]
+    fn test_parse_blame_porcelain() {
+        let output = "4f2e1a9c0d3b5e7f8a6c4b2d0e9f8a7b6c5d4e3f 12 12 1\n\
+            author Jane Doe\n\
+            author-mail <jane@example.com>\n\
+            summary Handle empty carts\n\
+            filename app/views.py\n\
+            \tdef checkout(request):";
+        let blame = parse_blame_porcelain(output).unwrap();
+        assert_eq!(blame.short_commit(), "4f2e1a9c");
+        assert_eq!(blame.author, "Jane Doe");
+        assert_eq!(blame.summary, "Handle empty carts");
+    }
+
+    #[test]
+    fn test_resolve_source_path() -> Result<()> {
+        use assert_fs::prelude::*;
+
+        let repo = assert_fs::TempDir::new()?;
+        repo.child("app/views.py").write_str("")?;
+        repo.child("src/index.js").write_str("")?;
+
+        let expected = Some(PathBuf::from("app/views.py"));
+        assert_eq!(resolve_source_path(repo.path(), "app/views.py"), expected);
+        assert_eq!(
+            resolve_source_path(repo.path(), "/srv/www/releases/42/app/views.py"),
+            expected
+        );
+        assert_eq!(
+            resolve_source_path(repo.path(), "webpack:///./src/index.js"),
+            Some(PathBuf::from("src/index.js"))
+        );
+        assert_eq!(resolve_source_path(repo.path(), "lib/missing.py"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_module_path() -> Result<()> {
+        use assert_fs::prelude::*;
+
+        let root = assert_fs::TempDir::new()?;
+        root.child("app/views.py").write_str("")?;
+        root.child("com/example/Checkout.java").write_str("")?;
+
+        assert_eq!(
+            resolve_module_path(root.path(), "app.views"),
+            Some(PathBuf::from("app/views.py"))
+        );
+        assert_eq!(
+            resolve_module_path(root.path(), "com.example.Checkout"),
+            Some(PathBuf::from("com/example/Checkout.java"))
+        );
+        assert_eq!(resolve_module_path(root.path(), "app.models"), None);
+        Ok(())
+    }
+}
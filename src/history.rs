@@ -0,0 +1,189 @@
+use anyhow::{Context, Result};
+use crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Searches kept for recall; older ones are forgotten.
+const MAX_RECENT: usize = 20;
+
+/// Searches typed into the filter prompts of the monitor and triage, and filters saved
+/// under a name to be recalled as `@name`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHistory {
+    /// Oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recent: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub saved: BTreeMap<String, String>,
+}
+
+impl SearchHistory {
+    pub fn is_empty(&self) -> bool {
+        self.recent.is_empty() && self.saved.is_empty()
+    }
+
+    /// Makes a search the most recent one, moving it up when it was used before.
+    pub fn record(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        self.recent.retain(|recent| recent != query);
+        self.recent.push(query.to_string());
+        if self.recent.len() > MAX_RECENT {
+            self.recent.remove(0);
+        }
+    }
+
+    pub fn save_filter(&mut self, name: &str, query: &str) {
+        self.saved
+            .insert(name.trim_start_matches('@').to_string(), query.to_string());
+    }
+
+    /// Expands `@name` to the saved filter of that name; other input is a search as is.
+    pub fn expand(&self, input: &str) -> Result<String> {
+        let input = input.trim();
+        match input.strip_prefix('@') {
+            Some(name) => self.saved.get(name).cloned().with_context(|| {
+                let names: Vec<&str> = self.saved.keys().map(String::as_str).collect();
+                format!(
+                    "No saved filter '{}'. Saved: {}",
+                    name,
+                    if names.is_empty() {
+                        "none".to_string()
+                    } else {
+                        names.join(", ")
+                    }
+                )
+            }),
+            None => Ok(input.to_string()),
+        }
+    }
+}
+
+/// What a key did to a [`FilterInput`].
+#[derive(Debug, PartialEq)]
+pub enum InputState {
+    Editing,
+    Submitted(String),
+    Cancelled,
+}
+
+/// Text typed into a filter prompt, where Up and Down step through earlier searches.
+pub struct FilterInput<'a> {
+    recent: &'a [String],
+    /// Entry of `recent` shown, `None` while editing new text
+    position: Option<usize>,
+    /// What was typed before stepping into the history
+    draft: String,
+    pub text: String,
+}
+
+impl<'a> FilterInput<'a> {
+    pub fn new(recent: &'a [String], text: &str) -> Self {
+        Self {
+            recent,
+            position: None,
+            draft: String::new(),
+            text: text.to_string(),
+        }
+    }
+
+    pub fn handle(&mut self, key: KeyCode) -> InputState {
+        match key {
+            KeyCode::Enter => return InputState::Submitted(self.text.trim().to_string()),
+            KeyCode::Esc => return InputState::Cancelled,
+            KeyCode::Backspace => {
+                self.text.pop();
+            }
+            KeyCode::Char(c) => self.text.push(c),
+            KeyCode::Up if !self.recent.is_empty() => {
+                let position = match self.position {
+                    Some(position) => position.saturating_sub(1),
+                    None => {
+                        self.draft = std::mem::take(&mut self.text);
+                        self.recent.len() - 1
+                    }
+                };
+                self.position = Some(position);
+                self.text = self.recent[position].clone();
+            }
+            KeyCode::Down => match self.position {
+                Some(position) if position + 1 < self.recent.len() => {
+                    self.position = Some(position + 1);
+                    self.text = self.recent[position + 1].clone();
+                }
+                Some(_) => {
+                    self.position = None;
+                    self.text = std::mem::take(&mut self.draft);
+                }
+                None => {}
+            },
+            _ => {}
+        }
+        InputState::Editing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_expand() {
+        let mut history = SearchHistory::default();
+        history.record("is:unresolved");
+        history.record("level:fatal");
+        history.record(" is:unresolved ");
+        history.record("");
+        assert_eq!(history.recent, vec!["level:fatal", "is:unresolved"]);
+
+        for i in 0..MAX_RECENT {
+            history.record(&format!("release:{}", i));
+        }
+        assert_eq!(history.recent.len(), MAX_RECENT);
+        assert_eq!(history.recent[0], "release:0");
+
+        history.save_filter("@fatal", "level:fatal is:unassigned");
+        assert_eq!(
+            history.expand("@fatal").unwrap(),
+            "level:fatal is:unassigned"
+        );
+        assert_eq!(
+            history.expand(" browser:Chrome ").unwrap(),
+            "browser:Chrome"
+        );
+        assert!(history
+            .expand("@missing")
+            .unwrap_err()
+            .to_string()
+            .contains("Saved: fatal"));
+    }
+
+    #[test]
+    fn test_filter_input_recall() {
+        let recent = vec!["first".to_string(), "second".to_string()];
+        let mut input = FilterInput::new(&recent, "");
+        input.handle(KeyCode::Char('x'));
+
+        input.handle(KeyCode::Up);
+        assert_eq!(input.text, "second");
+        input.handle(KeyCode::Up);
+        input.handle(KeyCode::Up);
+        assert_eq!(input.text, "first");
+        input.handle(KeyCode::Down);
+        assert_eq!(input.text, "second");
+        // Stepping past the newest search brings back what was typed
+        input.handle(KeyCode::Down);
+        assert_eq!(input.text, "x");
+
+        input.handle(KeyCode::Backspace);
+        input.handle(KeyCode::Char('@'));
+        input.handle(KeyCode::Char('a'));
+        assert_eq!(
+            input.handle(KeyCode::Enter),
+            InputState::Submitted("@a".to_string())
+        );
+        assert_eq!(input.handle(KeyCode::Esc), InputState::Cancelled);
+    }
+}
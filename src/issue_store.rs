@@ -0,0 +1,263 @@
+use crate::config::APP_NAME;
+use crate::sentry::Issue;
+use anyhow::{Context, Result};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const STORE_FILE: &str = "issues.db";
+
+/// Issues are kept as their API JSON next to the lowercased text `issue grep` searches,
+/// in the order the API returned them.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS syncs (
+    org_slug TEXT PRIMARY KEY,
+    synced_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS issues (
+    org_slug TEXT NOT NULL REFERENCES syncs(org_slug),
+    position INTEGER NOT NULL,
+    searchable TEXT NOT NULL,
+    issue TEXT NOT NULL,
+    PRIMARY KEY (org_slug, position)
+);
+";
+
+/// Unresolved issues synced per organization slug, searched offline by `issue grep`.
+pub struct IssueStore {
+    db: Connection,
+}
+
+impl IssueStore {
+    /// Opens the store, creating it when it is missing.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create cache directory")?;
+        }
+        let db =
+            Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        db.execute_batch(SCHEMA).with_context(|| {
+            format!(
+                "{} is not an issue store; remove it and run 'sex issue sync' again",
+                path.display()
+            )
+        })?;
+        Ok(Self { db })
+    }
+
+    /// Replaces the issues of an organization.
+    pub fn sync(&mut self, org_slug: &str, issues: &[Issue], now: u64) -> Result<()> {
+        let tx = self.db.transaction()?;
+        tx.execute("DELETE FROM issues WHERE org_slug = ?1", params![org_slug])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO syncs (org_slug, synced_at) VALUES (?1, ?2)",
+            params![org_slug, now as i64],
+        )?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO issues (org_slug, position, searchable, issue)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for (position, issue) in issues.iter().enumerate() {
+                insert.execute(params![
+                    org_slug,
+                    position as i64,
+                    searchable_text(issue),
+                    serde_json::to_string(issue)?
+                ])?;
+            }
+        }
+        tx.commit().context("Failed to write issue store")
+    }
+
+    /// Unix time an organization was last synced.
+    pub fn synced_at(&self, org_slug: &str) -> Result<Option<u64>> {
+        let synced_at: Option<i64> = self
+            .db
+            .query_row(
+                "SELECT synced_at FROM syncs WHERE org_slug = ?1",
+                params![org_slug],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(synced_at.map(|t| t as u64))
+    }
+
+    pub fn org_slugs(&self) -> Result<Vec<String>> {
+        let mut select = self
+            .db
+            .prepare("SELECT org_slug FROM syncs ORDER BY org_slug")?;
+        let slugs = select
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(slugs)
+    }
+
+    /// Issues whose title, culprit or message contain every word of `text`, ignoring
+    /// case, with the organization they belong to. Searches one organization or all.
+    pub fn search(&self, text: &str, org_slug: Option<&str>) -> Result<Vec<(String, Issue)>> {
+        let words: Vec<String> = text.split_whitespace().map(str::to_lowercase).collect();
+        let mut sql =
+            "SELECT org_slug, issue FROM issues WHERE (?1 IS NULL OR org_slug = ?1)".to_string();
+        for n in 0..words.len() {
+            sql.push_str(&format!(" AND instr(searchable, ?{}) > 0", n + 2));
+        }
+        sql.push_str(" ORDER BY org_slug, position");
+
+        let mut select = self.db.prepare(&sql)?;
+        let values =
+            std::iter::once(org_slug.map(str::to_string)).chain(words.into_iter().map(Some));
+        let rows = select.query_map(params_from_iter(values), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.map(|row| {
+            let (slug, issue) = row?;
+            let issue = serde_json::from_str(&issue).context("Failed to parse stored issue")?;
+            Ok((slug, issue))
+        })
+        .collect()
+    }
+}
+
+/// Issues of a project as they were at one point, e.g. before a deploy, saved by
+/// `issue snapshot` and compared against the live state by `issue diff`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssueSnapshot {
+    /// Project as given on the command line
+    pub target: String,
+    pub org_slug: String,
+    pub project: String,
+    pub query: String,
+    /// Unix time the snapshot was taken
+    pub taken_at: u64,
+    pub issues: Vec<Issue>,
+}
+
+impl IssueSnapshot {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("{} is not an issue snapshot", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Lowercased title, culprit and message of an issue.
+fn searchable_text(issue: &Issue) -> String {
+    let message = issue
+        .metadata
+        .as_ref()
+        .and_then(|m| m.value.as_deref())
+        .unwrap_or_default();
+    format!("{}\n{}\n{}", issue.title, issue.culprit, message).to_lowercase()
+}
+
+pub fn store_path() -> Result<PathBuf> {
+    Ok(dirs::cache_dir()
+        .context("Failed to determine cache directory")?
+        .join(APP_NAME)
+        .join(STORE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+
+    fn issue(id: &str, title: &str, culprit: &str, message: &str) -> Issue {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": title,
+            "status": "unresolved",
+            "level": "error",
+            "culprit": culprit,
+            "lastSeen": "2024-01-01T00:00:00Z",
+            "count": 1,
+            "userCount": 1,
+            "metadata": {"type": "Error", "value": message}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_issue_store() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("cache").join(STORE_FILE);
+        let mut store = IssueStore::open(&path)?;
+        assert!(store.org_slugs()?.is_empty());
+
+        store.sync(
+            "acme",
+            &[
+                issue(
+                    "1",
+                    "TimeoutError",
+                    "payments.charge in run",
+                    "Stripe timed out",
+                ),
+                issue("2", "KeyError: 'user'", "api.views in show", ""),
+            ],
+            1000,
+        )?;
+        store.sync("globex", &[issue("3", "old", "db", "")], 900)?;
+        // A new sync replaces the organization's issues
+        store.sync(
+            "globex",
+            &[issue("3", "TimeoutError", "db in query", "")],
+            1000,
+        )?;
+        drop(store);
+
+        let store = IssueStore::open(&path)?;
+        assert_eq!(store.org_slugs()?, vec!["acme", "globex"]);
+        assert_eq!(store.synced_at("globex")?, Some(1000));
+        assert_eq!(store.synced_at("initech")?, None);
+
+        let ids = |found: Vec<(String, Issue)>| -> Vec<String> {
+            found.into_iter().map(|(_, i)| i.id).collect()
+        };
+        assert_eq!(ids(store.search("timeouterror", None)?), vec!["1", "3"]);
+        assert_eq!(
+            ids(store.search("TimeoutError", Some("globex"))?),
+            vec!["3"]
+        );
+        // Words match anywhere in the title, culprit or message
+        assert_eq!(ids(store.search("stripe payments", None)?), vec!["1"]);
+        assert!(store.search("stripe db", None)?.is_empty());
+        assert!(store.search("old", None)?.is_empty());
+
+        std::fs::write(&path, "not a database")?;
+        let error = IssueStore::open(&path).err().unwrap();
+        assert!(error.to_string().contains("is not an issue store"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_issue_snapshot() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("snap.json");
+        IssueSnapshot {
+            target: "acme/web".to_string(),
+            org_slug: "acme".to_string(),
+            project: "web".to_string(),
+            query: "is:unresolved".to_string(),
+            taken_at: 1000,
+            issues: vec![issue("1", "TimeoutError", "db in query", "")],
+        }
+        .save(&path)?;
+
+        let snapshot = IssueSnapshot::load(&path)?;
+        assert_eq!(snapshot.target, "acme/web");
+        assert_eq!(snapshot.issues[0].title, "TimeoutError");
+
+        std::fs::write(&path, "[]")?;
+        let error = IssueSnapshot::load(&path).unwrap_err();
+        assert!(error.to_string().ends_with("is not an issue snapshot"));
+        Ok(())
+    }
+}
@@ -1,6 +1,12 @@
+use crate::editor::{open_in_editor, SourceLocation};
+use crate::history::{FilterInput, InputState};
+use crate::keys::{Nav, NavKeys, NAV_HINT};
+use crate::ownership::AssigneeSuggestion;
+use crate::sentry::{AffectedUser, EventRequest, EventUser, MAX_PAGE_SIZE};
+use crate::table::truncate;
 use crate::tui::Tui;
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyCode;
 
 #[derive(Debug, PartialEq)]
 pub struct Issue {
@@ -14,27 +20,132 @@ pub struct Issue {
     pub users: u32,
 }
 
+/// What the viewer shows: the issue's details or one of the scrollable text tabs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tab {
+    Details,
+    Grouping,
+    RawEvent,
+}
+
 pub struct IssueViewer {
     tui: Tui,
     issue: Issue,
     scroll_offset: u16,
+    source: Option<SourceLocation>,
+    suggestions: Vec<AssigneeSuggestion>,
+    keys: NavKeys,
+    /// OS, device, runtime and browser of the latest event, e.g. `OS: Linux`
+    contexts: Vec<String>,
+    /// User of the latest event and the users affected most
+    user: Vec<String>,
+    /// HTTP request of the latest event, credentials masked
+    request: Vec<String>,
+    /// Why the issue's events grouped together, see `GroupingInfo::lines`
+    grouping: Vec<String>,
+    /// Pretty-printed JSON of the latest event, one entry per line
+    raw_event: Vec<String>,
+    tab: Tab,
+    /// Scroll position in the grouping and raw JSON tabs
+    line_offset: usize,
+    /// Text searched for in a text tab, and earlier searches of this session
+    search: Option<String>,
+    searches: Vec<String>,
+    message: Option<String>,
 }
 
 impl IssueViewer {
     pub fn new(issue: Issue) -> Result<Self> {
-        Ok(Self {
-            tui: Tui::new()?,
-            issue,
-            scroll_offset: 0,
-        })
+        Ok(Self::new_with_tui(issue, Tui::new()?))
     }
 
-    #[cfg(test)]
-    pub fn new_with_tui(issue: Issue, tui: Tui) -> Self {
+    /// Enables opening the crashing file in `$EDITOR` with `o`.
+    pub fn with_source(mut self, source: Option<SourceLocation>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Shows who could take an unassigned issue.
+    pub fn with_suggestions(mut self, suggestions: Vec<AssigneeSuggestion>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    /// Shows where the latest event happened as chips, e.g. `[OS: Linux] [Runtime: CPython 3.11]`.
+    pub fn with_contexts(mut self, contexts: Vec<(&str, String)>) -> Self {
+        self.contexts = contexts
+            .into_iter()
+            .map(|(label, value)| format!("{}: {}", label, value))
+            .collect();
+        self
+    }
+
+    /// Shows the user of the latest event and the users who ran into the issue most.
+    pub fn with_users(mut self, user: Option<&EventUser>, affected: &[AffectedUser]) -> Self {
+        self.user.clear();
+        if let Some(user) = user {
+            self.user.push(format!("Latest event: {}", user.summary()));
+        }
+        if !affected.is_empty() {
+            let top: Vec<String> = affected
+                .iter()
+                .take(3)
+                .map(|user| format!("{} ({})", user.label(), user.count))
+                .collect();
+            // Only the first page of the user tag's values is fetched
+            let distinct = if affected.len() >= MAX_PAGE_SIZE {
+                format!("{}+", MAX_PAGE_SIZE)
+            } else {
+                affected.len().to_string()
+            };
+            self.user.push(format!(
+                "{} distinct, most affected: {}",
+                distinct,
+                top.join(", ")
+            ));
+        }
+        self
+    }
+
+    /// Shows the HTTP request the latest event happened during.
+    pub fn with_request(mut self, request: Option<EventRequest>) -> Self {
+        self.request = request.map(|request| request.lines()).unwrap_or_default();
+        self
+    }
+
+    /// Enables switching to the grouping information with `f`.
+    pub fn with_grouping(mut self, grouping: Vec<String>) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Enables switching to the raw JSON of the latest event with `J`.
+    pub fn with_raw_event(mut self, event: Option<&serde_json::Value>) -> Self {
+        self.raw_event = event
+            .and_then(|event| serde_json::to_string_pretty(event).ok())
+            .map(|json| json.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        self
+    }
+
+    fn new_with_tui(issue: Issue, tui: Tui) -> Self {
         Self {
             tui,
             issue,
             scroll_offset: 0,
+            source: None,
+            suggestions: Vec::new(),
+            keys: NavKeys::default(),
+            contexts: Vec::new(),
+            user: Vec::new(),
+            request: Vec::new(),
+            grouping: Vec::new(),
+            raw_event: Vec::new(),
+            tab: Tab::Details,
+            line_offset: 0,
+            search: None,
+            searches: Vec::new(),
+            message: None,
         }
     }
 
@@ -44,19 +155,22 @@ impl IssueViewer {
         loop {
             self.render()?;
 
-            match self.tui.read_key()? {
-                KeyEvent {
-                    code: KeyCode::Char('q'),
-                    ..
-                } => break,
-                KeyEvent {
-                    code: KeyCode::Char('j'),
-                    ..
-                } => self.scroll_down(),
-                KeyEvent {
-                    code: KeyCode::Char('k'),
-                    ..
-                } => self.scroll_up(),
+            let key = self.tui.read_key()?;
+            if let Some(nav) = self.keys.handle(&key) {
+                self.navigate(nav);
+                continue;
+            }
+            self.message = None;
+            match key.code {
+                KeyCode::Char('q') => break,
+                KeyCode::Char('o') => self.open_source()?,
+                KeyCode::Char('J') if !self.raw_event.is_empty() => self.switch_tab(Tab::RawEvent),
+                KeyCode::Char('f') if !self.grouping.is_empty() => self.switch_tab(Tab::Grouping),
+                KeyCode::Esc => self.tab = Tab::Details,
+                KeyCode::Char('/') if self.tab != Tab::Details => self.prompt_search()?,
+                KeyCode::Char('n') if self.tab != Tab::Details => {
+                    self.find_next(self.line_offset + 1)
+                }
                 _ => {}
             }
         }
@@ -65,7 +179,25 @@ impl IssueViewer {
         Ok(())
     }
 
+    /// Switches to a text tab, or back to the details when it is already shown.
+    fn switch_tab(&mut self, tab: Tab) {
+        self.tab = if self.tab == tab { Tab::Details } else { tab };
+        self.line_offset = 0;
+    }
+
+    /// Lines of the text tab shown.
+    fn lines(&self) -> &[String] {
+        match self.tab {
+            Tab::Details => &[],
+            Tab::Grouping => &self.grouping,
+            Tab::RawEvent => &self.raw_event,
+        }
+    }
+
     fn render(&self) -> Result<()> {
+        if self.tab != Tab::Details {
+            return self.render_lines();
+        }
         self.tui.clear()?;
 
         // Draw main box
@@ -82,39 +214,172 @@ impl IssueViewer {
             self.tui.write_at(i, 2, "─")?;
         }
 
-        // Draw issue details
-        self.tui.write_at(2, 3, &format!("ID: {}", self.issue.id))?;
-        self.tui
-            .write_at(2, 4, &format!("Title: {}", self.issue.title))?;
-        self.tui
-            .write_at(2, 5, &format!("Status: {}", self.issue.status))?;
-        self.tui
-            .write_at(2, 6, &format!("Level: {}", self.issue.level))?;
-        self.tui
-            .write_at(2, 7, &format!("Culprit: {}", self.issue.culprit))?;
-        self.tui
-            .write_at(2, 8, &format!("Last Seen: {}", self.issue.last_seen))?;
-        self.tui
-            .write_at(2, 9, &format!("Events: {}", self.issue.events))?;
-        self.tui
-            .write_at(2, 10, &format!("Users Affected: {}", self.issue.users))?;
+        // Draw issue details from the scroll position on, above the footer
+        let rows = 3..self.tui.height() - 1;
+        for (row, (indent, line)) in rows.zip(
+            self.details_lines()
+                .into_iter()
+                .skip(self.scroll_offset as usize),
+        ) {
+            let width = (self.tui.width() as usize).saturating_sub(indent as usize + 2);
+            self.tui.write_at(indent, row, &truncate(&line, width))?;
+        }
 
         // Draw footer
-        self.tui
-            .write_at(2, self.tui.height() - 1, "j/k: scroll down/up")?;
+        let mut footer = NAV_HINT.to_string();
+        if self.source.is_some() {
+            footer.push_str("  o: open in editor");
+        }
+        if !self.grouping.is_empty() {
+            footer.push_str("  f: grouping");
+        }
+        if !self.raw_event.is_empty() {
+            footer.push_str("  J: raw event JSON");
+        }
+        self.tui.write_at(2, self.tui.height() - 1, &footer)?;
 
         Ok(())
     }
 
-    fn scroll_up(&mut self) {
-        if self.scroll_offset > 0 {
-            self.scroll_offset -= 1;
+    /// Lines of the details tab with their indent: the issue's fields, then its
+    /// suggestions, user and request sections, each followed by a gap.
+    fn details_lines(&self) -> Vec<(u16, String)> {
+        let issue = &self.issue;
+        let mut lines: Vec<(u16, String)> = [
+            format!("ID: {}", issue.id),
+            format!("Title: {}", issue.title),
+            format!("Status: {}", issue.status),
+            format!("Level: {}", issue.level),
+            format!("Culprit: {}", issue.culprit),
+            format!("Last Seen: {}", issue.last_seen),
+            format!("Events: {}", issue.events),
+            format!("Users Affected: {}", issue.users),
+        ]
+        .into_iter()
+        .map(|line| (2, line))
+        .collect();
+        if let Some(source) = &self.source {
+            lines.push((
+                2,
+                format!("Source: {}:{}", source.path.display(), source.line),
+            ));
         }
+        if !self.contexts.is_empty() {
+            let chips: Vec<String> = self
+                .contexts
+                .iter()
+                .map(|context| format!("[{}]", context))
+                .collect();
+            lines.push((2, chips.join(" ")));
+        }
+        lines.push((2, String::new()));
+
+        let suggestions: Vec<String> = self
+            .suggestions
+            .iter()
+            .map(|suggestion| format!("{} - {}", suggestion.label, suggestion.reason))
+            .collect();
+        for (title, section) in [
+            ("Suggested assignees:", &suggestions),
+            ("User:", &self.user),
+            ("Request:", &self.request),
+        ] {
+            if section.is_empty() {
+                continue;
+            }
+            lines.push((2, title.to_string()));
+            lines.extend(section.iter().map(|line| (4, line.clone())));
+            lines.push((2, String::new()));
+        }
+        lines
+    }
+
+    /// Hands the terminal to the editor and takes it back once the editor exits.
+    fn open_source(&self) -> Result<()> {
+        let Some(source) = &self.source else {
+            return Ok(());
+        };
+        self.tui.stop()?;
+        let result = open_in_editor(source);
+        self.tui.start()?;
+        result
     }
 
-    fn scroll_down(&mut self) {
-        // TODO: Add max scroll limit based on content
-        self.scroll_offset += 1;
+    /// Shows the lines of a text tab from the scroll position on.
+    fn render_lines(&self) -> Result<()> {
+        self.tui.clear()?;
+        let width = self.tui.width();
+        let height = self.tui.height();
+        self.tui.draw_box(0, 0, width, height)?;
+        let (title, key) = match self.tab {
+            Tab::Grouping => (format!("Grouping of {}", self.issue.id), 'f'),
+            _ => (format!("Latest event of {} (raw JSON)", self.issue.id), 'J'),
+        };
+        self.tui.write_at(2, 1, &title)?;
+        self.tui.write_at(width - 20, 1, "Press 'q' to quit")?;
+
+        let fit = |text: &str| truncate(text, (width as usize).saturating_sub(4));
+        for (row, line) in (2..height - 2).zip(self.lines().iter().skip(self.line_offset)) {
+            self.tui.write_at(2, row, &fit(line))?;
+        }
+
+        let footer = match &self.message {
+            Some(message) => message.clone(),
+            None => format!("{}  /: search  n: next match  {}: details", NAV_HINT, key),
+        };
+        self.tui.write_at(2, height - 1, &fit(&footer))?;
+        Ok(())
+    }
+
+    /// Asks for text to find in the tab shown and scrolls to its first match.
+    fn prompt_search(&mut self) -> Result<()> {
+        let mut input = FilterInput::new(&self.searches, "");
+        let row = self.tui.height() - 1;
+        let text = loop {
+            self.tui
+                .write_at(0, row, &" ".repeat(self.tui.width() as usize))?;
+            self.tui
+                .write_at(2, row, &format!("Search: {}", input.text))?;
+            match input.handle(self.tui.read_key()?.code) {
+                InputState::Editing => {}
+                InputState::Submitted(text) => break text,
+                InputState::Cancelled => return Ok(()),
+            }
+        };
+        if text.is_empty() {
+            return Ok(());
+        }
+        self.searches.retain(|search| *search != text);
+        self.searches.push(text.clone());
+        self.search = Some(text);
+        self.find_next(self.line_offset);
+        Ok(())
+    }
+
+    /// Scrolls to the next line matching the search at or after `from`, wrapping around.
+    fn find_next(&mut self, from: usize) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        match find_line(self.lines(), search, from) {
+            Some(line) => self.line_offset = line,
+            None => self.message = Some(format!("No match for '{}'", search)),
+        }
+    }
+
+    /// Rows of details, source and suggestions there are to scroll through.
+    fn content_rows(&self) -> usize {
+        self.details_lines().len()
+    }
+
+    fn navigate(&mut self, nav: Nav) {
+        let page = self.tui.height().saturating_sub(2) as usize;
+        if self.tab != Tab::Details {
+            self.line_offset = nav.apply(self.line_offset, self.lines().len(), page);
+            return;
+        }
+        let offset = nav.apply(self.scroll_offset as usize, self.content_rows(), page);
+        self.scroll_offset = offset as u16;
     }
 
     #[cfg(test)]
@@ -123,6 +388,15 @@ impl IssueViewer {
     }
 }
 
+/// First line at or after `from` containing `needle`, ignoring case and wrapping around
+/// to the top.
+fn find_line(lines: &[String], needle: &str, from: usize) -> Option<usize> {
+    let needle = needle.to_lowercase();
+    (0..lines.len())
+        .map(|i| (from + i) % lines.len())
+        .find(|&i| lines[i].to_lowercase().contains(&needle))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,27 +415,45 @@ mod tests {
     }
 
     #[test]
-    fn test_scroll_up_down() {
+    fn test_scroll_up_down() -> Result<()> {
         let issue = create_test_issue();
         let tui = Tui::new_with_size(80, 24);
         let mut viewer = IssueViewer::new_with_tui(issue, tui);
 
         assert_eq!(viewer.scroll_offset(), 0);
 
-        viewer.scroll_down();
+        viewer.navigate(Nav::Down);
         assert_eq!(viewer.scroll_offset(), 1);
 
-        viewer.scroll_down();
+        viewer.navigate(Nav::Down);
         assert_eq!(viewer.scroll_offset(), 2);
 
-        viewer.scroll_up();
+        viewer.navigate(Nav::Up);
         assert_eq!(viewer.scroll_offset(), 1);
 
-        viewer.scroll_up();
+        viewer.navigate(Nav::Up);
         assert_eq!(viewer.scroll_offset(), 0);
 
-        viewer.scroll_up();
+        viewer.navigate(Nav::Up);
         assert_eq!(viewer.scroll_offset(), 0);
+
+        // Eight fields and the gap after them
+        viewer.navigate(Nav::Bottom);
+        assert_eq!(viewer.scroll_offset(), 8);
+
+        let request = EventRequest {
+            method: Some("GET".to_string()),
+            url: Some("/checkout".to_string()),
+            query: Vec::new(),
+            headers: vec![("Accept".to_string(), "*/*".to_string())],
+        };
+        let mut viewer = viewer.with_request(Some(request));
+        assert_eq!(viewer.details_lines()[9], (2, "Request:".to_string()));
+        assert_eq!(viewer.details_lines()[11], (4, "Accept: */*".to_string()));
+        viewer.navigate(Nav::Bottom);
+        assert_eq!(viewer.scroll_offset(), 12);
+        viewer.render()?;
+        Ok(())
     }
 
     #[test]
@@ -173,4 +465,152 @@ mod tests {
         viewer.render()?;
         Ok(())
     }
+
+    #[test]
+    fn test_find_line() {
+        let lines: Vec<String> = [
+            "{",
+            "  \"level\": \"error\",",
+            "  \"platform\": \"python\"",
+            "}",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        assert_eq!(find_line(&lines, "PLATFORM", 0), Some(2));
+        assert_eq!(find_line(&lines, "\"", 3), Some(1));
+        assert_eq!(find_line(&lines, "release", 0), None);
+    }
+
+    #[test]
+    fn test_raw_event_view() -> Result<()> {
+        let issue = create_test_issue();
+        let tui = Tui::new_with_size(80, 24);
+        let event = serde_json::json!({"eventID": "abc123", "tags": [["level", "error"]]});
+        let mut viewer = IssueViewer::new_with_tui(issue, tui).with_raw_event(Some(&event));
+        assert_eq!(viewer.raw_event.len(), 9);
+
+        viewer.switch_tab(Tab::RawEvent);
+        viewer.navigate(Nav::Bottom);
+        assert_eq!(viewer.line_offset, 8);
+        assert_eq!(viewer.scroll_offset, 0);
+
+        viewer.line_offset = 0;
+        viewer.search = Some("level".to_string());
+        viewer.find_next(0);
+        assert_eq!(viewer.line_offset, 4);
+        viewer.render()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_grouping_tab() -> Result<()> {
+        let issue = create_test_issue();
+        let tui = Tui::new_with_size(80, 24);
+        let mut viewer = IssueViewer::new_with_tui(issue, tui)
+            .with_grouping(vec!["Title: Test Issue".to_string(), "  a1b2".to_string()]);
+
+        viewer.switch_tab(Tab::Grouping);
+        assert_eq!(viewer.lines().len(), 2);
+        viewer.navigate(Nav::Bottom);
+        assert_eq!(viewer.line_offset, 1);
+        viewer.render()?;
+
+        // Switching to the tab shown goes back to the details
+        viewer.switch_tab(Tab::Grouping);
+        assert_eq!(viewer.tab, Tab::Details);
+        assert_eq!(viewer.line_offset, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_contexts() -> Result<()> {
+        let issue = create_test_issue();
+        let tui = Tui::new_with_size(40, 24);
+        let viewer = IssueViewer::new_with_tui(issue, tui).with_contexts(vec![
+            ("OS", "Android 14".to_string()),
+            ("Device", "Pixel 8".to_string()),
+        ]);
+        assert_eq!(viewer.contexts, vec!["OS: Android 14", "Device: Pixel 8"]);
+
+        viewer.render()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_users() -> Result<()> {
+        let issue = create_test_issue();
+        let tui = Tui::new_with_size(80, 24);
+        let user = EventUser {
+            id: Some("42".to_string()),
+            email: Some("jane@example.com".to_string()),
+            username: None,
+            ip_address: Some("10.0.0.1".to_string()),
+        };
+        let affected: Vec<AffectedUser> = serde_json::from_value(serde_json::json!([
+            {"value": "id:42", "count": 9, "email": "jane@example.com"},
+            {"value": "ip:10.0.0.2", "count": 2}
+        ]))?;
+        let viewer = IssueViewer::new_with_tui(issue, tui).with_users(Some(&user), &affected);
+        assert_eq!(
+            viewer.user,
+            vec![
+                "Latest event: id 42, jane@example.com, 10.0.0.1",
+                "2 distinct, most affected: jane@example.com (9), ip:10.0.0.2 (2)",
+            ]
+        );
+
+        viewer.render()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_request() -> Result<()> {
+        let issue = create_test_issue();
+        let tui = Tui::new_with_size(80, 24);
+        let viewer = IssueViewer::new_with_tui(issue, tui).with_request(Some(EventRequest {
+            method: Some("GET".to_string()),
+            url: Some("https://example.com/api/orders".to_string()),
+            query: Vec::new(),
+            headers: vec![("Authorization".to_string(), "Bearer secret".to_string())],
+        }));
+        assert_eq!(
+            viewer.request,
+            vec![
+                "GET https://example.com/api/orders",
+                "Authorization: [masked]"
+            ]
+        );
+
+        viewer.render()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_source() -> Result<()> {
+        let issue = create_test_issue();
+        let tui = Tui::new_with_size(80, 24);
+        let viewer = IssueViewer::new_with_tui(issue, tui).with_source(Some(SourceLocation {
+            path: "/src/app/views.py".into(),
+            line: 12,
+        }));
+
+        viewer.render()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_suggestions() -> Result<()> {
+        let issue = create_test_issue();
+        let tui = Tui::new_with_size(80, 24);
+        let viewer =
+            IssueViewer::new_with_tui(issue, tui).with_suggestions(vec![AssigneeSuggestion {
+                assignee: "jane@example.com".to_string(),
+                label: "jane@example.com".to_string(),
+                reason: "suspect commit 4f2e1a9".to_string(),
+            }]);
+
+        viewer.render()?;
+        Ok(())
+    }
 }
@@ -0,0 +1,113 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A move through a list or scrolled view, the same in every TUI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Nav {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Half a page, like Ctrl-u in vim
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+}
+
+impl Nav {
+    /// Moves a position among `len` items, where a page is `page` items. Left and Right
+    /// leave it as is, for views that use them otherwise.
+    pub fn apply(self, position: usize, len: usize, page: usize) -> usize {
+        let last = len.saturating_sub(1);
+        let half_page = (page / 2).max(1);
+        match self {
+            Nav::Up => position.saturating_sub(1),
+            Nav::Down => (position + 1).min(last),
+            Nav::PageUp => position.saturating_sub(half_page),
+            Nav::PageDown => (position + half_page).min(last),
+            Nav::Top => 0,
+            Nav::Bottom => last,
+            Nav::Left | Nav::Right => position,
+        }
+    }
+}
+
+/// Turns keys into moves: arrows or hjkl, Ctrl-u/Ctrl-d or PageUp/PageDown, and gg/G
+/// or Home/End. Keeps the first `g` of `gg` between keys.
+#[derive(Debug, Default)]
+pub struct NavKeys {
+    pending_g: bool,
+}
+
+impl NavKeys {
+    /// The move a key makes, or `None` for keys the view handles itself.
+    pub fn handle(&mut self, key: &KeyEvent) -> Option<Nav> {
+        let pending_g = std::mem::take(&mut self.pending_g);
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('u') if ctrl => Some(Nav::PageUp),
+            KeyCode::Char('d') if ctrl => Some(Nav::PageDown),
+            _ if ctrl => None,
+            KeyCode::Up | KeyCode::Char('k') => Some(Nav::Up),
+            KeyCode::Down | KeyCode::Char('j') => Some(Nav::Down),
+            KeyCode::Left | KeyCode::Char('h') => Some(Nav::Left),
+            KeyCode::Right | KeyCode::Char('l') => Some(Nav::Right),
+            KeyCode::PageUp => Some(Nav::PageUp),
+            KeyCode::PageDown => Some(Nav::PageDown),
+            KeyCode::Home => Some(Nav::Top),
+            KeyCode::End | KeyCode::Char('G') => Some(Nav::Bottom),
+            KeyCode::Char('g') if pending_g => Some(Nav::Top),
+            KeyCode::Char('g') => {
+                self.pending_g = true;
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Footer hint for the shared navigation keys.
+pub const NAV_HINT: &str = "j/k: move  gg/G: top/bottom  ^u/^d: page";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_nav_keys() {
+        let mut keys = NavKeys::default();
+        assert_eq!(keys.handle(&key(KeyCode::Char('j'))), Some(Nav::Down));
+        assert_eq!(keys.handle(&key(KeyCode::Up)), Some(Nav::Up));
+        assert_eq!(keys.handle(&key(KeyCode::Char('G'))), Some(Nav::Bottom));
+        assert_eq!(
+            keys.handle(&KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            Some(Nav::PageDown)
+        );
+        assert_eq!(keys.handle(&key(KeyCode::Char('q'))), None);
+
+        assert_eq!(keys.handle(&key(KeyCode::Char('g'))), None);
+        assert_eq!(keys.handle(&key(KeyCode::Char('g'))), Some(Nav::Top));
+        // Another key in between cancels the pending g
+        keys.handle(&key(KeyCode::Char('g')));
+        keys.handle(&key(KeyCode::Char('x')));
+        assert_eq!(keys.handle(&key(KeyCode::Char('g'))), None);
+    }
+
+    #[test]
+    fn test_nav_apply() {
+        assert_eq!(Nav::Down.apply(0, 10, 20), 1);
+        assert_eq!(Nav::Down.apply(9, 10, 20), 9);
+        assert_eq!(Nav::Up.apply(0, 10, 20), 0);
+        assert_eq!(Nav::PageDown.apply(2, 100, 20), 12);
+        assert_eq!(Nav::PageDown.apply(95, 100, 20), 99);
+        assert_eq!(Nav::PageUp.apply(5, 100, 20), 0);
+        assert_eq!(Nav::Bottom.apply(0, 10, 20), 9);
+        assert_eq!(Nav::Top.apply(7, 10, 20), 0);
+        assert_eq!(Nav::Bottom.apply(0, 0, 20), 0);
+        assert_eq!(Nav::Right.apply(3, 10, 20), 3);
+    }
+}
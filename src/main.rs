@@ -1,10 +1,38 @@
+mod alert;
+mod badge;
 mod config;
 mod commands;
 mod tui;
+mod issue_store;
 mod issue_viewer;
+mod keys;
+mod project_viewer;
 mod sentry;
 mod dashboard;
+mod debug_files;
+mod editor;
+mod error;
+mod event_log;
+mod export;
+mod fixtures;
+mod git;
+mod history;
+mod notify;
+mod ownership;
+mod progress;
+mod report;
+mod scopes;
+mod secrets;
+mod table;
+mod timing;
+mod trace_viewer;
+mod triage;
 
-fn main() -> anyhow::Result<()> {
-    commands::Cli::run()
+fn main() {
+    let result = commands::Cli::run();
+    timing::print_report();
+    if let Err(e) = result {
+        eprintln!("Error: {:?}", e);
+        std::process::exit(error::exit_code(&e).code());
+    }
 }
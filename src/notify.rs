@@ -0,0 +1,32 @@
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// Shows a desktop notification using the platform's notification tool.
+pub fn desktop_notification(title: &str, body: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    Command::new("osascript")
+        .arg("-e")
+        .arg(format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(title)
+        ))
+        .output()
+        .context("Failed to run osascript")?;
+    #[cfg(target_os = "linux")]
+    Command::new("notify-send")
+        .args(["--app-name", "sex-cli", title, body])
+        .output()
+        .context("Failed to run notify-send")?;
+    #[cfg(target_os = "windows")]
+    Command::new("msg")
+        .args(["*", &format!("{}: {}", title, body)])
+        .output()
+        .context("Failed to run msg")?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
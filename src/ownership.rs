@@ -0,0 +1,316 @@
+use crate::sentry::{Committer, Issue, OwnershipRule, SentryClient, Team};
+use anyhow::{Context, Result};
+
+/// Someone who could take an issue, with the reason they are suggested.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssigneeSuggestion {
+    /// Value for the API's `assignedTo`, e.g. an email or `team:<id>`
+    pub assignee: String,
+    /// How the assignee is shown, e.g. `#backend` or an email
+    pub label: String,
+    pub reason: String,
+}
+
+/// Matches text against a pattern where `*` matches any run of characters and `?`
+/// a single character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it is currently matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// The ownership rule that decides the owners of a culprit. Only path and module rules
+/// can match a culprit; when several match, the last one takes precedence as in Sentry.
+pub fn matching_owner_rule<'a>(
+    rules: &'a [OwnershipRule],
+    culprit: &str,
+) -> Option<&'a OwnershipRule> {
+    // Culprits read like "app/views.py in handler" or "handler(src/app.js)"
+    let candidates: Vec<&str> = std::iter::once(culprit)
+        .chain(culprit.split(|c: char| c.is_whitespace() || c == '(' || c == ')'))
+        .filter(|part| !part.is_empty())
+        .collect();
+    rules.iter().rev().find(|rule| {
+        matches!(rule.kind.as_str(), "path" | "module" | "codeowners")
+            && candidates
+                .iter()
+                .any(|candidate| glob_match(&rule.pattern, candidate))
+    })
+}
+
+/// Turns `me`, `#team` or an email into a value for the API's `assignedTo`. Teams must
+/// be among `teams`, the teams with access to the issue's project.
+pub fn resolve_assignee(client: &SentryClient, input: &str, teams: &[Team]) -> Result<String> {
+    if input == "me" {
+        return Ok(client
+            .get_current_user()
+            .context("Cannot resolve 'me'; use an email instead")?
+            .handle()
+            .to_string());
+    }
+    match input.strip_prefix('#') {
+        Some(slug) => teams
+            .iter()
+            .find(|team| team.slug == slug)
+            .map(|team| format!("team:{}", team.id))
+            .with_context(|| format!("Team '{}' has no access to the project", slug)),
+        None => Ok(input.to_string()),
+    }
+}
+
+/// Resolves what was typed into an assign picker: the number of a suggestion, or
+/// anything [`resolve_assignee`] accepts. Returns the `assignedTo` value and its label.
+pub fn pick_assignee(
+    client: &SentryClient,
+    input: &str,
+    teams: &[Team],
+    suggestions: &[AssigneeSuggestion],
+) -> Result<(String, String)> {
+    if let Ok(number) = input.parse::<usize>() {
+        let suggestion = number
+            .checked_sub(1)
+            .and_then(|index| suggestions.get(index))
+            .with_context(|| format!("No suggestion number {}", number))?;
+        return Ok((suggestion.assignee.clone(), suggestion.label.clone()));
+    }
+    Ok((resolve_assignee(client, input, teams)?, input.to_string()))
+}
+
+/// Suggests assignees from the ownership rule matching the culprit, then from the
+/// authors of suspect commits, without duplicates.
+pub fn suggest_assignees(
+    rules: &[OwnershipRule],
+    teams: &[Team],
+    culprit: &str,
+    committers: &[Committer],
+) -> Vec<AssigneeSuggestion> {
+    let mut suggestions: Vec<AssigneeSuggestion> = Vec::new();
+    if let Some(rule) = matching_owner_rule(rules, culprit) {
+        for owner in &rule.owners {
+            let assignee = match owner.strip_prefix('#') {
+                Some(slug) => match teams.iter().find(|team| team.slug == slug) {
+                    Some(team) => format!("team:{}", team.id),
+                    // Teams without access to the project cannot be assigned
+                    None => continue,
+                },
+                None => owner.clone(),
+            };
+            suggestions.push(AssigneeSuggestion {
+                assignee,
+                label: owner.clone(),
+                reason: format!("owns {}:{}", rule.kind, rule.pattern),
+            });
+        }
+    }
+
+    for committer in committers {
+        let Some(email) = committer.author.as_ref().and_then(|a| a.email.clone()) else {
+            continue;
+        };
+        let reason = match committer.commits.first() {
+            Some(commit) => format!("suspect commit {}", &commit.id[..commit.id.len().min(7)]),
+            None => "suspect commit".to_string(),
+        };
+        suggestions.push(AssigneeSuggestion {
+            assignee: email.clone(),
+            label: email,
+            reason,
+        });
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    suggestions.retain(|s| seen.insert(s.assignee.clone()));
+    suggestions
+}
+
+/// Fetches what is needed to suggest assignees for an issue. Projects without ownership
+/// rules or a repository integration simply yield fewer suggestions.
+pub fn fetch_suggestions(
+    client: &SentryClient,
+    org_slug: &str,
+    issue: &Issue,
+) -> Result<(Vec<Team>, Vec<AssigneeSuggestion>)> {
+    let Some(project) = &issue.project else {
+        return Ok((Vec::new(), Vec::new()));
+    };
+    let teams = client
+        .get_project(org_slug, &project.slug)?
+        .teams
+        .unwrap_or_default();
+    let rules = client
+        .get_project_ownership(org_slug, &project.slug)
+        .map(|ownership| ownership.rules())
+        .unwrap_or_default();
+    let committers = client
+        .get_latest_event(&issue.id)
+        .and_then(|event| client.list_event_committers(org_slug, &project.slug, &event.event_id))
+        .unwrap_or_default();
+    let suggestions = suggest_assignees(&rules, &teams, &issue.culprit, &committers);
+    Ok((teams, suggestions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentry::{Commit, CommitAuthor};
+
+    fn rule(kind: &str, pattern: &str, owners: &[&str]) -> OwnershipRule {
+        OwnershipRule {
+            kind: kind.to_string(),
+            pattern: pattern.to_string(),
+            owners: owners.iter().map(|o| o.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("api-*", "api-gateway"));
+        assert!(glob_match("api-*", "api-"));
+        assert!(glob_match("*-worker", "billing-worker"));
+        assert!(glob_match("api-?", "api-1"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(glob_match("frontend", "frontend"));
+        assert!(!glob_match("api-*", "web-api"));
+        assert!(!glob_match("api-?", "api-12"));
+        assert!(!glob_match("frontend", "frontend-v2"));
+    }
+
+    #[test]
+    fn test_matching_owner_rule() {
+        let rules = vec![
+            rule("path", "src/*", &["#backend"]),
+            rule("path", "src/billing/*", &["#billing"]),
+            rule("module", "app.auth.*", &["#identity"]),
+            rule("tags.browser", "*", &["#web"]),
+        ];
+
+        let owners =
+            |culprit: &str| matching_owner_rule(&rules, culprit).map(|rule| rule.owners[0].clone());
+        assert_eq!(
+            owners("src/billing/charge.rs in charge"),
+            Some("#billing".into())
+        );
+        assert_eq!(owners("handler(src/api.js)"), Some("#backend".into()));
+        assert_eq!(owners("app.auth.views in login"), Some("#identity".into()));
+        assert_eq!(owners("lib/vendor.js in init"), None);
+    }
+
+    #[test]
+    fn test_suggest_assignees() {
+        let rules = vec![rule(
+            "path",
+            "src/billing/*",
+            &["#billing", "#payments", "jane@example.com"],
+        )];
+        let teams = vec![Team {
+            id: "7".to_string(),
+            name: "Billing".to_string(),
+            slug: "billing".to_string(),
+        }];
+        let committers = vec![
+            Committer {
+                author: Some(CommitAuthor {
+                    name: Some("Max".to_string()),
+                    email: Some("max@example.com".to_string()),
+                }),
+                commits: vec![Commit {
+                    id: "4f2e1a9c0d3b".to_string(),
+                    message: None,
+                    date_created: None,
+                }],
+            },
+            // Already suggested as an owner
+            Committer {
+                author: Some(CommitAuthor {
+                    name: None,
+                    email: Some("jane@example.com".to_string()),
+                }),
+                commits: Vec::new(),
+            },
+        ];
+
+        let suggestions = suggest_assignees(
+            &rules,
+            &teams,
+            "src/billing/charge.py in charge",
+            &committers,
+        );
+        let summary: Vec<(&str, &str, &str)> = suggestions
+            .iter()
+            .map(|s| (s.assignee.as_str(), s.label.as_str(), s.reason.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("team:7", "#billing", "owns path:src/billing/*"),
+                (
+                    "jane@example.com",
+                    "jane@example.com",
+                    "owns path:src/billing/*"
+                ),
+                (
+                    "max@example.com",
+                    "max@example.com",
+                    "suspect commit 4f2e1a9"
+                ),
+            ]
+        );
+
+        assert!(suggest_assignees(&[], &teams, "lib/x.py", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_assignee() {
+        let client = SentryClient::new().unwrap();
+        let teams = vec![Team {
+            id: "7".to_string(),
+            name: "Billing".to_string(),
+            slug: "billing".to_string(),
+        }];
+        assert_eq!(
+            resolve_assignee(&client, "#billing", &teams).unwrap(),
+            "team:7"
+        );
+        assert!(resolve_assignee(&client, "#web", &teams).is_err());
+
+        let suggestions = vec![AssigneeSuggestion {
+            assignee: "team:7".to_string(),
+            label: "#billing".to_string(),
+            reason: "owns path:src/*".to_string(),
+        }];
+        assert_eq!(
+            pick_assignee(&client, "1", &teams, &suggestions).unwrap(),
+            ("team:7".to_string(), "#billing".to_string())
+        );
+        assert!(pick_assignee(&client, "2", &teams, &suggestions).is_err());
+        assert!(pick_assignee(&client, "0", &teams, &suggestions).is_err());
+        assert_eq!(
+            resolve_assignee(&client, "jane@example.com", &teams).unwrap(),
+            "jane@example.com"
+        );
+    }
+}
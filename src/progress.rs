@@ -0,0 +1,99 @@
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::Mutex;
+use std::time::Duration;
+
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The progress shown now, told about each page fetched by [`page`].
+static ACTIVE: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// A spinner or bar on stderr while data is fetched, cleared when dropped. Hidden when
+/// stdout is not a terminal, so piped and redirected output stays clean.
+pub struct Progress {
+    bar: ProgressBar,
+}
+
+impl Progress {
+    pub fn spinner(message: impl Into<String>) -> Self {
+        let bar = ProgressBar::new_spinner();
+        bar.set_style(
+            ProgressStyle::with_template("{spinner} {msg} {prefix}")
+                .expect("valid progress template"),
+        );
+        Self::start(bar, message.into())
+    }
+
+    /// A bar advancing one step per e.g. organization.
+    pub fn bar(len: usize, message: impl Into<String>) -> Self {
+        let bar = ProgressBar::new(len as u64);
+        bar.set_style(
+            ProgressStyle::with_template("{spinner} [{pos}/{len}] {msg} {prefix}")
+                .expect("valid progress template"),
+        );
+        Self::start(bar, message.into())
+    }
+
+    fn start(bar: ProgressBar, message: String) -> Self {
+        if !std::io::stdout().is_terminal() {
+            bar.set_draw_target(ProgressDrawTarget::hidden());
+        }
+        bar.set_message(message);
+        bar.enable_steady_tick(TICK_INTERVAL);
+        if let Ok(mut active) = ACTIVE.lock() {
+            *active = Some(bar.clone());
+        }
+        Self { bar }
+    }
+
+    /// Shows what is fetched now, forgetting the page count of the previous step.
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.bar.set_prefix("");
+        self.bar.set_message(message.into());
+    }
+
+    pub fn inc(&self) {
+        self.bar.inc(1);
+    }
+
+    /// Prints a line above the progress instead of through it.
+    pub fn println(&self, line: impl AsRef<str>) {
+        if self.bar.is_hidden() {
+            println!("{}", line.as_ref());
+        } else {
+            self.bar.println(line);
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.bar.finish_and_clear();
+        if let Ok(mut active) = ACTIVE.lock() {
+            *active = None;
+        }
+    }
+}
+
+/// Reports a fetched page of a paginated list to the progress shown, if any.
+pub fn page(page: usize, fetched: usize) {
+    if let Ok(active) = ACTIVE.lock() {
+        if let Some(bar) = active.as_ref() {
+            bar.set_prefix(page_label(page, fetched));
+        }
+    }
+}
+
+fn page_label(page: usize, fetched: usize) -> String {
+    format!("(page {}, {} fetched)", page, fetched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_label() {
+        assert_eq!(page_label(3, 250), "(page 3, 250 fetched)");
+    }
+}
@@ -0,0 +1,233 @@
+use crate::sentry::{Issue, Project, Release, TeamMember};
+use crate::table::{format_count, truncate};
+use crate::tui::{sparkline, Tui};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 16;
+const CHART_HEIGHT: u16 = 6;
+
+pub struct ProjectViewer {
+    tui: Tui,
+    project: Project,
+    issues: Vec<Issue>,
+    releases: Vec<Release>,
+    members: Vec<TeamMember>,
+}
+
+impl ProjectViewer {
+    pub fn new(
+        project: Project,
+        issues: Vec<Issue>,
+        releases: Vec<Release>,
+        members: Vec<TeamMember>,
+    ) -> Result<Self> {
+        Ok(Self {
+            tui: Tui::new()?,
+            project,
+            issues,
+            releases,
+            members,
+        })
+    }
+
+    #[cfg(test)]
+    pub fn new_with_tui(project: Project, issues: Vec<Issue>, tui: Tui) -> Self {
+        Self {
+            tui,
+            project,
+            issues,
+            releases: Vec::new(),
+            members: Vec::new(),
+        }
+    }
+
+    pub fn show(&mut self) -> Result<()> {
+        self.tui.start()?;
+
+        loop {
+            self.render()?;
+
+            if let KeyEvent {
+                code: KeyCode::Char('q') | KeyCode::Esc,
+                ..
+            } = self.tui.read_key()?
+            {
+                break;
+            }
+        }
+
+        self.tui.stop()?;
+        Ok(())
+    }
+
+    fn render(&self) -> Result<()> {
+        self.tui.clear()?;
+
+        let width = self.tui.width();
+        let height = self.tui.height();
+        if width < MIN_WIDTH || height < MIN_HEIGHT {
+            self.tui
+                .write_at(0, 0, "Terminal too small. Press 'q' to quit")?;
+            return Ok(());
+        }
+
+        // Header
+        let platform = self.project.platform.as_deref().unwrap_or("-");
+        let title = format!(
+            "Project: {} ({}) [{}]",
+            self.project.name, self.project.slug, platform
+        );
+        self.tui
+            .write_at(1, 0, &truncate(&title, width as usize - 22))?;
+        self.tui.write_at(width - 18, 0, "Press 'q' to quit")?;
+
+        // Event charts
+        self.tui.draw_box(0, 1, width, CHART_HEIGHT)?;
+        self.tui.write_at(2, 1, " Events ")?;
+        let chart_width = width as usize - 4;
+        let (last_24h, last_30d) = match &self.project.stats {
+            Some(stats) => (
+                stats.last_24h.iter().map(|(_, c)| *c).collect(),
+                stats.last_30d.iter().map(|(_, c)| *c).collect(),
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+        self.tui.write_at(
+            2,
+            2,
+            &format!("Last 24h: {} events", last_24h.iter().sum::<i64>()),
+        )?;
+        self.tui
+            .write_at(2, 3, &sparkline(&last_24h, chart_width))?;
+        self.tui.write_at(
+            2,
+            4,
+            &format!("Last 30d: {} events", last_30d.iter().sum::<i64>()),
+        )?;
+        self.tui
+            .write_at(2, 5, &sparkline(&last_30d, chart_width))?;
+
+        // Panels: top issues on the left, releases and members stacked on the right
+        let panels_y = 1 + CHART_HEIGHT;
+        let panels_height = height - panels_y - 1;
+        let left_width = width / 2;
+        let right_width = width - left_width;
+        let releases_height = panels_height / 2;
+        let members_height = panels_height - releases_height;
+
+        let issues: Vec<String> = self
+            .issues
+            .iter()
+            .map(|i| format!("{:>6} {}", format_count(i.count), i.title))
+            .collect();
+        self.draw_panel(
+            0,
+            panels_y,
+            left_width,
+            panels_height,
+            "Top Issues",
+            &issues,
+        )?;
+
+        let releases: Vec<String> = self
+            .releases
+            .iter()
+            .map(|r| format!("{} ({} new)", r.version, r.new_groups))
+            .collect();
+        self.draw_panel(
+            left_width,
+            panels_y,
+            right_width,
+            releases_height,
+            "Recent Releases",
+            &releases,
+        )?;
+
+        let members: Vec<String> = self
+            .members
+            .iter()
+            .map(|m| {
+                if m.name.is_empty() || m.name == m.email {
+                    m.email.clone()
+                } else {
+                    format!("{} <{}>", m.name, m.email)
+                }
+            })
+            .collect();
+        self.draw_panel(
+            left_width,
+            panels_y + releases_height,
+            right_width,
+            members_height,
+            "Team Members",
+            &members,
+        )?;
+
+        // Footer
+        self.tui.write_at(1, height - 1, "q: quit")?;
+
+        Ok(())
+    }
+
+    fn draw_panel(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        title: &str,
+        lines: &[String],
+    ) -> Result<()> {
+        self.tui.draw_box(x, y, width, height)?;
+        self.tui.write_at(x + 2, y, &format!(" {} ", title))?;
+
+        let inner_width = width.saturating_sub(4) as usize;
+        let inner_height = height.saturating_sub(2) as usize;
+        if lines.is_empty() {
+            return self.tui.write_at(x + 2, y + 1, "(none)");
+        }
+        for (i, line) in lines.iter().take(inner_height).enumerate() {
+            self.tui
+                .write_at(x + 2, y + 1 + i as u16, &truncate(line, inner_width))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_project() -> Project {
+        serde_json::from_value(serde_json::json!({
+            "slug": "test-project",
+            "name": "Test Project",
+            "platform": "python",
+            "stats": {
+                "24h": [[0, 1], [1, 5], [2, 3]],
+                "30d": [[0, 10], [1, 0], [2, 40]]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let tui = Tui::new_with_size(80, 24);
+        let viewer = ProjectViewer::new_with_tui(create_test_project(), Vec::new(), tui);
+
+        viewer.render()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_small_terminal() -> Result<()> {
+        let tui = Tui::new_with_size(20, 5);
+        let viewer = ProjectViewer::new_with_tui(create_test_project(), Vec::new(), tui);
+
+        viewer.render()?;
+        Ok(())
+    }
+}
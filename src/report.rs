@@ -0,0 +1,1583 @@
+use crate::sentry::{
+    crash_frame, Activity, Committer, EventDetails, Frame, Issue, ProjectTotals, TagSummary,
+    UsageGroup,
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+const TOP_ISSUES: usize = 10;
+const REPORT_TAG_VALUES: usize = 3;
+const DIGEST_LIST_LIMIT: usize = 10;
+const DIGEST_OFFENDERS_PER_PROJECT: usize = 3;
+/// Share of a quota, in percent, from which usage is flagged.
+const QUOTA_WARNING_PERCENT: f64 = 80.0;
+
+/// Per-project aggregate of an issue summary.
+#[derive(Debug, PartialEq)]
+pub struct ProjectBreakdown {
+    pub project: String,
+    pub issues: usize,
+    pub new_issues: usize,
+    pub events: u64,
+    pub users: u64,
+}
+
+/// Organization-wide health check over a period.
+#[derive(Debug)]
+pub struct IssueSummary {
+    pub unresolved: usize,
+    pub new_issues: usize,
+    pub top_by_events: Vec<Issue>,
+    pub top_by_users: Vec<Issue>,
+    pub projects: Vec<ProjectBreakdown>,
+}
+
+/// Growth of an issue's recent event rate compared to its own baseline.
+#[derive(Debug)]
+pub struct IssueTrend {
+    pub issue: Issue,
+    pub recent_rate: f64,
+    pub baseline_rate: f64,
+    pub growth: f64,
+    pub buckets: Vec<i64>,
+}
+
+/// Everything known about an issue for a shareable incident report.
+pub struct IssueReport {
+    pub issue: Issue,
+    pub tags: Vec<TagSummary>,
+    pub event: Option<EventDetails>,
+    pub committers: Vec<Committer>,
+}
+
+impl IssueReport {
+    pub fn to_markdown(&self) -> String {
+        let issue = &self.issue;
+        let mut out = String::new();
+
+        match &issue.short_id {
+            Some(short_id) => writeln!(out, "# {}: {}", short_id, issue.title),
+            None => writeln!(out, "# {}", issue.title),
+        }
+        .unwrap();
+        writeln!(
+            out,
+            "\n**Status:** {} | **Level:** {} | **Project:** {}",
+            issue.status,
+            issue.level,
+            project_slug(issue)
+        )
+        .unwrap();
+        if let Some(permalink) = &issue.permalink {
+            writeln!(out, "\n**Link:** {}", permalink).unwrap();
+        }
+
+        writeln!(out, "\n## Summary\n").unwrap();
+        writeln!(out, "- **ID:** {}", issue.id).unwrap();
+        if let Some(metadata) = &issue.metadata {
+            match (&metadata.kind, &metadata.value) {
+                (Some(kind), Some(value)) => writeln!(out, "- **Error:** `{}: {}`", kind, value),
+                (Some(kind), None) => writeln!(out, "- **Error:** `{}`", kind),
+                (None, Some(value)) => writeln!(out, "- **Message:** {}", value),
+                (None, None) => Ok(()),
+            }
+            .unwrap();
+            match (&metadata.filename, &metadata.function) {
+                (Some(file), Some(function)) => {
+                    writeln!(out, "- **Location:** `{}` in `{}`", file, function).unwrap()
+                }
+                (Some(file), None) => writeln!(out, "- **Location:** `{}`", file).unwrap(),
+                _ => {}
+            }
+        }
+        if !issue.culprit.is_empty() {
+            writeln!(out, "- **Culprit:** `{}`", issue.culprit).unwrap();
+        }
+        writeln!(out, "- **Events:** {}", issue.count).unwrap();
+        writeln!(out, "- **Users affected:** {}", issue.user_count).unwrap();
+
+        writeln!(out, "\n## Timeline\n").unwrap();
+        writeln!(
+            out,
+            "- **First seen:** {}",
+            issue.first_seen.as_deref().unwrap_or("unknown")
+        )
+        .unwrap();
+        writeln!(out, "- **Last seen:** {}", issue.last_seen).unwrap();
+
+        let tags: Vec<&TagSummary> = self
+            .tags
+            .iter()
+            .filter(|t| t.total_values > 0 && !t.top_values.is_empty())
+            .collect();
+        if !tags.is_empty() {
+            writeln!(out, "\n## Top tags\n").unwrap();
+            for tag in tags {
+                let values = tag
+                    .top_values
+                    .iter()
+                    .take(REPORT_TAG_VALUES)
+                    .map(|v| {
+                        let share = v.count as f64 * 100.0 / tag.total_values as f64;
+                        format!("{} ({:.0}%)", v.value, share)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let name = if tag.name.is_empty() {
+                    &tag.key
+                } else {
+                    &tag.name
+                };
+                writeln!(out, "- **{}:** {}", name, values).unwrap();
+            }
+        }
+
+        if let Some(event) = &self.event {
+            let exceptions = event.exceptions();
+            if !exceptions.is_empty() {
+                writeln!(out, "\n## Stack trace\n\n```").unwrap();
+                for exception in exceptions.iter().rev() {
+                    writeln!(
+                        out,
+                        "{}: {}",
+                        exception.kind.as_deref().unwrap_or("Error"),
+                        exception.value.as_deref().unwrap_or("")
+                    )
+                    .unwrap();
+                    for frame in exception
+                        .stacktrace
+                        .iter()
+                        .flat_map(|s| s.frames.iter().rev())
+                    {
+                        writeln!(out, "  {}", frame).unwrap();
+                    }
+                }
+                writeln!(out, "```").unwrap();
+            }
+        }
+
+        if !self.committers.is_empty() {
+            writeln!(out, "\n## Suspect commits\n").unwrap();
+            for committer in &self.committers {
+                let author = committer
+                    .author
+                    .as_ref()
+                    .and_then(|a| a.name.clone().or(a.email.clone()))
+                    .unwrap_or_else(|| "unknown author".to_string());
+                for commit in &committer.commits {
+                    let message = commit
+                        .message
+                        .as_deref()
+                        .and_then(|m| m.lines().next())
+                        .unwrap_or("");
+                    let id = &commit.id[..commit.id.len().min(8)];
+                    writeln!(out, "- `{}` {} ({})", id, message, author).unwrap();
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Periodic organization digest of issue activity.
+pub struct Digest {
+    pub org_name: String,
+    pub period: String,
+    pub new_issues: Vec<Issue>,
+    /// Resolved issues with events in the period. The search API cannot tell when an
+    /// issue was resolved, so these are not necessarily resolved in the period.
+    pub resolved: Vec<Issue>,
+    pub regressions: Vec<Issue>,
+    /// Busiest unresolved issues, grouped by project
+    pub offenders: Vec<(String, Vec<Issue>)>,
+}
+
+impl Digest {
+    pub fn new(
+        org_name: &str,
+        period: &str,
+        unresolved: Vec<Issue>,
+        mut new_issues: Vec<Issue>,
+        mut resolved: Vec<Issue>,
+        mut regressions: Vec<Issue>,
+    ) -> Self {
+        for issues in [&mut new_issues, &mut resolved, &mut regressions] {
+            issues.sort_by_key(|i| std::cmp::Reverse(i.count));
+        }
+
+        let mut offenders: Vec<(String, Vec<Issue>)> = Vec::new();
+        for issue in unresolved {
+            let slug = project_slug(&issue);
+            match offenders.iter_mut().find(|(project, _)| *project == slug) {
+                Some((_, issues)) => issues.push(issue),
+                None => offenders.push((slug, vec![issue])),
+            }
+        }
+        for (_, issues) in &mut offenders {
+            issues.sort_by_key(|i| std::cmp::Reverse(i.count));
+            issues.truncate(DIGEST_OFFENDERS_PER_PROJECT);
+        }
+        offenders.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Self {
+            org_name: org_name.to_string(),
+            period: period.to_string(),
+            new_issues,
+            resolved,
+            regressions,
+            offenders,
+        }
+    }
+
+    fn sections(&self) -> [(&'static str, &[Issue]); 3] {
+        [
+            ("New issues", &self.new_issues),
+            ("Regressions", &self.regressions),
+            ("Resolved, seen in period", &self.resolved),
+        ]
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "# Sentry digest: {} (last {})",
+            self.org_name, self.period
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "\n{} new, {} regressed, {} resolved and seen",
+            self.new_issues.len(),
+            self.regressions.len(),
+            self.resolved.len()
+        )
+        .unwrap();
+
+        for (title, issues) in self.sections() {
+            writeln!(out, "\n## {} ({})\n", title, issues.len()).unwrap();
+            if issues.is_empty() {
+                writeln!(out, "None").unwrap();
+            }
+            for issue in issues.iter().take(DIGEST_LIST_LIMIT) {
+                writeln!(out, "- {}", markdown_issue(issue)).unwrap();
+            }
+            if issues.len() > DIGEST_LIST_LIMIT {
+                writeln!(out, "- ...and {} more", issues.len() - DIGEST_LIST_LIMIT).unwrap();
+            }
+        }
+
+        writeln!(out, "\n## Top offenders by project").unwrap();
+        if self.offenders.is_empty() {
+            writeln!(out, "\nNone").unwrap();
+        }
+        for (project, issues) in &self.offenders {
+            writeln!(out, "\n### {}\n", project).unwrap();
+            for issue in issues {
+                writeln!(out, "- {}", markdown_issue(issue)).unwrap();
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "<!DOCTYPE html>\n<html>\n<body>").unwrap();
+        writeln!(
+            out,
+            "<h1>Sentry digest: {} (last {})</h1>",
+            escape_html(&self.org_name),
+            escape_html(&self.period)
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "<p>{} new, {} regressed, {} resolved and seen</p>",
+            self.new_issues.len(),
+            self.regressions.len(),
+            self.resolved.len()
+        )
+        .unwrap();
+
+        for (title, issues) in self.sections() {
+            writeln!(out, "<h2>{} ({})</h2>", title, issues.len()).unwrap();
+            if issues.is_empty() {
+                writeln!(out, "<p>None</p>").unwrap();
+                continue;
+            }
+            writeln!(out, "<ul>").unwrap();
+            for issue in issues.iter().take(DIGEST_LIST_LIMIT) {
+                writeln!(out, "<li>{}</li>", html_issue(issue)).unwrap();
+            }
+            if issues.len() > DIGEST_LIST_LIMIT {
+                writeln!(
+                    out,
+                    "<li>...and {} more</li>",
+                    issues.len() - DIGEST_LIST_LIMIT
+                )
+                .unwrap();
+            }
+            writeln!(out, "</ul>").unwrap();
+        }
+
+        writeln!(out, "<h2>Top offenders by project</h2>").unwrap();
+        if self.offenders.is_empty() {
+            writeln!(out, "<p>None</p>").unwrap();
+        }
+        for (project, issues) in &self.offenders {
+            writeln!(out, "<h3>{}</h3>\n<ul>", escape_html(project)).unwrap();
+            for issue in issues {
+                writeln!(out, "<li>{}</li>", html_issue(issue)).unwrap();
+            }
+            writeln!(out, "</ul>").unwrap();
+        }
+        writeln!(out, "</body>\n</html>").unwrap();
+        out
+    }
+}
+
+fn markdown_issue(issue: &Issue) -> String {
+    let label = issue.short_id.as_deref().unwrap_or(&issue.id);
+    let label = match &issue.permalink {
+        Some(url) => format!("[{}]({})", label, url),
+        None => label.to_string(),
+    };
+    format!(
+        "{} {} ({} events, {} users)",
+        label, issue.title, issue.count, issue.user_count
+    )
+}
+
+fn html_issue(issue: &Issue) -> String {
+    let label = escape_html(issue.short_id.as_deref().unwrap_or(&issue.id));
+    let label = match &issue.permalink {
+        Some(url) => format!("<a href=\"{}\">{}</a>", escape_html(url), label),
+        None => label,
+    };
+    format!(
+        "{} {} ({} events, {} users)",
+        label,
+        escape_html(&issue.title),
+        issue.count,
+        issue.user_count
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders an issue as a GitHub Actions workflow command, so it shows up as an inline
+/// annotation at `file:line` on pull request checks.
+pub fn gh_annotation(issue: &Issue, file: Option<&str>, line: Option<u32>) -> String {
+    let command = match issue.level.as_str() {
+        "fatal" | "error" => "error",
+        "warning" => "warning",
+        _ => "notice",
+    };
+
+    let mut properties = Vec::new();
+    if let Some(file) = file {
+        properties.push(format!("file={}", escape_gh_property(file)));
+        if let Some(line) = line {
+            properties.push(format!("line={}", line));
+        }
+    }
+    let title = issue.short_id.as_deref().unwrap_or(&issue.id);
+    properties.push(format!(
+        "title={}",
+        escape_gh_property(&format!("Sentry {}", title))
+    ));
+
+    let mut message = format!(
+        "{} ({} events, {} users)",
+        issue.title, issue.count, issue.user_count
+    );
+    if let Some(permalink) = &issue.permalink {
+        message.push_str(&format!(" {}", permalink));
+    }
+    format!(
+        "::{} {}::{}",
+        command,
+        properties.join(","),
+        escape_gh_data(&message)
+    )
+}
+
+/// Renders issues as a JUnit XML report, one suite per project and one failed test
+/// case per issue.
+pub fn junit_report(issues: &[Issue]) -> String {
+    let mut suites: Vec<(String, Vec<&Issue>)> = Vec::new();
+    for issue in issues {
+        let project = project_slug(issue);
+        match suites.iter_mut().find(|(name, _)| *name == project) {
+            Some((_, suite)) => suite.push(issue),
+            None => suites.push((project, vec![issue])),
+        }
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        out,
+        "<testsuites name=\"sentry\" tests=\"{0}\" failures=\"{0}\">",
+        issues.len()
+    )
+    .unwrap();
+    for (project, issues) in suites {
+        writeln!(
+            out,
+            "  <testsuite name=\"{0}\" tests=\"{1}\" failures=\"{1}\">",
+            escape_html(&project),
+            issues.len()
+        )
+        .unwrap();
+        for issue in issues {
+            let id = issue.short_id.as_deref().unwrap_or(&issue.id);
+            writeln!(
+                out,
+                "    <testcase classname=\"{}\" name=\"{}: {}\">",
+                escape_html(&project),
+                escape_html(id),
+                escape_html(&issue.title)
+            )
+            .unwrap();
+            let mut details = format!(
+                "{}\n{} events, {} users, last seen {}",
+                issue.culprit, issue.count, issue.user_count, issue.last_seen
+            );
+            if let Some(permalink) = &issue.permalink {
+                details.push_str(&format!("\n{}", permalink));
+            }
+            writeln!(
+                out,
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                escape_html(&issue.title),
+                escape_html(&issue.level),
+                escape_html(&details)
+            )
+            .unwrap();
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// Renders issues with the frames of their latest crash (innermost last) as a SARIF
+/// 2.1.0 log for code-scanning dashboards. Frame paths should be relative to the
+/// repository root.
+pub fn sarif_report(issues: &[(Issue, Vec<Frame>)]) -> String {
+    let rules: Vec<serde_json::Value> = issues
+        .iter()
+        .map(|(issue, _)| {
+            let mut rule = serde_json::json!({
+                "id": sarif_rule_id(issue),
+                "shortDescription": {"text": issue.title},
+            });
+            if let Some(permalink) = &issue.permalink {
+                rule["helpUri"] = serde_json::json!(permalink);
+            }
+            rule
+        })
+        .collect();
+
+    let results: Vec<serde_json::Value> = issues
+        .iter()
+        .map(|(issue, frames)| {
+            let level = match issue.level.as_str() {
+                "fatal" | "error" => "error",
+                "warning" => "warning",
+                _ => "note",
+            };
+            let mut result = serde_json::json!({
+                "ruleId": sarif_rule_id(issue),
+                "level": level,
+                "message": {"text": format!(
+                    "{} in {} ({} events, {} users)",
+                    issue.title, issue.culprit, issue.count, issue.user_count
+                )},
+                "partialFingerprints": {"sentryIssueId/v1": issue.id},
+            });
+            if let Some(location) = crash_frame(frames).and_then(sarif_location) {
+                result["locations"] = serde_json::json!([location]);
+            }
+            // SARIF stacks list the innermost frame first
+            let stack: Vec<serde_json::Value> = frames
+                .iter()
+                .rev()
+                .filter_map(|frame| {
+                    let mut stack_frame = serde_json::json!({"location": sarif_location(frame)?});
+                    if let Some(function) = &frame.function {
+                        stack_frame["location"]["message"] = serde_json::json!({"text": function});
+                    }
+                    Some(stack_frame)
+                })
+                .collect();
+            if !stack.is_empty() {
+                result["stacks"] = serde_json::json!([{"frames": stack}]);
+            }
+            result
+        })
+        .collect();
+
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {"driver": {
+                "name": "sex-cli",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rules,
+            }},
+            "results": results,
+        }],
+    });
+    serde_json::to_string_pretty(&log).unwrap()
+}
+
+fn sarif_rule_id(issue: &Issue) -> String {
+    issue.short_id.clone().unwrap_or_else(|| issue.id.clone())
+}
+
+fn sarif_location(frame: &Frame) -> Option<serde_json::Value> {
+    let path = frame.filename.as_deref().or(frame.abs_path.as_deref())?;
+    let mut location = serde_json::json!({"physicalLocation": {
+        "artifactLocation": {"uri": path.trim_start_matches('/'), "uriBaseId": "%SRCROOT%"},
+    }});
+    if let Some(line) = frame.line_no {
+        location["physicalLocation"]["region"] = serde_json::json!({"startLine": line});
+    }
+    Some(location)
+}
+
+fn escape_gh_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_gh_property(text: &str) -> String {
+    escape_gh_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Usage of a single data category such as `error` or `transaction`.
+#[derive(Debug, PartialEq)]
+pub struct CategoryUsage {
+    pub category: String,
+    pub accepted: u64,
+    /// Dropped because a quota or rate limit was hit
+    pub rate_limited: u64,
+    pub quota: Option<u64>,
+}
+
+impl CategoryUsage {
+    pub fn used_percent(&self) -> Option<f64> {
+        self.quota
+            .filter(|quota| *quota > 0)
+            .map(|quota| self.accepted as f64 / quota as f64 * 100.0)
+    }
+
+    pub fn warning(&self) -> Option<String> {
+        match self.used_percent() {
+            Some(percent) if percent >= 100.0 => {
+                return Some(format!("{} quota exhausted", self.category))
+            }
+            Some(percent) if percent >= QUOTA_WARNING_PERCENT => {
+                return Some(format!("{:.0}% of {} quota used", percent, self.category))
+            }
+            _ => {}
+        }
+        (self.rate_limited > 0).then(|| {
+            format!(
+                "{} {} events dropped by quotas or rate limits",
+                self.rate_limited, self.category
+            )
+        })
+    }
+}
+
+/// Totals usage per category and attaches the known quotas.
+pub fn summarize_usage(groups: &[UsageGroup], quotas: &HashMap<String, u64>) -> Vec<CategoryUsage> {
+    let mut usage: Vec<CategoryUsage> = Vec::new();
+    for group in groups {
+        let index = match usage.iter().position(|u| u.category == group.by.category) {
+            Some(index) => index,
+            None => {
+                usage.push(CategoryUsage {
+                    category: group.by.category.clone(),
+                    accepted: 0,
+                    rate_limited: 0,
+                    quota: quotas.get(&group.by.category).copied(),
+                });
+                usage.len() - 1
+            }
+        };
+        match group.by.outcome.as_str() {
+            "accepted" => usage[index].accepted += group.totals.quantity,
+            "rate_limited" => usage[index].rate_limited += group.totals.quantity,
+            _ => {}
+        }
+    }
+    usage.sort_by(|a, b| a.category.cmp(&b.category));
+    usage
+}
+
+/// A metric in the current and the previous period.
+#[derive(Debug, PartialEq)]
+pub struct MetricDelta {
+    pub label: &'static str,
+    pub current: u64,
+    pub previous: u64,
+}
+
+impl MetricDelta {
+    /// Change relative to the previous period, or `None` when there is nothing to compare to.
+    pub fn percent_change(&self) -> Option<f64> {
+        if self.previous == 0 {
+            return None;
+        }
+        Some((self.current as f64 - self.previous as f64) / self.previous as f64 * 100.0)
+    }
+}
+
+pub fn compare_totals(current: &ProjectTotals, previous: &ProjectTotals) -> Vec<MetricDelta> {
+    vec![
+        MetricDelta {
+            label: "Events",
+            current: current.events,
+            previous: previous.events,
+        },
+        MetricDelta {
+            label: "Issues",
+            current: current.issues,
+            previous: previous.issues,
+        },
+        MetricDelta {
+            label: "Users affected",
+            current: current.users,
+            previous: previous.users,
+        },
+    ]
+}
+
+/// Limits a freshly deployed release must stay within to pass a canary check.
+#[derive(Debug, Clone, Copy)]
+pub struct CanaryThresholds {
+    pub max_new_issues: usize,
+    /// Highest acceptable crash rate in percent of sessions
+    pub max_crash_rate: Option<f64>,
+}
+
+impl CanaryThresholds {
+    /// Reasons the release fails, empty when it passes. A crash-free rate of `None`
+    /// means there are no sessions to judge by.
+    pub fn failures(&self, new_issues: usize, crash_free_rate: Option<f64>) -> Vec<String> {
+        let mut failures = Vec::new();
+        if new_issues > self.max_new_issues {
+            failures.push(format!(
+                "{} new issue(s), more than the allowed {}",
+                new_issues, self.max_new_issues
+            ));
+        }
+        if let (Some(max), Some(crash_free)) = (self.max_crash_rate, crash_free_rate) {
+            let crash_rate = (1.0 - crash_free) * 100.0;
+            if crash_rate > max {
+                failures.push(format!(
+                    "crash rate {:.2}%, above the allowed {}%",
+                    crash_rate, max
+                ));
+            }
+        }
+        failures
+    }
+}
+
+/// Issues a release introduced and issues fixed since the release it is compared to.
+#[derive(Debug, Default)]
+pub struct ReleaseDiff {
+    /// First seen in the newer release and never seen in the older one
+    pub new: Vec<Issue>,
+    /// Seen in the older release, resolved since and not seen in the newer one
+    pub resolved: Vec<Issue>,
+}
+
+/// Compares the issues of two releases, given issues first seen in the newer release
+/// and issues with events in each release.
+pub fn release_diff(
+    first_seen_in_new: Vec<Issue>,
+    seen_in_old: Vec<Issue>,
+    seen_in_new: &[Issue],
+) -> ReleaseDiff {
+    let old_ids: HashSet<&str> = seen_in_old.iter().map(|i| i.id.as_str()).collect();
+    let new_ids: HashSet<&str> = seen_in_new.iter().map(|i| i.id.as_str()).collect();
+    let new = first_seen_in_new
+        .into_iter()
+        .filter(|issue| !old_ids.contains(issue.id.as_str()))
+        .collect();
+    let resolved = seen_in_old
+        .iter()
+        .filter(|issue| issue.status == "resolved" && !new_ids.contains(issue.id.as_str()))
+        .cloned()
+        .collect();
+    ReleaseDiff { new, resolved }
+}
+
+/// How the issues of a project moved since a snapshot.
+#[derive(Debug, Default)]
+pub struct SnapshotDiff {
+    /// Matching the query now but not in the snapshot
+    pub new: Vec<Issue>,
+    /// In the snapshot but no longer matching the query, e.g. since resolved
+    pub gone: Vec<Issue>,
+    /// In both, as `(before, now)`, whether anything changed or not
+    pub kept: Vec<(Issue, Issue)>,
+}
+
+/// Compares issues from a snapshot with the issues matching the same query now.
+pub fn snapshot_diff(before: Vec<Issue>, now: Vec<Issue>) -> SnapshotDiff {
+    let mut before: HashMap<String, Issue> =
+        before.into_iter().map(|i| (i.id.clone(), i)).collect();
+    let mut diff = SnapshotDiff::default();
+    for issue in now {
+        match before.remove(&issue.id) {
+            Some(previous) => diff.kept.push((previous, issue)),
+            None => diff.new.push(issue),
+        }
+    }
+    diff.gone = before.into_values().collect();
+    diff.gone.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+    diff
+}
+
+/// Where an issue resolved in a release stands.
+#[derive(Debug, PartialEq)]
+pub enum ResolutionState {
+    /// Resolved in the next release, which Sentry has not seen yet
+    Pending,
+    /// Resolved in the release and not back since
+    Holding,
+    /// Came back after the release shipped, on the given date
+    Regressed(String),
+}
+
+impl std::fmt::Display for ResolutionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolutionState::Pending => write!(f, "pending next release"),
+            ResolutionState::Holding => write!(f, "resolved"),
+            ResolutionState::Regressed(date) => write!(f, "regressed {}", date),
+        }
+    }
+}
+
+/// Checks the issues resolved in `version`, given resolved issues and regressed issues
+/// with their activity logs. Regressed issues count when they were resolved in the
+/// release and regressed after that. Issues resolved in the next release are pending
+/// only when `version` is the latest release, as the next one follows it.
+pub fn check_resolved(
+    version: &str,
+    is_latest: bool,
+    resolved: Vec<Issue>,
+    regressed: Vec<(Issue, Vec<Activity>)>,
+) -> Vec<(Issue, ResolutionState)> {
+    let mut states: Vec<(Issue, ResolutionState)> = resolved
+        .into_iter()
+        .filter_map(|issue| {
+            let details = &issue.status_details;
+            let state = if details.in_release.as_deref() == Some(version) {
+                ResolutionState::Holding
+            } else if details.in_next_release && is_latest {
+                ResolutionState::Pending
+            } else {
+                return None;
+            };
+            Some((issue, state))
+        })
+        .collect();
+
+    for (issue, activities) in regressed {
+        let Some(resolved_at) = activities
+            .iter()
+            .filter(|a| a.kind == "set_resolved_in_release" && a.data["version"] == version)
+            .map(|a| a.date_created.as_str())
+            .max()
+        else {
+            continue;
+        };
+        let regressed_at = activities
+            .iter()
+            .filter(|a| a.kind == "set_regression" && a.date_created.as_str() > resolved_at)
+            .map(|a| a.date_created.clone())
+            .min();
+        if let Some(date) = regressed_at {
+            states.push((issue, ResolutionState::Regressed(date)));
+        }
+    }
+    states
+}
+
+/// Issues that look like the same bug, e.g. a shared library failing in every service.
+#[derive(Debug)]
+pub struct DuplicateCluster {
+    pub issues: Vec<Issue>,
+}
+
+impl DuplicateCluster {
+    pub fn projects(&self) -> Vec<String> {
+        let mut projects: Vec<String> = self.issues.iter().map(project_slug).collect();
+        projects.sort();
+        projects.dedup();
+        projects
+    }
+
+    pub fn events(&self) -> u64 {
+        self.issues.iter().map(|i| u64::from(i.count)).sum()
+    }
+}
+
+/// Lowercases text and masks what differs between occurrences of one bug: quoted
+/// values, hex IDs and numbers.
+pub fn normalize_issue_text(text: &str) -> String {
+    let mut unquoted = String::new();
+    let mut quote = None;
+    for c in text.to_lowercase().chars() {
+        match quote {
+            Some(q) if c == q => {
+                quote = None;
+                unquoted.push_str("<str>");
+            }
+            Some(_) => {}
+            None if c == '\'' || c == '"' || c == '`' => quote = Some(c),
+            None => unquoted.push(c),
+        }
+    }
+
+    unquoted
+        .split_whitespace()
+        .map(|word| {
+            let core = word.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+            let is_id = core.len() >= 8
+                && core.chars().any(|c| c.is_ascii_digit())
+                && core.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+            if is_id {
+                return word.replace(core, "<id>");
+            }
+            let mut masked = String::new();
+            for c in word.chars() {
+                if !c.is_ascii_digit() {
+                    masked.push(c);
+                } else if !masked.ends_with('0') {
+                    masked.push('0');
+                }
+            }
+            masked
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Sorted character pairs of a text, for comparing texts regardless of word order.
+fn bigrams(text: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut pairs: Vec<(char, char)> = chars.windows(2).map(|w| (w[0], w[1])).collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Dice coefficient of two sorted bigram lists: 1.0 for equal texts, 0.0 for nothing shared.
+fn dice(a: &[(char, char)], b: &[(char, char)]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    2.0 * shared as f64 / (a.len() + b.len()) as f64
+}
+
+/// Clusters issues whose normalized title and culprit are at least `threshold` similar
+/// (0.0 to 1.0). Clusters spanning the most projects come first, then the busiest.
+pub fn find_duplicates(issues: Vec<Issue>, threshold: f64) -> Vec<DuplicateCluster> {
+    let keys: Vec<Vec<(char, char)>> = issues
+        .iter()
+        .map(|i| {
+            bigrams(&normalize_issue_text(&format!(
+                "{} | {}",
+                i.title, i.culprit
+            )))
+        })
+        .collect();
+
+    // Union-find over all similar pairs
+    let mut parent: Vec<usize> = (0..issues.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for a in 0..keys.len() {
+        for b in a + 1..keys.len() {
+            if dice(&keys[a], &keys[b]) >= threshold {
+                let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+                parent[rb] = ra;
+            }
+        }
+    }
+
+    let mut clusters: HashMap<usize, Vec<Issue>> = HashMap::new();
+    for (index, issue) in issues.into_iter().enumerate() {
+        let r = root(&mut parent, index);
+        clusters.entry(r).or_default().push(issue);
+    }
+    let mut clusters: Vec<DuplicateCluster> = clusters
+        .into_values()
+        .filter(|issues| issues.len() > 1)
+        .map(|mut issues| {
+            issues.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.id.cmp(&b.id)));
+            DuplicateCluster { issues }
+        })
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.projects()
+            .len()
+            .cmp(&a.projects().len())
+            .then_with(|| b.events().cmp(&a.events()))
+            .then_with(|| a.issues[0].id.cmp(&b.issues[0].id))
+    });
+    clusters
+}
+
+/// Renders event count buckets as CSV with a header row.
+pub fn stats_csv(buckets: &[(i64, i64)]) -> String {
+    let mut out = String::from("timestamp,count\n");
+    for (timestamp, count) in buckets {
+        writeln!(out, "{},{}", timestamp, count).unwrap();
+    }
+    out
+}
+
+pub fn project_slug(issue: &Issue) -> String {
+    issue
+        .project
+        .as_ref()
+        .map(|p| p.slug.clone())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Aggregates unresolved issues, using `new_issue_ids` to mark those first seen in the period.
+pub fn summarize(issues: Vec<Issue>, new_issue_ids: &HashSet<String>) -> IssueSummary {
+    let mut projects: Vec<ProjectBreakdown> = Vec::new();
+    for issue in &issues {
+        let slug = project_slug(issue);
+        let index = match projects.iter().position(|p| p.project == slug) {
+            Some(index) => index,
+            None => {
+                projects.push(ProjectBreakdown {
+                    project: slug,
+                    issues: 0,
+                    new_issues: 0,
+                    events: 0,
+                    users: 0,
+                });
+                projects.len() - 1
+            }
+        };
+
+        let breakdown = &mut projects[index];
+        breakdown.issues += 1;
+        if new_issue_ids.contains(&issue.id) {
+            breakdown.new_issues += 1;
+        }
+        breakdown.events += issue.count as u64;
+        breakdown.users += issue.user_count as u64;
+    }
+    projects.sort_by(|a, b| {
+        b.events
+            .cmp(&a.events)
+            .then_with(|| a.project.cmp(&b.project))
+    });
+
+    let mut top_by_events = issues.clone();
+    top_by_events.sort_by_key(|i| std::cmp::Reverse(i.count));
+    top_by_events.truncate(TOP_ISSUES);
+
+    let mut top_by_users = issues.clone();
+    top_by_users.sort_by_key(|i| std::cmp::Reverse(i.user_count));
+    top_by_users.truncate(TOP_ISSUES);
+
+    IssueSummary {
+        unresolved: issues.len(),
+        new_issues: issues
+            .iter()
+            .filter(|i| new_issue_ids.contains(&i.id))
+            .count(),
+        top_by_events,
+        top_by_users,
+        projects,
+    }
+}
+
+/// Splits each issue's `stats_period` histogram into a recent window (the last
+/// `recent_buckets` buckets) and a baseline (everything before it), and returns
+/// the issues with recent events sorted by growth factor, fastest growing first.
+pub fn issue_trends(
+    issues: Vec<Issue>,
+    stats_period: &str,
+    recent_buckets: usize,
+) -> Vec<IssueTrend> {
+    let mut trends: Vec<IssueTrend> = issues
+        .into_iter()
+        .filter_map(|issue| {
+            let buckets: Vec<i64> = issue
+                .stats
+                .as_ref()?
+                .get(stats_period)?
+                .iter()
+                .map(|(_, count)| *count)
+                .collect();
+            if recent_buckets == 0 || buckets.len() <= recent_buckets {
+                return None;
+            }
+
+            let (baseline, recent) = buckets.split_at(buckets.len() - recent_buckets);
+            let recent_rate = recent.iter().sum::<i64>() as f64 / recent.len() as f64;
+            if recent_rate == 0.0 {
+                return None;
+            }
+            let baseline_rate = baseline.iter().sum::<i64>() as f64 / baseline.len() as f64;
+
+            // Add-one smoothing keeps brand new, low-volume issues from reporting infinite growth
+            let growth = (recent_rate + 1.0) / (baseline_rate + 1.0);
+            Some(IssueTrend {
+                issue,
+                recent_rate,
+                baseline_rate,
+                growth,
+                buckets,
+            })
+        })
+        .collect();
+
+    trends.sort_by(|a, b| b.growth.total_cmp(&a.growth));
+    trends
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_issue(id: &str, project: &str, count: u32, users: u32) -> Issue {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": format!("Issue {}", id),
+            "status": "unresolved",
+            "level": "error",
+            "culprit": "test.js:42",
+            "lastSeen": "2024-01-01T00:00:00Z",
+            "count": count,
+            "userCount": users,
+            "project": {"id": "1", "slug": project, "name": project}
+        }))
+        .unwrap()
+    }
+
+    fn with_stats(mut issue: Issue, period: &str, counts: &[i64]) -> Issue {
+        let buckets = counts
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i as i64, *c))
+            .collect();
+        issue.stats = Some([(period.to_string(), buckets)].into_iter().collect());
+        issue
+    }
+
+    #[test]
+    fn test_issue_trends() {
+        let issues = vec![
+            with_stats(
+                create_test_issue("steady", "api", 0, 0),
+                "14d",
+                &[10, 10, 10, 10],
+            ),
+            with_stats(
+                create_test_issue("spike", "api", 0, 0),
+                "14d",
+                &[1, 1, 1, 40],
+            ),
+            with_stats(
+                create_test_issue("quiet", "api", 0, 0),
+                "14d",
+                &[5, 5, 5, 0],
+            ),
+            with_stats(
+                create_test_issue("other", "api", 0, 0),
+                "24h",
+                &[1, 1, 1, 40],
+            ),
+        ];
+
+        let trends = issue_trends(issues, "14d", 1);
+        let ids: Vec<&str> = trends.iter().map(|t| t.issue.id.as_str()).collect();
+        assert_eq!(ids, vec!["spike", "steady"]);
+        assert_eq!(trends[0].recent_rate, 40.0);
+        assert_eq!(trends[0].baseline_rate, 1.0);
+        assert_eq!(trends[0].growth, 20.5);
+        assert_eq!(trends[1].growth, 1.0);
+    }
+
+    #[test]
+    fn test_issue_report_markdown() {
+        let mut issue = create_test_issue("1", "api", 120, 8);
+        issue.short_id = Some("API-1".to_string());
+        issue.permalink = Some("https://sentry.io/organizations/acme/issues/1/".to_string());
+        issue.first_seen = Some("2023-12-31T00:00:00Z".to_string());
+        issue.metadata = serde_json::from_value(serde_json::json!({
+            "type": "ValueError",
+            "value": "bad input",
+            "filename": "app/views.py",
+            "function": "checkout"
+        }))
+        .unwrap();
+
+        let report = IssueReport {
+            issue,
+            tags: serde_json::from_value(serde_json::json!([
+                {"key": "browser", "name": "Browser", "totalValues": 100, "topValues": [
+                    {"value": "Chrome", "count": 75},
+                    {"value": "Firefox", "count": 25}
+                ]},
+                {"key": "empty", "name": "Empty", "totalValues": 0, "topValues": []}
+            ]))
+            .unwrap(),
+            event: Some(
+                serde_json::from_value(serde_json::json!({
+                    "eventID": "abc",
+                    "dateCreated": "2024-01-01T00:00:00Z",
+                    "entries": [{"type": "exception", "data": {"values": [{
+                        "type": "ValueError",
+                        "value": "bad input",
+                        "stacktrace": {"frames": [
+                            {"filename": "app/views.py", "function": "checkout", "lineNo": 12, "inApp": true}
+                        ]}
+                    }]}}]
+                }))
+                .unwrap(),
+            ),
+            committers: serde_json::from_value(serde_json::json!([{
+                "author": {"name": "Jane Doe", "email": "jane@example.com"},
+                "commits": [{"id": "4f2e1a9c0d3b", "message": "Handle empty carts\n\nDetails"}]
+            }]))
+            .unwrap(),
+        };
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("# API-1: Issue 1\n"));
+        assert!(markdown.contains("**Link:** https://sentry.io/organizations/acme/issues/1/"));
+        assert!(markdown.contains("- **ID:** 1\n- **Error:** `ValueError: bad input`\n"));
+        assert!(markdown.contains("- **Location:** `app/views.py` in `checkout`"));
+        assert!(markdown.contains("- **Events:** 120\n- **Users affected:** 8"));
+        assert!(markdown.contains("- **First seen:** 2023-12-31T00:00:00Z"));
+        assert!(markdown.contains("- **Browser:** Chrome (75%), Firefox (25%)"));
+        assert!(!markdown.contains("Empty"));
+        assert!(markdown
+            .contains("```\nValueError: bad input\n  at checkout (app/views.py:12) [in app]\n```"));
+        assert!(markdown.contains("- `4f2e1a9c` Handle empty carts (Jane Doe)"));
+    }
+
+    #[test]
+    fn test_summarize_usage() {
+        let groups: Vec<UsageGroup> = serde_json::from_value(serde_json::json!([
+            {"by": {"category": "transaction", "outcome": "accepted"}, "totals": {"sum(quantity)": 10}},
+            {"by": {"category": "error", "outcome": "accepted"}, "totals": {"sum(quantity)": 900}},
+            {"by": {"category": "error", "outcome": "rate_limited"}, "totals": {"sum(quantity)": 5}},
+            {"by": {"category": "error", "outcome": "filtered"}, "totals": {"sum(quantity)": 70}}
+        ]))
+        .unwrap();
+        let quotas = HashMap::from([("error".to_string(), 1000)]);
+
+        let usage = summarize_usage(&groups, &quotas);
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].category, "error");
+        assert_eq!(usage[0].accepted, 900);
+        assert_eq!(usage[0].rate_limited, 5);
+        assert_eq!(usage[0].used_percent(), Some(90.0));
+        assert_eq!(
+            usage[0].warning().as_deref(),
+            Some("90% of error quota used")
+        );
+        assert_eq!(usage[1].used_percent(), None);
+        assert_eq!(usage[1].warning(), None);
+    }
+
+    #[test]
+    fn test_gh_annotation() {
+        let mut issue = create_test_issue("1", "web", 5, 2);
+        issue.title = "TypeError: x is undefined".to_string();
+        issue.short_id = Some("WEB-1".to_string());
+        assert_eq!(
+            gh_annotation(&issue, Some("src/app.js"), Some(42)),
+            "::error file=src/app.js,line=42,title=Sentry WEB-1::TypeError: x is undefined (5 events, 2 users)"
+        );
+
+        issue.level = "warning".to_string();
+        issue.title = "50% done\nthen failed".to_string();
+        assert_eq!(
+            gh_annotation(&issue, None, Some(42)),
+            "::warning title=Sentry WEB-1::50%25 done%0Athen failed (5 events, 2 users)"
+        );
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let mut first = create_test_issue("1", "web", 5, 2);
+        first.title = "Error: <a> & \"b\"".to_string();
+        let issues = vec![
+            first,
+            create_test_issue("2", "api", 1, 1),
+            create_test_issue("3", "web", 1, 1),
+        ];
+        let xml = junit_report(&issues);
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+        assert!(xml.contains("<testsuites name=\"sentry\" tests=\"3\" failures=\"3\">"));
+        assert!(xml.contains("<testsuite name=\"web\" tests=\"2\" failures=\"2\">"));
+        assert!(xml.contains("<testsuite name=\"api\" tests=\"1\" failures=\"1\">"));
+        assert!(xml.contains(
+            "<testcase classname=\"web\" name=\"1: Error: &lt;a&gt; &amp; &quot;b&quot;\">"
+        ));
+        assert!(xml.contains("type=\"error\">test.js:42\n5 events, 2 users"));
+
+        let empty = junit_report(&[]);
+        assert!(empty.contains("tests=\"0\" failures=\"0\""));
+        assert!(!empty.contains("<testsuite "));
+    }
+
+    #[test]
+    fn test_sarif_report() {
+        let mut issue = create_test_issue("1", "web", 5, 2);
+        issue.short_id = Some("WEB-1".to_string());
+        let frames: Vec<Frame> = serde_json::from_value(serde_json::json!([
+            {"filename": "src/main.js", "function": "main", "lineNo": 3, "inApp": true},
+            {"filename": "src/app.js", "function": "render", "lineNo": 42, "inApp": true},
+            {"filename": "node_modules/lib.js", "function": "call", "lineNo": 7, "inApp": false}
+        ]))
+        .unwrap();
+        let sarif: serde_json::Value = serde_json::from_str(&sarif_report(&[
+            (issue, frames),
+            (create_test_issue("2", "api", 1, 1), Vec::new()),
+        ]))
+        .unwrap();
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "WEB-1");
+
+        let result = &run["results"][0];
+        assert_eq!(result["level"], "error");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/app.js");
+        assert_eq!(location["region"]["startLine"], 42);
+        let stack = &result["stacks"][0]["frames"];
+        assert_eq!(stack.as_array().unwrap().len(), 3);
+        assert_eq!(stack[0]["location"]["message"]["text"], "call");
+
+        // Issues without frames have no location
+        assert!(run["results"][1].get("locations").is_none());
+    }
+
+    #[test]
+    fn test_canary_thresholds() {
+        let thresholds = CanaryThresholds {
+            max_new_issues: 0,
+            max_crash_rate: Some(1.0),
+        };
+        assert!(thresholds.failures(0, Some(0.995)).is_empty());
+        assert!(thresholds.failures(0, None).is_empty());
+
+        let failures = thresholds.failures(2, Some(0.97));
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0], "2 new issue(s), more than the allowed 0");
+        assert_eq!(failures[1], "crash rate 3.00%, above the allowed 1%");
+    }
+
+    #[test]
+    fn test_release_diff() {
+        let mut fixed = create_test_issue("fixed", "api", 5, 1);
+        fixed.status = "resolved".to_string();
+        let mut still_failing = create_test_issue("still", "api", 5, 1);
+        still_failing.status = "resolved".to_string();
+        let old_bug = create_test_issue("old", "api", 5, 1);
+
+        let diff = release_diff(
+            vec![
+                create_test_issue("new", "api", 1, 1),
+                // Backported into the old release as well, so not new
+                create_test_issue("old", "api", 5, 1),
+            ],
+            vec![fixed, still_failing.clone(), old_bug],
+            &[still_failing],
+        );
+        let ids = |issues: &[Issue]| issues.iter().map(|i| i.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&diff.new), vec!["new"]);
+        assert_eq!(ids(&diff.resolved), vec!["fixed"]);
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let before = vec![
+            create_test_issue("fixed", "api", 5, 1),
+            create_test_issue("growing", "api", 5, 1),
+            create_test_issue("quiet", "api", 2, 1),
+        ];
+        let now = vec![
+            create_test_issue("growing", "api", 50, 4),
+            create_test_issue("quiet", "api", 2, 1),
+            create_test_issue("new", "api", 1, 1),
+        ];
+        let diff = snapshot_diff(before, now);
+        let ids = |issues: &[Issue]| issues.iter().map(|i| i.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&diff.new), vec!["new"]);
+        assert_eq!(ids(&diff.gone), vec!["fixed"]);
+        let kept: Vec<(&str, u32, u32)> = diff
+            .kept
+            .iter()
+            .map(|(before, now)| (before.id.as_str(), before.count, now.count))
+            .collect();
+        assert_eq!(kept, vec![("growing", 5, 50), ("quiet", 2, 2)]);
+    }
+
+    #[test]
+    fn test_check_resolved() {
+        let mut holding = create_test_issue("holding", "api", 5, 1);
+        holding.status_details.in_release = Some("app@1.2.0".to_string());
+        let mut pending = create_test_issue("pending", "api", 5, 1);
+        pending.status_details.in_next_release = true;
+        let mut other_release = create_test_issue("other", "api", 5, 1);
+        other_release.status_details.in_release = Some("app@1.1.0".to_string());
+
+        let activity = |kind: &str, version: &str, date: &str| -> Activity {
+            serde_json::from_value(serde_json::json!({
+                "type": kind,
+                "data": {"version": version},
+                "dateCreated": date
+            }))
+            .unwrap()
+        };
+        let regressed = vec![
+            (
+                create_test_issue("back", "api", 9, 2),
+                vec![
+                    activity("set_regression", "app@1.3.0", "2024-01-05T00:00:00Z"),
+                    activity(
+                        "set_resolved_in_release",
+                        "app@1.2.0",
+                        "2024-01-02T00:00:00Z",
+                    ),
+                ],
+            ),
+            // Regressed after a resolution in another release
+            (
+                create_test_issue("elsewhere", "api", 9, 2),
+                vec![
+                    activity("set_regression", "app@1.3.0", "2024-01-05T00:00:00Z"),
+                    activity(
+                        "set_resolved_in_release",
+                        "app@1.1.0",
+                        "2024-01-02T00:00:00Z",
+                    ),
+                ],
+            ),
+        ];
+
+        // An older release has no next release to wait for
+        let older = check_resolved("app@1.2.0", false, vec![pending.clone()], Vec::new());
+        assert!(older.is_empty());
+
+        let states: Vec<(String, String)> = check_resolved(
+            "app@1.2.0",
+            true,
+            vec![holding, pending, other_release],
+            regressed,
+        )
+        .into_iter()
+        .map(|(issue, state)| (issue.id, state.to_string()))
+        .collect();
+        let expected = [
+            ("holding", "resolved"),
+            ("pending", "pending next release"),
+            ("back", "regressed 2024-01-05T00:00:00Z"),
+        ];
+        assert_eq!(
+            states,
+            expected.map(|(id, state)| (id.to_string(), state.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_compare_totals() {
+        let current = ProjectTotals {
+            events: 150,
+            issues: 3,
+            users: 0,
+        };
+        let previous = ProjectTotals {
+            events: 100,
+            issues: 4,
+            users: 0,
+        };
+        let deltas = compare_totals(&current, &previous);
+        assert_eq!(deltas[0].percent_change(), Some(50.0));
+        assert_eq!(deltas[1].percent_change(), Some(-25.0));
+        assert_eq!(deltas[2].percent_change(), None);
+    }
+
+    #[test]
+    fn test_stats_csv() {
+        assert_eq!(
+            stats_csv(&[(1700006400, 12), (1700092800, 0)]),
+            "timestamp,count\n1700006400,12\n1700092800,0\n"
+        );
+        assert_eq!(stats_csv(&[]), "timestamp,count\n");
+    }
+
+    #[test]
+    fn test_digest() {
+        let unresolved = vec![
+            create_test_issue("1", "web", 5, 1),
+            create_test_issue("2", "api", 50, 1),
+            create_test_issue("3", "api", 500, 1),
+            create_test_issue("4", "api", 1, 1),
+            create_test_issue("5", "api", 10, 1),
+        ];
+        let mut new_issue = create_test_issue("6", "web", 3, 1);
+        new_issue.title = "<script> in title".to_string();
+
+        let digest = Digest::new(
+            "Acme",
+            "7d",
+            unresolved,
+            vec![new_issue],
+            vec![create_test_issue("7", "api", 9, 1)],
+            Vec::new(),
+        );
+        let offenders: Vec<(&str, Vec<&str>)> = digest
+            .offenders
+            .iter()
+            .map(|(p, issues)| (p.as_str(), issues.iter().map(|i| i.id.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            offenders,
+            vec![("api", vec!["3", "2", "5"]), ("web", vec!["1"])]
+        );
+
+        let markdown = digest.to_markdown();
+        assert!(markdown.contains("1 new, 0 regressed, 1 resolved and seen"));
+        assert!(markdown.contains("## Resolved, seen in period (1)"));
+        assert!(markdown.contains("## Regressions (0)\n\nNone"));
+        assert!(markdown.contains("### api\n\n- 3 Issue 3 (500 events, 1 users)"));
+
+        let html = digest.to_html();
+        assert!(html.contains("&lt;script&gt; in title"));
+        assert!(html.contains("<h3>web</h3>"));
+    }
+
+    #[test]
+    fn test_summarize() {
+        let issues = vec![
+            create_test_issue("1", "api", 100, 1),
+            create_test_issue("2", "web", 5, 50),
+            create_test_issue("3", "api", 20, 2),
+        ];
+        let new_ids: HashSet<String> = ["3".to_string()].into_iter().collect();
+
+        let summary = summarize(issues, &new_ids);
+        assert_eq!(summary.unresolved, 3);
+        assert_eq!(summary.new_issues, 1);
+        assert_eq!(summary.top_by_events[0].id, "1");
+        assert_eq!(summary.top_by_users[0].id, "2");
+        assert_eq!(
+            summary.projects[0],
+            ProjectBreakdown {
+                project: "api".to_string(),
+                issues: 2,
+                new_issues: 1,
+                events: 120,
+                users: 3,
+            }
+        );
+        assert_eq!(summary.projects[1].project, "web");
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        assert_eq!(
+            normalize_issue_text("Timeout after 3000ms for 'orders' (req 8f3a9c21d0e4)"),
+            "timeout after 0ms for <str> (req <id>)"
+        );
+
+        let issue = |id: &str, project: &str, title: &str, culprit: &str| {
+            let mut issue = create_test_issue(id, project, 10, 1);
+            issue.title = title.to_string();
+            issue.culprit = culprit.to_string();
+            issue
+        };
+        let issues = vec![
+            issue(
+                "1",
+                "api",
+                "ConnectionError: pool 'main' exhausted after 30s",
+                "db.pool in checkout",
+            ),
+            issue(
+                "2",
+                "worker",
+                "ConnectionError: pool 'jobs' exhausted after 5s",
+                "db.pool in checkout",
+            ),
+            issue(
+                "3",
+                "web",
+                "ConnectionError: pool 'web' exhausted after 12s",
+                "db.pool in checkout",
+            ),
+            issue("4", "api", "KeyError: 'user_id'", "api.views in show"),
+            issue(
+                "5",
+                "api",
+                "TypeError: undefined is not a function",
+                "app.js in render",
+            ),
+            issue(
+                "6",
+                "api",
+                "TypeError: undefined is not a function",
+                "app.js in render",
+            ),
+        ];
+
+        let clusters = find_duplicates(issues, 0.9);
+        let ids: Vec<Vec<&str>> = clusters
+            .iter()
+            .map(|c| c.issues.iter().map(|i| i.id.as_str()).collect())
+            .collect();
+        assert_eq!(ids, vec![vec!["1", "2", "3"], vec!["5", "6"]]);
+        assert_eq!(clusters[0].projects(), vec!["api", "web", "worker"]);
+        assert_eq!(clusters[1].events(), 20);
+    }
+}
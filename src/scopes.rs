@@ -0,0 +1,105 @@
+/// Scopes every read-only command relies on.
+pub const REQUIRED_SCOPES: &[&str] = &["org:read", "project:read", "team:read", "event:read"];
+
+/// Scopes that unlock optional commands, with a description of what needs them.
+pub const FEATURE_SCOPES: &[(&str, &str)] = &[
+    ("event:write", "bookmarking and updating issues"),
+    (
+        "project:releases",
+        "release set-commits and debug-files upload",
+    ),
+];
+
+/// Scopes that grant more than any command uses.
+pub const BROAD_SCOPES: &[&str] = &[
+    "org:write",
+    "org:admin",
+    "org:integrations",
+    "project:write",
+    "project:admin",
+    "team:write",
+    "team:admin",
+    "member:write",
+    "member:admin",
+    "event:admin",
+];
+
+/// Comparison of a token's scopes against what the CLI uses.
+#[derive(Debug, PartialEq)]
+pub struct ScopeCheck {
+    pub missing: Vec<&'static str>,
+    /// Optional features the token cannot use, as `(scope, feature)`
+    pub unavailable: Vec<(&'static str, &'static str)>,
+    pub excessive: Vec<String>,
+}
+
+impl ScopeCheck {
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if !self.missing.is_empty() {
+            warnings.push(format!(
+                "Token lacks scopes most commands need: {}",
+                self.missing.join(", ")
+            ));
+        }
+        for (scope, feature) in &self.unavailable {
+            warnings.push(format!("Without {}, {} will fail", scope, feature));
+        }
+        if !self.excessive.is_empty() {
+            warnings.push(format!(
+                "Token has broader scopes than sex-cli needs: {}. Consider a token with fewer scopes.",
+                self.excessive.join(", ")
+            ));
+        }
+        warnings
+    }
+}
+
+pub fn check_scopes(scopes: &[String]) -> ScopeCheck {
+    let has = |scope: &str| scopes.iter().any(|s| s == scope);
+    ScopeCheck {
+        missing: REQUIRED_SCOPES
+            .iter()
+            .copied()
+            .filter(|scope| !has(scope))
+            .collect(),
+        unavailable: FEATURE_SCOPES
+            .iter()
+            .copied()
+            .filter(|(scope, _)| !has(scope))
+            .collect(),
+        excessive: scopes
+            .iter()
+            .filter(|scope| BROAD_SCOPES.contains(&scope.as_str()))
+            .cloned()
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_check_scopes() {
+        let check = check_scopes(&scopes(&[
+            "org:read",
+            "project:read",
+            "team:read",
+            "event:read",
+            "event:write",
+            "project:releases",
+        ]));
+        assert!(check.warnings().is_empty());
+
+        let check = check_scopes(&scopes(&["org:read", "project:read", "org:admin"]));
+        assert_eq!(check.missing, vec!["team:read", "event:read"]);
+        assert_eq!(check.unavailable.len(), 2);
+        assert_eq!(check.excessive, vec!["org:admin"]);
+        assert_eq!(check.warnings().len(), 4);
+    }
+}
@@ -0,0 +1,265 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use keyring::Entry;
+use serde::{Deserialize, Serialize};
+use sodiumoxide::crypto::secretbox;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const KEYRING_SERVICE: &str = "sex-cli";
+const SECRETS_FILE: &str = "secrets.json";
+const SECRETS_KEY_FILE: &str = "secrets.key";
+const ENV_PREFIX: &str = "SEX_CLI_";
+
+/// Where tokens and the project encryption key are kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretsBackend {
+    /// Platform keyring (macOS Keychain, Secret Service, Windows Credential Manager)
+    #[default]
+    Keyring,
+    /// `secrets.json` in the config directory, encrypted with a key file next to it
+    File,
+    /// Read-only, from `SEX_CLI_*` environment variables
+    Env,
+}
+
+impl std::fmt::Display for SecretsBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretsBackend::Keyring => write!(f, "keyring"),
+            SecretsBackend::File => write!(f, "file"),
+            SecretsBackend::Env => write!(f, "env"),
+        }
+    }
+}
+
+impl SecretsBackend {
+    /// Secrets are addressed by account: `<org>/<entry>` for organization tokens, or a
+    /// plain name for global secrets.
+    pub fn get(self, account: &str) -> Result<Option<String>> {
+        match self {
+            SecretsBackend::Keyring => Ok(keyring_entry(account)?.get_password().ok()),
+            SecretsBackend::File => FileStore::default_location()?.get(account),
+            SecretsBackend::Env => Ok(std::env::var(env_var_name(account)).ok()),
+        }
+    }
+
+    pub fn set(self, account: &str, value: &str) -> Result<()> {
+        match self {
+            SecretsBackend::Keyring => keyring_entry(account)?
+                .set_password(value)
+                .context("Failed to store secret in keyring"),
+            SecretsBackend::File => FileStore::default_location()?.set(account, value),
+            SecretsBackend::Env => Err(anyhow::anyhow!(
+                "The env secrets backend is read-only. Export {} instead.",
+                env_var_name(account)
+            )),
+        }
+    }
+
+    pub fn delete(self, account: &str) -> Result<()> {
+        match self {
+            SecretsBackend::Keyring => match keyring_entry(account)?.delete_password() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(e).context("Failed to delete secret from keyring"),
+            },
+            SecretsBackend::File => FileStore::default_location()?.delete(account),
+            SecretsBackend::Env => Ok(()),
+        }
+    }
+
+    /// Backend for secrets the CLI generates itself, which cannot live in the environment.
+    pub fn generated_secrets(self) -> Self {
+        match self {
+            SecretsBackend::Env => SecretsBackend::File,
+            backend => backend,
+        }
+    }
+}
+
+/// Keeps the keyring entry names used before backends were selectable.
+fn keyring_entry(account: &str) -> Result<Entry> {
+    let entry = match account.split_once('/') {
+        Some((org, name)) => Entry::new(&format!("{}-{}", KEYRING_SERVICE, org), name),
+        None => Entry::new(KEYRING_SERVICE, account),
+    };
+    entry.context("Failed to access keyring")
+}
+
+/// Environment variable holding an account's secret, e.g. `SEX_CLI_ACME_AUTH_TOKEN`.
+pub fn env_var_name(account: &str) -> String {
+    let name: String = account
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}{}", ENV_PREFIX, name)
+}
+
+/// Secrets encrypted with secretbox in a JSON file, keyed by a separate key file.
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn default_location() -> Result<Self> {
+        Ok(Self::new(&crate::config::config_dir()?))
+    }
+
+    fn key(&self) -> Result<secretbox::Key> {
+        let path = self.dir.join(SECRETS_KEY_FILE);
+        if path.exists() {
+            let encoded = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let bytes = BASE64
+                .decode(encoded.trim())
+                .context("Failed to decode secrets key")?;
+            return secretbox::Key::from_slice(&bytes).context("Invalid secrets key length");
+        }
+
+        let key = secretbox::gen_key();
+        write_private(&path, &BASE64.encode(key.as_ref()))?;
+        Ok(key)
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>> {
+        let path = self.dir.join(SECRETS_FILE);
+        if !path.exists() {
+            return Ok(HashMap::new());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn save(&self, secrets: &HashMap<String, String>) -> Result<()> {
+        let content =
+            serde_json::to_string_pretty(secrets).context("Failed to serialize secrets")?;
+        write_private(&self.dir.join(SECRETS_FILE), &content)
+    }
+
+    pub fn get(&self, account: &str) -> Result<Option<String>> {
+        let Some(sealed) = self.load()?.remove(account) else {
+            return Ok(None);
+        };
+        let combined = BASE64.decode(sealed).context("Failed to decode secret")?;
+        if combined.len() < secretbox::NONCEBYTES {
+            return Err(anyhow::anyhow!("Invalid encrypted secret"));
+        }
+        let (nonce, encrypted) = combined.split_at(secretbox::NONCEBYTES);
+        let nonce = secretbox::Nonce::from_slice(nonce).context("Invalid nonce length")?;
+        let decrypted = secretbox::open(encrypted, &nonce, &self.key()?)
+            .map_err(|_| anyhow::anyhow!("Failed to decrypt secret for {}", account))?;
+        String::from_utf8(decrypted)
+            .map(Some)
+            .context("Invalid UTF-8 in decrypted secret")
+    }
+
+    pub fn set(&self, account: &str, value: &str) -> Result<()> {
+        let nonce = secretbox::gen_nonce();
+        let mut combined = nonce.as_ref().to_vec();
+        combined.extend(secretbox::seal(value.as_bytes(), &nonce, &self.key()?));
+
+        let mut secrets = self.load()?;
+        secrets.insert(account.to_string(), BASE64.encode(combined));
+        self.save(&secrets)
+    }
+
+    pub fn delete(&self, account: &str) -> Result<()> {
+        let mut secrets = self.load()?;
+        if secrets.remove(account).is_some() {
+            self.save(&secrets)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a file readable only by the current user.
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // Created private, so the content is never readable by others, even briefly
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    // The mode only applies to new files; restrict older ones before writing
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to restrict permissions of {}", path.display()))?;
+    }
+    file.write_all(content.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!(env_var_name("acme/auth-token"), "SEX_CLI_ACME_AUTH_TOKEN");
+        assert_eq!(
+            env_var_name("my org/auth-token-write"),
+            "SEX_CLI_MY_ORG_AUTH_TOKEN_WRITE"
+        );
+    }
+
+    #[test]
+    fn test_file_store_roundtrip() -> Result<()> {
+        let dir = assert_fs::TempDir::new()?;
+        let store = FileStore::new(dir.path());
+
+        assert_eq!(store.get("acme/auth-token")?, None);
+        store.set("acme/auth-token", "secret-token")?;
+        store.set("acme/auth-token-write", "write-token")?;
+        assert_eq!(
+            store.get("acme/auth-token")?.as_deref(),
+            Some("secret-token")
+        );
+
+        // Tokens never sit on disk in cleartext
+        let content = fs::read_to_string(dir.path().join(SECRETS_FILE))?;
+        assert!(!content.contains("secret-token"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            for file in [SECRETS_FILE, SECRETS_KEY_FILE] {
+                let mode = fs::metadata(dir.path().join(file))?.permissions().mode();
+                assert_eq!(mode & 0o777, 0o600, "{}", file);
+            }
+        }
+
+        store.delete("acme/auth-token")?;
+        assert_eq!(store.get("acme/auth-token")?, None);
+        assert_eq!(
+            FileStore::new(dir.path())
+                .get("acme/auth-token-write")?
+                .as_deref(),
+            Some("write-token")
+        );
+        Ok(())
+    }
+}
@@ -1,24 +1,36 @@
+use crate::editor::open_in_browser;
+use crate::error::{auth_error, SentryError};
+use crate::fixtures::{Fixture, FixtureMode};
+use crate::progress;
+use crate::timing;
 use anyhow::{Context, Result};
 use rand::{thread_rng, Rng};
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, Request, RequestBuilder, Response};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
 use rpassword::prompt_password;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::io::{self, Read, Write};
 use std::net::TcpListener;
-use std::process::Command;
-use urlencoding;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
 const SENTRY_OAUTH_URL: &str = "https://sentry.io/oauth/authorize";
 const REDIRECT_URI: &str = "http://localhost:8123/callback";
+pub const MAX_PAGE_SIZE: usize = 100;
+const USER_AGENT: &str = concat!("sex-cli/", env!("CARGO_PKG_VERSION"));
+/// Idle connections kept per host, enough for the monitor's per-project threads
+const POOL_MAX_IDLE_PER_HOST: usize = 16;
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
 
 fn get_client_id() -> Result<String> {
     dotenvy::dotenv().ok(); // Load .env file if it exists
     env::var("SENTRY_CLIENT_ID").context("SENTRY_CLIENT_ID environment variable not set")
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Issue {
     pub id: String,
     pub title: String,
@@ -27,9 +39,383 @@ pub struct Issue {
     pub culprit: String,
     #[serde(rename = "lastSeen")]
     pub last_seen: String,
+    #[serde(rename = "firstSeen")]
+    pub first_seen: Option<String>,
+    #[serde(deserialize_with = "string_or_number")]
     pub count: u32,
     #[serde(rename = "userCount")]
     pub user_count: u32,
+    pub project: Option<IssueProject>,
+    #[serde(rename = "assignedTo", default)]
+    pub assigned_to: Option<Assignee>,
+    #[serde(rename = "shortId")]
+    pub short_id: Option<String>,
+    pub permalink: Option<String>,
+    /// Event count histograms keyed by stats period, as `(timestamp, count)` buckets
+    pub stats: Option<HashMap<String, Vec<(i64, i64)>>>,
+    #[serde(default)]
+    pub metadata: Option<IssueMetadata>,
+    #[serde(rename = "statusDetails", default)]
+    pub status_details: StatusDetails,
+    /// Set while the issue is shared publicly
+    #[serde(rename = "shareId", default)]
+    pub share_id: Option<String>,
+}
+
+/// How an issue was resolved, e.g. in the next release rather than right away.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusDetails {
+    #[serde(rename = "inNextRelease", default)]
+    pub in_next_release: bool,
+    #[serde(rename = "inRelease")]
+    pub in_release: Option<String>,
+}
+
+/// When an ignored issue comes back: after a while, or once it sees a number of events
+/// or users, counted within a window when one is given. Periods are in minutes.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IgnoreCondition {
+    pub duration: Option<u64>,
+    pub count: Option<u64>,
+    pub user_count: Option<u64>,
+    pub window: Option<u64>,
+}
+
+impl IgnoreCondition {
+    /// Parses conditions such as `2w`, `1000 events in 1h` or `50 users`, which can be
+    /// combined, e.g. `2w 1000 events`.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut condition = Self::default();
+        let mut words = input.split_whitespace();
+        while let Some(word) = words.next() {
+            if word == "in" {
+                let period = words.next().context("Expected a period after 'in'")?;
+                condition.window = Some(period_minutes(period)?);
+            } else if let Ok(number) = word.parse::<u64>() {
+                match words.next() {
+                    Some("events" | "event") => condition.count = Some(number),
+                    Some("users" | "user") => condition.user_count = Some(number),
+                    _ => anyhow::bail!("Expected 'events' or 'users' after {}", number),
+                }
+            } else {
+                condition.duration = Some(period_minutes(word)?);
+            }
+        }
+        if condition.window.is_some() && condition.count.is_none() && condition.user_count.is_none()
+        {
+            anyhow::bail!("A window needs a count to apply to, e.g. '100 events in 1h'");
+        }
+        Ok(condition)
+    }
+
+    /// Changes ignoring an issue until the condition is met, or for good without one.
+    pub fn changes(&self) -> serde_json::Value {
+        let mut details = serde_json::Map::new();
+        let mut set = |key: &str, value: Option<u64>| {
+            if let Some(value) = value {
+                details.insert(key.to_string(), value.into());
+            }
+        };
+        set("ignoreDuration", self.duration);
+        set("ignoreCount", self.count);
+        set("ignoreUserCount", self.user_count);
+        if self.count.is_some() {
+            set("ignoreWindow", self.window);
+        }
+        if self.user_count.is_some() {
+            set("ignoreUserWindow", self.window);
+        }
+        serde_json::json!({"status": "ignored", "statusDetails": details})
+    }
+}
+
+impl std::fmt::Display for IgnoreCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(duration) = self.duration {
+            parts.push(format!("for {}", format_minutes(duration)));
+        }
+        let window = self
+            .window
+            .map(|window| format!(" in {}", format_minutes(window)))
+            .unwrap_or_default();
+        if let Some(count) = self.count {
+            parts.push(format!("until {} more events{}", count, window));
+        }
+        if let Some(users) = self.user_count {
+            parts.push(format!("until {} more users{}", users, window));
+        }
+        if parts.is_empty() {
+            write!(f, "forever")
+        } else {
+            write!(f, "{}", parts.join(" or "))
+        }
+    }
+}
+
+/// Minutes in the largest whole unit, e.g. `2w` or `90m`.
+fn format_minutes(minutes: u64) -> String {
+    [(7 * 24 * 60, "w"), (24 * 60, "d"), (60, "h")]
+        .into_iter()
+        .find(|(unit, _)| minutes.is_multiple_of(*unit))
+        .map(|(unit, suffix)| format!("{}{}", minutes / unit, suffix))
+        .unwrap_or_else(|| format!("{}m", minutes))
+}
+
+/// A period such as `2w` in whole minutes, at least one.
+pub fn period_minutes(period: &str) -> Result<u64> {
+    Ok((parse_stats_period(period)? / 60).max(1))
+}
+
+/// An entry of an issue's activity log, e.g. `set_resolved_in_release` or `set_regression`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Activity {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub data: serde_json::Value,
+    #[serde(rename = "dateCreated")]
+    pub date_created: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ActivityList {
+    activity: Vec<Activity>,
+}
+
+/// What Sentry derived an issue's title from: the exception, message or a fingerprint rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueMetadata {
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub value: Option<String>,
+    pub title: Option<String>,
+    pub filename: Option<String>,
+    pub function: Option<String>,
+}
+
+/// Sentry serializes some counters (e.g. issue `count`) as strings.
+fn string_or_number<'de, D>(deserializer: D) -> std::result::Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Count {
+        Number(u32),
+        Text(String),
+    }
+
+    match Count::deserialize(deserializer)? {
+        Count::Number(n) => Ok(n),
+        Count::Text(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueProject {
+    pub id: String,
+    pub slug: String,
+    pub name: String,
+}
+
+/// A user or team an issue is assigned to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Assignee {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+    pub name: String,
+}
+
+impl std::fmt::Display for Assignee {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.kind == "team" {
+            write!(f, "#{}", self.name)
+        } else {
+            write!(f, "{}", self.name)
+        }
+    }
+}
+
+/// Search parameters for the organization issues endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssueQuery {
+    pub query: String,
+    pub stats_period: String,
+    pub sort: String,
+    /// Maximum number of issues to fetch, following pagination as needed
+    pub limit: usize,
+}
+
+impl Default for IssueQuery {
+    fn default() -> Self {
+        Self {
+            query: "is:unresolved".to_string(),
+            stats_period: "14d".to_string(),
+            sort: "date".to_string(),
+            limit: MAX_PAGE_SIZE,
+        }
+    }
+}
+
+/// Validates a Sentry stats period such as `24h` or `7d` and returns its length in seconds.
+pub fn parse_stats_period(period: &str) -> Result<u64> {
+    let unit_index = period
+        .find(|c: char| !c.is_ascii_digit())
+        .filter(|&i| i > 0)
+        .with_context(|| format!("Invalid period '{}'. Use e.g. 1h, 24h, 7d or 2w", period))?;
+    let (value, unit) = period.split_at(unit_index);
+    let value: u64 = value.parse().context("Invalid period value")?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => anyhow::bail!("Invalid period unit '{}'. Use s, m, h, d or w", unit),
+    };
+    Ok(value * multiplier)
+}
+
+/// Extracts the cursor of the next page from a Sentry `Link` header, if there are more results.
+fn next_cursor(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get(reqwest::header::LINK)?.to_str().ok()?;
+    link.split(',')
+        .find(|part| part.contains("rel=\"next\"") && part.contains("results=\"true\""))
+        .and_then(|part| part.split("cursor=\"").nth(1))
+        .and_then(|rest| rest.split('"').next())
+        .map(|cursor| cursor.to_string())
+}
+
+/// Turns a failed response into a [`SentryError`], naming the organization from the
+/// request path so that login hints point at the right one.
+fn api_error(response: reqwest::blocking::Response) -> anyhow::Error {
+    let status = response.status();
+    let org = url_path_org(response.url().path());
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok());
+    let body = response.text().unwrap_or_default();
+    SentryError::new(status, org, retry_after, &body).into()
+}
+
+/// Organization slug of an API path such as `/api/0/organizations/<org>/...` or
+/// `/api/0/projects/<org>/<project>/...`.
+fn url_path_org(path: &str) -> Option<String> {
+    let mut segments = path.split('/');
+    segments
+        .find(|segment| matches!(*segment, "organizations" | "projects"))
+        .and_then(|_| segments.next())
+        .filter(|slug| !slug.is_empty())
+        .map(|slug| slug.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+struct ShortIdResolution {
+    group: Issue,
+}
+
+/// An issue reference as typed by the user: a numeric ID, a project short ID
+/// (e.g. `BACKEND-4X3`) or a web URL copied from the Sentry UI.
+#[derive(Debug, PartialEq)]
+pub enum IssueRef {
+    Id(String),
+    ShortId(String),
+    Url { org_slug: String, id: String },
+}
+
+impl IssueRef {
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.contains("://") {
+            return Self::parse_url(input);
+        }
+        if !input.is_empty() && input.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(Self::Id(input.to_string()));
+        }
+        if input.contains('-')
+            && input
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Ok(Self::ShortId(input.to_uppercase()));
+        }
+        Err(anyhow::anyhow!(
+            "Invalid issue reference '{}'. Use a numeric ID, a short ID (e.g. BACKEND-4X3) or an issue URL",
+            input
+        ))
+    }
+
+    fn parse_url(input: &str) -> Result<Self> {
+        let url = reqwest::Url::parse(input).context("Invalid issue URL")?;
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|s| s.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+
+        let id = segments
+            .iter()
+            .position(|s| *s == "issues")
+            .and_then(|i| segments.get(i + 1))
+            .filter(|id| id.chars().all(|c| c.is_ascii_digit()))
+            .context("Issue URL does not contain an issue ID")?;
+
+        let org_slug =
+            url_org_slug(&url, &segments).context("Issue URL does not contain an organization")?;
+
+        Ok(Self::Url {
+            org_slug,
+            id: id.to_string(),
+        })
+    }
+}
+
+/// Organization and server of any page of the Sentry web UI.
+#[derive(Debug, PartialEq)]
+pub struct OrgUrl {
+    pub slug: String,
+    /// Base URL of a self-hosted server; `None` for sentry.io
+    pub server: Option<String>,
+}
+
+impl OrgUrl {
+    pub fn parse(input: &str) -> Result<Self> {
+        let url = reqwest::Url::parse(input.trim()).context("Invalid Sentry URL")?;
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|s| s.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        let slug = url_org_slug(&url, &segments).with_context(|| {
+            format!(
+                "URL '{}' does not contain an organization. Copy one like https://sentry.io/organizations/<org>/issues/",
+                input.trim()
+            )
+        })?;
+
+        let host = url.host_str().unwrap_or_default();
+        let server = (host != "sentry.io" && !host.ends_with(".sentry.io"))
+            .then(|| url.origin().ascii_serialization());
+        Ok(Self { slug, server })
+    }
+}
+
+/// Organization slug of a web UI URL: either sentry.io/organizations/<org>/... or
+/// <org>.sentry.io/...
+fn url_org_slug(url: &reqwest::Url, segments: &[&str]) -> Option<String> {
+    segments
+        .iter()
+        .position(|s| *s == "organizations")
+        .and_then(|i| segments.get(i + 1))
+        .map(|s| s.to_string())
+        .or_else(|| {
+            url.host_str()
+                .and_then(|host| host.strip_suffix(".sentry.io"))
+                .filter(|sub| !sub.contains('.'))
+                .map(|sub| sub.to_string())
+        })
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,10 +431,67 @@ pub struct Project {
     pub last_event: Option<String>,
     pub stats: Option<ProjectStats>,
     pub id: Option<String>,
-    pub isBookmarked: Option<bool>,
-    pub isMember: Option<bool>,
-    pub hasAccess: Option<bool>,
+    #[serde(rename = "isBookmarked")]
+    pub is_bookmarked: Option<bool>,
+    #[serde(rename = "isMember")]
+    pub is_member: Option<bool>,
+    #[serde(rename = "hasAccess")]
+    pub has_access: Option<bool>,
     pub teams: Option<Vec<Team>>,
+    #[serde(default)]
+    pub options: HashMap<String, serde_json::Value>,
+}
+
+impl Project {
+    /// Whether spike protection guards the organization's quota against this project.
+    pub fn spike_protection(&self) -> bool {
+        !self
+            .options
+            .get("quotas:spike-protection-disabled")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+}
+
+/// Inbound data filters a project can turn on to drop events before they count.
+pub const INBOUND_FILTERS: &[&str] = &[
+    "browser-extensions",
+    "legacy-browsers",
+    "localhost",
+    "web-crawlers",
+    "filtered-transaction",
+];
+
+/// Browsers filtered when the legacy browser filter is turned on.
+const LEGACY_BROWSERS: &[&str] = &[
+    "ie_pre_9",
+    "ie9",
+    "ie10",
+    "ie11",
+    "safari_pre_6",
+    "opera_pre_15",
+    "opera_mini_pre_8",
+    "android_pre_4",
+    "edge_pre_79",
+];
+
+/// An inbound data filter of a project. The legacy browser filter is set per browser,
+/// so its `active` lists the filtered browsers instead of being a flag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboundFilter {
+    pub id: String,
+    #[serde(default)]
+    pub active: serde_json::Value,
+}
+
+impl InboundFilter {
+    pub fn is_active(&self) -> bool {
+        match &self.active {
+            serde_json::Value::Bool(active) => *active,
+            serde_json::Value::Array(browsers) => !browsers.is_empty(),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,6 +508,39 @@ pub struct Organization {
     pub name: String,
 }
 
+/// Settings of an organization, from its detail endpoint.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationDetails {
+    pub slug: String,
+    pub name: String,
+    #[serde(default)]
+    pub date_created: Option<String>,
+    /// Feature flags enabled for the organization
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Role given to new members
+    #[serde(default)]
+    pub default_role: Option<String>,
+    /// Whether members can join any team
+    #[serde(default)]
+    pub open_membership: bool,
+    #[serde(default, rename = "require2FA")]
+    pub require_2fa: bool,
+    #[serde(default)]
+    pub trusted_relays: Vec<TrustedRelay>,
+}
+
+/// A Relay allowed to forward events for an organization.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedRelay {
+    pub name: String,
+    pub public_key: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Team {
     pub id: String,
@@ -72,551 +548,4255 @@ pub struct Team {
     pub slug: String,
 }
 
-#[derive(Clone)]
-pub struct SentryClient {
-    client: Client,
-    base_url: String,
-    auth_token: Option<String>,
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeamMember {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub email: String,
 }
 
-impl SentryClient {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            client: Client::new(),
-            base_url: Self::get_base_url(),
-            auth_token: None,
-        })
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientKey {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "isActive", default)]
+    pub is_active: bool,
+    pub dsn: KeyDsn,
+    #[serde(rename = "rateLimit", default)]
+    pub rate_limit: Option<KeyRateLimit>,
+}
+
+/// Most events a client key may send per window of seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyRateLimit {
+    pub count: u64,
+    pub window: u64,
+}
+
+impl std::fmt::Display for KeyRateLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} events per {}s", self.count, self.window)
     }
+}
 
-    #[cfg(not(test))]
-    fn get_base_url() -> String {
-        "https://sentry.io/api/0".to_string()
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeyDsn {
+    pub public: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Release {
+    pub version: String,
+    #[serde(rename = "dateCreated")]
+    pub date_created: String,
+    #[serde(rename = "newGroups", default)]
+    pub new_groups: u32,
+    #[serde(rename = "lastEvent")]
+    pub last_event: Option<String>,
+    #[serde(rename = "commitCount", default)]
+    pub commit_count: u32,
+}
+
+/// Issue ownership settings of a project.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProjectOwnership {
+    /// Rules in Sentry's text syntax, one `type:pattern owner...` per line
+    #[serde(default)]
+    pub raw: Option<String>,
+    /// Whether issues no rule matches are assigned to all project members
+    #[serde(default)]
+    pub fallthrough: bool,
+    #[serde(rename = "autoAssignment", default)]
+    pub auto_assignment: Option<String>,
+}
+
+/// One ownership rule, e.g. `path:src/billing/* #billing jane@example.com`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnershipRule {
+    /// Matcher type: path, module, url, tags.<key> or codeowners
+    pub kind: String,
+    pub pattern: String,
+    pub owners: Vec<String>,
+}
+
+impl ProjectOwnership {
+    /// Parses the raw rules, skipping blank lines and `#` comments.
+    pub fn rules(&self) -> Vec<OwnershipRule> {
+        self.raw
+            .as_deref()
+            .unwrap_or("")
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let (kind, pattern) = parts.next()?.split_once(':')?;
+                Some(OwnershipRule {
+                    kind: kind.to_string(),
+                    pattern: pattern.to_string(),
+                    owners: parts.map(str::to_string).collect(),
+                })
+            })
+            .collect()
     }
+}
 
-    #[cfg(test)]
-    fn get_base_url() -> String {
-        "http://localhost:1234".to_string()
+/// A commit range in a repository known to Sentry's repository integrations.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReleaseRef {
+    pub repository: String,
+    pub commit: String,
+    #[serde(rename = "previousCommit", skip_serializing_if = "Option::is_none")]
+    pub previous_commit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    #[serde(rename = "groupID")]
+    pub group_id: Option<String>,
+    #[serde(default)]
+    pub title: String,
+    #[serde(rename = "dateCreated")]
+    pub date_created: String,
+    #[serde(default)]
+    pub tags: Vec<EventTag>,
+    pub user: Option<EventUser>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventTag {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventUser {
+    pub id: Option<String>,
+    pub email: Option<String>,
+    pub username: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl EventUser {
+    /// The known fields of the user, e.g. `id 42, jane@example.com, 10.0.0.1, jane`.
+    pub fn summary(&self) -> String {
+        let id = self.id.as_ref().map(|id| format!("id {}", id));
+        [
+            id.as_deref(),
+            self.email.as_deref(),
+            self.ip_address.as_deref(),
+            self.username.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ")
     }
+}
 
-    pub fn login_with_prompt(&mut self) -> Result<()> {
-        let token = prompt_password("Enter your Sentry auth token: ")
-            .context("Failed to read auth token")?;
-        self.login(token)
+impl Event {
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|t| t.key == key)
+            .map(|t| t.value.as_str())
     }
 
-    pub(crate) fn get_current_token(&self) -> Option<String> {
-        self.auth_token.clone()
+    pub fn level(&self) -> &str {
+        self.tag("level").unwrap_or("error")
     }
 
-    pub fn login(&mut self, auth_token: String) -> Result<()> {
-        self.auth_token = Some(auth_token);
-        Ok(())
+    /// A context such as `os` or `browser`, from the tags Sentry derives from it.
+    pub fn context(&self, key: &str) -> Option<&str> {
+        self.tag(key).or_else(|| self.tag(&format!("{}.name", key)))
     }
 
-    pub fn list_organizations(&self) -> Result<Vec<Organization>> {
-        let url = format!("{}/organizations/", self.base_url);
+    /// Best human-readable identifier of the affected user.
+    pub fn user_label(&self) -> Option<&str> {
+        let user = self.user.as_ref()?;
+        user.email
+            .as_deref()
+            .or(user.username.as_deref())
+            .or(user.id.as_deref())
+            .or(user.ip_address.as_deref())
+    }
+}
 
-        let response = self
-            .client
-            .get(&url)
-            .headers(self.get_headers()?)
-            .send()
-            .context("Failed to send request")?;
+/// A single event with its full payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventDetails {
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(rename = "dateCreated")]
+    pub date_created: String,
+    #[serde(default)]
+    pub tags: Vec<EventTag>,
+    #[serde(default)]
+    pub entries: Vec<EventEntry>,
+    #[serde(default)]
+    pub user: Option<EventUser>,
+    #[serde(default)]
+    pub contexts: serde_json::Map<String, serde_json::Value>,
+}
 
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "API request failed: {} - {}",
-                response.status(),
-                response.text()?
-            ));
+/// Contexts worth a glance when triaging, with their labels.
+pub const CONTEXTS: &[(&str, &str)] = &[
+    ("os", "OS"),
+    ("device", "Device"),
+    ("runtime", "Runtime"),
+    ("browser", "Browser"),
+];
+
+/// An interface of an event payload (exception, breadcrumbs, request, ...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventEntry {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExceptionValue {
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub value: Option<String>,
+    pub stacktrace: Option<Stacktrace>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stacktrace {
+    #[serde(default)]
+    pub frames: Vec<Frame>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Frame {
+    pub filename: Option<String>,
+    #[serde(rename = "absPath")]
+    pub abs_path: Option<String>,
+    pub function: Option<String>,
+    pub module: Option<String>,
+    #[serde(rename = "lineNo")]
+    pub line_no: Option<u32>,
+    #[serde(rename = "inApp", default)]
+    pub in_app: Option<bool>,
+}
+
+impl std::fmt::Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location = self
+            .filename
+            .as_deref()
+            .or(self.abs_path.as_deref())
+            .or(self.module.as_deref())
+            .unwrap_or("?");
+        write!(
+            f,
+            "at {} ({}",
+            self.function.as_deref().unwrap_or("?"),
+            location
+        )?;
+        if let Some(line) = self.line_no {
+            write!(f, ":{}", line)?;
+        }
+        write!(f, ")")?;
+        if self.in_app == Some(true) {
+            write!(f, " [in app]")?;
         }
+        Ok(())
+    }
+}
 
-        response
-            .json::<Vec<Organization>>()
-            .context("Failed to parse response")
+impl EventDetails {
+    pub fn level(&self) -> &str {
+        self.tags
+            .iter()
+            .find(|t| t.key == "level")
+            .map(|t| t.value.as_str())
+            .unwrap_or("error")
     }
 
-    pub fn login_with_browser(&mut self) -> Result<Organization> {
-        // Start local server to receive OAuth callback
-        let listener = TcpListener::bind("127.0.0.1:8123")?;
-        println!("Starting local server for OAuth callback...");
+    /// The innermost in-app frame of the last exception, where the crash happened.
+    pub fn crash_frame(&self) -> Option<Frame> {
+        crash_frame(&self.crash_frames()).cloned()
+    }
 
-        // Generate OAuth URL with all required parameters
-        let auth_url = format!(
-            "{}?client_id={}&response_type=token&redirect_uri={}&scope={}&state={}",
-            SENTRY_OAUTH_URL,
-            get_client_id()?,
-            REDIRECT_URI,
-            "org:read project:read team:read member:read",
-            Self::generate_state()
-        );
+    /// Frames of the last exception, innermost last.
+    pub fn crash_frames(&self) -> Vec<Frame> {
+        self.exceptions()
+            .into_iter()
+            .last()
+            .and_then(|exception| exception.stacktrace)
+            .map(|stacktrace| stacktrace.frames)
+            .unwrap_or_default()
+    }
 
-        // Create a success page that extracts the token from URL fragment
-        let success_page = r#"
-            <html>
-            <body>
-                <h1>Waiting for authentication...</h1>
-                <script>
-                    function handleAuth() {
-                        const hash = window.location.hash;
-                        if (!hash) {
-                            document.body.innerHTML = '<h1>Error</h1><p>No authentication data received. Please try again.</p>';
-                            return;
-                        }
+    /// Exceptions of the event, outermost last as Sentry reports them.
+    pub fn exceptions(&self) -> Vec<ExceptionValue> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.kind == "exception")
+            .filter_map(|entry| entry.data.get("values"))
+            .filter_map(|values| Vec::<ExceptionValue>::deserialize(values).ok())
+            .flatten()
+            .collect()
+    }
 
-                        // Remove the leading # and parse parameters
-                        const params = new URLSearchParams(hash.substring(1));
-                        const token = params.get('access_token');
+    /// The OS, device, runtime and browser the event happened on, as `(label, value)`,
+    /// e.g. `("Runtime", "CPython 3.11.4")`.
+    pub fn context_summary(&self) -> Vec<(&'static str, String)> {
+        CONTEXTS
+            .iter()
+            .filter_map(|(key, label)| {
+                let context = self.contexts.get(*key)?;
+                let text = |field: &str| context.get(field).and_then(|v| v.as_str());
+                let name = text("name").or(text("model")).or(text("family"))?;
+                let value = match text("version") {
+                    Some(version) => format!("{} {}", name, version),
+                    None => name.to_string(),
+                };
+                Some((*label, value))
+            })
+            .collect()
+    }
 
-                        if (!token) {
-                            document.body.innerHTML = '<h1>Error</h1><p>No access token found. Please try again.</p>';
-                            return;
-                        }
+    /// The HTTP request the event happened during, when the SDK recorded one.
+    pub fn request(&self) -> Option<EventRequest> {
+        let data = &self
+            .entries
+            .iter()
+            .find(|entry| entry.kind == "request")?
+            .data;
+        let text = |key: &str| data.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        Some(EventRequest {
+            method: text("method"),
+            url: text("url"),
+            query: data.get("query").map(key_values).unwrap_or_default(),
+            headers: data.get("headers").map(key_values).unwrap_or_default(),
+        })
+    }
+}
 
-                        // Send token back to the server by redirecting to /token endpoint
-                        window.location.href = '/token?access_token=' + encodeURIComponent(token);
-                    }
+/// One way Sentry hashed an event, e.g. by its in-app stack trace (`app`) or by a
+/// fingerprint set in the SDK (`custom-fingerprint`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupingVariant {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub description: Option<String>,
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub contributes: bool,
+    pub hint: Option<String>,
+    pub config: Option<GroupingConfig>,
+    /// Fingerprint values of custom fingerprint variants
+    #[serde(default)]
+    pub values: Vec<String>,
+}
 
-                    // Run the auth handler when the page loads
-                    handleAuth();
-                </script>
-            </body>
-            </html>
-        "#;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupingConfig {
+    pub id: String,
+}
 
-        // Start background thread to handle browser callback
-        let (tx, rx) = std::sync::mpsc::channel();
-        let _handle = std::thread::spawn(move || {
-            // Accept up to 2 connections (callback and token)
-            for _ in 0..2 {
-                if let Ok(mut stream) = listener.accept().map(|(s, _)| s) {
-                    let mut buffer = [0; 1024];
-                    if stream.read(&mut buffer).is_ok() {
-                        let request = String::from_utf8_lossy(&buffer[..]);
-                        // First request - serve the success page
-                        if request.contains("GET /callback") {
-                            let response = format!(
-                                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\r\n{}",
-                                success_page.len(),
-                                success_page
-                            );
-                            let _ = stream.write_all(response.as_bytes());
-                        }
-                        // Second request - receive the token
-                        else if request.contains("GET /token?access_token=") {
-                            if let Some(token) = request
-                                .split("access_token=")
-                                .nth(1)
-                                .and_then(|s| s.split(' ').next())
-                                .and_then(|s| s.split('&').next())
-                                .and_then(|s| s.split("HTTP").next())
-                                .map(|s| urlencoding::decode(s).unwrap_or_else(|_| s.into()))
-                            {
-                                let response = "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n\
-                                    <html><body><h1>Successfully authenticated!</h1>\
-                                    <p>You can close this window and return to the CLI.</p></body></html>";
-                                let _ = stream.write_all(response.as_bytes());
-                                let _ = tx.send(token.to_string());
-                            }
-                        }
-                    }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueHash {
+    pub id: String,
+}
+
+/// Why an issue's events grouped together: its title and metadata, the hashes merged
+/// into it and how its latest event was hashed.
+pub struct GroupingInfo<'a> {
+    pub issue: &'a Issue,
+    pub hashes: Vec<IssueHash>,
+    pub variants: BTreeMap<String, GroupingVariant>,
+}
+
+impl GroupingInfo<'_> {
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("Title: {}", self.issue.title)];
+        if let Some(metadata) = &self.issue.metadata {
+            let derived = match (&metadata.kind, &metadata.value) {
+                (Some(kind), Some(value)) => Some(format!("{}: {}", kind, value)),
+                (Some(kind), None) => Some(kind.clone()),
+                _ => metadata.title.clone(),
+            };
+            if let Some(derived) = derived {
+                lines.push(format!("Metadata: {}", derived));
+                if !self.issue.title.starts_with(&derived) {
+                    lines.push(
+                        "  The title differs from the metadata, e.g. set by a fingerprint rule"
+                            .to_string(),
+                    );
                 }
             }
-        });
+            let location: Vec<&str> = [metadata.function.as_deref(), metadata.filename.as_deref()]
+                .into_iter()
+                .flatten()
+                .collect();
+            if !location.is_empty() {
+                lines.push(format!("Location: {}", location.join(" in ")));
+            }
+        }
 
-        // Open browser after server is ready
-        #[cfg(target_os = "macos")]
-        Command::new("open").arg(&auth_url).spawn()?;
-        #[cfg(target_os = "linux")]
-        Command::new("xdg-open").arg(&auth_url).spawn()?;
-        #[cfg(target_os = "windows")]
-        Command::new("cmd")
-            .args(["/C", "start", &auth_url])
-            .spawn()?;
+        if let Some(config) = self.variants.values().find_map(|v| v.config.as_ref()) {
+            lines.push(format!("Grouping config: {}", config.id));
+        }
+        lines.push(format!(
+            "Hashes merged into the issue: {}",
+            self.hashes.len()
+        ));
+        for hash in &self.hashes {
+            lines.push(format!("  {}", hash.id));
+        }
 
-        println!("Opening browser for authentication...");
-        println!("If the browser doesn't open automatically, please visit:");
-        println!("{}", auth_url);
+        if !self.variants.is_empty() {
+            lines.push("Variants of the latest event:".to_string());
+        }
+        for (name, variant) in &self.variants {
+            lines.push(format!(
+                "  {} ({}): {}",
+                name,
+                if variant.contributes {
+                    "contributes"
+                } else {
+                    "ignored"
+                },
+                variant.hash.as_deref().unwrap_or("no hash")
+            ));
+            if let Some(description) = &variant.description {
+                lines.push(format!("    {}", description));
+            }
+            if !variant.values.is_empty() {
+                lines.push(format!("    fingerprint: {}", variant.values.join(", ")));
+            }
+            if let Some(hint) = &variant.hint {
+                lines.push(format!("    {}", hint));
+            }
+        }
+        lines
+    }
+}
 
-        // Wait for token from callback handler
-        if let Ok(token) = rx.recv_timeout(std::time::Duration::from_secs(120)) {
-            self.auth_token = Some(token);
+/// An event's request interface: the HTTP request being handled when it happened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRequest {
+    pub method: Option<String>,
+    pub url: Option<String>,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+}
 
-            // Get available organizations
-            let orgs = self.list_organizations()?;
-            match orgs.len() {
-                0 => anyhow::bail!("No organizations found for your account"),
-                1 => return Ok(orgs[0].clone()),
-                _ => {
-                    println!("\nMultiple organizations found. Please select one:");
-                    for (i, org) in orgs.iter().enumerate() {
-                        println!("{}. {} ({})", i + 1, org.name, org.slug);
-                    }
+/// Header and query parameter names whose values are credentials and never shown.
+const SENSITIVE_NAMES: &[&str] = &[
+    "auth", "cookie", "token", "secret", "key", "session", "csrf",
+];
 
-                    print!("Enter number (1-{}): ", orgs.len());
-                    io::stdout().flush()?;
-                    let mut input = String::new();
-                    io::stdin().read_line(&mut input)?;
-                    let selection = input
-                        .trim()
-                        .parse::<usize>()
-                        .context("Invalid selection")
-                        .and_then(|n| {
-                            if n > 0 && n <= orgs.len() {
-                                Ok(n - 1)
-                            } else {
-                                Err(anyhow::anyhow!("Selection out of range"))
-                            }
-                        })?;
-                    return Ok(orgs[selection].clone());
-                }
-            }
+impl EventRequest {
+    /// Lines describing the request, with credentials in headers and the query masked.
+    pub fn lines(&self) -> Vec<String> {
+        let url = self.url.as_deref().map(|url| match url.split_once('?') {
+            Some((path, query)) => format!("{}?{}", path, mask_query_string(query)),
+            None => url.to_string(),
+        });
+        let mut lines = vec![format!(
+            "{} {}",
+            self.method.as_deref().unwrap_or("?"),
+            url.as_deref().unwrap_or("?")
+        )];
+        if !self.query.is_empty() {
+            let query: Vec<String> = self
+                .query
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, mask(key, value)))
+                .collect();
+            lines.push(format!("Query: {}", query.join("&")));
+        }
+        for (name, value) in &self.headers {
+            lines.push(format!("{}: {}", name, mask(name, value)));
         }
+        lines
+    }
+}
 
-        anyhow::bail!("Authentication timed out")
+/// A raw query string, e.g. `page=2&token=abc`, with credential values masked.
+fn mask_query_string(query: &str) -> String {
+    query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => format!("{}={}", key, mask(key, value)),
+            None => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn mask<'a>(name: &str, value: &'a str) -> &'a str {
+    let name = name.to_lowercase();
+    if SENSITIVE_NAMES.iter().any(|part| name.contains(part)) {
+        "[masked]"
+    } else {
+        value
     }
+}
 
-    fn generate_state() -> String {
-        const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
-                                abcdefghijklmnopqrstuvwxyz\
-                                0123456789";
-        let mut rng = thread_rng();
-        (0..32)
-            .map(|_| {
-                let idx = rng.gen_range(0..CHARSET.len());
-                CHARSET[idx] as char
+/// Pairs of a request interface field, sent as `[[key, value], ...]`, an object or, for
+/// the query, a raw query string.
+fn key_values(value: &serde_json::Value) -> Vec<(String, String)> {
+    let text = |value: &serde_json::Value| match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    match value {
+        serde_json::Value::Array(pairs) => pairs
+            .iter()
+            .filter_map(|pair| match pair.as_array()?.as_slice() {
+                [key, value] => Some((text(key), text(value))),
+                _ => None,
             })
-            .collect()
+            .collect(),
+        serde_json::Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| (key.clone(), text(value)))
+            .collect(),
+        serde_json::Value::String(query) => query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (pair.to_string(), String::new()),
+            })
+            .collect(),
+        _ => Vec::new(),
     }
+}
 
-    fn get_headers(&self) -> Result<HeaderMap> {
-        let auth_token = self
-            .auth_token
-            .as_ref()
-            .context("Not authenticated. Please set the auth token first.")?;
+/// The innermost in-app frame of a stack, or the innermost frame when none is in-app.
+pub fn crash_frame(frames: &[Frame]) -> Option<&Frame> {
+    frames
+        .iter()
+        .rev()
+        .find(|f| f.in_app == Some(true))
+        .or(frames.last())
+}
 
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", auth_token))
-                .context("Invalid auth token")?,
-        );
-        Ok(headers)
+/// Distribution of a tag's values across an issue's events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagSummary {
+    pub key: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(rename = "totalValues", default)]
+    pub total_values: u64,
+    #[serde(rename = "topValues", default)]
+    pub top_values: Vec<TagValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagValue {
+    pub value: String,
+    pub count: u64,
+}
+
+/// A user affected by an issue, a value of its `user` tag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AffectedUser {
+    /// Tag value, e.g. `id:42` or `email:jane@example.com`
+    pub value: String,
+    pub count: u64,
+    #[serde(rename = "lastSeen")]
+    pub last_seen: Option<String>,
+    pub email: Option<String>,
+    pub username: Option<String>,
+    #[serde(rename = "ipAddress")]
+    pub ip_address: Option<String>,
+}
+
+impl AffectedUser {
+    pub fn label(&self) -> &str {
+        self.email
+            .as_deref()
+            .or(self.username.as_deref())
+            .unwrap_or(&self.value)
     }
+}
 
-    pub fn list_projects(&self, org_slug: &str) -> Result<Vec<Project>> {
-        let mut all_projects = Vec::new();
-        let cursor: Option<String> = None;
+/// An author whose commits Sentry suspects of causing an event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Committer {
+    pub author: Option<CommitAuthor>,
+    #[serde(default)]
+    pub commits: Vec<Commit>,
+}
 
-        loop {
-            // Build URL with pagination
-            let mut url = format!(
-                "{}/organizations/{}/projects/?all_projects=1&per_page=100",
-                self.base_url, org_slug
-            );
-            if let Some(cur) = &cursor {
-                url.push_str(&format!("&cursor={}", cur));
-            }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitAuthor {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
 
-            let response = self
-                .client
-                .get(&url)
-                .headers(self.get_headers()?)
-                .send()
-                .context("Failed to send request")?;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Commit {
+    pub id: String,
+    pub message: Option<String>,
+    #[serde(rename = "dateCreated")]
+    pub date_created: Option<String>,
+}
 
-            if !response.status().is_success() {
-                return Err(anyhow::anyhow!(
-                    "API request failed: {} - {}",
-                    response.status(),
-                    response.text()?
-                ));
-            }
+#[derive(Deserialize)]
+struct CommittersResponse {
+    committers: Vec<Committer>,
+}
 
-            let mut page_projects = response
-                .json::<Vec<Project>>()
-                .context("Failed to parse response")?;
+/// A transaction in a distributed trace, with its child transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceTransaction {
+    pub event_id: String,
+    pub transaction: String,
+    pub project_slug: String,
+    #[serde(rename = "transaction.op", default)]
+    pub op: String,
+    pub start_timestamp: f64,
+    pub timestamp: f64,
+    #[serde(default)]
+    pub errors: Vec<TraceError>,
+    #[serde(default)]
+    pub children: Vec<TraceTransaction>,
+}
 
-            if page_projects.is_empty() {
-                break;
-            }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceError {
+    pub event_id: String,
+    #[serde(default)]
+    pub title: String,
+}
+
+/// Newer Sentry versions wrap the trace tree in an object, older ones return the bare list.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TraceResponse {
+    Wrapped { transactions: Vec<TraceTransaction> },
+    List(Vec<TraceTransaction>),
+}
+
+/// Aggregated timing of a function across sampled profiles. Durations are in nanoseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileFunction {
+    pub function: String,
+    #[serde(default)]
+    pub package: String,
+    #[serde(rename = "count()")]
+    pub count: u64,
+    #[serde(rename = "p75()")]
+    pub p75: f64,
+    #[serde(rename = "sum()")]
+    pub total: f64,
+}
+
+/// Profile count and duration of a transaction. Durations are in nanoseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfiledTransaction {
+    pub transaction: String,
+    #[serde(rename = "count()")]
+    pub count: u64,
+    #[serde(rename = "p75()")]
+    pub p75: f64,
+}
+
+/// Performance of a transaction, or of all transactions when `transaction` is empty.
+/// Apdex uses the project's threshold; latency is in milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransactionPerformance {
+    #[serde(default)]
+    pub transaction: String,
+    #[serde(rename = "count()")]
+    pub count: u64,
+    #[serde(rename = "apdex()", default)]
+    pub apdex: f64,
+    #[serde(rename = "failure_rate()", default)]
+    pub failure_rate: f64,
+    #[serde(rename = "p95(transaction.duration)", default)]
+    pub p95: f64,
+}
+
+/// Outcome totals of the organization stats endpoint, grouped by category and outcome.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UsageGroup {
+    pub by: UsageKey,
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UsageKey {
+    pub category: String,
+    pub outcome: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UsageTotals {
+    #[serde(rename = "sum(quantity)")]
+    pub quantity: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiIndex {
+    auth: Option<ApiAuth>,
+    user: Option<CurrentUser>,
+}
+
+/// User the current token acts on behalf of.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CurrentUser {
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub email: String,
+}
+
+impl CurrentUser {
+    /// Email, or username when the account has none; both work in searches and assignments.
+    pub fn handle(&self) -> &str {
+        if self.email.is_empty() {
+            &self.username
+        } else {
+            &self.email
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiAuth {
+    #[serde(default)]
+    scopes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionsResponse {
+    #[serde(default)]
+    groups: Vec<SessionsGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionsGroup {
+    totals: SessionsTotals,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionsTotals {
+    #[serde(rename = "crash_free_rate(session)")]
+    crash_free_rate: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageResponse {
+    #[serde(default)]
+    groups: Vec<UsageGroup>,
+}
+
+/// Rate limit state reported by the `X-Sentry-Rate-Limit-*` response headers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimitStatus {
+    /// Requests allowed per window
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Unix timestamp at which the window resets
+    pub reset: Option<u64>,
+    pub concurrent_limit: Option<u64>,
+    pub concurrent_remaining: Option<u64>,
+}
+
+impl RateLimitStatus {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let value = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse().ok())
+        };
+        Self {
+            limit: value("x-sentry-rate-limit-limit"),
+            remaining: value("x-sentry-rate-limit-remaining"),
+            reset: value("x-sentry-rate-limit-reset"),
+            concurrent_limit: value("x-sentry-rate-limit-concurrentlimit"),
+            concurrent_remaining: value("x-sentry-rate-limit-concurrentremaining"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Error volume of a project over a time window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectTotals {
+    #[serde(rename = "count()")]
+    pub events: u64,
+    #[serde(rename = "count_unique(issue)")]
+    pub issues: u64,
+    #[serde(rename = "count_unique(user)")]
+    pub users: u64,
+}
+
+/// Server limits for chunked file uploads.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChunkUploadOptions {
+    pub url: String,
+    #[serde(rename = "chunkSize")]
+    pub chunk_size: usize,
+    #[serde(rename = "chunksPerRequest")]
+    pub chunks_per_request: usize,
+    #[serde(rename = "maxRequestSize")]
+    pub max_request_size: usize,
+}
+
+/// A file to assemble from previously uploaded chunks.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssembleRequest {
+    pub name: String,
+    pub chunks: Vec<String>,
+}
+
+/// Assembly status of a debug file, keyed by file checksum in the assemble response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssembleStatus {
+    pub state: String,
+    #[serde(rename = "missingChunks", default)]
+    pub missing_chunks: Vec<String>,
+    pub detail: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DiscoverResponse<T> {
+    data: Vec<T>,
+}
+
+#[derive(Clone)]
+pub struct SentryClient {
+    client: Client,
+    base_url: String,
+    auth_token: Option<String>,
+    /// Print requests that would change data instead of sending them
+    dry_run: bool,
+    fixtures: FixtureMode,
+}
+
+/// The HTTP client of the process. Clones share its connection pool, so paginated and
+/// multi-organization fetches reuse connections. Fails when the TLS backend cannot be
+/// initialized.
+pub fn http_client() -> Result<Client> {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client.clone());
+    }
+    let client = Client::builder()
+        .user_agent(USER_AGENT)
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .build()
+        .context("Failed to initialize the HTTP client")?;
+    Ok(CLIENT.get_or_init(|| client).clone())
+}
+
+/// Reads the whole body of a response, giving back a response that serves it from memory.
+fn buffer_body(response: Response) -> Result<Response> {
+    let mut buffered = http::Response::builder().status(response.status());
+    if let Some(headers) = buffered.headers_mut() {
+        *headers = response.headers().clone();
+    }
+    let body = response.bytes().context("Failed to read response")?;
+    Ok(buffered.body(body).context("Invalid response")?.into())
+}
+
+/// Sends API requests through [`SentryClient::send`], so that every call can be
+/// recorded or replayed.
+trait SendVia {
+    fn send_via(self, client: &SentryClient) -> Result<Response>;
+}
+
+impl SendVia for RequestBuilder {
+    fn send_via(self, client: &SentryClient) -> Result<Response> {
+        client.send(self)
+    }
+}
+
+impl SentryClient {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: http_client()?,
+            base_url: Self::get_base_url(),
+            auth_token: None,
+            dry_run: false,
+            fixtures: FixtureMode::Off,
+        })
+    }
+
+    /// A client for an API served at `base_url`, e.g. a mock server.
+    #[cfg(test)]
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            ..Self::new().expect("HTTP client")
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Records API responses to a directory, or serves recorded ones instead of
+    /// sending requests.
+    pub fn with_fixtures(mut self, fixtures: FixtureMode) -> Self {
+        self.fixtures = fixtures;
+        self
+    }
+
+    fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let request = request.build().context("Failed to build request")?;
+        let label = timing::is_enabled().then(|| {
+            let url = request.url();
+            match url.query() {
+                Some(query) => format!("{} {}?{}", request.method(), url.path(), query),
+                None => format!("{} {}", request.method(), url.path()),
+            }
+        });
+        let started = Instant::now();
+        let result = match label {
+            // Big pages take longer to read than to arrive, so the body is timed too
+            Some(_) => self.execute(request).and_then(buffer_body),
+            None => self.execute(request),
+        };
+        if let Some(label) = label {
+            let outcome = match &result {
+                Ok(response) => response.status().as_u16().to_string(),
+                Err(_) => "failed".to_string(),
+            };
+            timing::record("api", format!("{} {}", label, outcome), started.elapsed());
+        }
+        result
+    }
+
+    /// Sends a request, records its response or answers it from a fixture.
+    fn execute(&self, request: Request) -> Result<Response> {
+        match &self.fixtures {
+            FixtureMode::Off => Ok(self.client.execute(request)?),
+            FixtureMode::Record(dir) => {
+                let recorded = request.try_clone();
+                let response = self.client.execute(request)?;
+                let Some(recorded) = recorded else {
+                    // Streamed uploads cannot be read back to name their fixture
+                    return Ok(response);
+                };
+                let fixture = Fixture::record(&recorded, response)?;
+                fixture.save(dir, &recorded)?;
+                fixture.into_response()
+            }
+            FixtureMode::Replay(dir) => Fixture::load(dir, &request)?.into_response(),
+        }
+    }
+
+    /// Sends a request that changes data in Sentry. In dry-run mode the method, URL and
+    /// payload are printed instead and `None` is returned.
+    fn send_change(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        payload: &serde_json::Value,
+    ) -> Result<Option<reqwest::blocking::Response>> {
+        if self.dry_run {
+            println!("[dry-run] {} {}", method, url);
+            for line in serde_json::to_string_pretty(payload)?.lines() {
+                println!("[dry-run]   {}", line);
+            }
+            return Ok(None);
+        }
+
+        let response = self
+            .client
+            .request(method, url)
+            .headers(self.get_headers()?)
+            .json(payload)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+        Ok(Some(response))
+    }
+
+    /// Points the client at a self-hosted Sentry server.
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.base_url = format!("{}/api/0", url.trim_end_matches('/'));
+        self
+    }
+
+    #[cfg(not(test))]
+    fn get_base_url() -> String {
+        "https://sentry.io/api/0".to_string()
+    }
+
+    #[cfg(test)]
+    fn get_base_url() -> String {
+        "http://localhost:1234".to_string()
+    }
+
+    pub fn login_with_prompt(&mut self) -> Result<()> {
+        let token = prompt_password("Enter your Sentry auth token: ")
+            .context("Failed to read auth token")?;
+        self.login(token)
+    }
+
+    pub(crate) fn get_current_token(&self) -> Option<String> {
+        self.auth_token.clone()
+    }
+
+    pub fn login(&mut self, auth_token: String) -> Result<()> {
+        self.auth_token = Some(auth_token);
+        Ok(())
+    }
+
+    pub fn list_organizations(&self) -> Result<Vec<Organization>> {
+        let url = format!("{}/organizations/", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response
+            .json::<Vec<Organization>>()
+            .context("Failed to parse response")
+    }
+
+    pub fn get_organization(&self, org_slug: &str) -> Result<Organization> {
+        let url = format!("{}/organizations/{}/", self.base_url, org_slug);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response
+            .json::<Organization>()
+            .context("Failed to parse response")
+    }
+
+    pub fn get_organization_details(&self, org_slug: &str) -> Result<OrganizationDetails> {
+        let url = format!("{}/organizations/{}/", self.base_url, org_slug);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response
+            .json::<OrganizationDetails>()
+            .context("Failed to parse response")
+    }
+
+    pub fn login_with_browser(&mut self) -> Result<Organization> {
+        // Start local server to receive OAuth callback
+        let listener = TcpListener::bind("127.0.0.1:8123")?;
+        println!("Starting local server for OAuth callback...");
+
+        // Generate OAuth URL with all required parameters
+        let auth_url = format!(
+            "{}?client_id={}&response_type=token&redirect_uri={}&scope={}&state={}",
+            SENTRY_OAUTH_URL,
+            get_client_id()?,
+            REDIRECT_URI,
+            "org:read project:read team:read member:read",
+            Self::generate_state()
+        );
+
+        // Create a success page that extracts the token from URL fragment
+        let success_page = r#"
+            <html>
+            <body>
+                <h1>Waiting for authentication...</h1>
+                <script>
+                    function handleAuth() {
+                        const hash = window.location.hash;
+                        if (!hash) {
+                            document.body.innerHTML = '<h1>Error</h1><p>No authentication data received. Please try again.</p>';
+                            return;
+                        }
+
+                        // Remove the leading # and parse parameters
+                        const params = new URLSearchParams(hash.substring(1));
+                        const token = params.get('access_token');
+
+                        if (!token) {
+                            document.body.innerHTML = '<h1>Error</h1><p>No access token found. Please try again.</p>';
+                            return;
+                        }
+
+                        // Send token back to the server by redirecting to /token endpoint
+                        window.location.href = '/token?access_token=' + encodeURIComponent(token);
+                    }
+
+                    // Run the auth handler when the page loads
+                    handleAuth();
+                </script>
+            </body>
+            </html>
+        "#;
+
+        // Start background thread to handle browser callback
+        let (tx, rx) = std::sync::mpsc::channel();
+        let _handle = std::thread::spawn(move || {
+            // Accept up to 2 connections (callback and token)
+            for _ in 0..2 {
+                if let Ok(mut stream) = listener.accept().map(|(s, _)| s) {
+                    let mut buffer = [0; 1024];
+                    if stream.read(&mut buffer).is_ok() {
+                        let request = String::from_utf8_lossy(&buffer[..]);
+                        // First request - serve the success page
+                        if request.contains("GET /callback") {
+                            let response = format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\r\n{}",
+                                success_page.len(),
+                                success_page
+                            );
+                            let _ = stream.write_all(response.as_bytes());
+                        }
+                        // Second request - receive the token
+                        else if request.contains("GET /token?access_token=") {
+                            if let Some(token) = request
+                                .split("access_token=")
+                                .nth(1)
+                                .and_then(|s| s.split(' ').next())
+                                .and_then(|s| s.split('&').next())
+                                .and_then(|s| s.split("HTTP").next())
+                                .map(|s| urlencoding::decode(s).unwrap_or_else(|_| s.into()))
+                            {
+                                let response = "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n\
+                                    <html><body><h1>Successfully authenticated!</h1>\
+                                    <p>You can close this window and return to the CLI.</p></body></html>";
+                                let _ = stream.write_all(response.as_bytes());
+                                let _ = tx.send(token.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        // Open browser after server is ready
+        open_in_browser(&auth_url)?;
+
+        println!("Opening browser for authentication...");
+        println!("If the browser doesn't open automatically, please visit:");
+        println!("{}", auth_url);
+
+        // Wait for token from callback handler
+        if let Ok(token) = rx.recv_timeout(Duration::from_secs(120)) {
+            self.auth_token = Some(token);
+
+            // Get available organizations
+            let orgs = self.list_organizations()?;
+            match orgs.len() {
+                0 => anyhow::bail!("No organizations found for your account"),
+                1 => return Ok(orgs[0].clone()),
+                _ => {
+                    println!("\nMultiple organizations found. Please select one:");
+                    for (i, org) in orgs.iter().enumerate() {
+                        println!("{}. {} ({})", i + 1, org.name, org.slug);
+                    }
+
+                    print!("Enter number (1-{}): ", orgs.len());
+                    io::stdout().flush()?;
+                    let mut input = String::new();
+                    io::stdin().read_line(&mut input)?;
+                    let selection = input
+                        .trim()
+                        .parse::<usize>()
+                        .context("Invalid selection")
+                        .and_then(|n| {
+                            if n > 0 && n <= orgs.len() {
+                                Ok(n - 1)
+                            } else {
+                                Err(anyhow::anyhow!("Selection out of range"))
+                            }
+                        })?;
+                    return Ok(orgs[selection].clone());
+                }
+            }
+        }
+
+        anyhow::bail!("Authentication timed out")
+    }
+
+    fn generate_state() -> String {
+        const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+                                abcdefghijklmnopqrstuvwxyz\
+                                0123456789";
+        let mut rng = thread_rng();
+        (0..32)
+            .map(|_| {
+                let idx = rng.gen_range(0..CHARSET.len());
+                CHARSET[idx] as char
+            })
+            .collect()
+    }
+
+    fn get_headers(&self) -> Result<HeaderMap> {
+        let auth_token = self
+            .auth_token
+            .as_ref()
+            .ok_or_else(|| auth_error("Not authenticated. Please set the auth token first."))?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", auth_token))
+                .context("Invalid auth token")?,
+        );
+        Ok(headers)
+    }
+
+    /// Returns the scopes granted to the current token, as reported by the API index.
+    pub fn get_token_scopes(&self) -> Result<Vec<String>> {
+        self.get_api_index()?
+            .auth
+            .map(|auth| auth.scopes)
+            .context("The server did not recognize the token")
+    }
+
+    /// Returns the user the current token belongs to. Organization tokens have none.
+    pub fn get_current_user(&self) -> Result<CurrentUser> {
+        self.get_api_index()?
+            .user
+            .context("The token does not belong to a user")
+    }
+
+    fn get_api_index(&self) -> Result<ApiIndex> {
+        let url = format!("{}/", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response
+            .json::<ApiIndex>()
+            .context("Failed to parse response")
+    }
+
+    /// Returns event usage of an organization per data category and outcome.
+    pub fn get_org_usage(&self, org_slug: &str, stats_period: &str) -> Result<Vec<UsageGroup>> {
+        let url = format!(
+            "{}/organizations/{}/stats_v2/?field=sum(quantity)&groupBy=category&groupBy=outcome&interval=1d&statsPeriod={}",
+            self.base_url, org_slug, stats_period
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        let body = response
+            .json::<UsageResponse>()
+            .context("Failed to parse response")?;
+        Ok(body.groups)
+    }
+
+    /// Returns the crash-free session rate (0.0 to 1.0) of a release over a period, or
+    /// `None` when the release has no sessions yet.
+    pub fn get_release_crash_free_rate(
+        &self,
+        org_slug: &str,
+        project_id: &str,
+        version: &str,
+        stats_period: &str,
+    ) -> Result<Option<f64>> {
+        let url = format!(
+            "{}/organizations/{}/sessions/?field=crash_free_rate(session)&project={}&query={}&statsPeriod={}&interval=1h",
+            self.base_url,
+            org_slug,
+            project_id,
+            urlencoding::encode(&format!("release:\"{}\"", version)),
+            stats_period
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        let body = response
+            .json::<SessionsResponse>()
+            .context("Failed to parse response")?;
+        Ok(body
+            .groups
+            .into_iter()
+            .next()
+            .and_then(|group| group.totals.crash_free_rate))
+    }
+
+    /// Makes a minimal request against an organization and returns the rate limit headers.
+    pub fn get_rate_limits(&self, org_slug: &str) -> Result<RateLimitStatus> {
+        let url = format!(
+            "{}/organizations/{}/projects/?per_page=1",
+            self.base_url, org_slug
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        // A 429 still carries the headers, and is exactly what the caller wants to see
+        let status = response.status();
+        if !status.is_success() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(api_error(response));
+        }
+
+        Ok(RateLimitStatus::from_headers(response.headers()))
+    }
+
+    pub fn list_projects(&self, org_slug: &str) -> Result<Vec<Project>> {
+        let mut all_projects = Vec::new();
+        let cursor: Option<String> = None;
+
+        loop {
+            // Build URL with pagination
+            let mut url = format!(
+                "{}/organizations/{}/projects/?all_projects=1&per_page=100",
+                self.base_url, org_slug
+            );
+            if let Some(cur) = &cursor {
+                url.push_str(&format!("&cursor={}", cur));
+            }
+
+            let response = self
+                .client
+                .get(&url)
+                .headers(self.get_headers()?)
+                .send_via(self)
+                .context("Failed to send request")?;
+
+            if !response.status().is_success() {
+                return Err(api_error(response));
+            }
+
+            let mut page_projects = response
+                .json::<Vec<Project>>()
+                .context("Failed to parse response")?;
+
+            if page_projects.is_empty() {
+                break;
+            }
+
+            all_projects.append(&mut page_projects);
+
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        // Sort projects by name
+        all_projects.sort_by_key(|p| p.name.to_lowercase());
+        Ok(all_projects)
+    }
+
+    pub fn list_issues(&self, org_slug: &str, project_slug: &str) -> Result<Vec<Issue>> {
+        self.list_project_issues(org_slug, project_slug, &IssueQuery::default())
+    }
+
+    pub fn list_project_issues(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        query: &IssueQuery,
+    ) -> Result<Vec<Issue>> {
+        let endpoint = format!(
+            "{}/projects/{}/{}/issues/?",
+            self.base_url, org_slug, project_slug
+        );
+        self.fetch_issues(&endpoint, query)
+    }
+
+    pub fn list_org_issues(&self, org_slug: &str, query: &IssueQuery) -> Result<Vec<Issue>> {
+        let endpoint = format!(
+            "{}/organizations/{}/issues/?project=-1&",
+            self.base_url, org_slug
+        );
+        self.fetch_issues(&endpoint, query)
+    }
+
+    /// Fetches issues from an issues endpoint, following pagination up to the query limit.
+    fn fetch_issues(&self, endpoint: &str, query: &IssueQuery) -> Result<Vec<Issue>> {
+        let mut all_issues = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        for page in 1.. {
+            let mut url = format!(
+                "{}statsPeriod={}&query={}&sort={}&limit={}",
+                endpoint,
+                query.stats_period,
+                urlencoding::encode(&query.query),
+                query.sort,
+                query.limit.min(MAX_PAGE_SIZE)
+            );
+            if let Some(cur) = &cursor {
+                url.push_str(&format!("&cursor={}", cur));
+            }
+
+            let response = self
+                .client
+                .get(&url)
+                .headers(self.get_headers()?)
+                .send_via(self)
+                .context("Failed to send request")?;
+
+            if !response.status().is_success() {
+                return Err(api_error(response));
+            }
+
+            cursor = next_cursor(response.headers());
+            let mut page_issues = response
+                .json::<Vec<Issue>>()
+                .context("Failed to parse response")?;
+            all_issues.append(&mut page_issues);
+            progress::page(page, all_issues.len());
+
+            if cursor.is_none() || all_issues.len() >= query.limit {
+                break;
+            }
+        }
+
+        all_issues.truncate(query.limit);
+        Ok(all_issues)
+    }
+
+    pub fn get_issue(&self, issue_id: &str) -> Result<Issue> {
+        let url = format!("{}/issues/{}/", self.base_url, issue_id);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json::<Issue>().context("Failed to parse response")
+    }
+
+    /// Applies changes such as `{"status": "resolved"}` or `{"assignedTo": "jane@example.com"}` to an issue.
+    pub fn update_issue(&self, issue_id: &str, changes: &serde_json::Value) -> Result<()> {
+        let url = format!("{}/issues/{}/", self.base_url, issue_id);
+        self.send_change(reqwest::Method::PUT, &url, changes)?;
+        Ok(())
+    }
+
+    /// Shares an issue publicly or stops sharing it, returning the issue with its share ID.
+    pub fn set_issue_public(&self, issue_id: &str, public: bool) -> Result<Option<Issue>> {
+        let url = format!("{}/issues/{}/", self.base_url, issue_id);
+        let payload = serde_json::json!({ "isPublic": public });
+        self.send_change(reqwest::Method::PUT, &url, &payload)?
+            .map(|response| response.json().context("Failed to parse response"))
+            .transpose()
+    }
+
+    pub fn resolve_short_id(&self, org_slug: &str, short_id: &str) -> Result<Issue> {
+        let url = format!(
+            "{}/organizations/{}/shortids/{}/",
+            self.base_url, org_slug, short_id
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response
+            .json::<ShortIdResolution>()
+            .map(|resolution| resolution.group)
+            .context("Failed to parse response")
+    }
+
+    pub fn get_project(&self, org_slug: &str, project_slug: &str) -> Result<Project> {
+        let url = format!(
+            "{}/projects/{}/{}/?statsPeriod=24h",
+            self.base_url, org_slug, project_slug
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    pub fn get_project_ownership(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+    ) -> Result<ProjectOwnership> {
+        let url = format!(
+            "{}/projects/{}/{}/ownership/",
+            self.base_url, org_slug, project_slug
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    /// Returns received event counts for a project as `(timestamp, count)` buckets since
+    /// a unix timestamp, at a resolution of `1h` or `1d`.
+    pub fn get_project_event_stats(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        since: u64,
+        resolution: &str,
+    ) -> Result<Vec<(i64, i64)>> {
+        let url = format!(
+            "{}/projects/{}/{}/stats/?stat=received&since={}&resolution={}",
+            self.base_url, org_slug, project_slug, since, resolution
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    pub fn set_project_bookmark(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        bookmarked: bool,
+    ) -> Result<Option<Project>> {
+        let url = format!("{}/projects/{}/{}/", self.base_url, org_slug, project_slug);
+        let payload = serde_json::json!({ "isBookmarked": bookmarked });
+        self.send_change(reqwest::Method::PUT, &url, &payload)?
+            .map(|response| response.json().context("Failed to parse response"))
+            .transpose()
+    }
+
+    pub fn list_releases(&self, org_slug: &str, project_slug: &str) -> Result<Vec<Release>> {
+        let url = format!(
+            "{}/projects/{}/{}/releases/?per_page=10",
+            self.base_url, org_slug, project_slug
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response
+            .json::<Vec<Release>>()
+            .context("Failed to parse response")
+    }
+
+    /// The most recently created release of a project, if it has any.
+    pub fn get_latest_release(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+    ) -> Result<Option<Release>> {
+        Ok(self
+            .list_releases(org_slug, project_slug)?
+            .into_iter()
+            .max_by(|a, b| a.date_created.cmp(&b.date_created)))
+    }
+
+    /// Lists the most recent events of a project matching a search query, newest first,
+    /// following pagination up to `limit` events.
+    pub fn list_project_events(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        let mut all_events = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut url = format!(
+                "{}/projects/{}/{}/events/?query={}",
+                self.base_url,
+                org_slug,
+                project_slug,
+                urlencoding::encode(query)
+            );
+            if let Some(cur) = &cursor {
+                url.push_str(&format!("&cursor={}", cur));
+            }
+
+            let response = self
+                .client
+                .get(&url)
+                .headers(self.get_headers()?)
+                .send_via(self)
+                .context("Failed to send request")?;
+
+            if !response.status().is_success() {
+                return Err(api_error(response));
+            }
+
+            cursor = next_cursor(response.headers());
+            let mut page_events = response
+                .json::<Vec<Event>>()
+                .context("Failed to parse response")?;
+            all_events.append(&mut page_events);
+
+            if cursor.is_none() || all_events.len() >= limit {
+                break;
+            }
+        }
+
+        all_events.truncate(limit);
+        Ok(all_events)
+    }
+
+    pub fn get_project_event(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        event_id: &str,
+    ) -> Result<EventDetails> {
+        let url = format!(
+            "{}/projects/{}/{}/events/{}/",
+            self.base_url, org_slug, project_slug, event_id
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    pub fn get_latest_event(&self, issue_id: &str) -> Result<EventDetails> {
+        serde_json::from_value(self.get_latest_event_json(issue_id)?)
+            .context("Failed to parse response")
+    }
+
+    /// The latest event of an issue as Sentry returns it, including fields that
+    /// [`EventDetails`] leaves out.
+    pub fn get_latest_event_json(&self, issue_id: &str) -> Result<serde_json::Value> {
+        let url = format!("{}/issues/{}/events/latest/", self.base_url, issue_id);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    pub fn list_issue_tags(&self, issue_id: &str) -> Result<Vec<TagSummary>> {
+        let url = format!("{}/issues/{}/tags/", self.base_url, issue_id);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    /// Lists an issue's activity log, newest first.
+    pub fn list_issue_activities(&self, issue_id: &str) -> Result<Vec<Activity>> {
+        let url = format!("{}/issues/{}/activities/", self.base_url, issue_id);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response
+            .json::<ActivityList>()
+            .map(|list| list.activity)
+            .context("Failed to parse response")
+    }
+
+    /// Lists the hashes grouped into an issue; more than one means issues were merged.
+    pub fn list_issue_hashes(&self, issue_id: &str) -> Result<Vec<IssueHash>> {
+        let url = format!("{}/issues/{}/hashes/", self.base_url, issue_id);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    /// Describes how an event was hashed by each grouping variant.
+    pub fn get_grouping_info(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        event_id: &str,
+    ) -> Result<BTreeMap<String, GroupingVariant>> {
+        let url = format!(
+            "{}/projects/{}/{}/events/{}/grouping-info/",
+            self.base_url, org_slug, project_slug, event_id
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    /// Lists the users an issue affected, most events first.
+    pub fn list_issue_users(&self, issue_id: &str) -> Result<Vec<AffectedUser>> {
+        let url = format!("{}/issues/{}/tags/user/values/", self.base_url, issue_id);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        let mut users: Vec<AffectedUser> = response.json().context("Failed to parse response")?;
+        users.sort_by_key(|user| std::cmp::Reverse(user.count));
+        Ok(users)
+    }
+
+    /// Lists suspect commits for an event. Requires a repository integration and
+    /// releases with associated commits.
+    pub fn list_event_committers(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        event_id: &str,
+    ) -> Result<Vec<Committer>> {
+        let url = format!(
+            "{}/projects/{}/{}/events/{}/committers/",
+            self.base_url, org_slug, project_slug, event_id
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        let body: CommittersResponse = response.json().context("Failed to parse response")?;
+        Ok(body.committers)
+    }
+
+    /// Fetches the root transactions of a distributed trace.
+    pub fn get_trace(&self, org_slug: &str, trace_id: &str) -> Result<Vec<TraceTransaction>> {
+        let url = format!(
+            "{}/organizations/{}/events-trace/{}/?limit=10000",
+            self.base_url, org_slug, trace_id
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        match response
+            .json::<TraceResponse>()
+            .context("Failed to parse response")?
+        {
+            TraceResponse::Wrapped { transactions } | TraceResponse::List(transactions) => {
+                Ok(transactions)
+            }
+        }
+    }
+
+    /// Lists the functions of a project that take the most CPU time in aggregate.
+    pub fn list_profile_functions(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        stats_period: &str,
+        limit: usize,
+    ) -> Result<Vec<ProfileFunction>> {
+        self.discover(
+            org_slug,
+            &[
+                ("dataset", "profileFunctions"),
+                ("field", "function"),
+                ("field", "package"),
+                ("field", "count()"),
+                ("field", "p75()"),
+                ("field", "sum()"),
+                ("sort", "-sum()"),
+                ("query", &format!("project:{}", project_slug)),
+                ("statsPeriod", stats_period),
+                ("per_page", &limit.min(MAX_PAGE_SIZE).to_string()),
+            ],
+        )
+    }
+
+    /// Lists the transactions of a project with the most profiles.
+    pub fn list_profiled_transactions(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        stats_period: &str,
+        limit: usize,
+    ) -> Result<Vec<ProfiledTransaction>> {
+        self.discover(
+            org_slug,
+            &[
+                ("dataset", "profiles"),
+                ("field", "transaction"),
+                ("field", "count()"),
+                ("field", "p75()"),
+                ("sort", "-count()"),
+                ("query", &format!("project:{}", project_slug)),
+                ("statsPeriod", stats_period),
+                ("per_page", &limit.min(MAX_PAGE_SIZE).to_string()),
+            ],
+        )
+    }
+
+    /// Lists the busiest transactions of a project with their Apdex, failure rate and
+    /// p95 latency, followed by the same metrics over all transactions as `overall`.
+    pub fn get_transaction_performance(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        stats_period: &str,
+        limit: usize,
+    ) -> Result<(Vec<TransactionPerformance>, TransactionPerformance)> {
+        let query = format!("event.type:transaction project:{}", project_slug);
+        let metrics = [
+            ("field", "count()"),
+            ("field", "apdex()"),
+            ("field", "failure_rate()"),
+            ("field", "p95(transaction.duration)"),
+            ("query", query.as_str()),
+            ("statsPeriod", stats_period),
+        ];
+
+        let per_page = limit.min(MAX_PAGE_SIZE).to_string();
+        let mut params = vec![("field", "transaction")];
+        params.extend(metrics);
+        params.extend([("sort", "-count()"), ("per_page", per_page.as_str())]);
+        let transactions = self.discover(org_slug, &params)?;
+
+        let overall: Vec<TransactionPerformance> = self.discover(org_slug, &metrics)?;
+        Ok((transactions, overall.into_iter().next().unwrap_or_default()))
+    }
+
+    /// Totals error events, issues and affected users of a project in a window that
+    /// starts `stats_period_start` ago and ends `stats_period_end` ago, or now.
+    pub fn get_project_totals(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        stats_period_start: &str,
+        stats_period_end: Option<&str>,
+    ) -> Result<ProjectTotals> {
+        let query = format!("project:{}", project_slug);
+        let mut params = vec![
+            ("dataset", "errors"),
+            ("field", "count()"),
+            ("field", "count_unique(issue)"),
+            ("field", "count_unique(user)"),
+            ("query", query.as_str()),
+        ];
+        match stats_period_end {
+            Some(end) => {
+                params.push(("statsPeriodStart", stats_period_start));
+                params.push(("statsPeriodEnd", end));
+            }
+            None => params.push(("statsPeriod", stats_period_start)),
+        }
+
+        let rows: Vec<ProjectTotals> = self.discover(org_slug, &params)?;
+        Ok(rows.into_iter().next().unwrap_or_default())
+    }
+
+    /// Runs a query against the organization events (Discover) endpoint.
+    fn discover<T: serde::de::DeserializeOwned>(
+        &self,
+        org_slug: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Vec<T>> {
+        let query = params
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, urlencoding::encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        let url = format!(
+            "{}/organizations/{}/events/?{}",
+            self.base_url, org_slug, query
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        let body = response
+            .json::<DiscoverResponse<T>>()
+            .context("Failed to parse response")?;
+        Ok(body.data)
+    }
+
+    pub fn get_chunk_upload_options(&self, org_slug: &str) -> Result<ChunkUploadOptions> {
+        let url = format!("{}/organizations/{}/chunk-upload/", self.base_url, org_slug);
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    /// Uploads one batch of `(checksum, data)` chunks to the chunk upload endpoint.
+    pub fn upload_chunks(&self, upload_url: &str, chunks: &[(String, Vec<u8>)]) -> Result<()> {
+        // The server may advertise a path relative to the API host
+        let url = if upload_url.starts_with("http") {
+            upload_url.to_string()
+        } else {
+            format!("{}{}", self.web_url(), upload_url)
+        };
+
+        let mut form = reqwest::blocking::multipart::Form::new();
+        for (checksum, data) in chunks {
+            let part =
+                reqwest::blocking::multipart::Part::bytes(data.clone()).file_name(checksum.clone());
+            form = form.part("file", part);
+        }
+
+        let response = self
+            .client
+            .post(&url)
+            .headers(self.get_headers()?)
+            .multipart(form)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+        Ok(())
+    }
+
+    /// Asks the server to assemble debug files from uploaded chunks. Files the server
+    /// already knows are reported as `OK` without needing any chunks.
+    pub fn assemble_difs(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        files: &HashMap<String, AssembleRequest>,
+    ) -> Result<HashMap<String, AssembleStatus>> {
+        let url = format!(
+            "{}/projects/{}/{}/files/difs/assemble/",
+            self.base_url, org_slug, project_slug
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .headers(self.get_headers()?)
+            .json(files)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    /// Web UI address of the Sentry instance the client talks to.
+    pub fn web_url(&self) -> String {
+        self.base_url.trim_end_matches("/api/0").to_string()
+    }
+
+    /// Link to an issue in the web UI, preferring the permalink Sentry reports.
+    pub fn issue_url(&self, org_slug: &str, issue: &Issue) -> String {
+        issue.permalink.clone().unwrap_or_else(|| {
+            format!(
+                "{}/organizations/{}/issues/{}/",
+                self.web_url(),
+                org_slug,
+                issue.id
+            )
+        })
+    }
+
+    /// Public link to a shared issue, viewable without a Sentry account.
+    pub fn share_url(&self, share_id: &str) -> String {
+        format!("{}/share/issue/{}/", self.web_url(), share_id)
+    }
+
+    pub fn project_url(&self, org_slug: &str, project_slug: &str) -> String {
+        format!(
+            "{}/organizations/{}/projects/{}/",
+            self.web_url(),
+            org_slug,
+            project_slug
+        )
+    }
+
+    pub fn release_url(&self, org_slug: &str, version: &str) -> String {
+        format!(
+            "{}/organizations/{}/releases/{}/",
+            self.web_url(),
+            org_slug,
+            urlencoding::encode(version)
+        )
+    }
+
+    pub fn event_url(&self, org_slug: &str, event: &Event) -> String {
+        match &event.group_id {
+            Some(group_id) => format!(
+                "{}/organizations/{}/issues/{}/events/{}/",
+                self.web_url(),
+                org_slug,
+                group_id,
+                event.event_id
+            ),
+            None => format!(
+                "{}/organizations/{}/discover/results/?query=id:{}",
+                self.web_url(),
+                org_slug,
+                event.event_id
+            ),
+        }
+    }
+
+    /// Associates commit ranges with a release so Sentry can attribute suspect commits.
+    pub fn set_release_refs(
+        &self,
+        org_slug: &str,
+        version: &str,
+        refs: &[ReleaseRef],
+    ) -> Result<Option<Release>> {
+        let url = format!(
+            "{}/organizations/{}/releases/{}/",
+            self.base_url,
+            org_slug,
+            urlencoding::encode(version)
+        );
+        let payload = serde_json::json!({ "refs": refs });
+        self.send_change(reqwest::Method::PUT, &url, &payload)?
+            .map(|response| response.json().context("Failed to parse response"))
+            .transpose()
+    }
+
+    pub fn list_team_members(&self, org_slug: &str, team_slug: &str) -> Result<Vec<TeamMember>> {
+        let url = format!(
+            "{}/teams/{}/{}/members/",
+            self.base_url, org_slug, team_slug
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response
+            .json::<Vec<TeamMember>>()
+            .context("Failed to parse response")
+    }
+
+    pub fn list_client_keys(&self, org_slug: &str, project_slug: &str) -> Result<Vec<ClientKey>> {
+        let url = format!(
+            "{}/projects/{}/{}/keys/",
+            self.base_url, org_slug, project_slug
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response
+            .json::<Vec<ClientKey>>()
+            .context("Failed to parse response")
+    }
+
+    pub fn list_inbound_filters(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+    ) -> Result<Vec<InboundFilter>> {
+        let url = format!(
+            "{}/projects/{}/{}/filters/",
+            self.base_url, org_slug, project_slug
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_headers()?)
+            .send_via(self)
+            .context("Failed to send request")?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response));
+        }
+
+        response.json().context("Failed to parse response")
+    }
+
+    /// Turns an inbound filter on or off; the legacy browser filter covers all old browsers.
+    pub fn set_inbound_filter(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        filter_id: &str,
+        active: bool,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/projects/{}/{}/filters/{}/",
+            self.base_url, org_slug, project_slug, filter_id
+        );
+        let payload = if filter_id == "legacy-browsers" {
+            let browsers: &[&str] = if active { LEGACY_BROWSERS } else { &[] };
+            serde_json::json!({ "subfilters": browsers })
+        } else {
+            serde_json::json!({ "active": active })
+        };
+        self.send_change(reqwest::Method::PUT, &url, &payload)?;
+        Ok(())
+    }
+
+    pub fn set_spike_protection(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/organizations/{}/spike-protections/",
+            self.base_url, org_slug
+        );
+        let method = if enabled {
+            reqwest::Method::POST
+        } else {
+            reqwest::Method::DELETE
+        };
+        let payload = serde_json::json!({ "projects": [project_slug] });
+        self.send_change(method, &url, &payload)?;
+        Ok(())
+    }
+
+    /// Sets or, with `None`, removes the rate limit of a client key.
+    pub fn set_key_rate_limit(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        key_id: &str,
+        rate_limit: Option<&KeyRateLimit>,
+    ) -> Result<Option<ClientKey>> {
+        let url = format!(
+            "{}/projects/{}/{}/keys/{}/",
+            self.base_url, org_slug, project_slug, key_id
+        );
+        let payload = serde_json::json!({ "rateLimit": rate_limit });
+        self.send_change(reqwest::Method::PUT, &url, &payload)?
+            .map(|response| response.json().context("Failed to parse response"))
+            .transpose()
+    }
+
+    pub fn get_project_info(
+        &self,
+        org_slug: &str,
+        project_slug: &str,
+        show_dsn: bool,
+    ) -> Result<Vec<(String, String)>> {
+        let project = self.get_project(org_slug, project_slug)?;
+
+        // Collect project information
+        let mut info = Vec::new();
+        info.push(("Name".to_string(), project.name));
+        info.push(("Slug".to_string(), project.slug));
+        if let Some(platform) = project.platform {
+            info.push(("Platform".to_string(), platform));
+        }
+        if !project.status.is_empty() {
+            info.push(("Status".to_string(), project.status));
+        }
+        if let Some(first) = project.first_event {
+            info.push(("First Event".to_string(), first));
+        }
+        if let Some(last) = project.last_event {
+            info.push(("Last Event".to_string(), last));
+        }
+        if let Some(teams) = project.teams {
+            let team_names = teams
+                .iter()
+                .map(|t| t.name.clone())
+                .collect::<Vec<_>>()
+                .join(", ");
+            info.push(("Teams".to_string(), team_names));
+        }
+
+        // Add stats if available
+        if let Some(stats) = project.stats {
+            let total_24h: i64 = stats.last_24h.iter().map(|(_, count)| count).sum();
+            let total_30d: i64 = stats.last_30d.iter().map(|(_, count)| count).sum();
+            info.push(("Events (24h)".to_string(), total_24h.to_string()));
+            info.push(("Events (30d)".to_string(), total_30d.to_string()));
+
+            // Calculate daily average for last 30 days
+            let avg_30d = total_30d as f64 / 30.0;
+            info.push(("Daily Average (30d)".to_string(), format!("{:.1}", avg_30d)));
+        }
+
+        if show_dsn {
+            let keys = self.list_client_keys(org_slug, project_slug)?;
+            for key in keys.into_iter().filter(|k| k.is_active) {
+                info.push((format!("DSN ({})", key.name), key.dsn.public));
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+    use serde_json::json;
+
+    #[test]
+    fn test_client_creation() {
+        let server = Server::new();
+        let client = SentryClient::with_base_url(server.url());
+        assert!(client.auth_token.is_none());
+    }
+
+    #[test]
+    fn test_login() {
+        let mut client = SentryClient::new().unwrap();
+        client.login("test-token".to_string()).unwrap();
+        assert_eq!(client.auth_token, Some("test-token".to_string()));
+    }
+
+    #[test]
+    fn test_list_projects() -> Result<()> {
+        let mut server = Server::new();
+        let mock_response = json!([
+            {
+                "slug": "test-project",
+                "name": "Test Project"
+            },
+            {
+                "slug": "another-project",
+                "name": "Another Project"
+            }
+        ]);
+
+        let mock = server
+            .mock("GET", "/organizations/test-org/projects/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "all_projects".into(),
+                "1".into(),
+            ))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let projects = client.list_projects("test-org")?;
+        // Sorted by name
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].slug, "another-project");
+        assert_eq!(projects[0].name, "Another Project");
+        assert_eq!(projects[1].slug, "test-project");
+        assert_eq!(projects[1].name, "Test Project");
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_projects_unauthorized() -> Result<()> {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/organizations/test-org/projects/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "all_projects".into(),
+                "1".into(),
+            ))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(401)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"error": "Unauthorized"}).to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let result = client.list_projects("test-org");
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("API request failed: 401"));
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_issues() -> Result<()> {
+        let mut server = Server::new();
+        let mock_response = json!([
+            {
+                "id": "1",
+                "title": "Test Issue",
+                "status": "unresolved",
+                "level": "error",
+                "culprit": "test.js:42",
+                "lastSeen": "2024-01-01T00:00:00Z",
+                "count": 5,
+                "userCount": 3
+            }
+        ]);
+
+        let mock = server
+            .mock("GET", "/projects/test-org/test-project/issues/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("statsPeriod".into(), "14d".into()),
+                mockito::Matcher::UrlEncoded("query".into(), "is:unresolved".into()),
+                mockito::Matcher::UrlEncoded("sort".into(), "date".into()),
+            ]))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let issues = client.list_issues("test-org", "test-project")?;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].id, "1");
+        assert_eq!(issues[0].title, "Test Issue");
+        assert_eq!(issues[0].status, "unresolved");
+        assert_eq!(issues[0].level, "error");
+        assert_eq!(issues[0].count, 5);
+        assert_eq!(issues[0].user_count, 3);
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_issues_not_found() -> Result<()> {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/projects/test-org/nonexistent-project/issues/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("statsPeriod".into(), "14d".into()),
+                mockito::Matcher::UrlEncoded("query".into(), "is:unresolved".into()),
+                mockito::Matcher::UrlEncoded("sort".into(), "date".into()),
+            ]))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"error": "Project not found"}).to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let result = client.list_issues("test-org", "nonexistent-project");
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("API request failed: 404"));
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_org_issues() -> Result<()> {
+        let mut server = Server::new();
+        let mock_response = json!([
+            {
+                "id": "1",
+                "title": "Test Issue",
+                "status": "unresolved",
+                "level": "error",
+                "culprit": "test.js:42",
+                "lastSeen": "2024-01-01T00:00:00Z",
+                "count": 5,
+                "userCount": 3,
+                "project": {"id": "10", "slug": "backend", "name": "Backend"}
+            }
+        ]);
+
+        let mock = server
+            .mock("GET", "/organizations/test-org/issues/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("project".into(), "-1".into()),
+                mockito::Matcher::UrlEncoded("query".into(), "is:unresolved".into()),
+            ]))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let issues = client.list_org_issues("test-org", &IssueQuery::default())?;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].project.as_ref().unwrap().slug, "backend");
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_org_issues_pagination() -> Result<()> {
+        let mut server = Server::new();
+        let issue = |id: &str| {
+            json!({
+                "id": id,
+                "title": "Test Issue",
+                "status": "unresolved",
+                "level": "error",
+                "culprit": "test.js:42",
+                "lastSeen": "2024-01-01T00:00:00Z",
+                "count": 1,
+                "userCount": 1
+            })
+        };
+
+        // Declared first so that cursor requests prefer it over the catch-all first page
+        let second_page = server
+            .mock("GET", "/organizations/test-org/issues/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "cursor".into(),
+                "0:100:0".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header(
+                "link",
+                "<http://x/?cursor=0:200:0>; rel=\"next\"; results=\"false\"; cursor=\"0:200:0\"",
+            )
+            .with_body(json!([issue("2")]).to_string())
+            .create();
+
+        let first_page = server
+            .mock("GET", "/organizations/test-org/issues/")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header(
+                "link",
+                "<http://x/?cursor=0:0:1>; rel=\"previous\"; results=\"false\"; cursor=\"0:0:1\", \
+                 <http://x/?cursor=0:100:0>; rel=\"next\"; results=\"true\"; cursor=\"0:100:0\"",
+            )
+            .with_body(json!([issue("1")]).to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let query = IssueQuery {
+            limit: 500,
+            ..IssueQuery::default()
+        };
+        let issues = client.list_org_issues("test-org", &query)?;
+        let ids: Vec<&str> = issues.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+
+        first_page.assert();
+        second_page.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_stats_period() {
+        assert_eq!(parse_stats_period("24h").unwrap(), 86_400);
+        assert_eq!(parse_stats_period("7d").unwrap(), 604_800);
+        assert_eq!(parse_stats_period("2w").unwrap(), 1_209_600);
+        assert!(parse_stats_period("7").is_err());
+        assert!(parse_stats_period("d").is_err());
+        assert!(parse_stats_period("7y").is_err());
+    }
+
+    #[test]
+    fn test_issue_ref_parse() -> Result<()> {
+        assert_eq!(IssueRef::parse("12345")?, IssueRef::Id("12345".to_string()));
+        assert_eq!(
+            IssueRef::parse("backend-4x3")?,
+            IssueRef::ShortId("BACKEND-4X3".to_string())
+        );
+        assert_eq!(
+            IssueRef::parse("https://sentry.io/organizations/acme/issues/12345/?project=1")?,
+            IssueRef::Url {
+                org_slug: "acme".to_string(),
+                id: "12345".to_string()
+            }
+        );
+        assert_eq!(
+            IssueRef::parse("https://acme.sentry.io/issues/12345/events/latest/")?,
+            IssueRef::Url {
+                org_slug: "acme".to_string(),
+                id: "12345".to_string()
+            }
+        );
+        assert_eq!(
+            IssueRef::parse("https://sentry.example.com/organizations/acme/issues/7/")?,
+            IssueRef::Url {
+                org_slug: "acme".to_string(),
+                id: "7".to_string()
+            }
+        );
+        assert!(IssueRef::parse("https://sentry.io/settings/").is_err());
+        assert!(IssueRef::parse("not an id").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_org_url_parse() -> Result<()> {
+        assert_eq!(
+            OrgUrl::parse("https://sentry.io/organizations/acme/issues/")?,
+            OrgUrl {
+                slug: "acme".to_string(),
+                server: None
+            }
+        );
+        assert_eq!(
+            OrgUrl::parse("https://acme.sentry.io/projects/")?,
+            OrgUrl {
+                slug: "acme".to_string(),
+                server: None
+            }
+        );
+        assert_eq!(
+            OrgUrl::parse("http://sentry.internal:9000/organizations/ops/issues/?query=is%3A")?,
+            OrgUrl {
+                slug: "ops".to_string(),
+                server: Some("http://sentry.internal:9000".to_string())
+            }
+        );
+        assert!(OrgUrl::parse("https://sentry.io/settings/account/").is_err());
+        assert!(OrgUrl::parse("acme").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_issue() -> Result<()> {
+        let mut server = Server::new();
+        let mock_response = json!({
+            "id": "42",
+            "title": "Test Issue",
+            "status": "unresolved",
+            "level": "error",
+            "culprit": "test.js:42",
+            "lastSeen": "2024-01-01T00:00:00Z",
+            "count": "1234",
+            "userCount": 3
+        });
+
+        let mock = server
+            .mock("GET", "/issues/42/")
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let issue = client.get_issue("42")?;
+        assert_eq!(issue.id, "42");
+        assert_eq!(issue.count, 1234);
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_issue_not_found() -> Result<()> {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/issues/404/")
+            .match_header("authorization", "Bearer test-token")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"detail": "The requested resource does not exist"}).to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let result = client.get_issue("404");
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("API request failed: 404"));
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_short_id() -> Result<()> {
+        let mut server = Server::new();
+        let mock_response = json!({
+            "organizationSlug": "test-org",
+            "projectSlug": "backend",
+            "groupId": "1",
+            "shortId": "BACKEND-4X3",
+            "group": {
+                "id": "1",
+                "title": "Test Issue",
+                "status": "unresolved",
+                "level": "error",
+                "culprit": "test.js:42",
+                "lastSeen": "2024-01-01T00:00:00Z",
+                "count": 5,
+                "userCount": 3
+            }
+        });
+
+        let mock = server
+            .mock("GET", "/organizations/test-org/shortids/BACKEND-4X3/")
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let issue = client.resolve_short_id("test-org", "BACKEND-4X3")?;
+        assert_eq!(issue.id, "1");
+        assert_eq!(issue.title, "Test Issue");
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_project_bookmark() -> Result<()> {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("PUT", "/projects/test-org/test-project/")
+            .match_header("authorization", "Bearer test-token")
+            .match_body(mockito::Matcher::Json(json!({"isBookmarked": true})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"slug": "test-project", "name": "Test Project", "isBookmarked": true})
+                    .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let project = client
+            .set_project_bookmark("test-org", "test-project", true)?
+            .unwrap();
+        assert_eq!(project.is_bookmarked, Some(true));
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_inbound_filters() -> Result<()> {
+        let mut server = Server::new();
+
+        let list = server
+            .mock("GET", "/projects/test-org/web/filters/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([
+                    {"id": "browser-extensions", "active": true},
+                    {"id": "legacy-browsers", "active": ["ie9", "ie10"]},
+                    {"id": "localhost", "active": false},
+                    {"id": "web-crawlers", "active": []}
+                ])
+                .to_string(),
+            )
+            .create();
+        let legacy = server
+            .mock("PUT", "/projects/test-org/web/filters/legacy-browsers/")
+            .match_body(mockito::Matcher::Json(json!({"subfilters": []})))
+            .with_status(204)
+            .create();
+        let spike = server
+            .mock("DELETE", "/organizations/test-org/spike-protections/")
+            .match_body(mockito::Matcher::Json(json!({"projects": ["web"]})))
+            .with_status(200)
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let active: Vec<bool> = client
+            .list_inbound_filters("test-org", "web")?
+            .iter()
+            .map(InboundFilter::is_active)
+            .collect();
+        assert_eq!(active, vec![true, true, false, false]);
+        client.set_inbound_filter("test-org", "web", "legacy-browsers", false)?;
+        client.set_spike_protection("test-org", "web", false)?;
+
+        let project: Project = serde_json::from_value(json!({
+            "slug": "web",
+            "name": "Web",
+            "options": {"quotas:spike-protection-disabled": true}
+        }))?;
+        assert!(!project.spike_protection());
+
+        list.assert();
+        legacy.assert();
+        spike.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_agent() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/issues/42/tags/")
+            .match_header("user-agent", concat!("sex-cli/", env!("CARGO_PKG_VERSION")))
+            .with_status(200)
+            .with_body("[]")
+            .expect(2)
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+        client.list_issue_tags("42")?;
+        client.clone().list_issue_tags("42")?;
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_and_replay() -> Result<()> {
+        let dir = assert_fs::TempDir::new()?;
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/issues/42/tags/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{"key": "browser", "name": "Browser", "totalValues": 3, "topValues": []}])
+                    .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url())
+            .with_fixtures(FixtureMode::Record(dir.path().to_path_buf()));
+        client.login("test-token".to_string())?;
+        assert_eq!(client.list_issue_tags("42")?[0].key, "browser");
+
+        // Replayed responses come from disk, so the server sees no second request
+        let client = client.with_fixtures(FixtureMode::Replay(dir.path().to_path_buf()));
+        assert_eq!(client.list_issue_tags("42")?[0].key, "browser");
+        assert!(client.list_issue_tags("43").is_err());
+        mock.assert();
+
+        // Fixtures never hold the token
+        for entry in std::fs::read_dir(dir.path())? {
+            let content = std::fs::read_to_string(entry?.path())?;
+            assert!(!content.contains("test-token"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_key_rate_limit() -> Result<()> {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("PUT", "/projects/test-org/api/keys/abc/")
+            .match_header("authorization", "Bearer test-token")
+            .match_body(mockito::Matcher::Json(
+                json!({"rateLimit": {"count": 1000, "window": 60}}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "abc",
+                    "name": "Default",
+                    "isActive": true,
+                    "dsn": {"public": "https://abc@o1.ingest.sentry.io/1"},
+                    "rateLimit": {"count": 1000, "window": 60}
+                })
+                .to_string(),
+            )
+            .create();
+        let remove = server
+            .mock("PUT", "/projects/test-org/api/keys/abc/")
+            .match_body(mockito::Matcher::Json(json!({"rateLimit": null})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "abc",
+                    "name": "Default",
+                    "dsn": {"public": "https://abc@o1.ingest.sentry.io/1"},
+                    "rateLimit": null
+                })
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let limit = KeyRateLimit {
+            count: 1000,
+            window: 60,
+        };
+        let key = client
+            .set_key_rate_limit("test-org", "api", "abc", Some(&limit))?
+            .unwrap();
+        assert_eq!(key.rate_limit, Some(limit));
+        assert_eq!(key.rate_limit.unwrap().to_string(), "1000 events per 60s");
+        let key = client
+            .set_key_rate_limit("test-org", "api", "abc", None)?
+            .unwrap();
+        assert_eq!(key.rate_limit, None);
+
+        mock.assert();
+        remove.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_issue_public() -> Result<()> {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("PUT", "/api/0/issues/123/")
+            .match_header("authorization", "Bearer test-token")
+            .match_body(mockito::Matcher::Json(json!({"isPublic": true})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "123",
+                    "title": "ValueError",
+                    "status": "unresolved",
+                    "level": "error",
+                    "culprit": "app.views",
+                    "lastSeen": "2024-01-02T00:00:00Z",
+                    "count": "5",
+                    "userCount": 1,
+                    "shareId": "b3f9c2"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(format!("{}/api/0", server.url()));
+        client.login("test-token".to_string())?;
+
+        let issue = client.set_issue_public("123", true)?.unwrap();
+        assert_eq!(
+            client.share_url(issue.share_id.as_deref().unwrap()),
+            format!("{}/share/issue/b3f9c2/", server.url())
+        );
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_api_error() -> Result<()> {
+        let mut server = Server::new();
+
+        let expired = server
+            .mock("GET", "/organizations/acme/")
+            .with_status(401)
+            .with_body(json!({"detail": "Invalid token"}).to_string())
+            .create();
+        let limited = server
+            .mock("GET", "/projects/acme/web/?statsPeriod=24h")
+            .with_status(429)
+            .with_header("retry-after", "17")
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let error = client.get_organization("acme").unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<SentryError>(),
+            Some(&SentryError::Unauthorized {
+                status: reqwest::StatusCode::UNAUTHORIZED,
+                org: Some("acme".to_string()),
+                detail: "Invalid token".to_string(),
+            })
+        );
+        assert!(error.to_string().contains("run 'login acme'"));
+
+        let error = client.get_project("acme", "web").unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<SentryError>(),
+            Some(&SentryError::RateLimited {
+                retry_after: Some(17)
+            })
+        );
+
+        assert_eq!(url_path_org("/api/0/issues/42/"), None);
+        expired.assert();
+        limited.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_issue() -> Result<()> {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("PUT", "/issues/123/")
+            .match_header("authorization", "Bearer test-token")
+            .match_body(mockito::Matcher::Json(json!({"status": "resolved"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"id": "123", "status": "resolved"}).to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        client.update_issue("123", &json!({"status": "resolved"}))?;
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_dry_run_sends_nothing() -> Result<()> {
+        let mut server = Server::new();
+
+        let mock = server.mock("PUT", mockito::Matcher::Any).expect(0).create();
+
+        let mut client = SentryClient::with_base_url(server.url()).with_dry_run(true);
+        client.login("test-token".to_string())?;
+        assert!(client.is_dry_run());
+
+        client.update_issue("123", &json!({"status": "resolved"}))?;
+        assert!(client
+            .set_project_bookmark("test-org", "test-project", true)?
+            .is_none());
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_release_refs() -> Result<()> {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("PUT", "/organizations/test-org/releases/app%401.2.0/")
+            .match_header("authorization", "Bearer test-token")
+            .match_body(mockito::Matcher::Json(json!({
+                "refs": [
+                    {"repository": "acme/app", "commit": "def456", "previousCommit": "abc123"},
+                    {"repository": "acme/lib", "commit": "fff000"}
+                ]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "version": "app@1.2.0",
+                    "dateCreated": "2024-01-02T00:00:00Z",
+                    "lastEvent": null,
+                    "commitCount": 7
+                })
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let refs = vec![
+            ReleaseRef {
+                repository: "acme/app".to_string(),
+                commit: "def456".to_string(),
+                previous_commit: Some("abc123".to_string()),
+            },
+            ReleaseRef {
+                repository: "acme/lib".to_string(),
+                commit: "fff000".to_string(),
+                previous_commit: None,
+            },
+        ];
+        let release = client
+            .set_release_refs("test-org", "app@1.2.0", &refs)?
+            .unwrap();
+        assert_eq!(release.commit_count, 7);
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_project_info_with_dsn() -> Result<()> {
+        let mut server = Server::new();
+
+        let project_mock = server
+            .mock("GET", "/projects/test-org/test-project/")
+            .match_query(mockito::Matcher::Any)
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"slug": "test-project", "name": "Test Project"}).to_string())
+            .create();
+
+        let keys_mock = server
+            .mock("GET", "/projects/test-org/test-project/keys/")
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([
+                    {
+                        "id": "a1",
+                        "name": "Default",
+                        "isActive": true,
+                        "dsn": {"public": "https://abc@o1.ingest.sentry.io/1"}
+                    },
+                    {
+                        "id": "b2",
+                        "name": "Revoked",
+                        "isActive": false,
+                        "dsn": {"public": "https://def@o1.ingest.sentry.io/1"}
+                    }
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let info = client.get_project_info("test-org", "test-project", true)?;
+        let dsns: Vec<&(String, String)> =
+            info.iter().filter(|(k, _)| k.starts_with("DSN")).collect();
+        assert_eq!(dsns.len(), 1);
+        assert_eq!(dsns[0].0, "DSN (Default)");
+        assert_eq!(dsns[0].1, "https://abc@o1.ingest.sentry.io/1");
+
+        project_mock.assert();
+        keys_mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_project_events() -> Result<()> {
+        let mut server = Server::new();
+        let mock_response = json!([
+            {
+                "eventID": "abc123",
+                "groupID": "7",
+                "title": "TypeError: x is undefined",
+                "dateCreated": "2024-01-02T00:00:00Z",
+                "tags": [{"key": "level", "value": "warning"}],
+                "user": {"id": "42", "email": null, "username": "jane", "ip_address": null}
+            }
+        ]);
+
+        let mock = server
+            .mock("GET", "/projects/test-org/test-project/events/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "query".into(),
+                "browser:Chrome".into(),
+            ))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let events =
+            client.list_project_events("test-org", "test-project", "browser:Chrome", 100)?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_id, "abc123");
+        assert_eq!(events[0].level(), "warning");
+        assert_eq!(events[0].user_label(), Some("jane"));
+        assert_eq!(
+            client.event_url("test-org", &events[0]),
+            format!(
+                "{}/organizations/test-org/issues/7/events/abc123/",
+                server.url()
+            )
+        );
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_project_event() -> Result<()> {
+        let mut server = Server::new();
+        let mock_response = json!({
+            "eventID": "abc123",
+            "title": "ValueError: bad input",
+            "dateCreated": "2024-01-02T00:00:00Z",
+            "tags": [{"key": "level", "value": "error"}],
+            "user": {"id": "42", "email": "jane@example.com", "ip_address": null, "username": "jane"},
+            "contexts": {
+                "runtime": {"name": "CPython", "version": "3.11.4", "type": "runtime"},
+                "os": {"name": "Linux", "type": "os"},
+                "trace": {"trace_id": "abc"}
+            },
+            "entries": [
+                {"type": "breadcrumbs", "data": {"values": []}},
+                {
+                    "type": "exception",
+                    "data": {
+                        "values": [{
+                            "type": "ValueError",
+                            "value": "bad input",
+                            "stacktrace": {
+                                "frames": [
+                                    {"filename": "django/core/handlers.py", "function": "inner", "lineNo": 47, "inApp": false},
+                                    {"filename": "app/views.py", "function": "checkout", "lineNo": 12, "inApp": true}
+                                ]
+                            }
+                        }]
+                    }
+                }
+            ]
+        });
+
+        let mock = server
+            .mock("GET", "/projects/test-org/test-project/events/abc123/")
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let event = client.get_project_event("test-org", "test-project", "abc123")?;
+        let exceptions = event.exceptions();
+        assert_eq!(exceptions.len(), 1);
+        assert_eq!(exceptions[0].kind.as_deref(), Some("ValueError"));
+        let frames = &exceptions[0].stacktrace.as_ref().unwrap().frames;
+        assert_eq!(frames[1].function.as_deref(), Some("checkout"));
+        assert_eq!(frames[1].in_app, Some(true));
+        assert_eq!(
+            event.crash_frame().and_then(|f| f.filename),
+            Some("app/views.py".to_string())
+        );
+        assert_eq!(
+            event.context_summary(),
+            vec![
+                ("OS", "Linux".to_string()),
+                ("Runtime", "CPython 3.11.4".to_string())
+            ]
+        );
+        assert_eq!(
+            event.user.unwrap().summary(),
+            "id 42, jane@example.com, jane"
+        );
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_event_request() {
+        let event: EventDetails = serde_json::from_value(json!({
+            "eventID": "abc123",
+            "dateCreated": "2024-01-02T00:00:00Z",
+            "entries": [{
+                "type": "request",
+                "data": {
+                    "method": "POST",
+                    "url": "https://shop.example.com/api/orders?api_key=k-123&page=2",
+                    "query": [["page", "2"], ["access_token", "abc"]],
+                    "headers": [
+                        ["Content-Type", "application/json"],
+                        ["Authorization", "Bearer secret"],
+                        ["Cookie", "sessionid=abc"],
+                        ["X-Api-Key", "k-123"]
+                    ]
+                }
+            }]
+        }))
+        .unwrap();
+
+        let request = event.request().unwrap();
+        assert_eq!(
+            request.lines(),
+            vec![
+                "POST https://shop.example.com/api/orders?api_key=[masked]&page=2",
+                "Query: page=2&access_token=[masked]",
+                "Content-Type: application/json",
+                "Authorization: [masked]",
+                "Cookie: [masked]",
+                "X-Api-Key: [masked]",
+            ]
+        );
+
+        assert_eq!(
+            key_values(&json!("a=1&b")),
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), String::new())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_condition() -> Result<()> {
+        let condition = IgnoreCondition::parse("2w 1000 events in 1h")?;
+        assert_eq!(
+            condition,
+            IgnoreCondition {
+                duration: Some(20160),
+                count: Some(1000),
+                user_count: None,
+                window: Some(60),
+            }
+        );
+        assert_eq!(
+            condition.changes(),
+            json!({
+                "status": "ignored",
+                "statusDetails": {"ignoreDuration": 20160, "ignoreCount": 1000, "ignoreWindow": 60}
+            })
+        );
+        assert_eq!(
+            condition.to_string(),
+            "for 2w or until 1000 more events in 1h"
+        );
+
+        let users = IgnoreCondition::parse("50 users")?;
+        assert_eq!(
+            users.changes(),
+            json!({"status": "ignored", "statusDetails": {"ignoreUserCount": 50}})
+        );
+        assert_eq!(
+            IgnoreCondition::default().changes(),
+            json!({"status": "ignored", "statusDetails": {}})
+        );
+        assert!(IgnoreCondition::parse("1000").is_err());
+        assert!(IgnoreCondition::parse("soon").is_err());
+        assert!(IgnoreCondition::parse("2w in 1h").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_issue_activities() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/issues/123/activities/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"activity": [
+                    {"type": "set_regression", "data": {"version": "app@1.3.0"}, "dateCreated": "2024-01-05T00:00:00Z"},
+                    {"type": "set_resolved_in_release", "data": {"version": "app@1.2.0"}, "dateCreated": "2024-01-02T00:00:00Z"}
+                ]})
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let activities = client.list_issue_activities("123")?;
+        assert_eq!(activities.len(), 2);
+        assert_eq!(activities[1].kind, "set_resolved_in_release");
+        assert_eq!(activities[1].data["version"], "app@1.2.0");
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_grouping_info() -> Result<()> {
+        let mut server = Server::new();
+        let hashes_mock = server
+            .mock("GET", "/issues/123/hashes/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!([{"id": "a1b2"}, {"id": "c3d4"}]).to_string())
+            .create();
+        let grouping_mock = server
+            .mock("GET", "/projects/test-org/api/events/abc123/grouping-info/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "app": {
+                        "type": "component",
+                        "description": "in-app exception stack-trace",
+                        "hash": "a1b2",
+                        "contributes": true,
+                        "hint": null,
+                        "config": {"id": "newstyle:2023-01-11"}
+                    },
+                    "system": {
+                        "type": "component",
+                        "description": "exception stack-trace",
+                        "hash": null,
+                        "contributes": false,
+                        "hint": "ignored because app variant is used",
+                        "config": {"id": "newstyle:2023-01-11"}
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let issue: Issue = serde_json::from_value(json!({
+            "id": "123",
+            "title": "Checkout failed",
+            "status": "unresolved",
+            "level": "error",
+            "culprit": "app.views",
+            "lastSeen": "2024-01-02T00:00:00Z",
+            "count": "5",
+            "userCount": 1,
+            "metadata": {"type": "ValueError", "value": "bad input", "function": "checkout"}
+        }))?;
+        let info = GroupingInfo {
+            issue: &issue,
+            hashes: client.list_issue_hashes("123")?,
+            variants: client.get_grouping_info("test-org", "api", "abc123")?,
+        };
+        assert_eq!(
+            info.lines(),
+            vec![
+                "Title: Checkout failed",
+                "Metadata: ValueError: bad input",
+                "  The title differs from the metadata, e.g. set by a fingerprint rule",
+                "Location: checkout",
+                "Grouping config: newstyle:2023-01-11",
+                "Hashes merged into the issue: 2",
+                "  a1b2",
+                "  c3d4",
+                "Variants of the latest event:",
+                "  app (contributes): a1b2",
+                "    in-app exception stack-trace",
+                "  system (ignored): no hash",
+                "    exception stack-trace",
+                "    ignored because app variant is used",
+            ]
+        );
+
+        hashes_mock.assert();
+        grouping_mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_issue_users() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/issues/123/tags/user/values/")
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([
+                    {"value": "ip:10.0.0.1", "count": 2, "ipAddress": "10.0.0.1"},
+                    {"value": "id:42", "count": 9, "email": "jane@example.com", "lastSeen": "2024-01-02T00:00:00Z"}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let users = client.list_issue_users("123")?;
+        assert_eq!(users[0].label(), "jane@example.com");
+        assert_eq!(users[1].label(), "ip:10.0.0.1");
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_event_committers() -> Result<()> {
+        let mut server = Server::new();
+        let mock_response = json!({
+            "committers": [{
+                "author": {"name": "Jane Doe", "email": "jane@example.com"},
+                "commits": [{
+                    "id": "4f2e1a9c0d3b",
+                    "message": "Handle empty carts",
+                    "dateCreated": "2024-01-01T00:00:00Z"
+                }]
+            }]
+        });
+
+        let mock = server
+            .mock(
+                "GET",
+                "/projects/test-org/test-project/events/abc123/committers/",
+            )
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let committers = client.list_event_committers("test-org", "test-project", "abc123")?;
+        assert_eq!(committers.len(), 1);
+        assert_eq!(committers[0].commits[0].id, "4f2e1a9c0d3b");
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_trace() -> Result<()> {
+        let mut server = Server::new();
+        let mock_response = json!({
+            "transactions": [
+                {
+                    "event_id": "root",
+                    "transaction": "GET /checkout",
+                    "project_slug": "frontend",
+                    "transaction.op": "http.server",
+                    "start_timestamp": 100.0,
+                    "timestamp": 101.5,
+                    "errors": [],
+                    "children": [
+                        {
+                            "event_id": "child",
+                            "transaction": "charge_card",
+                            "project_slug": "payments",
+                            "start_timestamp": 100.2,
+                            "timestamp": 101.0,
+                            "errors": [{"event_id": "err", "title": "Timeout"}]
+                        }
+                    ]
+                }
+            ],
+            "orphan_errors": []
+        });
+
+        let mock = server
+            .mock("GET", "/organizations/test-org/events-trace/abc123/")
+            .match_query(mockito::Matcher::Any)
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let trace = client.get_trace("test-org", "abc123")?;
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].op, "http.server");
+        assert_eq!(trace[0].children[0].project_slug, "payments");
+        assert_eq!(trace[0].children[0].errors[0].title, "Timeout");
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_project_event_stats() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/projects/test-org/test-project/stats/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("stat".into(), "received".into()),
+                mockito::Matcher::UrlEncoded("since".into(), "1700000000".into()),
+                mockito::Matcher::UrlEncoded("resolution".into(), "1d".into()),
+            ]))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!([[1700006400, 12], [1700092800, 0]]).to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let buckets =
+            client.get_project_event_stats("test-org", "test-project", 1_700_000_000, "1d")?;
+        assert_eq!(buckets, vec![(1700006400, 12), (1700092800, 0)]);
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_token_scopes() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/")
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "version": "0",
+                    "auth": {"scopes": ["org:read", "event:read"]},
+                    "user": null
+                })
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        assert_eq!(client.get_token_scopes()?, vec!["org:read", "event:read"]);
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_current_user() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/")
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "version": "0",
+                    "auth": {"scopes": ["event:read"]},
+                    "user": {"id": "42", "username": "jane", "email": "jane@example.com"}
+                })
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let user = client.get_current_user()?;
+        assert_eq!(user.username, "jane");
+        assert_eq!(user.email, "jane@example.com");
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_organization() -> Result<()> {
+        let mut server = Server::new();
+        let found = server
+            .mock("GET", "/organizations/acme/")
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"slug": "acme", "name": "Acme Inc."}).to_string())
+            .create();
+        let missing = server
+            .mock("GET", "/organizations/acmee/")
+            .with_status(404)
+            .with_body(json!({"detail": "The requested resource does not exist"}).to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        assert_eq!(client.get_organization("acme")?.name, "Acme Inc.");
+        let error = client.get_organization("acmee").unwrap_err();
+        assert!(error.to_string().starts_with("API request failed: 404"));
+        assert!(matches!(
+            error.downcast_ref::<SentryError>(),
+            Some(SentryError::NotFound { .. })
+        ));
+
+        found.assert();
+        missing.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_organization_details() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/organizations/acme/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "slug": "acme",
+                    "name": "Acme Inc.",
+                    "dateCreated": "2020-01-01T00:00:00Z",
+                    "features": ["discover", "performance-view"],
+                    "defaultRole": "member",
+                    "openMembership": true,
+                    "require2FA": false,
+                    "trustedRelays": [{
+                        "name": "relay-eu",
+                        "publicKey": "abc123",
+                        "description": null,
+                        "created": "2021-01-01T00:00:00Z"
+                    }]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let org = client.get_organization_details("acme")?;
+        assert_eq!(org.features, vec!["discover", "performance-view"]);
+        assert_eq!(org.default_role.as_deref(), Some("member"));
+        assert!(org.open_membership);
+        assert!(!org.require_2fa);
+        assert_eq!(org.trusted_relays[0].public_key, "abc123");
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_org_usage() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/organizations/test-org/stats_v2/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("field".into(), "sum(quantity)".into()),
+                mockito::Matcher::UrlEncoded("statsPeriod".into(), "30d".into()),
+            ]))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "intervals": [],
+                    "groups": [
+                        {
+                            "by": {"category": "error", "outcome": "accepted"},
+                            "totals": {"sum(quantity)": 4200},
+                            "series": {}
+                        }
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
 
-            all_projects.append(&mut page_projects);
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
 
-            if cursor.is_none() {
-                break;
-            }
-        }
+        let groups = client.get_org_usage("test-org", "30d")?;
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].by.category, "error");
+        assert_eq!(groups[0].totals.quantity, 4200);
 
-        // Sort projects by name
-        all_projects.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-        Ok(all_projects)
+        mock.assert();
+        Ok(())
     }
 
-    pub fn list_issues(&self, org_slug: &str, project_slug: &str) -> Result<Vec<Issue>> {
-        let url = format!(
-            "{}/projects/{}/{}/issues/?statsPeriod=14d&query=is:unresolved&sort=date",
-            self.base_url, org_slug, project_slug
-        );
+    #[test]
+    fn test_get_release_crash_free_rate() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/organizations/test-org/sessions/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("field".into(), "crash_free_rate(session)".into()),
+                mockito::Matcher::UrlEncoded("project".into(), "42".into()),
+                mockito::Matcher::UrlEncoded("query".into(), "release:\"app@1.2.0\"".into()),
+                mockito::Matcher::UrlEncoded("statsPeriod".into(), "30m".into()),
+            ]))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "groups": [
+                        {"by": {}, "totals": {"crash_free_rate(session)": 0.985}, "series": {}}
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
 
-        let response = self
-            .client
-            .get(&url)
-            .headers(self.get_headers()?)
-            .send()
-            .context("Failed to send request")?;
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
 
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "API request failed: {} - {}",
-                response.status(),
-                response.text()?
-            ));
-        }
+        let rate = client.get_release_crash_free_rate("test-org", "42", "app@1.2.0", "30m")?;
+        assert_eq!(rate, Some(0.985));
 
-        response
-            .json::<Vec<Issue>>()
-            .context("Failed to parse response")
+        mock.assert();
+        Ok(())
     }
 
-    pub fn get_project_info(
-        &self,
-        org_slug: &str,
-        project_slug: &str,
-    ) -> Result<Vec<(String, String)>> {
-        let url = format!(
-            "{}/projects/{}/{}/?statsPeriod=24h",
-            self.base_url, org_slug, project_slug
-        );
+    #[test]
+    fn test_get_rate_limits() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/organizations/test-org/projects/")
+            .match_query(mockito::Matcher::UrlEncoded("per_page".into(), "1".into()))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("X-Sentry-Rate-Limit-Limit", "40")
+            .with_header("X-Sentry-Rate-Limit-Remaining", "39")
+            .with_header("X-Sentry-Rate-Limit-Reset", "1700000001")
+            .with_header("X-Sentry-Rate-Limit-ConcurrentLimit", "25")
+            .with_header("X-Sentry-Rate-Limit-ConcurrentRemaining", "24")
+            .with_body("[]")
+            .create();
 
-        let response = self
-            .client
-            .get(&url)
-            .headers(self.get_headers()?)
-            .send()
-            .context("Failed to send request")?;
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
 
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "API request failed: {} - {}",
-                response.status(),
-                response.text()?
-            ));
-        }
+        let limits = client.get_rate_limits("test-org")?;
+        assert_eq!(
+            limits,
+            RateLimitStatus {
+                limit: Some(40),
+                remaining: Some(39),
+                reset: Some(1700000001),
+                concurrent_limit: Some(25),
+                concurrent_remaining: Some(24),
+            }
+        );
 
-        let project: Project = response.json().context("Failed to parse response")?;
+        mock.assert();
+        Ok(())
+    }
 
-        // Collect project information
-        let mut info = Vec::new();
-        info.push(("Name".to_string(), project.name));
-        info.push(("Slug".to_string(), project.slug));
-        if let Some(platform) = project.platform {
-            info.push(("Platform".to_string(), platform));
-        }
-        if !project.status.is_empty() {
-            info.push(("Status".to_string(), project.status));
-        }
-        if let Some(first) = project.first_event {
-            info.push(("First Event".to_string(), first));
-        }
-        if let Some(last) = project.last_event {
-            info.push(("Last Event".to_string(), last));
-        }
-        if let Some(teams) = project.teams {
-            let team_names = teams
-                .iter()
-                .map(|t| t.name.clone())
-                .collect::<Vec<_>>()
-                .join(", ");
-            info.push(("Teams".to_string(), team_names));
-        }
+    #[test]
+    fn test_get_project_totals() -> Result<()> {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/organizations/test-org/events/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("dataset".into(), "errors".into()),
+                mockito::Matcher::UrlEncoded("query".into(), "project:test-project".into()),
+                mockito::Matcher::UrlEncoded("statsPeriodStart".into(), "14d".into()),
+                mockito::Matcher::UrlEncoded("statsPeriodEnd".into(), "7d".into()),
+            ]))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "data": [{"count()": 120, "count_unique(issue)": 4, "count_unique(user)": 30}],
+                    "meta": {}
+                })
+                .to_string(),
+            )
+            .create();
 
-        // Add stats if available
-        if let Some(stats) = project.stats {
-            let total_24h: i64 = stats.last_24h.iter().map(|(_, count)| count).sum();
-            let total_30d: i64 = stats.last_30d.iter().map(|(_, count)| count).sum();
-            info.push(("Events (24h)".to_string(), total_24h.to_string()));
-            info.push(("Events (30d)".to_string(), total_30d.to_string()));
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
 
-            // Calculate daily average for last 30 days
-            let avg_30d = total_30d as f64 / 30.0;
-            info.push(("Daily Average (30d)".to_string(), format!("{:.1}", avg_30d)));
-        }
+        let totals = client.get_project_totals("test-org", "test-project", "14d", Some("7d"))?;
+        assert_eq!(
+            totals,
+            ProjectTotals {
+                events: 120,
+                issues: 4,
+                users: 30
+            }
+        );
 
-        Ok(info)
+        mock.assert();
+        Ok(())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use mockito::Server;
-    use serde_json::json;
 
     #[test]
-    fn test_client_creation() {
-        let server = Server::new();
-        let mut client = SentryClient::new().unwrap();
-        client.base_url = server.url();
-        assert!(client.auth_token.is_none());
+    fn test_list_profile_functions() -> Result<()> {
+        let mut server = Server::new();
+        let mock_response = json!({
+            "data": [
+                {
+                    "function": "parse_json",
+                    "package": "serde_json",
+                    "count()": 420,
+                    "p75()": 1500000.0,
+                    "sum()": 900000000.0
+                }
+            ],
+            "meta": {}
+        });
+
+        let mock = server
+            .mock("GET", "/organizations/test-org/events/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("dataset".into(), "profileFunctions".into()),
+                mockito::Matcher::UrlEncoded("query".into(), "project:test-project".into()),
+                mockito::Matcher::UrlEncoded("sort".into(), "-sum()".into()),
+            ]))
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let functions = client.list_profile_functions("test-org", "test-project", "24h", 10)?;
+        assert_eq!(functions.len(), 1);
+        assert_eq!(functions[0].function, "parse_json");
+        assert_eq!(functions[0].count, 420);
+        assert_eq!(functions[0].p75, 1500000.0);
+
+        mock.assert();
+        Ok(())
     }
 
     #[test]
-    fn test_login() {
-        let mut client = SentryClient::new().unwrap();
-        client.login("test-token".to_string()).unwrap();
-        assert_eq!(client.auth_token, Some("test-token".to_string()));
+    fn test_get_transaction_performance() -> Result<()> {
+        let mut server = Server::new();
+        let transactions = server
+            .mock("GET", "/organizations/test-org/events/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex("^field=transaction&".into()),
+                mockito::Matcher::UrlEncoded("sort".into(), "-count()".into()),
+                mockito::Matcher::UrlEncoded(
+                    "query".into(),
+                    "event.type:transaction project:test-project".into(),
+                ),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "data": [{
+                        "transaction": "GET /checkout",
+                        "count()": 1200,
+                        "apdex()": 0.82,
+                        "failure_rate()": 0.05,
+                        "p95(transaction.duration)": 840.0
+                    }]
+                })
+                .to_string(),
+            )
+            .create();
+        // Without the transaction field the metrics cover the whole project
+        let overall = server
+            .mock("GET", "/organizations/test-org/events/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex("^field=count".into()),
+                mockito::Matcher::UrlEncoded("statsPeriod".into(), "7d".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "data": [{
+                        "count()": 5000,
+                        "apdex()": 0.91,
+                        "failure_rate()": 0.02,
+                        "p95(transaction.duration)": 420.0
+                    }]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let (transactions_result, overall_result) =
+            client.get_transaction_performance("test-org", "test-project", "7d", 10)?;
+        assert_eq!(transactions_result.len(), 1);
+        assert_eq!(transactions_result[0].transaction, "GET /checkout");
+        assert_eq!(transactions_result[0].p95, 840.0);
+        assert_eq!(overall_result.transaction, "");
+        assert_eq!(overall_result.count, 5000);
+        assert_eq!(overall_result.apdex, 0.91);
+
+        transactions.assert();
+        overall.assert();
+        Ok(())
     }
 
     #[test]
-    fn test_list_projects() -> Result<()> {
+    fn test_assemble_difs() -> Result<()> {
         let mut server = Server::new();
-        let mock_response = json!([
-            {
-                "slug": "test-project",
-                "name": "Test Project"
-            },
-            {
-                "slug": "another-project",
-                "name": "Another Project"
-            }
-        ]);
+        let mock_response = json!({
+            "aaa": {"state": "OK", "missingChunks": []},
+            "bbb": {"state": "NOT_FOUND", "missingChunks": ["c1"]}
+        });
 
         let mock = server
-            .mock("GET", "/organizations/test-org/projects/")
+            .mock(
+                "POST",
+                "/projects/test-org/test-project/files/difs/assemble/",
+            )
             .match_header("authorization", "Bearer test-token")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "bbb": {"name": "libapp.so", "chunks": ["c1"]}
+            })))
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(mock_response.to_string())
             .create();
 
-        let mut client = SentryClient {
-            client: Client::new(),
-            base_url: server.url(),
-            auth_token: None,
-        };
+        let mut client = SentryClient::with_base_url(server.url());
         client.login("test-token".to_string())?;
 
-        let projects = client.list_projects("test-org")?;
-        assert_eq!(projects.len(), 2);
-        assert_eq!(projects[0].slug, "test-project");
-        assert_eq!(projects[0].name, "Test Project");
-        assert_eq!(projects[1].slug, "another-project");
-        assert_eq!(projects[1].name, "Another Project");
+        let files: HashMap<String, AssembleRequest> = [
+            (
+                "aaa".to_string(),
+                AssembleRequest {
+                    name: "App".to_string(),
+                    chunks: vec!["c0".to_string()],
+                },
+            ),
+            (
+                "bbb".to_string(),
+                AssembleRequest {
+                    name: "libapp.so".to_string(),
+                    chunks: vec!["c1".to_string()],
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let statuses = client.assemble_difs("test-org", "test-project", &files)?;
+        assert_eq!(statuses["aaa"].state, "OK");
+        assert_eq!(statuses["bbb"].missing_chunks, vec!["c1"]);
 
         mock.assert();
         Ok(())
     }
 
     #[test]
-    fn test_list_projects_unauthorized() -> Result<()> {
+    fn test_upload_chunks() -> Result<()> {
         let mut server = Server::new();
-
         let mock = server
-            .mock("GET", "/organizations/test-org/projects/")
+            .mock("POST", "/api/0/organizations/test-org/chunk-upload/")
             .match_header("authorization", "Bearer test-token")
-            .with_status(401)
-            .with_header("content-type", "application/json")
-            .with_body(json!({"error": "Unauthorized"}).to_string())
+            .match_body(mockito::Matcher::Regex("filename=\"c1\"".to_string()))
+            .with_status(200)
             .create();
 
-        let mut client = SentryClient {
-            client: Client::new(),
-            base_url: server.url(),
-            auth_token: None,
-        };
+        let mut client = SentryClient::with_base_url(format!("{}/api/0", server.url()));
         client.login("test-token".to_string())?;
 
-        let result = client.list_projects("test-org");
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("API request failed: 401"));
+        client.upload_chunks(
+            "/api/0/organizations/test-org/chunk-upload/",
+            &[("c1".to_string(), b"data".to_vec())],
+        )?;
 
         mock.assert();
         Ok(())
     }
 
     #[test]
-    fn test_list_issues() -> Result<()> {
+    fn test_list_releases() -> Result<()> {
         let mut server = Server::new();
         let mock_response = json!([
             {
-                "id": "1",
-                "title": "Test Issue",
-                "status": "unresolved",
-                "level": "error",
-                "culprit": "test.js:42",
-                "lastSeen": "2024-01-01T00:00:00Z",
-                "count": 5,
-                "userCount": 3
+                "version": "1.2.0",
+                "dateCreated": "2024-01-02T00:00:00Z",
+                "newGroups": 3,
+                "lastEvent": null
             }
         ]);
 
         let mock = server
-            .mock("GET", "/projects/test-org/test-project/issues/")
-            .match_query(mockito::Matcher::AllOf(vec![
-                mockito::Matcher::UrlEncoded("statsPeriod".into(), "14d".into()),
-                mockito::Matcher::UrlEncoded("query".into(), "is:unresolved".into()),
-                mockito::Matcher::UrlEncoded("sort".into(), "date".into()),
-            ]))
+            .mock("GET", "/projects/test-org/test-project/releases/")
+            .match_query(mockito::Matcher::Any)
             .match_header("authorization", "Bearer test-token")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(mock_response.to_string())
             .create();
 
-        let mut client = SentryClient {
-            client: Client::new(),
-            base_url: server.url(),
-            auth_token: None,
-        };
+        let mut client = SentryClient::with_base_url(server.url());
         client.login("test-token".to_string())?;
 
-        let issues = client.list_issues("test-org", "test-project")?;
-        assert_eq!(issues.len(), 1);
-        assert_eq!(issues[0].id, "1");
-        assert_eq!(issues[0].title, "Test Issue");
-        assert_eq!(issues[0].status, "unresolved");
-        assert_eq!(issues[0].level, "error");
-        assert_eq!(issues[0].count, 5);
-        assert_eq!(issues[0].user_count, 3);
+        let releases = client.list_releases("test-org", "test-project")?;
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].version, "1.2.0");
+        assert_eq!(releases[0].new_groups, 3);
 
         mock.assert();
         Ok(())
     }
 
     #[test]
-    fn test_list_issues_not_found() -> Result<()> {
+    fn test_get_project_ownership() -> Result<()> {
         let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/projects/test-org/test-project/ownership/")
+            .match_header("authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "raw": "# Billing\npath:src/billing/* #billing jane@example.com\n\ntags.browser:Chrome* #web\n",
+                    "fallthrough": true,
+                    "autoAssignment": "Auto Assign to Issue Owner"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let mut client = SentryClient::with_base_url(server.url());
+        client.login("test-token".to_string())?;
+
+        let ownership = client.get_project_ownership("test-org", "test-project")?;
+        assert!(ownership.fallthrough);
+        assert_eq!(
+            ownership.rules(),
+            vec![
+                OwnershipRule {
+                    kind: "path".to_string(),
+                    pattern: "src/billing/*".to_string(),
+                    owners: vec!["#billing".to_string(), "jane@example.com".to_string()],
+                },
+                OwnershipRule {
+                    kind: "tags.browser".to_string(),
+                    pattern: "Chrome*".to_string(),
+                    owners: vec!["#web".to_string()],
+                },
+            ]
+        );
+
+        mock.assert();
+        Ok(())
+    }
 
+    #[test]
+    fn test_get_latest_release() -> Result<()> {
+        let mut server = Server::new();
         let mock = server
-            .mock("GET", "/projects/test-org/nonexistent-project/issues/")
-            .match_query(mockito::Matcher::AllOf(vec![
-                mockito::Matcher::UrlEncoded("statsPeriod".into(), "14d".into()),
-                mockito::Matcher::UrlEncoded("query".into(), "is:unresolved".into()),
-                mockito::Matcher::UrlEncoded("sort".into(), "date".into()),
-            ]))
+            .mock("GET", "/projects/test-org/test-project/releases/")
+            .match_query(mockito::Matcher::Any)
             .match_header("authorization", "Bearer test-token")
-            .with_status(404)
+            .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(json!({"error": "Project not found"}).to_string())
+            .with_body(
+                json!([
+                    {"version": "1.1.0", "dateCreated": "2024-01-01T00:00:00Z"},
+                    {"version": "1.2.0", "dateCreated": "2024-01-02T00:00:00Z"},
+                    {"version": "1.0.0", "dateCreated": "2023-12-01T00:00:00Z"}
+                ])
+                .to_string(),
+            )
             .create();
 
-        let mut client = SentryClient {
-            client: Client::new(),
-            base_url: server.url(),
-            auth_token: None,
-        };
+        let mut client = SentryClient::with_base_url(server.url());
         client.login("test-token".to_string())?;
 
-        let result = client.list_issues("test-org", "nonexistent-project");
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("API request failed: 404"));
+        let release = client.get_latest_release("test-org", "test-project")?;
+        assert_eq!(release.map(|r| r.version), Some("1.2.0".to_string()));
 
         mock.assert();
         Ok(())
@@ -0,0 +1,574 @@
+use crate::timing;
+use anyhow::Result;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const ELLIPSIS: char = '…';
+const COLUMN_GAP: &str = "  ";
+/// Width a flexible column keeps before lower-priority columns are dropped for it.
+const FLEX_MIN_WIDTH: usize = 30;
+/// Starts and ends an OSC 8 hyperlink: `OSC8_START url OSC8_END text OSC8_START OSC8_END`.
+const OSC8_START: &str = "\x1b]8;;";
+const OSC8_END: &str = "\x1b\\";
+/// `TERM_PROGRAM` values of terminals known to render OSC 8 hyperlinks.
+const HYPERLINK_TERMINALS: &[&str] = &["iTerm.app", "WezTerm", "vscode", "Hyper", "ghostty"];
+/// Variables only set inside terminals known to render OSC 8 hyperlinks.
+const HYPERLINK_TERMINAL_VARS: &[&str] = &["WT_SESSION", "KITTY_WINDOW_ID", "KONSOLE_VERSION"];
+
+/// Whether [`link`] emits hyperlinks; set once at startup from `--hyperlinks`.
+static HYPERLINKS: AtomicBool = AtomicBool::new(false);
+/// Whether [`format_count`] prints counts in full; set once at startup from `--exact`.
+static EXACT_COUNTS: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// A table column that knows how to extract its cell value from a row of type `T`.
+pub struct Column<T> {
+    pub name: &'static str,
+    pub header: &'static str,
+    pub max_width: Option<usize>,
+    pub align: Align,
+    /// Columns with a higher priority are dropped first on narrow terminals; 0 is never dropped
+    pub priority: u8,
+    /// Takes the width left over by other columns when the table fits a terminal
+    pub flex: bool,
+    pub value: fn(&T) -> String,
+}
+
+impl<T> Column<T> {
+    pub fn new(name: &'static str, header: &'static str, value: fn(&T) -> String) -> Self {
+        Self {
+            name,
+            header,
+            max_width: None,
+            align: Align::Left,
+            priority: 0,
+            flex: false,
+            value,
+        }
+    }
+
+    pub fn max_width(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+
+    pub fn align_right(mut self) -> Self {
+        self.align = Align::Right;
+        self
+    }
+
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Lets the column grow past its `max_width` on wide terminals and shrink on narrow ones.
+    pub fn flex(mut self) -> Self {
+        self.flex = true;
+        self
+    }
+}
+
+/// Renders rows as an aligned table, measuring cells by display width so that
+/// wide unicode characters (CJK, emoji) do not break column alignment.
+pub struct Table<T> {
+    columns: Vec<Column<T>>,
+    indent: usize,
+    /// Terminal width to fit; without one, columns are only capped by their `max_width`
+    width: Option<usize>,
+}
+
+impl<T> Table<T> {
+    pub fn new(columns: Vec<Column<T>>) -> Self {
+        Self {
+            columns,
+            indent: 0,
+            width: None,
+        }
+    }
+
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Fits the table into `width` columns: flexible columns take the room that is left,
+    /// and low-priority columns are dropped when even that is not enough.
+    pub fn fit_width(mut self, width: Option<usize>) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn render(&self, rows: &[T]) -> String {
+        timing::time(
+            "table",
+            || format!("{} rows", rows.len()),
+            || self.render_rows(rows),
+        )
+    }
+
+    fn render_rows(&self, rows: &[T]) -> String {
+        let mut cells: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| {
+                self.columns
+                    .iter()
+                    .map(|column| {
+                        let value = (column.value)(row);
+                        match column.max_width {
+                            // Flexible columns are sized below once the width is known
+                            Some(_) if column.flex && self.width.is_some() => value,
+                            Some(width) => truncate(&value, width),
+                            None => value,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                cells
+                    .iter()
+                    .map(|row| display_width(&row[i]))
+                    .chain(std::iter::once(column.header.width()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut visible: Vec<usize> = (0..self.columns.len()).collect();
+        if let Some(width) = self.width {
+            self.fit(width, &mut visible, &mut widths);
+            for row in &mut cells {
+                for &i in &visible {
+                    row[i] = truncate(&row[i], widths[i]);
+                }
+            }
+        }
+
+        let headers: Vec<String> = self.columns.iter().map(|c| c.header.to_string()).collect();
+        let mut output = self.render_line(&headers, &widths, &visible);
+        for row in &cells {
+            output.push_str(&self.render_line(row, &widths, &visible));
+        }
+        output
+    }
+
+    /// Drops columns and shrinks flexible ones until the visible columns fit `width`.
+    fn fit(&self, width: usize, visible: &mut Vec<usize>, widths: &mut [usize]) {
+        let header = |i: usize| self.columns[i].header.width();
+        // Width of the visible columns with flexible ones at `flex_width(i)`
+        let total = |visible: &[usize], flex_width: &dyn Fn(usize) -> usize| {
+            self.indent
+                + visible
+                    .iter()
+                    .map(|&i| match self.columns[i].flex {
+                        true => flex_width(i),
+                        false => widths[i],
+                    })
+                    .sum::<usize>()
+                + COLUMN_GAP.len() * visible.len().saturating_sub(1)
+        };
+
+        let flex_min = |i: usize| widths[i].min(FLEX_MIN_WIDTH.max(header(i)));
+        while total(visible, &flex_min) > width {
+            // The highest priority goes first, the rightmost of equal ones
+            let Some(position) = (0..visible.len())
+                .filter(|&p| self.columns[visible[p]].priority > 0)
+                .max_by_key(|&p| (self.columns[visible[p]].priority, p))
+            else {
+                break;
+            };
+            visible.remove(position);
+        }
+
+        // Share what is left among flexible columns, never beyond their content
+        let flexible: Vec<usize> = visible
+            .iter()
+            .copied()
+            .filter(|&i| self.columns[i].flex)
+            .collect();
+        if flexible.is_empty() {
+            return;
+        }
+        let fixed = total(visible, &|_| 0);
+        let share = width.saturating_sub(fixed) / flexible.len();
+        for i in flexible {
+            widths[i] = widths[i].min(share.max(header(i)));
+        }
+    }
+
+    fn render_line(&self, cells: &[String], widths: &[usize], visible: &[usize]) -> String {
+        let line = visible
+            .iter()
+            .map(|&i| pad(&cells[i], widths[i], self.columns[i].align))
+            .collect::<Vec<_>>()
+            .join(COLUMN_GAP);
+        format!("{}{}\n", " ".repeat(self.indent), line.trim_end())
+    }
+}
+
+/// Width of the terminal stdout is attached to, or `None` when piped.
+pub fn terminal_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+}
+
+/// Picks and orders columns by name. An empty selection keeps the given defaults.
+pub fn select_columns<T>(
+    available: Vec<Column<T>>,
+    selection: &[String],
+    defaults: &[&str],
+) -> Result<Vec<Column<T>>> {
+    let names: Vec<&str> = if selection.is_empty() {
+        defaults.to_vec()
+    } else {
+        selection.iter().map(|s| s.trim()).collect()
+    };
+
+    let mut available: Vec<Option<Column<T>>> = available.into_iter().map(Some).collect();
+    let mut selected = Vec::new();
+    for name in names {
+        let index = available
+            .iter()
+            .position(|c| c.as_ref().is_some_and(|c| c.name == name))
+            .ok_or_else(|| {
+                let known = available
+                    .iter()
+                    .flatten()
+                    .map(|c| c.name)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                anyhow::anyhow!(
+                    "Unknown or duplicate column '{}'. Available: {}",
+                    name,
+                    known
+                )
+            })?;
+        selected.push(available[index].take().unwrap());
+    }
+    Ok(selected)
+}
+
+/// Shortens text to at most `width` display columns, marking the cut with an ellipsis.
+/// Hyperlinks are kept and a link cut in the middle is closed after the ellipsis.
+pub fn truncate(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut result = String::new();
+    let mut used = 0;
+    let mut in_link = false;
+    let mut rest = text;
+    loop {
+        if let Some(params) = rest.strip_prefix(OSC8_START) {
+            let Some(end) = params.find(OSC8_END) else {
+                break;
+            };
+            let url = &params[..end];
+            result.push_str(&rest[..OSC8_START.len() + end + OSC8_END.len()]);
+            in_link = !url.is_empty();
+            rest = &params[end + OSC8_END.len()..];
+            continue;
+        }
+        let Some(ch) = rest.chars().next() else {
+            break;
+        };
+        let ch_width = ch.width().unwrap_or(0);
+        if used + ch_width > width - 1 {
+            break;
+        }
+        result.push(ch);
+        used += ch_width;
+        rest = &rest[ch.len_utf8()..];
+    }
+    result.push(ELLIPSIS);
+    if in_link {
+        result.push_str(OSC8_START);
+        result.push_str(OSC8_END);
+    }
+    result
+}
+
+/// Wraps text in an OSC 8 escape sequence, which modern terminals render as a
+/// clickable link and others ignore.
+pub fn hyperlink(text: &str, url: &str) -> String {
+    format!("{OSC8_START}{url}{OSC8_END}{text}{OSC8_START}{OSC8_END}")
+}
+
+pub fn set_hyperlinks(enabled: bool) {
+    HYPERLINKS.store(enabled, Ordering::Relaxed);
+}
+
+/// Text linking to a Sentry page when hyperlinks are enabled, plain text otherwise.
+pub fn link(text: &str, url: &str) -> String {
+    if HYPERLINKS.load(Ordering::Relaxed) {
+        hyperlink(text, url)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Renders a count in at most five characters, e.g. `950`, `1.2k`, `34.6k` or `3.4M`.
+/// Counts of a trillion and more stay in billions, e.g. `2500B`.
+pub fn human_count(count: u64) -> String {
+    if count < 1000 {
+        return count.to_string();
+    }
+    let mut value = count as f64;
+    for unit in ["k", "M", "B"] {
+        value /= 1000.0;
+        // 999_999 would round to 1000k; move on to the next unit instead
+        if value >= 999.5 && unit != "B" {
+            continue;
+        }
+        let text = if value < 99.95 {
+            format!("{:.1}", value)
+        } else {
+            format!("{:.0}", value)
+        };
+        return format!("{}{}", text.trim_end_matches(".0"), unit);
+    }
+    unreachable!()
+}
+
+pub fn set_exact_counts(enabled: bool) {
+    EXACT_COUNTS.store(enabled, Ordering::Relaxed);
+}
+
+/// Event or user count as shown in tables and the dashboard: humanized so that six
+/// digit counts keep columns narrow, or in full with `--exact`.
+pub fn format_count(count: impl Into<u64>) -> String {
+    let count = count.into();
+    if EXACT_COUNTS.load(Ordering::Relaxed) {
+        count.to_string()
+    } else {
+        human_count(count)
+    }
+}
+
+/// Guesses from the environment whether the terminal renders OSC 8 hyperlinks.
+/// Terminals that do not understand them may print the escape sequences literally,
+/// so only known terminals qualify.
+pub fn supports_hyperlinks(var: impl Fn(&str) -> Option<String>) -> bool {
+    if var("TERM").as_deref() == Some("dumb") {
+        return false;
+    }
+    var("TERM_PROGRAM").is_some_and(|program| HYPERLINK_TERMINALS.contains(&program.as_str()))
+        || HYPERLINK_TERMINAL_VARS.iter().any(|name| var(name).is_some())
+        // VTE based terminals (GNOME Terminal, Tilix) support them since 0.50
+        || var("VTE_VERSION")
+            .and_then(|version| version.parse::<u32>().ok())
+            .is_some_and(|version| version >= 5000)
+}
+
+/// Display width of text, not counting the escape sequences of hyperlinks.
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find(OSC8_START) {
+        width += rest[..start].width();
+        let params = &rest[start + OSC8_START.len()..];
+        rest = match params.find(OSC8_END) {
+            Some(end) => &params[end + OSC8_END.len()..],
+            None => "",
+        };
+    }
+    width + rest.width()
+}
+
+/// Pads text with spaces to exactly `width` display columns.
+pub fn pad(text: &str, width: usize, align: Align) -> String {
+    let padding = " ".repeat(width.saturating_sub(display_width(text)));
+    match align {
+        Align::Left => format!("{}{}", text, padding),
+        Align::Right => format!("{}{}", padding, text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_count() {
+        assert_eq!(human_count(0), "0");
+        assert_eq!(human_count(999), "999");
+        assert_eq!(human_count(1000), "1k");
+        assert_eq!(human_count(1234), "1.2k");
+        assert_eq!(human_count(34_567), "34.6k");
+        assert_eq!(human_count(123_456), "123k");
+        assert_eq!(human_count(999_999), "1M");
+        assert_eq!(human_count(3_400_000), "3.4M");
+        assert_eq!(human_count(2_500_000_000_000), "2500B");
+    }
+
+    struct Row {
+        name: String,
+        count: u32,
+    }
+
+    fn columns() -> Vec<Column<Row>> {
+        vec![
+            Column::new("name", "Name", |r: &Row| r.name.clone()).max_width(8),
+            Column::new("count", "Count", |r: &Row| r.count.to_string()).align_right(),
+        ]
+    }
+
+    #[test]
+    fn test_truncate_ascii() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a long title", 6), "a lon…");
+    }
+
+    #[test]
+    fn test_truncate_wide_characters() {
+        // Each CJK character occupies two terminal columns
+        let truncated = truncate("错误错误错误", 7);
+        assert_eq!(truncated, "错误错…");
+        assert!(truncated.width() <= 7);
+    }
+
+    #[test]
+    fn test_render_aligns_unicode() {
+        let rows = vec![
+            Row {
+                name: "错误".to_string(),
+                count: 5,
+            },
+            Row {
+                name: "error".to_string(),
+                count: 120,
+            },
+        ];
+        let output = Table::new(columns()).render(&rows);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Name   Count");
+        assert_eq!(lines[1], "错误       5");
+        assert_eq!(lines[2], "error    120");
+    }
+
+    #[test]
+    fn test_fit_width() {
+        let rows = vec![Row {
+            name: "a rather long name".to_string(),
+            count: 120,
+        }];
+        let columns = || {
+            vec![
+                Column::new("name", "Name", |r: &Row| r.name.clone())
+                    .max_width(8)
+                    .flex(),
+                Column::new("count", "Count", |r: &Row| r.count.to_string())
+                    .align_right()
+                    .priority(1),
+            ]
+        };
+
+        // Wide terminals let flexible columns grow past their max width
+        let output = Table::new(columns()).fit_width(Some(80)).render(&rows);
+        assert_eq!(output.lines().nth(1), Some("a rather long name    120"));
+
+        // Narrow ones drop low-priority columns, then shrink flexible ones
+        let output = Table::new(columns()).fit_width(Some(16)).render(&rows);
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            vec!["Name", "a rather long n…"]
+        );
+
+        // Piped output keeps the max width
+        let output = Table::new(columns()).render(&rows);
+        assert_eq!(output.lines().nth(1), Some("a rathe…    120"));
+    }
+
+    #[test]
+    fn test_hyperlinks_keep_alignment() {
+        let link = hyperlink("42", "https://sentry.io/issues/42/");
+        assert_eq!(
+            link,
+            "\x1b]8;;https://sentry.io/issues/42/\x1b\\42\x1b]8;;\x1b\\"
+        );
+        assert_eq!(display_width(&link), 2);
+        assert_eq!(display_width(&format!("错{}!", link)), 5);
+
+        let long = hyperlink("TypeError in checkout", "https://sentry.io/issues/42/");
+        let truncated = truncate(&long, 6);
+        assert_eq!(
+            truncated,
+            hyperlink("TypeE…", "https://sentry.io/issues/42/")
+        );
+        assert_eq!(display_width(&truncated), 6);
+        assert_eq!(
+            truncate(&format!("{} and more", link), 5),
+            format!("{} a…", link)
+        );
+
+        let rows = vec![Row {
+            name: link.clone(),
+            count: 5,
+        }];
+        let output = Table::new(columns()).render(&rows);
+        assert_eq!(
+            output.lines().nth(1),
+            Some(format!("{}        5", link).as_str())
+        );
+    }
+
+    #[test]
+    fn test_supports_hyperlinks() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert!(supports_hyperlinks(env(&[("TERM_PROGRAM", "WezTerm")])));
+        assert!(supports_hyperlinks(env(&[("VTE_VERSION", "7200")])));
+        assert!(supports_hyperlinks(env(&[("KITTY_WINDOW_ID", "1")])));
+        assert!(!supports_hyperlinks(env(&[("VTE_VERSION", "4601")])));
+        assert!(!supports_hyperlinks(env(&[(
+            "TERM_PROGRAM",
+            "Apple_Terminal"
+        )])));
+        assert!(!supports_hyperlinks(env(&[
+            ("TERM", "dumb"),
+            ("TERM_PROGRAM", "iTerm.app")
+        ])));
+    }
+
+    #[test]
+    fn test_select_columns_order() -> Result<()> {
+        let selected = select_columns(columns(), &["count".to_string(), "name".to_string()], &[])?;
+        let names: Vec<&str> = selected.iter().map(|c| c.name).collect();
+        assert_eq!(names, vec!["count", "name"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_select_columns_defaults_and_unknown() {
+        let selected = select_columns(columns(), &[], &["name"]).unwrap();
+        assert_eq!(selected.len(), 1);
+
+        let result = select_columns(columns(), &["bogus".to_string()], &["name"]);
+        assert!(result.is_err());
+    }
+}
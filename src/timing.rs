@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Entries repeated under the totals, slowest first.
+const SLOWEST_COUNT: usize = 5;
+
+/// Whether timings are recorded; set once at startup from `--timing`.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TIMINGS: Mutex<Vec<Timing>> = Mutex::new(Vec::new());
+
+/// How long one API call or render took, or all repeats of it together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    /// `api`, `render` for a full screen or `table` for a printed table
+    pub kind: &'static str,
+    pub label: String,
+    /// Total of all repeats
+    pub elapsed: Duration,
+    pub count: u32,
+    /// Slowest single repeat
+    pub max: Duration,
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn record(kind: &'static str, label: String, elapsed: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    if let Ok(mut timings) = TIMINGS.lock() {
+        timings.push(Timing {
+            kind,
+            label,
+            elapsed,
+            count: 1,
+            max: elapsed,
+        });
+    }
+}
+
+/// Adds to the entry of the same kind and label, for timings repeated many times such
+/// as a screen redrawn every few milliseconds.
+pub fn record_repeated(kind: &'static str, label: String, elapsed: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    if let Ok(mut timings) = TIMINGS.lock() {
+        add_repeat(&mut timings, kind, label, elapsed);
+    }
+}
+
+fn add_repeat(timings: &mut Vec<Timing>, kind: &'static str, label: String, elapsed: Duration) {
+    match timings
+        .iter_mut()
+        .find(|t| t.kind == kind && t.label == label)
+    {
+        Some(timing) => {
+            timing.elapsed += elapsed;
+            timing.count += 1;
+            timing.max = timing.max.max(elapsed);
+        }
+        None => timings.push(Timing {
+            kind,
+            label,
+            elapsed,
+            count: 1,
+            max: elapsed,
+        }),
+    }
+}
+
+/// Runs `f`, recording how long it took. The label is only built with `--timing`.
+pub fn time<T>(kind: &'static str, label: impl FnOnce() -> String, f: impl FnOnce() -> T) -> T {
+    time_with(record, kind, label, f)
+}
+
+/// Like [`time`], adding up repeats with [`record_repeated`].
+pub fn time_repeated<T>(
+    kind: &'static str,
+    label: impl FnOnce() -> String,
+    f: impl FnOnce() -> T,
+) -> T {
+    time_with(record_repeated, kind, label, f)
+}
+
+fn time_with<T>(
+    record: fn(&'static str, String, Duration),
+    kind: &'static str,
+    label: impl FnOnce() -> String,
+    f: impl FnOnce() -> T,
+) -> T {
+    if !is_enabled() {
+        return f();
+    }
+    let started = Instant::now();
+    let result = f();
+    record(kind, label(), started.elapsed());
+    result
+}
+
+/// Prints the timings of the command to stderr, so they never mix with its output.
+pub fn print_report() {
+    if !is_enabled() {
+        return;
+    }
+    if let Ok(timings) = TIMINGS.lock() {
+        eprint!("{}", report(&timings));
+    }
+}
+
+/// Every timing in the order recorded, then count, total, average and maximum per
+/// kind and the slowest entries overall.
+pub fn report(timings: &[Timing]) -> String {
+    if timings.is_empty() {
+        return "Timings: nothing recorded\n".to_string();
+    }
+    let mut out = String::from("Timings:\n");
+    for timing in timings {
+        let repeats = if timing.count > 1 {
+            format!(" ({}x, max {})", timing.count, format_duration(timing.max))
+        } else {
+            String::new()
+        };
+        out.push_str(&format!(
+            "  {:<6}  {:>8}  {}{}\n",
+            timing.kind,
+            format_duration(timing.elapsed),
+            timing.label,
+            repeats
+        ));
+    }
+
+    // Count, total and maximum per kind
+    let mut kinds: BTreeMap<&str, (u32, Duration, Duration)> = BTreeMap::new();
+    for timing in timings {
+        let (count, total, max) = kinds.entry(timing.kind).or_default();
+        *count += timing.count;
+        *total += timing.elapsed;
+        *max = (*max).max(timing.max);
+    }
+    out.push_str("Totals:\n");
+    for (kind, (count, total, max)) in &kinds {
+        out.push_str(&format!(
+            "  {:<6}  {:>5}x  total {:>8}  avg {:>8}  max {:>8}\n",
+            kind,
+            count,
+            format_duration(*total),
+            format_duration(*total / *count),
+            format_duration(*max)
+        ));
+    }
+
+    let mut slowest: Vec<&Timing> = timings.iter().collect();
+    slowest.sort_by_key(|t| std::cmp::Reverse(t.max));
+    out.push_str("Slowest:\n");
+    for timing in slowest.into_iter().take(SLOWEST_COUNT) {
+        out.push_str(&format!(
+            "  {:<6}  {:>8}  {}\n",
+            timing.kind,
+            format_duration(timing.max),
+            timing.label
+        ));
+    }
+    out
+}
+
+/// Milliseconds below a second, seconds with two decimals above.
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(kind: &'static str, label: &str, millis: u64) -> Timing {
+        Timing {
+            kind,
+            label: label.to_string(),
+            elapsed: Duration::from_millis(millis),
+            count: 1,
+            max: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let timings = vec![
+            timing("api", "GET /api/0/organizations/acme/issues/ 200", 400),
+            timing(
+                "api",
+                "GET /api/0/organizations/acme/issues/?cursor=1 200",
+                1600,
+            ),
+            timing("table", "50 rows", 3),
+        ];
+        let text = report(&timings);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "Timings:");
+        assert_eq!(
+            lines[2],
+            "  api        1.60s  GET /api/0/organizations/acme/issues/?cursor=1 200"
+        );
+        assert!(text.contains("  api         2x  total    2.00s  avg    1.00s  max    1.60s\n"));
+        assert!(text.contains("  table       1x  total      3ms"));
+        // The slowest entry comes first under the totals
+        let slowest = lines.iter().position(|l| *l == "Slowest:").unwrap();
+        assert!(lines[slowest + 1].contains("?cursor=1"));
+
+        assert_eq!(report(&[]), "Timings: nothing recorded\n");
+    }
+
+    #[test]
+    fn test_repeats_add_up() {
+        let mut timings = vec![timing("api", "GET /issues/ 200", 400)];
+        for millis in [10, 30, 20] {
+            add_repeat(
+                &mut timings,
+                "render",
+                "dashboard acme".to_string(),
+                Duration::from_millis(millis),
+            );
+        }
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings[1].count, 3);
+        assert_eq!(timings[1].max, Duration::from_millis(30));
+
+        let text = report(&timings);
+        assert!(text.contains("  render      60ms  dashboard acme (3x, max 30ms)\n"));
+        assert!(text.contains("  render      3x  total     60ms  avg     20ms  max     30ms\n"));
+    }
+}
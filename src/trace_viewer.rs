@@ -0,0 +1,318 @@
+use crate::keys::{Nav, NavKeys, NAV_HINT};
+use crate::sentry::TraceTransaction;
+use crate::table::{pad, truncate, Align};
+use crate::tui::Tui;
+use anyhow::Result;
+use crossterm::event::KeyCode;
+
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 8;
+/// Rows taken by the header, column titles and footer.
+const CHROME_HEIGHT: u16 = 4;
+
+/// A transaction flattened into the waterfall, indented by its depth in the trace tree.
+#[derive(Debug, PartialEq)]
+pub struct TraceRow {
+    pub depth: usize,
+    pub label: String,
+    pub project: String,
+    pub start: f64,
+    pub end: f64,
+    pub errors: usize,
+}
+
+impl TraceRow {
+    pub fn duration_ms(&self) -> f64 {
+        (self.end - self.start) * 1000.0
+    }
+}
+
+/// Flattens the trace tree depth-first, ordering siblings by start time.
+pub fn flatten_trace(transactions: &[TraceTransaction]) -> Vec<TraceRow> {
+    fn visit(transaction: &TraceTransaction, depth: usize, rows: &mut Vec<TraceRow>) {
+        let label = if transaction.op.is_empty() {
+            transaction.transaction.clone()
+        } else {
+            format!("{} {}", transaction.op, transaction.transaction)
+        };
+        rows.push(TraceRow {
+            depth,
+            label,
+            project: transaction.project_slug.clone(),
+            start: transaction.start_timestamp,
+            end: transaction.timestamp,
+            errors: transaction.errors.len(),
+        });
+
+        let mut children: Vec<&TraceTransaction> = transaction.children.iter().collect();
+        children.sort_by(|a, b| a.start_timestamp.total_cmp(&b.start_timestamp));
+        for child in children {
+            visit(child, depth + 1, rows);
+        }
+    }
+
+    let mut roots: Vec<&TraceTransaction> = transactions.iter().collect();
+    roots.sort_by(|a, b| a.start_timestamp.total_cmp(&b.start_timestamp));
+    let mut rows = Vec::new();
+    for root in roots {
+        visit(root, 0, &mut rows);
+    }
+    rows
+}
+
+/// Draws a transaction as a bar positioned within the trace's overall time span.
+pub fn waterfall_bar(
+    start: f64,
+    end: f64,
+    trace_start: f64,
+    trace_end: f64,
+    width: usize,
+) -> String {
+    let span = trace_end - trace_start;
+    if width == 0 || span <= 0.0 {
+        return "█".repeat(width);
+    }
+
+    let offset = (((start - trace_start) / span) * width as f64).floor() as usize;
+    let offset = offset.min(width - 1);
+    let length = (((end - start) / span) * width as f64).round() as usize;
+    let length = length.clamp(1, width - offset);
+    format!(
+        "{}{}{}",
+        " ".repeat(offset),
+        "█".repeat(length),
+        " ".repeat(width - offset - length)
+    )
+}
+
+pub struct TraceViewer {
+    tui: Tui,
+    trace_id: String,
+    rows: Vec<TraceRow>,
+    scroll_offset: usize,
+    keys: NavKeys,
+}
+
+impl TraceViewer {
+    pub fn new(trace_id: String, transactions: &[TraceTransaction]) -> Result<Self> {
+        Ok(Self {
+            tui: Tui::new()?,
+            trace_id,
+            rows: flatten_trace(transactions),
+            scroll_offset: 0,
+            keys: NavKeys::default(),
+        })
+    }
+
+    #[cfg(test)]
+    pub fn new_with_tui(trace_id: String, transactions: &[TraceTransaction], tui: Tui) -> Self {
+        Self {
+            tui,
+            trace_id,
+            rows: flatten_trace(transactions),
+            scroll_offset: 0,
+            keys: NavKeys::default(),
+        }
+    }
+
+    pub fn show(&mut self) -> Result<()> {
+        self.tui.start()?;
+
+        loop {
+            self.render()?;
+
+            let key = self.tui.read_key()?;
+            if let Some(nav) = self.keys.handle(&key) {
+                self.navigate(nav);
+            } else if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                break;
+            }
+        }
+
+        self.tui.stop()?;
+        Ok(())
+    }
+
+    fn render(&self) -> Result<()> {
+        self.tui.clear()?;
+
+        let width = self.tui.width();
+        let height = self.tui.height();
+        if width < MIN_WIDTH || height < MIN_HEIGHT {
+            self.tui
+                .write_at(0, 0, "Terminal too small. Press 'q' to quit")?;
+            return Ok(());
+        }
+
+        let trace_start = self
+            .rows
+            .iter()
+            .map(|r| r.start)
+            .fold(f64::INFINITY, f64::min);
+        let trace_end = self
+            .rows
+            .iter()
+            .map(|r| r.end)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let errors: usize = self.rows.iter().map(|r| r.errors).sum();
+
+        // Header
+        let title = format!(
+            "Trace {} - {} transactions, {} errors, {:.0}ms",
+            self.trace_id,
+            self.rows.len(),
+            errors,
+            (trace_end - trace_start).max(0.0) * 1000.0
+        );
+        self.tui
+            .write_at(1, 0, &truncate(&title, width as usize - 22))?;
+        self.tui.write_at(width - 18, 0, "Press 'q' to quit")?;
+
+        // Columns: label, project, duration, waterfall
+        let label_width = (width as usize * 2 / 5).max(20);
+        let project_width = 14;
+        let duration_width = 9;
+        let bar_width =
+            (width as usize).saturating_sub(label_width + project_width + duration_width + 5);
+
+        self.tui.write_at(
+            1,
+            2,
+            &format!(
+                "{} {} {} Waterfall",
+                pad("Transaction", label_width, Align::Left),
+                pad("Project", project_width, Align::Left),
+                pad("Duration", duration_width, Align::Right)
+            ),
+        )?;
+
+        let visible = (height - CHROME_HEIGHT) as usize;
+        for (i, row) in self
+            .rows
+            .iter()
+            .skip(self.scroll_offset)
+            .take(visible)
+            .enumerate()
+        {
+            let marker = if row.errors > 0 { "!" } else { " " };
+            let label = format!("{}{}{}", marker, "  ".repeat(row.depth), row.label);
+            let line = format!(
+                "{} {} {} {}",
+                pad(&truncate(&label, label_width), label_width, Align::Left),
+                pad(
+                    &truncate(&row.project, project_width),
+                    project_width,
+                    Align::Left
+                ),
+                pad(
+                    &format!("{:.0}ms", row.duration_ms()),
+                    duration_width,
+                    Align::Right
+                ),
+                waterfall_bar(row.start, row.end, trace_start, trace_end, bar_width)
+            );
+            self.tui.write_at(0, 3 + i as u16, &line)?;
+        }
+
+        // Footer
+        self.tui.write_at(
+            1,
+            height - 1,
+            &format!("{}  !: transaction has errors  q: quit", NAV_HINT),
+        )?;
+
+        Ok(())
+    }
+
+    fn navigate(&mut self, nav: Nav) {
+        let page = self.tui.height().saturating_sub(CHROME_HEIGHT) as usize;
+        self.scroll_offset = nav.apply(self.scroll_offset, self.rows.len(), page);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_trace() -> Vec<TraceTransaction> {
+        serde_json::from_value(serde_json::json!([
+            {
+                "event_id": "root",
+                "transaction": "GET /checkout",
+                "project_slug": "frontend",
+                "transaction.op": "http.server",
+                "start_timestamp": 100.0,
+                "timestamp": 102.0,
+                "children": [
+                    {
+                        "event_id": "late",
+                        "transaction": "send_receipt",
+                        "project_slug": "mailer",
+                        "start_timestamp": 101.0,
+                        "timestamp": 102.0
+                    },
+                    {
+                        "event_id": "early",
+                        "transaction": "charge_card",
+                        "project_slug": "payments",
+                        "start_timestamp": 100.5,
+                        "timestamp": 101.0,
+                        "errors": [{"event_id": "err", "title": "Timeout"}]
+                    }
+                ]
+            }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_flatten_trace() {
+        let rows = flatten_trace(&create_test_trace());
+        let labels: Vec<(usize, &str)> = rows.iter().map(|r| (r.depth, r.label.as_str())).collect();
+        assert_eq!(
+            labels,
+            vec![
+                (0, "http.server GET /checkout"),
+                (1, "charge_card"),
+                (1, "send_receipt"),
+            ]
+        );
+        assert_eq!(rows[1].errors, 1);
+        assert_eq!(rows[0].duration_ms(), 2000.0);
+    }
+
+    #[test]
+    fn test_waterfall_bar() {
+        assert_eq!(waterfall_bar(100.0, 102.0, 100.0, 102.0, 8), "████████");
+        assert_eq!(waterfall_bar(101.0, 102.0, 100.0, 102.0, 8), "    ████");
+        assert_eq!(waterfall_bar(100.5, 101.0, 100.0, 102.0, 8), "  ██    ");
+        // Very short transactions still get a visible bar
+        assert_eq!(waterfall_bar(101.0, 101.001, 100.0, 102.0, 8), "    █   ");
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let tui = Tui::new_with_size(100, 24);
+        let viewer = TraceViewer::new_with_tui("abc123".to_string(), &create_test_trace(), tui);
+
+        viewer.render()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_scroll_bounds() {
+        let tui = Tui::new_with_size(100, 24);
+        let mut viewer = TraceViewer::new_with_tui("abc123".to_string(), &create_test_trace(), tui);
+
+        viewer.navigate(Nav::Up);
+        assert_eq!(viewer.scroll_offset, 0);
+        for _ in 0..5 {
+            viewer.navigate(Nav::Down);
+        }
+        assert_eq!(viewer.scroll_offset, 2);
+        viewer.navigate(Nav::Top);
+        assert_eq!(viewer.scroll_offset, 0);
+        viewer.navigate(Nav::PageDown);
+        assert_eq!(viewer.scroll_offset, 2);
+    }
+}
@@ -0,0 +1,460 @@
+use crate::editor::open_in_browser;
+use crate::history::{FilterInput, InputState, SearchHistory};
+use crate::ownership::{fetch_suggestions, pick_assignee};
+use crate::sentry::{Frame, IgnoreCondition, Issue, IssueQuery, SentryClient};
+use crate::table::truncate;
+use crate::tui::Tui;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use serde_json::json;
+
+/// What happened to each issue during a triage session.
+#[derive(Debug, Default, PartialEq)]
+pub struct TriageTally {
+    pub resolved: usize,
+    pub ignored: usize,
+    pub assigned: usize,
+    pub skipped: usize,
+}
+
+impl std::fmt::Display for TriageTally {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "resolved {}, ignored {}, assigned {}, skipped {}",
+            self.resolved, self.ignored, self.assigned, self.skipped
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Resolved,
+    Ignored,
+    Assigned(String),
+    Skipped,
+}
+
+/// Full-screen inbox that steps through issues one at a time.
+pub struct Triage {
+    tui: Tui,
+    client: SentryClient,
+    org_slug: String,
+    project: String,
+    issues: Vec<Issue>,
+    index: usize,
+    /// Stack of the current issue's latest event, innermost last; `None` until loaded
+    frames: Option<Result<Vec<Frame>, String>>,
+    tally: TriageTally,
+    /// Result of the last action, shown above the footer
+    message: Option<String>,
+    /// Search the issues were selected with
+    query: String,
+    searches: SearchHistory,
+}
+
+impl Triage {
+    pub fn new(
+        client: SentryClient,
+        org_slug: String,
+        project: String,
+        issues: Vec<Issue>,
+    ) -> Result<Self> {
+        Ok(Self::new_with_tui(
+            client,
+            org_slug,
+            project,
+            issues,
+            Tui::new()?,
+        ))
+    }
+
+    fn new_with_tui(
+        client: SentryClient,
+        org_slug: String,
+        project: String,
+        issues: Vec<Issue>,
+        tui: Tui,
+    ) -> Self {
+        Self {
+            tui,
+            client,
+            org_slug,
+            project,
+            issues,
+            index: 0,
+            frames: None,
+            tally: TriageTally::default(),
+            message: None,
+            query: IssueQuery::default().query,
+            searches: SearchHistory::default(),
+        }
+    }
+
+    /// Search the issues were selected with, shown in the filter prompt.
+    pub fn with_query(mut self, query: String) -> Self {
+        self.query = query;
+        self
+    }
+
+    /// Starts with searches and saved filters from earlier sessions.
+    pub fn with_searches(mut self, searches: SearchHistory) -> Self {
+        self.searches = searches;
+        self
+    }
+
+    /// Runs until every issue was handled or the user quits.
+    pub fn run(&mut self) -> Result<()> {
+        self.tui.start()?;
+        let result = self.event_loop();
+        self.tui.stop()?;
+        result
+    }
+
+    pub fn tally(&self) -> &TriageTally {
+        &self.tally
+    }
+
+    /// Searches used and filters saved, to be kept once triage ends.
+    pub fn searches(&self) -> &SearchHistory {
+        &self.searches
+    }
+
+    fn event_loop(&mut self) -> Result<()> {
+        while self.index < self.issues.len() {
+            if self.frames.is_none() {
+                self.frames = Some(
+                    self.client
+                        .get_latest_event(&self.issues[self.index].id)
+                        .map(|event| event.crash_frames())
+                        .map_err(|e| e.to_string()),
+                );
+            }
+            self.render()?;
+
+            let KeyEvent { code, .. } = self.tui.read_key()?;
+            let result = match code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Char('r') => self.update(json!({"status": "resolved"}), Outcome::Resolved),
+                KeyCode::Char('i') => self.update(json!({"status": "ignored"}), Outcome::Ignored),
+                KeyCode::Char('I') => self.ignore_until(),
+                KeyCode::Char('a') => self.assign(),
+                KeyCode::Char('/') => self.change_filter(),
+                KeyCode::Char('S') => self.save_filter(),
+                KeyCode::Char('s') | KeyCode::Char(' ') | KeyCode::Right => {
+                    self.record(Outcome::Skipped);
+                    Ok(())
+                }
+                KeyCode::Char('o') => open_in_browser(
+                    &self
+                        .client
+                        .issue_url(&self.org_slug, &self.issues[self.index]),
+                ),
+                _ => Ok(()),
+            };
+            // Failed actions leave the issue in place so it can be retried
+            if let Err(e) = result {
+                self.message = Some(format!("Error: {}", e));
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, changes: serde_json::Value, outcome: Outcome) -> Result<()> {
+        self.client
+            .update_issue(&self.issues[self.index].id, &changes)?;
+        self.record(outcome);
+        Ok(())
+    }
+
+    /// Ignores the issue until a condition typed in, e.g. `2w` or `1000 events in 1h`.
+    fn ignore_until(&mut self) -> Result<()> {
+        let Some(input) = self.prompt("Ignore until (e.g. 2w, 1000 events in 1h, 50 users): ")?
+        else {
+            return Ok(());
+        };
+        let condition = IgnoreCondition::parse(&input)?;
+        self.update(condition.changes(), Outcome::Ignored)
+    }
+
+    /// Replaces the remaining issues with those matching a new search, where `@name`
+    /// picks a saved filter.
+    fn change_filter(&mut self) -> Result<()> {
+        let recent = self.searches.recent.clone();
+        let current = self.query.clone();
+        let Some(input) = self.read_line(
+            "Filter (Up/Down: history, @name: saved): ",
+            &recent,
+            &current,
+        )?
+        else {
+            return Ok(());
+        };
+        let query = match self.searches.expand(&input)? {
+            query if query.is_empty() => IssueQuery::default().query,
+            query => query,
+        };
+        self.searches.record(&query);
+        let issue_query = IssueQuery {
+            query: query.clone(),
+            ..IssueQuery::default()
+        };
+        self.issues =
+            self.client
+                .list_project_issues(&self.org_slug, &self.project, &issue_query)?;
+        self.index = 0;
+        self.frames = None;
+        self.message = Some(format!("{} issue(s) match '{}'", self.issues.len(), query));
+        self.query = query;
+        Ok(())
+    }
+
+    /// Saves the current search under a name, to be recalled as `@name`.
+    fn save_filter(&mut self) -> Result<()> {
+        let Some(name) = self.prompt("Save filter as: ")? else {
+            return Ok(());
+        };
+        self.searches.save_filter(&name, &self.query);
+        self.message = Some(format!("Saved filter @{}", name.trim_start_matches('@')));
+        Ok(())
+    }
+
+    /// Offers owners and suspect committers as numbered choices above the prompt.
+    fn assign(&mut self) -> Result<()> {
+        let (teams, suggestions) =
+            fetch_suggestions(&self.client, &self.org_slug, &self.issues[self.index])?;
+        let footer_row = self.tui.height().saturating_sub(1);
+        let first_row = footer_row.saturating_sub(1 + suggestions.len() as u16);
+        for (row, (index, suggestion)) in (first_row..).zip(suggestions.iter().enumerate()) {
+            let line = format!(
+                "{}) {} - {}",
+                index + 1,
+                suggestion.label,
+                suggestion.reason
+            );
+            self.tui.write_at(
+                2,
+                row,
+                &truncate(&line, (self.tui.width() as usize).saturating_sub(4)),
+            )?;
+        }
+
+        let Some(input) = self.prompt("Assign to (number, 'me', email or #team): ")? else {
+            return Ok(());
+        };
+        let (assignee, label) = pick_assignee(&self.client, &input, &teams, &suggestions)?;
+        self.update(json!({ "assignedTo": assignee }), Outcome::Assigned(label))
+    }
+
+    /// Counts the outcome and moves on to the next issue.
+    fn record(&mut self, outcome: Outcome) {
+        let issue = &self.issues[self.index];
+        let label = issue.short_id.as_deref().unwrap_or(&issue.id);
+        self.message = Some(match &outcome {
+            Outcome::Resolved => format!("Resolved {}", label),
+            Outcome::Ignored => format!("Ignored {}", label),
+            Outcome::Assigned(assignee) => format!("Assigned {} to {}", label, assignee),
+            Outcome::Skipped => format!("Skipped {}", label),
+        });
+        match outcome {
+            Outcome::Resolved => self.tally.resolved += 1,
+            Outcome::Ignored => self.tally.ignored += 1,
+            Outcome::Assigned(_) => self.tally.assigned += 1,
+            Outcome::Skipped => self.tally.skipped += 1,
+        }
+        self.index += 1;
+        self.frames = None;
+    }
+
+    /// Reads a line on the footer row. Returns `None` when cancelled with Esc or left empty.
+    fn prompt(&self, label: &str) -> Result<Option<String>> {
+        Ok(self
+            .read_line(label, &[], "")?
+            .filter(|input| !input.is_empty()))
+    }
+
+    /// Reads a line on the footer row, with Up/Down stepping through `recent`. Returns
+    /// `None` when cancelled with Esc.
+    fn read_line(&self, label: &str, recent: &[String], text: &str) -> Result<Option<String>> {
+        let mut input = FilterInput::new(recent, text);
+        loop {
+            let row = self.tui.height() - 1;
+            self.tui
+                .write_at(0, row, &" ".repeat(self.tui.width() as usize))?;
+            self.tui
+                .write_at(2, row, &format!("{}{}", label, input.text))?;
+            match input.handle(self.tui.read_key()?.code) {
+                InputState::Editing => {}
+                InputState::Submitted(text) => return Ok(Some(text)),
+                InputState::Cancelled => return Ok(None),
+            }
+        }
+    }
+
+    fn progress(&self) -> String {
+        format!("[{}/{}]", self.index + 1, self.issues.len())
+    }
+
+    fn render(&self) -> Result<()> {
+        let Some(issue) = self.issues.get(self.index) else {
+            return Ok(());
+        };
+        let width = self.tui.width() as usize;
+        let fit = |text: &str| truncate(text, width.saturating_sub(4));
+        self.tui.clear()?;
+
+        self.tui.write_at(
+            2,
+            0,
+            &fit(&format!(
+                "Triage {}/{} {}  {}",
+                self.org_slug,
+                self.project,
+                self.progress(),
+                self.tally
+            )),
+        )?;
+        let label = issue.short_id.as_deref().unwrap_or(&issue.id);
+        self.tui
+            .write_at(2, 2, &fit(&format!("{}: {}", label, issue.title)))?;
+        self.tui.write_at(
+            2,
+            3,
+            &fit(&format!(
+                "Level: {}  Status: {}  Events: {}  Users: {}  Last Seen: {}",
+                issue.level, issue.status, issue.count, issue.user_count, issue.last_seen
+            )),
+        )?;
+        self.tui
+            .write_at(2, 4, &fit(&format!("Culprit: {}", issue.culprit)))?;
+
+        // Stacktrace, most recent call first, in the space left above the footer
+        let last_row = self.tui.height().saturating_sub(3);
+        let mut row = 6;
+        match &self.frames {
+            Some(Ok(frames)) if !frames.is_empty() => {
+                self.tui.write_at(2, row, "Stacktrace:")?;
+                for frame in frames.iter().rev() {
+                    row += 1;
+                    if row > last_row {
+                        break;
+                    }
+                    self.tui.write_at(4, row, &fit(&frame.to_string()))?;
+                }
+            }
+            Some(Ok(_)) => self.tui.write_at(2, row, "No stacktrace")?,
+            Some(Err(e)) => {
+                self.tui
+                    .write_at(2, row, &fit(&format!("Stacktrace unavailable: {}", e)))?
+            }
+            None => self.tui.write_at(2, row, "Loading stacktrace...")?,
+        }
+
+        let footer_row = self.tui.height().saturating_sub(1);
+        if let Some(message) = &self.message {
+            self.tui.write_at(2, footer_row - 1, &fit(message))?;
+        }
+        // Kept short enough for an 80-column terminal
+        self.tui.write_at(
+            2,
+            footer_row,
+            &fit("r resolve  i/I ignore  a assign  s skip  o open  / filter  S save  q quit"),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: &str) -> Issue {
+        serde_json::from_value(json!({
+            "id": id,
+            "shortId": format!("WEB-{}", id),
+            "title": "Test Issue",
+            "status": "unresolved",
+            "level": "error",
+            "culprit": "test.js:42",
+            "lastSeen": "2024-01-01T00:00:00Z",
+            "count": 10,
+            "userCount": 2
+        }))
+        .unwrap()
+    }
+
+    fn triage(count: usize) -> Triage {
+        let issues = (1..=count).map(|i| issue(&i.to_string())).collect();
+        Triage::new_with_tui(
+            SentryClient::new().unwrap(),
+            "acme".to_string(),
+            "web".to_string(),
+            issues,
+            Tui::new_with_size(80, 24),
+        )
+    }
+
+    #[test]
+    fn test_record_outcomes() {
+        let mut triage = triage(3);
+        assert_eq!(triage.progress(), "[1/3]");
+
+        triage.record(Outcome::Resolved);
+        assert_eq!(triage.message.as_deref(), Some("Resolved WEB-1"));
+        triage.record(Outcome::Assigned("jane@example.com".to_string()));
+        assert_eq!(
+            triage.message.as_deref(),
+            Some("Assigned WEB-2 to jane@example.com")
+        );
+        assert_eq!(triage.progress(), "[3/3]");
+        triage.record(Outcome::Skipped);
+
+        assert_eq!(
+            triage.tally,
+            TriageTally {
+                resolved: 1,
+                ignored: 0,
+                assigned: 1,
+                skipped: 1,
+            }
+        );
+        assert_eq!(
+            triage.tally.to_string(),
+            "resolved 1, ignored 0, assigned 1, skipped 1"
+        );
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let mut triage = triage(2);
+        triage.frames = Some(Ok(Vec::new()));
+        triage.render()?;
+        assert!(triage.tui.row(0).starts_with("  Triage acme/web [1/2]"));
+        assert_eq!(triage.tui.row(2), "  WEB-1: Test Issue");
+        assert_eq!(triage.tui.row(6), "  No stacktrace");
+        let footer = triage.tui.row(23);
+        assert!(footer.starts_with("  r resolve"));
+        assert!(footer.ends_with("q quit"));
+
+        triage.frames = Some(Err("API request failed: 404".to_string()));
+        triage.render()?;
+        assert_eq!(
+            triage.tui.row(6),
+            "  Stacktrace unavailable: API request failed: 404"
+        );
+
+        // The next issue is shown with the outcome of the previous one
+        triage.record(Outcome::Ignored);
+        triage.render()?;
+        assert!(triage.tui.row(0).starts_with("  Triage acme/web [2/2]"));
+        assert_eq!(triage.tui.row(2), "  WEB-2: Test Issue");
+        assert_eq!(triage.tui.row(6), "  Loading stacktrace...");
+        assert_eq!(triage.tui.row(22), "  Ignored WEB-1");
+
+        // Nothing left to show once every issue was handled
+        triage.record(Outcome::Skipped);
+        triage.render()?;
+        assert_eq!(triage.tui.row(2), "  WEB-2: Test Issue");
+        Ok(())
+    }
+}
@@ -6,17 +6,26 @@ use crossterm::{
     style::Print,
     terminal::{self, ClearType},
 };
+use std::cell::RefCell;
 use std::io;
 
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
 pub struct Tui {
     width: u16,
     height: u16,
+    /// In-memory rows written instead of the terminal, so tests can read what was drawn
+    screen: Option<RefCell<Vec<Vec<char>>>>,
 }
 
 impl Tui {
     pub fn new() -> Result<Self> {
         let (width, height) = terminal::size()?;
-        Ok(Self { width, height })
+        Ok(Self {
+            width,
+            height,
+            screen: None,
+        })
     }
 
     pub fn start(&self) -> Result<()> {
@@ -32,6 +41,12 @@ impl Tui {
     }
 
     pub fn clear(&self) -> Result<()> {
+        if let Some(screen) = &self.screen {
+            for row in screen.borrow_mut().iter_mut() {
+                row.fill(' ');
+            }
+            return Ok(());
+        }
         execute!(
             io::stdout(),
             terminal::Clear(ClearType::All),
@@ -41,6 +56,14 @@ impl Tui {
     }
 
     pub fn write_at(&self, x: u16, y: u16, text: &str) -> Result<()> {
+        if let Some(screen) = &self.screen {
+            if let Some(row) = screen.borrow_mut().get_mut(y as usize) {
+                for (cell, c) in row.iter_mut().skip(x as usize).zip(text.chars()) {
+                    *cell = c;
+                }
+            }
+            return Ok(());
+        }
         execute!(io::stdout(), cursor::MoveTo(x, y), Print(text))?;
         Ok(())
     }
@@ -87,10 +110,58 @@ impl Tui {
 
     #[cfg(test)]
     pub fn new_with_size(width: u16, height: u16) -> Self {
-        Self { width, height }
+        let rows = vec![vec![' '; width as usize]; height as usize];
+        Self {
+            width,
+            height,
+            screen: Some(RefCell::new(rows)),
+        }
+    }
+
+    /// Returns row `y` as drawn so far, without trailing spaces.
+    #[cfg(test)]
+    pub fn row(&self, y: u16) -> String {
+        self.screen
+            .as_ref()
+            .and_then(|screen| screen.borrow().get(y as usize).cloned())
+            .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+            .unwrap_or_default()
     }
 }
 
+/// Renders values as a single-line bar chart, resampled to at most `width` columns.
+pub fn sparkline(values: &[i64], width: usize) -> String {
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+
+    // Sum neighbouring buckets so that every value is accounted for
+    let buckets: Vec<i64> = if values.len() > width {
+        (0..width)
+            .map(|i| {
+                let start = i * values.len() / width;
+                let end = (i + 1) * values.len() / width;
+                values[start..end].iter().sum()
+            })
+            .collect()
+    } else {
+        values.to_vec()
+    };
+
+    let max = buckets.iter().copied().max().unwrap_or(0);
+    buckets
+        .iter()
+        .map(|&value| {
+            if max <= 0 || value <= 0 {
+                ' '
+            } else {
+                let index = (value * (SPARK_CHARS.len() as i64 - 1) + max - 1) / max;
+                SPARK_CHARS[index as usize]
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,10 +173,22 @@ mod tests {
         assert_eq!(tui.height(), 24);
     }
 
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[], 10), "");
+        assert_eq!(sparkline(&[0, 1, 7], 10), " ▂█");
+        // Resampling keeps the chart within the requested width
+        let values: Vec<i64> = (0..30).collect();
+        assert_eq!(sparkline(&values, 10).chars().count(), 10);
+    }
+
     #[test]
     fn test_box_dimensions() -> Result<()> {
         let tui = Tui::new_with_size(80, 24);
         tui.draw_box(0, 0, 10, 5)?;
+        assert_eq!(tui.row(0), "┌────────┐");
+        assert_eq!(tui.row(2), "│        │");
+        assert_eq!(tui.row(4), "└────────┘");
         Ok(())
     }
 }